@@ -4,7 +4,7 @@ use astroport::cw20_ics20::TransferMsg;
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, IbcMsg, IbcQuery, MessageInfo, Order,
-    PortIdResponse, Response, StdError, StdResult,
+    PortIdResponse, Response, StdError, StdResult, Uint128,
 };
 use semver::Version;
 
@@ -18,11 +18,11 @@ use crate::ibc::Ics20Packet;
 use crate::migrations::standard_v1;
 use crate::msg::{
     AllowMsg, AllowedInfo, AllowedResponse, ChannelResponse, ConfigResponse, ExecuteMsg, InitMsg,
-    ListAllowedResponse, ListChannelsResponse, MigrateMsg, PortResponse, QueryMsg,
+    ListAllowedResponse, ListChannelsResponse, MigrateMsg, PortResponse, QueryMsg, RateLimitMsg,
 };
 use crate::state::{
-    increase_channel_balance, AllowInfo, Config, ADMIN, ALLOW_LIST, CHANNEL_INFO, CHANNEL_STATE,
-    CONFIG,
+    increase_channel_balance, record_outflow, AllowInfo, Config, RateLimit, ADMIN, ALLOW_LIST,
+    CHANNEL_INFO, CHANNEL_STATE, CONFIG,
 };
 use cw_utils::{maybe_addr, nonpayable, one_coin};
 
@@ -80,6 +80,10 @@ pub fn execute(
         ExecuteMsg::UpdateHookAddress { new_address } => {
             execute_update_hook_address(deps, info, new_address)
         }
+        ExecuteMsg::SetRateLimit(msg) => execute_set_rate_limit(deps, env, info, msg),
+        ExecuteMsg::ResetRateLimit { channel, denom } => {
+            execute_reset_rate_limit(deps, env, info, channel, denom)
+        }
     }
 }
 
@@ -148,6 +152,15 @@ pub fn execute_transfer(
     // Update the balance now (optimistically) like ibctransfer modules.
     // In on_packet_failure (ack with error message or a timeout), we reduce the balance appropriately.
     // This means the channel works fine if success acks are not relayed.
+    // Enforce the outflow quota of the (channel, denom) pair.
+    // The outflow is released again in on_packet_failure
+    record_outflow(
+        deps.storage,
+        &msg.channel,
+        &amount.denom(),
+        amount.amount(),
+        env.block.time,
+    )?;
     increase_channel_balance(deps.storage, &msg.channel, &amount.denom(), amount.amount())?;
 
     // prepare ibc message
@@ -228,6 +241,93 @@ pub fn execute_update_hook_address(
         .add_attribute("new_address", new_address))
 }
 
+/// Set the inflow and outflow quotas of a (channel, denom) pair.
+/// May only be executed by the contract admin
+pub fn execute_set_rate_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RateLimitMsg,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if !CHANNEL_INFO.has(deps.storage, &msg.channel) {
+        return Err(ContractError::NoSuchChannel { id: msg.channel });
+    }
+    if msg.period == 0 {
+        return Err(ContractError::InvalidRateLimitPeriod {});
+    }
+
+    let key = (msg.channel.as_str(), msg.denom.as_str());
+    let mut state = CHANNEL_STATE
+        .may_load(deps.storage, key)?
+        .unwrap_or_default();
+    state.rate_limit = if msg.max_inflow.is_none() && msg.max_outflow.is_none() {
+        None
+    } else {
+        let mut rate_limit = state.rate_limit.unwrap_or(RateLimit {
+            max_inflow: None,
+            max_outflow: None,
+            period: msg.period,
+            window_start: env.block.time,
+            inflow: Uint128::zero(),
+            outflow: Uint128::zero(),
+            prev_inflow: Uint128::zero(),
+            prev_outflow: Uint128::zero(),
+        });
+        rate_limit.max_inflow = msg.max_inflow;
+        rate_limit.max_outflow = msg.max_outflow;
+        rate_limit.period = msg.period;
+        Some(rate_limit)
+    };
+    CHANNEL_STATE.save(deps.storage, key, &state)?;
+
+    let limit_to_string = |limit: Option<Uint128>| {
+        limit
+            .map(|limit| limit.to_string())
+            .unwrap_or_else(|| "None".to_string())
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "set_rate_limit")
+        .add_attribute("channel", msg.channel)
+        .add_attribute("denom", msg.denom)
+        .add_attribute("max_inflow", limit_to_string(msg.max_inflow))
+        .add_attribute("max_outflow", limit_to_string(msg.max_outflow))
+        .add_attribute("period", msg.period.to_string()))
+}
+
+/// Clear the flows of a (channel, denom) pair and start a new window.
+/// May only be executed by the contract admin
+pub fn execute_reset_rate_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel: String,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let key = (channel.as_str(), denom.as_str());
+    let mut state = CHANNEL_STATE
+        .may_load(deps.storage, key)?
+        .unwrap_or_default();
+    let rate_limit = state
+        .rate_limit
+        .as_mut()
+        .ok_or_else(|| ContractError::NoRateLimit {
+            channel: channel.clone(),
+            denom: denom.clone(),
+        })?;
+    rate_limit.reset(env.block.time);
+    CHANNEL_STATE.save(deps.storage, key, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reset_rate_limit")
+        .add_attribute("channel", channel)
+        .add_attribute("denom", denom))
+}
+
 const MIGRATE_MIN_VERSION: &str = "0.13.4";
 const MIGRATE_VERSION_ASTROPORT_V1: &str = "1.1.1";
 
@@ -258,7 +358,8 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
         });
     }
     // Run the migration from minimum v0.13.4 to our custom Astroport v1.1.1
-    if storage_version <= MIGRATE_VERSION_ASTROPORT_V1.parse().map_err(from_semver)? {
+    // Contracts already running Astroport v1.1.1 store the new config thus they are skipped
+    if storage_version < MIGRATE_VERSION_ASTROPORT_V1.parse().map_err(from_semver)? {
         let old_config = standard_v1::CONFIG.load(deps.storage)?;
         let config = Config {
             default_timeout: old_config.default_timeout,
@@ -303,6 +404,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&list_allowed(deps, start_after, limit)?)
         }
         QueryMsg::Admin {} => to_binary(&ADMIN.query_admin(deps)?),
+        QueryMsg::RateLimit { channel, denom } => {
+            to_binary(&query_rate_limit(deps, channel, denom)?)
+        }
     }
 }
 
@@ -373,6 +477,12 @@ fn query_allowed(deps: Deps, contract: String) -> StdResult<AllowedResponse> {
     Ok(res)
 }
 
+fn query_rate_limit(deps: Deps, channel: String, denom: String) -> StdResult<Option<RateLimit>> {
+    Ok(CHANNEL_STATE
+        .may_load(deps.storage, (&channel, &denom))?
+        .and_then(|state| state.rate_limit))
+}
+
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
@@ -622,6 +732,34 @@ mod test {
         assert_eq!(config.hook_addr.unwrap(), "hook_contract");
     }
 
+    #[test]
+    fn astroport_v1_upgrade_keeps_config() {
+        let mut deps = setup(&["channel-3"], &[]);
+        set_contract_version(
+            deps.as_mut().storage,
+            CONTRACT_NAME,
+            MIGRATE_VERSION_ASTROPORT_V1,
+        )
+        .unwrap();
+
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                default_gas_limit: None,
+                hook_addr: Some("hook_contract".to_string()),
+            },
+        )
+        .unwrap();
+
+        // the Astroport config is not converted again
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.default_timeout, DEFAULT_TIMEOUT);
+        assert_eq!(config.hook_addr, None);
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+    }
+
     fn test_with_memo(memo: &str) {
         let send_channel = "channel-5";
         let mut deps = setup(&[send_channel, "channel-10"], &[]);
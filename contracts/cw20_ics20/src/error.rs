@@ -68,6 +68,18 @@ pub enum ContractError {
 
     #[error("Memo provided but Hook contract not set")]
     NoHookContract,
+
+    #[error("Inflow quota exceeded for {denom} on channel {channel}")]
+    InflowQuotaExceeded { channel: String, denom: String },
+
+    #[error("Outflow quota exceeded for {denom} on channel {channel}")]
+    OutflowQuotaExceeded { channel: String, denom: String },
+
+    #[error("No rate limit set for {denom} on channel {channel}")]
+    NoRateLimit { channel: String, denom: String },
+
+    #[error("Rate limit period must be greater than zero")]
+    InvalidRateLimitPeriod {},
 }
 
 impl From<FromUtf8Error> for ContractError {
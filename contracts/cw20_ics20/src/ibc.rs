@@ -14,8 +14,8 @@ use astroport::outpost_handler::Cw20HookMsg;
 use crate::amount::Amount;
 use crate::error::{ContractError, Never};
 use crate::state::{
-    reduce_channel_balance, undo_reduce_channel_balance, ChannelInfo, ReplyArgs, ALLOW_LIST,
-    CHANNEL_INFO, CONFIG, REPLY_ARGS,
+    record_inflow, reduce_channel_balance, undo_inflow, undo_outflow, undo_reduce_channel_balance,
+    ChannelInfo, ReplyArgs, ALLOW_LIST, CHANNEL_INFO, CONFIG, REPLY_ARGS,
 };
 
 pub const ICS20_VERSION: &str = "ics20-1";
@@ -112,6 +112,12 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
                     &reply_args.denom,
                     reply_args.amount,
                 )?;
+                undo_inflow(
+                    deps.storage,
+                    &reply_args.channel,
+                    &reply_args.denom,
+                    reply_args.amount,
+                )?;
 
                 Ok(Response::new().set_data(ack_fail(err)))
             }
@@ -194,12 +200,12 @@ pub fn ibc_channel_close(
 /// We should not return an error if possible, but rather an acknowledgement of failure
 pub fn ibc_packet_receive(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcPacketReceiveMsg,
 ) -> Result<IbcReceiveResponse, Never> {
     let packet = msg.packet;
 
-    do_ibc_packet_receive(deps, &packet).or_else(|err| {
+    do_ibc_packet_receive(deps, env, &packet).or_else(|err| {
         Ok(IbcReceiveResponse::new()
             .set_ack(ack_fail(err.to_string()))
             .add_attributes(vec![
@@ -238,6 +244,7 @@ fn parse_voucher_denom<'a>(
 // this does the work of ibc_packet_receive, we wrap it to turn errors into acknowledgements
 fn do_ibc_packet_receive(
     deps: DepsMut,
+    env: Env,
     packet: &IbcPacket,
 ) -> Result<IbcReceiveResponse, ContractError> {
     let msg: Ics20Packet = from_binary(&packet.data)?;
@@ -313,6 +320,12 @@ fn do_ibc_packet_receive(
     // the original contract since ContractErrors are handled as Ok to pass
     // back the ack. See original ibc_packet_receive for more info
     reduce_channel_balance(deps.storage, &channel, denom, msg.amount)?;
+    // Reject the packet if it exceeds the inflow quota. As errors are turned into acks,
+    // state is not reverted automatically, so we undo the balance update ourselves
+    if let Err(err) = record_inflow(deps.storage, &channel, denom, msg.amount, env.block.time) {
+        undo_reduce_channel_balance(deps.storage, &channel, denom, msg.amount)?;
+        return Err(err);
+    }
 
     let res = IbcReceiveResponse::new()
         .set_ack(ack_success())
@@ -406,6 +419,13 @@ fn on_packet_failure(
         &ics_msg.denom,
         ics_msg.amount,
     )?;
+    // the tokens are returned, so they don't count against the outflow quota
+    undo_outflow(
+        deps.storage,
+        &packet.src.channel_id,
+        &ics_msg.denom,
+        ics_msg.amount,
+    )?;
 
     let to_send = Amount::from_parts(ics_msg.denom.clone(), ics_msg.amount);
     let gas_limit = check_gas_limit(deps.as_ref(), &to_send)?;
@@ -495,8 +515,10 @@ mod test {
     use astroport::cw20_ics20::TransferMsg;
 
     use crate::contract::{execute, migrate, query_channel};
-    use crate::msg::{ExecuteMsg, MigrateMsg};
+    use crate::msg::{ExecuteMsg, MigrateMsg, QueryMsg, RateLimitMsg};
+    use crate::state::RateLimit;
     use crate::test_helpers::*;
+    use cw_controllers::AdminError;
 
     use super::*;
 
@@ -830,6 +852,185 @@ mod test {
         assert_eq!(state.total_sent, vec![Amount::native(987654321, denom)]);
     }
 
+    #[test]
+    fn rate_limits_per_channel_and_denom() {
+        let send_channel = "channel-9";
+        let mut deps = setup(&["channel-1", send_channel], &[]);
+        let denom = "uatom";
+
+        let rate_limit_msg = RateLimitMsg {
+            channel: send_channel.to_string(),
+            denom: denom.to_string(),
+            max_inflow: Some(Uint128::new(1000)),
+            max_outflow: Some(Uint128::new(3000)),
+            period: 3600,
+        };
+
+        // only the admin can set quotas
+        let msg = ExecuteMsg::SetRateLimit(rate_limit_msg.clone());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("foobar", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+        let msg = ExecuteMsg::SetRateLimit(RateLimitMsg {
+            period: 0,
+            ..rate_limit_msg.clone()
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidRateLimitPeriod {});
+
+        let msg = ExecuteMsg::SetRateLimit(rate_limit_msg);
+        execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap();
+
+        // outflow quota is enforced on send
+        transfer_native(deps.as_mut(), send_channel, denom, mock_env(), 2000).unwrap();
+        let err =
+            transfer_native(deps.as_mut(), send_channel, denom, mock_env(), 1001).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OutflowQuotaExceeded {
+                channel: send_channel.to_string(),
+                denom: denom.to_string(),
+            }
+        );
+
+        // a failed transfer releases its outflow
+        let sent_packet = IbcPacket::new(
+            to_binary(&Ics20Packet::new(
+                Uint128::new(2000),
+                denom,
+                "local-sender",
+                "my-remote-address",
+            ))
+            .unwrap(),
+            IbcEndpoint {
+                port_id: CONTRACT_PORT.to_string(),
+                channel_id: send_channel.to_string(),
+            },
+            IbcEndpoint {
+                port_id: REMOTE_PORT.to_string(),
+                channel_id: "channel-1234".to_string(),
+            },
+            2,
+            Timestamp::from_seconds(1665321069).into(),
+        );
+        let msg = IbcPacketTimeoutMsg::new(sent_packet);
+        ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+        transfer_native(deps.as_mut(), send_channel, denom, mock_env(), 3000).unwrap();
+
+        // inflow quota is enforced on receive with an ack error and the balance is untouched
+        let recv_packet = mock_receive_packet(send_channel, 1001, denom, "local-rcpt", None);
+        let msg = IbcPacketReceiveMsg::new(recv_packet);
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let ack: Ics20Ack = from_binary(&res.acknowledgement).unwrap();
+        let exceeded = ContractError::InflowQuotaExceeded {
+            channel: send_channel.to_string(),
+            denom: denom.to_string(),
+        };
+        assert_eq!(ack, Ics20Ack::Error(exceeded.to_string()));
+        let state = query_channel(deps.as_ref(), send_channel.to_string()).unwrap();
+        assert_eq!(state.balances, vec![Amount::native(3000, denom)]);
+
+        // an inflow whose delivery failed is released in the reply
+        let recv_packet = mock_receive_packet(send_channel, 1000, denom, "local-rcpt", None);
+        let msg = IbcPacketReceiveMsg::new(recv_packet.clone());
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        let reply_msg = Reply {
+            id: RECEIVE_ID,
+            result: SubMsgResult::Err("failed to send".to_string()),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        let msg = IbcPacketReceiveMsg::new(recv_packet);
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: Ics20Ack = from_binary(&res.acknowledgement).unwrap();
+        assert!(matches!(ack, Ics20Ack::Result(_)));
+
+        let rate_limit = query_rate_limit(deps.as_ref(), send_channel, denom);
+        assert_eq!(rate_limit.inflow.u128(), 1000);
+        assert_eq!(rate_limit.outflow.u128(), 3000);
+
+        // half of the previous window flows still count in the middle of the next window
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(5400);
+        let err =
+            transfer_native(deps.as_mut(), send_channel, denom, env.clone(), 1501).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OutflowQuotaExceeded {
+                channel: send_channel.to_string(),
+                denom: denom.to_string(),
+            }
+        );
+        transfer_native(deps.as_mut(), send_channel, denom, env.clone(), 1500).unwrap();
+        let rate_limit = query_rate_limit(deps.as_ref(), send_channel, denom);
+        assert_eq!(
+            rate_limit.window_start,
+            mock_env().block.time.plus_seconds(3600)
+        );
+        assert_eq!(rate_limit.prev_inflow.u128(), 1000);
+        assert_eq!(rate_limit.prev_outflow.u128(), 3000);
+        assert_eq!(rate_limit.inflow.u128(), 0);
+        assert_eq!(rate_limit.outflow.u128(), 1500);
+
+        // flows are cleared once the rolling window no longer covers them
+        env.block.time = env.block.time.plus_seconds(7200);
+        transfer_native(deps.as_mut(), send_channel, denom, env.clone(), 3000).unwrap();
+        let rate_limit = query_rate_limit(deps.as_ref(), send_channel, denom);
+        assert_eq!(rate_limit.window_start, env.block.time);
+        assert_eq!(rate_limit.prev_outflow.u128(), 0);
+        assert_eq!(rate_limit.outflow.u128(), 3000);
+
+        // or when the admin resets them
+        let msg = ExecuteMsg::ResetRateLimit {
+            channel: send_channel.to_string(),
+            denom: denom.to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("foobar", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+        execute(deps.as_mut(), env.clone(), mock_info("gov", &[]), msg).unwrap();
+        transfer_native(deps.as_mut(), send_channel, denom, env, 3000).unwrap();
+
+        // other denoms on the channel are not limited
+        transfer_native(deps.as_mut(), send_channel, "uosmo", mock_env(), 100_000).unwrap();
+    }
+
+    fn transfer_native(
+        deps: DepsMut,
+        channel: &str,
+        denom: &str,
+        env: Env,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer(TransferMsg {
+            channel: channel.to_string(),
+            remote_address: "my-remote-address".to_string(),
+            timeout: None,
+            memo: None,
+        });
+        let info = mock_info("local-sender", &coins(amount, denom));
+        execute(deps, env, info, msg)
+    }
+
+    fn query_rate_limit(deps: Deps, channel: &str, denom: &str) -> RateLimit {
+        let res = crate::contract::query(
+            deps,
+            mock_env(),
+            QueryMsg::RateLimit {
+                channel: channel.to_string(),
+                denom: denom.to_string(),
+            },
+        )
+        .unwrap();
+        from_binary::<Option<RateLimit>>(&res).unwrap().unwrap()
+    }
+
     #[test]
     fn check_gas_limit_handles_all_cases() {
         let send_channel = "channel-9";
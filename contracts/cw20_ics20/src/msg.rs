@@ -1,10 +1,10 @@
 use astroport::cw20_ics20::TransferMsg;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::amount::Amount;
-use crate::state::ChannelInfo;
+use crate::state::{ChannelInfo, RateLimit};
 
 #[cw_serde]
 pub struct InitMsg {
@@ -27,6 +27,19 @@ pub struct AllowMsg {
     pub gas_limit: Option<u64>,
 }
 
+#[cw_serde]
+pub struct RateLimitMsg {
+    pub channel: String,
+    /// Denom as stored in the channel state, i.e. `cw20:<contract>` for cw20 tokens
+    pub denom: String,
+    /// Maximum amount that can be received during one window (unlimited if not set)
+    pub max_inflow: Option<Uint128>,
+    /// Maximum amount that can be sent during one window (unlimited if not set)
+    pub max_outflow: Option<Uint128>,
+    /// Length of a window in seconds
+    pub period: u64,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {
     pub default_gas_limit: Option<u64>,
@@ -45,6 +58,11 @@ pub enum ExecuteMsg {
     UpdateAdmin { admin: String },
     /// Update hook contract address (must be called by admin)
     UpdateHookAddress { new_address: String },
+    /// Set the inflow and outflow quotas of a (channel, denom) pair (must be called by admin).
+    /// The flows of the current window are kept. Unset both quotas to remove the rate limit
+    SetRateLimit(RateLimitMsg),
+    /// Clear the flows of a (channel, denom) pair and start a new window (must be called by admin)
+    ResetRateLimit { channel: String, denom: String },
 }
 
 #[cw_serde]
@@ -73,6 +91,9 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Show the rate limit of a (channel, denom) pair, if any.
+    #[returns(Option<RateLimit>)]
+    RateLimit { channel: String, denom: String },
 }
 
 #[cw_serde]
@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, IbcEndpoint, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, IbcEndpoint, StdResult, Storage, Timestamp, Uint128};
 use cw_controllers::Admin;
 use cw_storage_plus::{Item, Map};
 
@@ -26,6 +26,70 @@ pub const ALLOW_LIST: Map<&Addr, AllowInfo> = Map::new("allow_list");
 pub struct ChannelState {
     pub outstanding: Uint128,
     pub total_sent: Uint128,
+    /// Inflow and outflow quotas of this (channel, denom) pair (optional)
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Quotas are enforced over a rolling window approximated with two fixed windows:
+/// the flows of the previous window are weighted by the share of it still covered by the rolling window.
+#[cw_serde]
+pub struct RateLimit {
+    /// Maximum amount that can be received over the channel during one window (unlimited if not set)
+    pub max_inflow: Option<Uint128>,
+    /// Maximum amount that can be sent over the channel during one window (unlimited if not set)
+    pub max_outflow: Option<Uint128>,
+    /// Length of a window in seconds
+    pub period: u64,
+    /// Time at which the current window started
+    pub window_start: Timestamp,
+    /// Amount received during the current window
+    pub inflow: Uint128,
+    /// Amount sent during the current window
+    pub outflow: Uint128,
+    /// Amount received during the previous window
+    #[serde(default)]
+    pub prev_inflow: Uint128,
+    /// Amount sent during the previous window
+    #[serde(default)]
+    pub prev_outflow: Uint128,
+}
+
+impl RateLimit {
+    /// Moves to the window containing the given time.
+    /// The previous flows are kept only if the current window directly precedes the new one
+    fn refresh(&mut self, now: Timestamp) {
+        let elapsed = now.seconds().saturating_sub(self.window_start.seconds());
+        match elapsed / self.period {
+            0 => {}
+            1 => {
+                self.prev_inflow = self.inflow;
+                self.prev_outflow = self.outflow;
+                self.inflow = Uint128::zero();
+                self.outflow = Uint128::zero();
+                self.window_start = self.window_start.plus_seconds(self.period);
+            }
+            _ => self.reset(now),
+        }
+    }
+
+    /// Returns the flow over the rolling window ending at the given time
+    fn rolling_flow(&self, current: Uint128, prev: Uint128, now: Timestamp) -> Uint128 {
+        let elapsed = now
+            .seconds()
+            .saturating_sub(self.window_start.seconds())
+            .min(self.period);
+        current + prev.multiply_ratio(self.period - elapsed, self.period)
+    }
+
+    /// Clears the flows and starts a new window at the given time
+    pub fn reset(&mut self, now: Timestamp) {
+        self.window_start = now;
+        self.inflow = Uint128::zero();
+        self.outflow = Uint128::zero();
+        self.prev_inflow = Uint128::zero();
+        self.prev_outflow = Uint128::zero();
+    }
 }
 
 #[cw_serde]
@@ -110,3 +174,100 @@ pub fn undo_reduce_channel_balance(
     })?;
     Ok(())
 }
+
+/// Adds the amount to the outflow of the current window,
+/// failing if the rolling outflow exceeds the outflow quota of the (channel, denom) pair
+pub fn record_outflow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &str,
+    amount: Uint128,
+    now: Timestamp,
+) -> Result<(), ContractError> {
+    update_rate_limit(storage, channel, denom, |rate_limit| {
+        rate_limit.refresh(now);
+        rate_limit.outflow += amount;
+        match rate_limit.max_outflow {
+            Some(max_outflow)
+                if rate_limit.rolling_flow(rate_limit.outflow, rate_limit.prev_outflow, now)
+                    > max_outflow =>
+            {
+                Err(ContractError::OutflowQuotaExceeded {
+                    channel: channel.to_string(),
+                    denom: denom.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+/// Adds the amount to the inflow of the current window,
+/// failing if the rolling inflow exceeds the inflow quota of the (channel, denom) pair
+pub fn record_inflow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &str,
+    amount: Uint128,
+    now: Timestamp,
+) -> Result<(), ContractError> {
+    update_rate_limit(storage, channel, denom, |rate_limit| {
+        rate_limit.refresh(now);
+        rate_limit.inflow += amount;
+        match rate_limit.max_inflow {
+            Some(max_inflow)
+                if rate_limit.rolling_flow(rate_limit.inflow, rate_limit.prev_inflow, now)
+                    > max_inflow =>
+            {
+                Err(ContractError::InflowQuotaExceeded {
+                    channel: channel.to_string(),
+                    denom: denom.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+// releases the outflow of a transfer that failed, so it doesn't count against the quota.
+// If a new window started in the meantime, this only frees up the current window.
+pub fn undo_outflow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    update_rate_limit(storage, channel, denom, |rate_limit| {
+        rate_limit.outflow = rate_limit.outflow.saturating_sub(amount);
+        Ok(())
+    })
+}
+
+// releases the inflow of a received packet whose tokens were not delivered
+pub fn undo_inflow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    update_rate_limit(storage, channel, denom, |rate_limit| {
+        rate_limit.inflow = rate_limit.inflow.saturating_sub(amount);
+        Ok(())
+    })
+}
+
+// applies the action to the rate limit of the (channel, denom) pair, if one is set
+fn update_rate_limit(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &str,
+    action: impl FnOnce(&mut RateLimit) -> Result<(), ContractError>,
+) -> Result<(), ContractError> {
+    if let Some(mut state) = CHANNEL_STATE.may_load(storage, (channel, denom))? {
+        if let Some(rate_limit) = state.rate_limit.as_mut() {
+            action(rate_limit)?;
+            CHANNEL_STATE.save(storage, (channel, denom), &state)?;
+        }
+    }
+    Ok(())
+}
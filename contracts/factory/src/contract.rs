@@ -3,17 +3,19 @@ use std::collections::HashSet;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply,
-    ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+    attr, from_binary, to_binary, wasm_execute, Addr, Attribute, Binary, CosmosMsg, Deps, DepsMut,
+    Env, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse,
+    SubMsgResult, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
-use cw_utils::parse_instantiate_response_data;
+use cw20::Cw20ExecuteMsg;
+use cw_utils::{nonpayable, parse_instantiate_response_data};
 
-use astroport::asset::{addr_opt_validate, AssetInfo, PairInfo};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::factory::{
-    Config, ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, MigrateMsg, PairConfig,
-    PairType, PairsResponse, QueryMsg,
+    Config, ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, ListingPolicy,
+    ListingPolicyQueryMsg, MigrateMsg, PairConfig, PairType, PairsResponse, QueryMsg,
 };
 use astroport::generator::ExecuteMsg::DeactivatePool;
 use astroport::pair::InstantiateMsg as PairInstantiateMsg;
@@ -21,10 +23,11 @@ use itertools::Itertools;
 
 use crate::error::ContractError;
 use crate::migration;
-use crate::migration::{migrate_configs, migrate_pair_configs};
+use crate::migration::{migrate_configs, migrate_pair_configs, migrate_pair_indexes};
 use crate::querier::query_pair_info;
 use crate::state::{
-    check_asset_infos, pair_key, read_pairs, TmpPairInfo, CONFIG, OWNERSHIP_PROPOSAL, PAIRS,
+    check_asset_infos, index_pair, pair_key, read_pairs, read_pairs_by_asset, read_pairs_by_type,
+    remove_pair_index, IndexedPair, TmpPairInfo, CONFIG, MANAGER_POOLS, OWNERSHIP_PROPOSAL, PAIRS,
     PAIR_CONFIGS, TMP_PAIR_INFO,
 };
 
@@ -54,6 +57,9 @@ pub fn instantiate(
         generator_address: None,
         whitelist_code_id: msg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(&msg.coin_registry_address)?,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     config.generator_address = addr_opt_validate(deps.api, &msg.generator_address)?;
@@ -75,6 +81,7 @@ pub fn instantiate(
         if !pc.valid_fee_bps() {
             return Err(ContractError::PairConfigInvalidFeeBps {});
         }
+        validate_listing_params(deps.as_ref(), pc)?;
         PAIR_CONFIGS.save(deps.storage, pc.pair_type.to_string(), pc)?;
     }
     CONFIG.save(deps.storage, &config)?;
@@ -93,6 +100,12 @@ pub struct UpdateConfig {
     /// CW1 whitelist contract code id used to store 3rd party staking rewards
     whitelist_code_id: Option<u64>,
     coin_registry_address: Option<String>,
+    /// Pool manager contract allowed to register the pools it hosts
+    pool_manager: Option<String>,
+    /// Fee charged for creating a pair
+    pool_creation_fee: Option<Asset>,
+    /// Listing policy queried before a pair is created. [`ListingPolicy::Disabled`] removes it
+    listing_policy: Option<ListingPolicy>,
 }
 
 /// Exposes all the execute functions available in the contract.
@@ -114,6 +127,22 @@ pub struct UpdateConfig {
 ///             init_params,
 ///         }** Creates a new pair with the specified input parameters.
 ///
+/// * **ExecuteMsg::RegisterManagerPool {
+///             asset_infos,
+///             liquidity_token,
+///             pair_type,
+///             pool_key,
+///         }** Registers a pool hosted by the pool manager.
+///
+/// * **ExecuteMsg::ApproveManagerPool {
+///             asset_infos,
+///             pair_type,
+///             payer,
+///         }** Checks the listing policy and charges the pool creation fee for a new pool manager pool.
+///
+/// * **ExecuteMsg::IndexPairs { start_after, limit }** Adds a page of the pairs created before
+/// the pair indexes were introduced to these indexes.
+///
 /// * **ExecuteMsg::Deregister { asset_infos }** Removes an existing pair from the factory.
 /// * The asset information is for the assets that are traded in the pair.
 ///
@@ -136,6 +165,9 @@ pub fn execute(
             generator_address,
             whitelist_code_id,
             coin_registry_address,
+            pool_manager,
+            pool_creation_fee,
+            listing_policy,
         } => execute_update_config(
             deps,
             info,
@@ -145,6 +177,9 @@ pub fn execute(
                 generator_address,
                 whitelist_code_id,
                 coin_registry_address,
+                pool_manager,
+                pool_creation_fee,
+                listing_policy,
             },
         ),
         ExecuteMsg::UpdatePairConfig { config } => execute_update_pair_config(deps, info, config),
@@ -152,7 +187,28 @@ pub fn execute(
             pair_type,
             asset_infos,
             init_params,
-        } => execute_create_pair(deps, env, pair_type, asset_infos, init_params),
+        } => execute_create_pair(deps, env, info, pair_type, asset_infos, init_params),
+        ExecuteMsg::RegisterManagerPool {
+            asset_infos,
+            liquidity_token,
+            pair_type,
+            pool_key,
+        } => execute_register_manager_pool(
+            deps,
+            info,
+            asset_infos,
+            liquidity_token,
+            pair_type,
+            pool_key,
+        ),
+        ExecuteMsg::ApproveManagerPool {
+            asset_infos,
+            pair_type,
+            payer,
+        } => execute_approve_manager_pool(deps, info, asset_infos, pair_type, payer),
+        ExecuteMsg::IndexPairs { start_after, limit } => {
+            execute_index_pairs(deps, info, start_after, limit)
+        }
         ExecuteMsg::Deregister { asset_infos } => deregister(deps, info, asset_infos),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
@@ -228,6 +284,23 @@ pub fn execute_update_config(
         config.coin_registry_address = deps.api.addr_validate(&coin_registry_address)?;
     }
 
+    if let Some(pool_manager) = param.pool_manager {
+        config.pool_manager = Some(deps.api.addr_validate(&pool_manager)?);
+    }
+
+    if let Some(pool_creation_fee) = param.pool_creation_fee {
+        pool_creation_fee.info.check(deps.api)?;
+        config.pool_creation_fee = Some(pool_creation_fee);
+    }
+
+    match param.listing_policy {
+        Some(ListingPolicy::Contract { address }) => {
+            config.listing_policy = Some(deps.api.addr_validate(address.as_str())?);
+        }
+        Some(ListingPolicy::Disabled {}) => config.listing_policy = None,
+        None => {}
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
@@ -256,6 +329,8 @@ pub fn execute_update_pair_config(
         return Err(ContractError::PairConfigInvalidFeeBps {});
     }
 
+    validate_listing_params(deps.as_ref(), &pair_config)?;
+
     PAIR_CONFIGS.save(
         deps.storage,
         pair_config.pair_type.to_string(),
@@ -265,7 +340,22 @@ pub fn execute_update_pair_config(
     Ok(Response::new().add_attribute("action", "update_pair_config"))
 }
 
+/// Validates the pool creation fee and listing policy overrides of a pair type.
+fn validate_listing_params(deps: Deps, pair_config: &PairConfig) -> Result<(), ContractError> {
+    if let Some(pool_creation_fee) = &pair_config.pool_creation_fee {
+        pool_creation_fee.info.check(deps.api)?;
+    }
+
+    if let Some(ListingPolicy::Contract { address }) = &pair_config.listing_policy {
+        deps.api.addr_validate(address.as_str())?;
+    }
+
+    Ok(())
+}
+
 /// Creates a new pair of `pair_type` with the assets specified in `asset_infos`.
+/// If a listing policy is set, it must allow the listing. If a pool creation fee is set,
+/// it is charged from the sender and sent to the fee address. Native fees must be sent exactly.
 ///
 /// * **pair_type** is the pair type of the newly created pair.
 ///
@@ -275,6 +365,7 @@ pub fn execute_update_pair_config(
 pub fn execute_create_pair(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     pair_type: PairType,
     asset_infos: Vec<AssetInfo>,
     init_params: Option<Binary>,
@@ -297,8 +388,25 @@ pub fn execute_create_pair(
         return Err(ContractError::PairConfigDisabled {});
     }
 
+    check_listing_policy(
+        deps.as_ref(),
+        &config,
+        Some(&pair_config),
+        &asset_infos,
+        &pair_type,
+    )?;
+    let (messages, attributes) =
+        charge_pool_creation_fee(&config, Some(&pair_config), &info, &info.sender)?;
+
     let pair_key = pair_key(&asset_infos);
-    TMP_PAIR_INFO.save(deps.storage, &TmpPairInfo { pair_key })?;
+    TMP_PAIR_INFO.save(
+        deps.storage,
+        &TmpPairInfo {
+            pair_key,
+            asset_infos: asset_infos.clone(),
+            pair_type,
+        },
+    )?;
 
     let sub_msg: Vec<SubMsg> = vec![SubMsg {
         id: INSTANTIATE_PAIR_REPLY_ID,
@@ -320,11 +428,13 @@ pub fn execute_create_pair(
     }];
 
     Ok(Response::new()
+        .add_messages(messages)
         .add_submessages(sub_msg)
         .add_attributes(vec![
             attr("action", "create_pair"),
             attr("pair", asset_infos.iter().join("-")),
-        ]))
+        ])
+        .add_attributes(attributes))
 }
 
 /// The entry point to the contract for processing replies from submessages.
@@ -349,6 +459,15 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             let pair_contract = deps.api.addr_validate(&init_response.contract_address)?;
 
             PAIRS.save(deps.storage, &tmp.pair_key, &pair_contract)?;
+            index_pair(
+                deps.storage,
+                &tmp.pair_key,
+                &tmp.asset_infos,
+                &IndexedPair {
+                    contract_addr: pair_contract.clone(),
+                    pair_type: tmp.pair_type,
+                },
+            )?;
 
             Ok(Response::new().add_attributes(vec![
                 attr("action", "register"),
@@ -359,6 +478,237 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
     }
 }
 
+/// Fails if the listing policy of the pair type, or the factory one if the pair type doesn't override it,
+/// doesn't allow creating a pair with the given assets.
+fn check_listing_policy(
+    deps: Deps,
+    config: &Config,
+    pair_config: Option<&PairConfig>,
+    asset_infos: &[AssetInfo],
+    pair_type: &PairType,
+) -> Result<(), ContractError> {
+    let listing_policy =
+        match pair_config.and_then(|pair_config| pair_config.listing_policy.clone()) {
+            Some(ListingPolicy::Contract { address }) => Some(address),
+            Some(ListingPolicy::Disabled {}) => None,
+            None => config.listing_policy.clone(),
+        };
+
+    if let Some(listing_policy) = listing_policy {
+        let can_list: bool = deps.querier.query_wasm_smart(
+            listing_policy,
+            &ListingPolicyQueryMsg::CanList {
+                asset_infos: asset_infos.to_vec(),
+                pair_type: pair_type.clone(),
+            },
+        )?;
+        if !can_list {
+            return Err(ContractError::ListingNotAllowed {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the messages sending the pool creation fee of the pair type, or the factory one
+/// if the pair type doesn't override it, from the payer to the fee address.
+/// Native fees must be attached exactly, any other funds are rejected.
+fn charge_pool_creation_fee(
+    config: &Config,
+    pair_config: Option<&PairConfig>,
+    info: &MessageInfo,
+    payer: &Addr,
+) -> Result<(Vec<CosmosMsg>, Vec<Attribute>), ContractError> {
+    let pool_creation_fee = pair_config
+        .and_then(|pair_config| pair_config.pool_creation_fee.clone())
+        .or_else(|| config.pool_creation_fee.clone())
+        .filter(|fee| !fee.amount.is_zero());
+
+    let pool_creation_fee = match pool_creation_fee {
+        Some(pool_creation_fee) => pool_creation_fee,
+        None => {
+            nonpayable(info)?;
+            return Ok((vec![], vec![]));
+        }
+    };
+
+    let fee_address = config
+        .fee_address
+        .as_ref()
+        .ok_or(ContractError::FeeAddressNotSet {})?;
+
+    let message = match &pool_creation_fee.info {
+        AssetInfo::NativeToken { .. } => {
+            pool_creation_fee.assert_sent_native_token_balance(info)?;
+            pool_creation_fee.clone().into_msg(fee_address)?
+        }
+        AssetInfo::Token { contract_addr } => {
+            nonpayable(info)?;
+            wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::TransferFrom {
+                    owner: payer.to_string(),
+                    recipient: fee_address.to_string(),
+                    amount: pool_creation_fee.amount,
+                },
+                vec![],
+            )?
+            .into()
+        }
+    };
+
+    Ok((
+        vec![message],
+        vec![attr("pool_creation_fee", pool_creation_fee.to_string())],
+    ))
+}
+
+/// Checks the listing policy and charges the pool creation fee for a pool the pool manager is about to create.
+/// Pair types without a config in the factory are subject to the factory listing policy and fee.
+///
+/// * **asset_infos** is a vector with the assets traded in the pool.
+///
+/// * **pair_type** is the type of the pool.
+///
+/// * **payer** is the account creating the pool. Cw20 fees are transferred from it.
+///
+/// ## Executor
+/// Only the pool manager set in the config can execute this.
+pub fn execute_approve_manager_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    pair_type: PairType,
+    payer: String,
+) -> Result<Response, ContractError> {
+    check_asset_infos(deps.api, &asset_infos)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if config.pool_manager.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_config = PAIR_CONFIGS.may_load(deps.storage, pair_type.to_string())?;
+    if matches!(&pair_config, Some(pair_config) if pair_config.is_disabled) {
+        return Err(ContractError::PairConfigDisabled {});
+    }
+
+    check_listing_policy(
+        deps.as_ref(),
+        &config,
+        pair_config.as_ref(),
+        &asset_infos,
+        &pair_type,
+    )?;
+    let payer = deps.api.addr_validate(&payer)?;
+    let (messages, attributes) =
+        charge_pool_creation_fee(&config, pair_config.as_ref(), &info, &payer)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "approve_manager_pool"),
+            attr("pair", asset_infos.iter().join("-")),
+            attr("payer", payer),
+        ])
+        .add_attributes(attributes))
+}
+
+/// Registers a pool hosted by the pool manager. The pool is stored in [`PAIRS`] with the pool manager
+/// address and its key in the pool manager so that it is discoverable through the factory like any other pair.
+///
+/// * **asset_infos** is a vector with the assets traded in the pool.
+///
+/// * **liquidity_token** is the LP token of the pool.
+///
+/// * **pair_type** is the type of the pool.
+///
+/// * **pool_key** is the key of the pool in the pool manager.
+///
+/// ## Executor
+/// Only the pool manager set in the config can execute this.
+pub fn execute_register_manager_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    liquidity_token: String,
+    pair_type: PairType,
+    pool_key: String,
+) -> Result<Response, ContractError> {
+    check_asset_infos(deps.api, &asset_infos)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if config.pool_manager.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = pair_key(&asset_infos);
+    if PAIRS.has(deps.storage, &pair_key) {
+        return Err(ContractError::PairWasRegistered {});
+    }
+
+    // Every pool hosted by the pool manager shares its address, so consumers route on the pool key
+    let pair_info = PairInfo {
+        asset_infos,
+        contract_addr: info.sender,
+        liquidity_token: deps.api.addr_validate(&liquidity_token)?,
+        pair_type,
+        pool_key: Some(pool_key),
+    };
+
+    PAIRS.save(deps.storage, &pair_key, &pair_info.contract_addr)?;
+    MANAGER_POOLS.save(deps.storage, &pair_key, &pair_info)?;
+    index_pair(
+        deps.storage,
+        &pair_key,
+        &pair_info.asset_infos,
+        &IndexedPair {
+            contract_addr: pair_info.contract_addr.clone(),
+            pair_type: pair_info.pair_type.clone(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_manager_pool"),
+        attr("pair", pair_info.asset_infos.iter().join("-")),
+        attr("liquidity_token", pair_info.liquidity_token),
+        attr("pool_key", pair_info.pool_key.unwrap_or_default()),
+    ]))
+}
+
+/// Adds a page of the pairs created before the pairs by asset and by pair type indexes were introduced
+/// to these indexes. Pairs which are already indexed are indexed again.
+///
+/// * **start_after** is the pair after which pairs are indexed.
+///
+/// * **limit** is the number of pairs to index.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn execute_index_pairs(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let indexed = migrate_pair_indexes(&mut deps, start_after, limit)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "index_pairs"),
+        attr("indexed_pairs", indexed.to_string()),
+    ]))
+}
+
 /// Removes an existing pair from the factory.
 ///
 /// * **asset_infos** is a vector with assets for which we deregister the pair.
@@ -378,12 +728,12 @@ pub fn deregister(
         return Err(ContractError::Unauthorized {});
     }
 
-    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    PAIRS.remove(deps.storage, &pair_key(&asset_infos));
+    let pair_key = pair_key(&asset_infos);
+    let pair_addr = PAIRS.load(deps.storage, &pair_key)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(generator) = config.generator_address {
-        let pair_info = query_pair_info(&deps.querier, &pair_addr)?;
+        let pair_info = load_pair_info(deps.as_ref(), &pair_key, &pair_addr)?;
 
         // sets the allocation point to zero for the lp_token
         messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -395,6 +745,10 @@ pub fn deregister(
         }));
     }
 
+    PAIRS.remove(deps.storage, &pair_key);
+    MANAGER_POOLS.remove(deps.storage, &pair_key);
+    remove_pair_index(deps.storage, &pair_key, &asset_infos)?;
+
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "deregister"),
         attr("pair_contract_addr", pair_addr),
@@ -411,6 +765,20 @@ pub fn deregister(
 /// * **QueryMsg::Pairs { start_after, limit }** Returns an array that contains items of type [`PairInfo`].
 /// This returns information about multiple Astroport pairs
 ///
+/// * **QueryMsg::PairsByAsset {
+///             asset_info,
+///             start_after,
+///             limit,
+///             exclude_disabled,
+///         }** Returns an array with the pairs containing `asset_info`.
+///
+/// * **QueryMsg::PairsByType {
+///             pair_type,
+///             start_after,
+///             limit,
+///             exclude_disabled,
+///         }** Returns an array with the pairs of `pair_type`.
+///
 /// * **QueryMsg::FeeInfo { pair_type }** Returns the fee structure (total and maker fees) for a specific pair type.
 ///
 /// * **QueryMsg::BlacklistedPairTypes {}** Returns a vector that contains blacklisted pair types (pair types that cannot get ASTRO emissions).
@@ -422,6 +790,30 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Pairs { start_after, limit } => {
             to_binary(&query_pairs(deps, start_after, limit)?)
         }
+        QueryMsg::PairsByAsset {
+            asset_info,
+            start_after,
+            limit,
+            exclude_disabled,
+        } => to_binary(&query_pairs_by_asset(
+            deps,
+            asset_info,
+            start_after,
+            limit,
+            exclude_disabled.unwrap_or_default(),
+        )?),
+        QueryMsg::PairsByType {
+            pair_type,
+            start_after,
+            limit,
+            exclude_disabled,
+        } => to_binary(&query_pairs_by_type(
+            deps,
+            pair_type,
+            start_after,
+            limit,
+            exclude_disabled.unwrap_or_default(),
+        )?),
         QueryMsg::FeeInfo { pair_type } => to_binary(&query_fee_info(deps, pair_type)?),
         QueryMsg::BlacklistedPairTypes {} => to_binary(&query_blacklisted_pair_types(deps)?),
     }
@@ -458,6 +850,9 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         generator_address: config.generator_address,
         whitelist_code_id: config.whitelist_code_id,
         coin_registry_address: config.coin_registry_address,
+        pool_manager: config.pool_manager,
+        pool_creation_fee: config.pool_creation_fee,
+        listing_policy: config.listing_policy,
     };
 
     Ok(resp)
@@ -466,8 +861,18 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 /// Returns a pair's data using the assets in `asset_infos` as input (those being the assets that are traded in the pair).
 /// * **asset_infos** is a vector with assets traded in the pair.
 pub fn query_pair(deps: Deps, asset_infos: Vec<AssetInfo>) -> StdResult<PairInfo> {
-    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    query_pair_info(&deps.querier, pair_addr)
+    let pair_key = pair_key(&asset_infos);
+    let pair_addr = PAIRS.load(deps.storage, &pair_key)?;
+    load_pair_info(deps, &pair_key, &pair_addr)
+}
+
+/// Returns the info of a registered pair. Pools registered by the pool manager are read from storage
+/// while standalone pairs are queried.
+pub(crate) fn load_pair_info(deps: Deps, pair_key: &[u8], pair_addr: &Addr) -> StdResult<PairInfo> {
+    match MANAGER_POOLS.may_load(deps.storage, pair_key)? {
+        Some(pair_info) => Ok(pair_info),
+        None => query_pair_info(&deps.querier, pair_addr),
+    }
 }
 
 /// Returns a vector with pair data that contains items of type [`PairInfo`]. Querying starts at `start_after` and returns `limit` pairs.
@@ -482,7 +887,55 @@ pub fn query_pairs(
 ) -> StdResult<PairsResponse> {
     let pairs = read_pairs(deps, start_after, limit)?
         .iter()
-        .map(|pair_addr| query_pair_info(&deps.querier, pair_addr))
+        .map(|(pair_key, pair_addr)| load_pair_info(deps, pair_key, pair_addr))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+/// Returns the pairs containing `asset_info` using a [`PairsResponse`] object.
+///
+/// * **asset_info** is the asset the pairs contain.
+///
+/// * **start_after** is a vector of asset infos used to start reading pair data.
+///
+/// * **limit** is the number of pairs to return.
+///
+/// * **exclude_disabled** skips the pairs whose pair type is disabled.
+pub fn query_pairs_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+    exclude_disabled: bool,
+) -> StdResult<PairsResponse> {
+    let pairs = read_pairs_by_asset(deps, &asset_info, start_after, limit, exclude_disabled)?
+        .iter()
+        .map(|(pair_key, pair_addr)| load_pair_info(deps, pair_key, pair_addr))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+/// Returns the pairs of `pair_type` using a [`PairsResponse`] object.
+///
+/// * **pair_type** is the type of the pairs.
+///
+/// * **start_after** is a vector of asset infos used to start reading pair data.
+///
+/// * **limit** is the number of pairs to return.
+///
+/// * **exclude_disabled** returns no pairs if the pair type is disabled.
+pub fn query_pairs_by_type(
+    deps: Deps,
+    pair_type: PairType,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+    exclude_disabled: bool,
+) -> StdResult<PairsResponse> {
+    let pairs = read_pairs_by_type(deps, &pair_type, start_after, limit, exclude_disabled)?
+        .iter()
+        .map(|(pair_key, pair_addr)| load_pair_info(deps, pair_key, pair_addr))
         .collect::<StdResult<Vec<_>>>()?;
 
     Ok(PairsResponse { pairs })
@@ -512,7 +965,7 @@ pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response
                 let msg: migration::MigrationMsg = from_binary(&msg.params)?;
                 migrate_configs(&mut deps, &msg)?;
             }
-            "1.3.0" | "1.5.1" => {}
+            "1.3.0" | "1.5.1" | "1.6.0" => {}
             "1.3.1" | "1.5.0" => {
                 migrate_pair_configs(deps.storage)?;
             }
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 /// This enum describes factory contract errors
@@ -7,6 +8,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -34,6 +38,12 @@ pub enum ContractError {
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
+    #[error("Listing is not allowed by the listing policy")]
+    ListingNotAllowed {},
+
+    #[error("Fee address must be set to charge the pool creation fee")]
+    FeeAddressNotSet {},
+
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
 }
@@ -1,4 +1,6 @@
-use crate::state::{CONFIG, PAIR_CONFIGS};
+use crate::contract::load_pair_info;
+use crate::state::{index_pair, read_pairs, IndexedPair, CONFIG, PAIR_CONFIGS};
+use astroport::asset::AssetInfo;
 use astroport::factory::{Config, PairConfig, PairType};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, DepsMut, StdError, StdResult, Storage};
@@ -41,6 +43,9 @@ pub fn migrate_configs(deps: &mut DepsMut, msg: &MigrationMsg) -> StdResult<()>
         fee_address: old_cfg.fee_address,
         whitelist_code_id: old_cfg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(msg.coin_registry_address.as_str())?,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     CONFIG.save(deps.storage, &new_config)
@@ -92,6 +97,8 @@ pub fn migrate_pair_configs(storage: &mut dyn Storage) -> Result<(), StdError> {
             maker_fee_bps: old_pair_configs.maker_fee_bps,
             is_disabled: old_pair_configs.is_disabled,
             is_generator_disabled: old_pair_configs.is_generator_disabled,
+            pool_creation_fee: None,
+            listing_policy: None,
         };
 
         if key != pair_type.to_string() {
@@ -104,3 +111,29 @@ pub fn migrate_pair_configs(storage: &mut dyn Storage) -> Result<(), StdError> {
 
     Ok(())
 }
+
+/// Backfills the pairs by asset and by pair type indexes for a page of the pairs created before they were introduced.
+/// Returns the number of indexed pairs.
+pub fn migrate_pair_indexes(
+    deps: &mut DepsMut,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> StdResult<usize> {
+    let pairs = read_pairs(deps.as_ref(), start_after, limit)?;
+    let indexed = pairs.len();
+
+    for (pair_key, pair_addr) in pairs {
+        let pair_info = load_pair_info(deps.as_ref(), &pair_key, &pair_addr)?;
+        index_pair(
+            deps.storage,
+            &pair_key,
+            &pair_info.asset_infos,
+            &IndexedPair {
+                contract_addr: pair_info.contract_addr,
+                pair_type: pair_info.pair_type,
+            },
+        )?;
+    }
+
+    Ok(indexed)
+}
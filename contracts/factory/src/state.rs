@@ -1,16 +1,18 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Deps, Order, StdResult};
+use cosmwasm_std::{Addr, Api, Deps, Order, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 use itertools::Itertools;
 
 use crate::error::ContractError;
-use astroport::asset::AssetInfo;
+use astroport::asset::{AssetInfo, PairInfo};
 use astroport::common::OwnershipProposal;
-use astroport::factory::{Config, PairConfig};
+use astroport::factory::{Config, PairConfig, PairType};
 /// This is an intermediate structure for storing a pair's key. It is used in a submessage response.
 #[cw_serde]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
+    pub asset_infos: Vec<AssetInfo>,
+    pub pair_type: PairType,
 }
 
 /// Saves a pair's key
@@ -22,6 +24,61 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Saves created pairs (from olders to latest)
 pub const PAIRS: Map<&[u8], Addr> = Map::new("pair_info");
 
+/// Saves the pools registered by the pool manager. They are also stored in [`PAIRS`] with the pool manager
+/// address, but as they have no contract of their own their info is stored here instead of being queried
+pub const MANAGER_POOLS: Map<&[u8], PairInfo> = Map::new("manager_pools");
+
+/// This structure describes a pair entry in the [`PAIRS_BY_ASSET`] index.
+#[cw_serde]
+pub struct IndexedPair {
+    /// The pair contract address
+    pub contract_addr: Addr,
+    /// The pair type
+    pub pair_type: PairType,
+}
+
+/// Indexes registered pairs by each of their assets. The key is (asset bytes, pair key)
+pub const PAIRS_BY_ASSET: Map<(&[u8], &[u8]), IndexedPair> = Map::new("pairs_by_asset");
+
+/// Indexes registered pairs by their pair type. The key is (pair type, pair key)
+pub const PAIRS_BY_TYPE: Map<(String, &[u8]), Addr> = Map::new("pairs_by_type");
+
+/// Adds a pair to the [`PAIRS_BY_ASSET`] and [`PAIRS_BY_TYPE`] indexes.
+pub fn index_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    asset_infos: &[AssetInfo],
+    indexed_pair: &IndexedPair,
+) -> StdResult<()> {
+    for asset_info in asset_infos {
+        PAIRS_BY_ASSET.save(storage, (asset_info.as_bytes(), pair_key), indexed_pair)?;
+    }
+
+    PAIRS_BY_TYPE.save(
+        storage,
+        (indexed_pair.pair_type.to_string(), pair_key),
+        &indexed_pair.contract_addr,
+    )
+}
+
+/// Removes a pair from the [`PAIRS_BY_ASSET`] and [`PAIRS_BY_TYPE`] indexes.
+pub fn remove_pair_index(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    asset_infos: &[AssetInfo],
+) -> StdResult<()> {
+    for asset_info in asset_infos {
+        if let Some(indexed_pair) =
+            PAIRS_BY_ASSET.may_load(storage, (asset_info.as_bytes(), pair_key))?
+        {
+            PAIRS_BY_TYPE.remove(storage, (indexed_pair.pair_type.to_string(), pair_key));
+            PAIRS_BY_ASSET.remove(storage, (asset_info.as_bytes(), pair_key));
+        }
+    }
+
+    Ok(())
+}
+
 /// Calculates a pair key from the specified parameters in the `asset_infos` variable.
 ///
 /// `asset_infos` is an array with multiple items of type [`AssetInfo`].
@@ -43,9 +100,12 @@ pub const PAIR_CONFIGS: Map<String, PairConfig> = Map::new("pair_configs");
 const MAX_LIMIT: u32 = 30;
 /// The default limit for reading pairs from [`PAIRS`]
 const DEFAULT_LIMIT: u32 = 10;
+/// The maximum number of [`PAIRS_BY_ASSET`] entries scanned while skipping disabled pairs
+const MAX_SCAN_LIMIT: usize = 100;
 
 /// Reads pairs from the [`PAIRS`] vector according to the `start_after` and `limit` variables.
 /// Otherwise, it returns the default number of pairs, starting from the oldest one.
+/// Each pair is returned along with its key.
 ///
 /// `start_after` is the pair from which the function starts to fetch results.
 ///
@@ -54,7 +114,7 @@ pub fn read_pairs(
     deps: Deps,
     start_after: Option<Vec<AssetInfo>>,
     limit: Option<u32>,
-) -> StdResult<Vec<Addr>> {
+) -> StdResult<Vec<(Vec<u8>, Addr)>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
     if let Some(start) = calc_range_start(start_after) {
@@ -66,23 +126,102 @@ pub fn read_pairs(
                 Order::Ascending,
             )
             .take(limit)
-            .map(|item| {
-                let (_, pair_addr) = item?;
-                Ok(pair_addr)
-            })
             .collect()
     } else {
         PAIRS
             .range(deps.storage, None, None, Order::Ascending)
             .take(limit)
-            .map(|item| {
-                let (_, pair_addr) = item?;
-                Ok(pair_addr)
-            })
             .collect()
     }
 }
 
+/// Returns whether the pair type is disabled in its [`PairConfig`].
+/// Pair types without a config (e.g. pools registered by the pool manager) are enabled.
+fn is_pair_type_disabled(deps: Deps, pair_type: &PairType) -> StdResult<bool> {
+    Ok(PAIR_CONFIGS
+        .may_load(deps.storage, pair_type.to_string())?
+        .map(|pair_config| pair_config.is_disabled)
+        .unwrap_or_default())
+}
+
+/// Reads the pairs containing `asset_info` from the [`PAIRS_BY_ASSET`] index according to the
+/// `start_after` and `limit` variables. Each pair is returned along with its key.
+///
+/// `start_after` is the pair from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+///
+/// `exclude_disabled` skips the pairs whose pair type is disabled. At most [`MAX_SCAN_LIMIT`] entries are scanned,
+/// thus fewer than `limit` pairs may be returned.
+pub fn read_pairs_by_asset(
+    deps: Deps,
+    asset_info: &AssetInfo,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+    exclude_disabled: bool,
+) -> StdResult<Vec<(Vec<u8>, Addr)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    PAIRS_BY_ASSET
+        .prefix(asset_info.as_bytes())
+        .range(
+            deps.storage,
+            start.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(MAX_SCAN_LIMIT)
+        .filter_map(|item| match item {
+            Ok((pair_key, indexed_pair)) => {
+                match exclude_disabled
+                    .then(|| is_pair_type_disabled(deps, &indexed_pair.pair_type))
+                    .transpose()
+                {
+                    Ok(Some(true)) => None,
+                    Ok(_) => Some(Ok((pair_key, indexed_pair.contract_addr))),
+                    Err(err) => Some(Err(err)),
+                }
+            }
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Reads the pairs of `pair_type` from the [`PAIRS_BY_TYPE`] index according to the
+/// `start_after` and `limit` variables. Each pair is returned along with its key.
+/// No pairs are returned if `exclude_disabled` is set and the pair type is disabled.
+///
+/// `start_after` is the pair from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pairs_by_type(
+    deps: Deps,
+    pair_type: &PairType,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+    exclude_disabled: bool,
+) -> StdResult<Vec<(Vec<u8>, Addr)>> {
+    if exclude_disabled && is_pair_type_disabled(deps, pair_type)? {
+        return Ok(vec![]);
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    PAIRS_BY_TYPE
+        .prefix(pair_type.to_string())
+        .range(
+            deps.storage,
+            start.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
 /// Calculates the key of a pair from which to start reading data.
 ///
 /// `start_after` is an [`Option`] type that accepts [`AssetInfo`] elements.
@@ -50,6 +50,8 @@ fn proper_initialization() {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
             PairConfig {
                 code_id: 325u64,
@@ -58,6 +60,8 @@ fn proper_initialization() {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
         ],
         token_code_id: 123u64,
@@ -82,6 +86,8 @@ fn proper_initialization() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: 123u64,
         fee_address: None,
@@ -108,6 +114,8 @@ fn proper_initialization() {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
             PairConfig {
                 code_id: 123u64,
@@ -116,6 +124,8 @@ fn proper_initialization() {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
         ],
         token_code_id: 123u64,
@@ -150,6 +160,8 @@ fn update_config() {
         maker_fee_bps: 166,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     }];
 
     let msg = InstantiateMsg {
@@ -177,6 +189,9 @@ fn update_config() {
         generator_address: Some(String::from("new_generator_addr")),
         whitelist_code_id: None,
         coin_registry_address: None,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -205,6 +220,9 @@ fn update_config() {
         generator_address: None,
         whitelist_code_id: None,
         coin_registry_address: None,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
@@ -301,6 +319,8 @@ fn update_pair_config() {
         maker_fee_bps: 10,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     }];
 
     let msg = InstantiateMsg {
@@ -332,6 +352,8 @@ fn update_pair_config() {
         maker_fee_bps: 2,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     // Unauthorized err
@@ -355,6 +377,8 @@ fn update_pair_config() {
             maker_fee_bps: 10_001,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         },
     };
 
@@ -382,6 +406,8 @@ fn update_pair_config() {
         maker_fee_bps: 20,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     let info = mock_info(owner.clone(), &[]);
@@ -411,6 +437,8 @@ fn create_pair() {
         maker_fee_bps: 10,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     let msg = InstantiateMsg {
@@ -512,6 +540,8 @@ fn register() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: 123u64,
         fee_address: None,
@@ -550,6 +580,7 @@ fn register() {
         contract_addr: Addr::unchecked("pair0000"),
         liquidity_token: Addr::unchecked("liquidity0000"),
         pair_type: PairType::Xyk {},
+        pool_key: None,
     };
 
     let mut deployed_pairs = vec![(&pair0_addr, &pair0_info)];
@@ -594,6 +625,7 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         }
     );
 
@@ -627,6 +659,7 @@ fn register() {
         contract_addr: Addr::unchecked("pair0001"),
         liquidity_token: Addr::unchecked("liquidity0001"),
         pair_type: PairType::Xyk {},
+        pool_key: None,
     };
 
     deployed_pairs.push((&pair1_addr, &pair1_info));
@@ -669,12 +702,14 @@ fn register() {
                 contract_addr: Addr::unchecked("pair0000"),
                 asset_infos: asset_infos.clone(),
                 pair_type: PairType::Xyk {},
+                pool_key: None,
             },
             PairInfo {
                 liquidity_token: Addr::unchecked("liquidity0001"),
                 contract_addr: Addr::unchecked("pair0001"),
                 asset_infos: asset_infos_2.clone(),
                 pair_type: PairType::Xyk {},
+                pool_key: None,
             }
         ]
     );
@@ -693,6 +728,7 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         }]
     );
 
@@ -710,6 +746,7 @@ fn register() {
             contract_addr: Addr::unchecked("pair0001"),
             asset_infos: asset_infos_2.clone(),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         }]
     );
 
@@ -757,6 +794,7 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         },]
     );
 }
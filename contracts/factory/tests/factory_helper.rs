@@ -77,6 +77,8 @@ impl FactoryHelper {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             }],
             token_code_id: cw20_token_code_id,
             fee_address: None,
@@ -121,6 +123,9 @@ impl FactoryHelper {
             generator_address,
             whitelist_code_id,
             coin_registry_address,
+            pool_manager: None,
+            pool_creation_fee: None,
+            listing_policy: None,
         };
 
         router.execute_contract(sender.clone(), self.factory.clone(), &msg, &[])
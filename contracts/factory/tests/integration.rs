@@ -2,15 +2,19 @@
 
 mod factory_helper;
 
-use cosmwasm_std::{attr, Addr};
+use cosmwasm_std::{
+    attr, coin, to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult,
+};
 
-use astroport::asset::{AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::factory::{
-    ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, PairConfig, PairType, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, ListingPolicy,
+    ListingPolicyQueryMsg, PairConfig, PairType, PairsResponse, QueryMsg,
 };
 
 use crate::factory_helper::{instantiate_token, FactoryHelper};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
 fn mock_app() -> App {
     App::default()
@@ -29,6 +33,29 @@ fn store_factory_code(app: &mut App) -> u64 {
     app.store_code(factory_contract)
 }
 
+fn listing_policy_instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn listing_policy_query(_: Deps, _: Env, msg: ListingPolicyQueryMsg) -> StdResult<Binary> {
+    match msg {
+        // Denies any pair with the "denied" denom
+        ListingPolicyQueryMsg::CanList { asset_infos, .. } => {
+            to_binary(&!asset_infos.contains(&AssetInfo::NativeToken {
+                denom: "denied".to_string(),
+            }))
+        }
+    }
+}
+
+fn listing_policy_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        listing_policy_instantiate,
+        listing_policy_instantiate,
+        listing_policy_query,
+    ))
+}
+
 #[test]
 fn proper_initialization() {
     let mut app = mock_app();
@@ -44,6 +71,8 @@ fn proper_initialization() {
         maker_fee_bps: 10,
         is_disabled: false,
         is_generator_disabled: false,
+        pool_creation_fee: None,
+        listing_policy: None,
     }];
 
     let msg = InstantiateMsg {
@@ -202,6 +231,8 @@ fn test_create_pair() {
                 maker_fee_bps: 40,
                 is_disabled: true,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
         },
         &[],
@@ -354,3 +385,683 @@ fn check_update_owner() {
 
     assert_eq!(res.owner, new_owner)
 }
+
+#[test]
+fn test_register_manager_pool() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let pool_manager = Addr::unchecked("pool_manager");
+    let helper = FactoryHelper::init(&mut app, &owner);
+
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: token1.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token2.clone(),
+        },
+    ];
+    let register_msg = ExecuteMsg::RegisterManagerPool {
+        asset_infos: asset_infos.clone(),
+        liquidity_token: "pool_lp".to_string(),
+        pair_type: PairType::Custom("concentrated".to_string()),
+        pool_key: "pool_key".to_string(),
+    };
+
+    // The pool manager is not set yet
+    let err = app
+        .execute_contract(
+            pool_manager.clone(),
+            helper.factory.clone(),
+            &register_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            pool_manager: Some(pool_manager.to_string()),
+            pool_creation_fee: None,
+            listing_policy: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let config_res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config_res.pool_manager, Some(pool_manager.clone()));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &register_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(
+        pool_manager.clone(),
+        helper.factory.clone(),
+        &register_msg,
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            pool_manager.clone(),
+            helper.factory.clone(),
+            &register_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair was already registered");
+
+    // The pool can't be created as a standalone pair anymore
+    let err = helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair was already created");
+
+    let expected = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: pool_manager.clone(),
+        liquidity_token: Addr::unchecked("pool_lp"),
+        pair_type: PairType::Custom("concentrated".to_string()),
+        pool_key: Some("pool_key".to_string()),
+    };
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res, expected);
+
+    let res: PairsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.pairs, vec![expected]);
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister { asset_infos },
+        &[],
+    )
+    .unwrap();
+
+    let res: PairsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(res.pairs.is_empty());
+}
+
+#[test]
+fn test_pool_creation_fee_and_listing_policy() {
+    let owner = Addr::unchecked("owner");
+    let pool_manager = Addr::unchecked("pool_manager");
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &owner,
+                vec![coin(1_000, "uusd"), coin(1_000, "uluna")],
+            )
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &pool_manager, vec![coin(1_000, "uusd")])
+            .unwrap()
+    });
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let policy_code_id = app.store_code(listing_policy_contract());
+    let listing_policy = app
+        .instantiate_contract(
+            policy_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "listing_policy",
+            None,
+        )
+        .unwrap();
+
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+    let token3 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenZ",
+        Some(18),
+    );
+
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: None,
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        pool_manager: Some(pool_manager.to_string()),
+        pool_creation_fee: Some(Asset::native("uusd", 100u128)),
+        listing_policy: Some(ListingPolicy::Contract {
+            address: listing_policy.clone(),
+        }),
+    };
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &update_config_msg,
+        &[],
+    )
+    .unwrap();
+
+    let config_res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        config_res.pool_creation_fee,
+        Some(Asset::native("uusd", 100u128))
+    );
+    assert_eq!(config_res.listing_policy, Some(listing_policy));
+
+    // The fee can't be charged without a fee address
+    let err = helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Fee address must be set to charge the pool creation fee"
+    );
+
+    helper
+        .update_config(
+            &mut app,
+            &owner,
+            None,
+            Some("maker".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let err = helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: No funds sent");
+
+    let create_pair_msg = ExecuteMsg::CreatePair {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: token1.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: token2.clone(),
+            },
+        ],
+        init_params: None,
+    };
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &create_pair_msg,
+            &[coin(50, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Native token balance mismatch between the argument and the transferred"
+    );
+
+    // Funds beyond the fee are rejected
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &create_pair_msg,
+            &[coin(100, "uluna"), coin(100, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Sent more than one denomination"
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &create_pair_msg,
+        &[coin(100, "uusd")],
+    )
+    .unwrap();
+    let maker_balance = app.wrap().query_balance("maker", "uusd").unwrap();
+    assert_eq!(maker_balance.amount.u128(), 100);
+
+    // The listing policy denies pairs with the "denied" denom
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::CreatePair {
+                pair_type: PairType::Xyk {},
+                asset_infos: vec![
+                    AssetInfo::Token {
+                        contract_addr: token1.clone(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: "denied".to_string(),
+                    },
+                ],
+                init_params: None,
+            },
+            &[coin(100, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Listing is not allowed by the listing policy"
+    );
+
+    // A zero fee override makes the pair type free
+    let pair_config = PairConfig {
+        pool_creation_fee: Some(Asset::native("uusd", 0u128)),
+        ..config_res.pair_configs[0].clone()
+    };
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: pair_config,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let create_pair_msg = ExecuteMsg::CreatePair {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: token2.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: token3.clone(),
+            },
+        ],
+        init_params: None,
+    };
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &create_pair_msg,
+            &[coin(100, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "This message does no accept funds"
+    );
+    app.execute_contract(owner.clone(), helper.factory.clone(), &create_pair_msg, &[])
+        .unwrap();
+    let maker_balance = app.wrap().query_balance("maker", "uusd").unwrap();
+    assert_eq!(maker_balance.amount.u128(), 100);
+
+    // Pools created by the pool manager are subject to the same policy and fee
+    let denied_assets = vec![
+        AssetInfo::Token {
+            contract_addr: token3.clone(),
+        },
+        AssetInfo::NativeToken {
+            denom: "denied".to_string(),
+        },
+    ];
+    let approve_msg = |asset_infos: Vec<AssetInfo>| ExecuteMsg::ApproveManagerPool {
+        asset_infos,
+        pair_type: PairType::Custom("concentrated".to_string()),
+        payer: owner.to_string(),
+    };
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &approve_msg(denied_assets.clone()),
+            &[coin(100, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    let err = app
+        .execute_contract(
+            pool_manager.clone(),
+            helper.factory.clone(),
+            &approve_msg(denied_assets.clone()),
+            &[coin(100, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Listing is not allowed by the listing policy"
+    );
+    let manager_assets = vec![
+        AssetInfo::Token {
+            contract_addr: token1.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token3.clone(),
+        },
+    ];
+    let err = app
+        .execute_contract(
+            pool_manager.clone(),
+            helper.factory.clone(),
+            &approve_msg(manager_assets.clone()),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: No funds sent");
+    app.execute_contract(
+        pool_manager.clone(),
+        helper.factory.clone(),
+        &approve_msg(manager_assets),
+        &[coin(100, "uusd")],
+    )
+    .unwrap();
+    let maker_balance = app.wrap().query_balance("maker", "uusd").unwrap();
+    assert_eq!(maker_balance.amount.u128(), 200);
+
+    // A pair type can opt out of the factory listing policy
+    let pair_config = PairConfig {
+        pool_creation_fee: Some(Asset::native("uusd", 0u128)),
+        listing_policy: Some(ListingPolicy::Disabled {}),
+        ..config_res.pair_configs[0].clone()
+    };
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: pair_config,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: denied_assets.clone(),
+            init_params: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The factory listing policy can be removed
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            pool_manager: None,
+            pool_creation_fee: None,
+            listing_policy: Some(ListingPolicy::Disabled {}),
+        },
+        &[],
+    )
+    .unwrap();
+    let config_res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config_res.listing_policy, None);
+}
+
+#[test]
+fn test_pairs_by_asset_and_type() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let pool_manager = Addr::unchecked("pool_manager");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let [token1, token2, token3] = ["tokenX", "tokenY", "tokenZ"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+    let asset_info = |token: &Addr| AssetInfo::Token {
+        contract_addr: token.clone(),
+    };
+
+    for tokens in [[&token1, &token2], [&token1, &token3], [&token2, &token3]] {
+        helper
+            .create_pair(&mut app, &owner, PairType::Xyk {}, tokens, None)
+            .unwrap();
+    }
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            pool_manager: Some(pool_manager.to_string()),
+            pool_creation_fee: None,
+            listing_policy: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let native_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    app.execute_contract(
+        pool_manager.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::RegisterManagerPool {
+            asset_infos: vec![asset_info(&token1), native_asset_info.clone()],
+            liquidity_token: "pool_lp".to_string(),
+            pair_type: PairType::Custom("concentrated".to_string()),
+            pool_key: "pool_key".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let query_by_asset = |app: &App,
+                          asset_info: AssetInfo,
+                          start_after: Option<Vec<AssetInfo>>,
+                          limit: Option<u32>,
+                          exclude_disabled: Option<bool>| {
+        app.wrap()
+            .query_wasm_smart::<PairsResponse>(
+                &helper.factory,
+                &QueryMsg::PairsByAsset {
+                    asset_info,
+                    start_after,
+                    limit,
+                    exclude_disabled,
+                },
+            )
+            .unwrap()
+            .pairs
+    };
+    let query_by_type = |app: &App, pair_type: PairType, exclude_disabled: Option<bool>| {
+        app.wrap()
+            .query_wasm_smart::<PairsResponse>(
+                &helper.factory,
+                &QueryMsg::PairsByType {
+                    pair_type,
+                    start_after: None,
+                    limit: None,
+                    exclude_disabled,
+                },
+            )
+            .unwrap()
+            .pairs
+    };
+
+    let pairs = query_by_asset(&app, asset_info(&token1), None, None, None);
+    assert_eq!(pairs.len(), 3);
+    assert!(pairs
+        .iter()
+        .all(|pair| pair.asset_infos.contains(&asset_info(&token1))));
+
+    // Paginate over the pairs containing token1
+    let first_page = query_by_asset(&app, asset_info(&token1), None, Some(2), None);
+    let second_page = query_by_asset(
+        &app,
+        asset_info(&token1),
+        Some(first_page[1].asset_infos.clone()),
+        Some(2),
+        None,
+    );
+    assert_eq!([first_page, second_page].concat(), pairs);
+
+    let pairs = query_by_asset(&app, native_asset_info, None, None, None);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].contract_addr, pool_manager);
+
+    assert_eq!(query_by_type(&app, PairType::Xyk {}, None).len(), 3);
+    let pairs = query_by_type(&app, PairType::Custom("concentrated".to_string()), None);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].liquidity_token, Addr::unchecked("pool_lp"));
+
+    // Disable the XYK pair type
+    let config_res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                is_disabled: true,
+                ..config_res.pair_configs[0].clone()
+            },
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_by_asset(&app, asset_info(&token1), None, None, None).len(),
+        3
+    );
+    let pairs = query_by_asset(&app, asset_info(&token1), None, None, Some(true));
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].contract_addr, pool_manager);
+    assert!(query_by_type(&app, PairType::Xyk {}, Some(true)).is_empty());
+
+    // Deregistered pairs are removed from the indexes
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister {
+            asset_infos: vec![asset_info(&token1), asset_info(&token2)],
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_by_asset(&app, asset_info(&token1), None, None, None).len(),
+        2
+    );
+    assert_eq!(
+        query_by_asset(&app, asset_info(&token2), None, None, None).len(),
+        1
+    );
+    assert_eq!(query_by_type(&app, PairType::Xyk {}, None).len(), 2);
+
+    // Indexing existing pairs is paginated and permissioned
+    let index_msg = ExecuteMsg::IndexPairs {
+        start_after: None,
+        limit: Some(2),
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &index_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    let res = app
+        .execute_contract(owner.clone(), helper.factory.clone(), &index_msg, &[])
+        .unwrap();
+    assert_eq!(res.events[1].attributes[2], attr("indexed_pairs", "2"));
+    assert_eq!(
+        query_by_asset(&app, asset_info(&token1), None, None, None).len(),
+        2
+    );
+}
@@ -6,8 +6,8 @@ use std::vec;
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
-    Fraction, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
+    Fraction, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult, Storage,
+    SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
@@ -18,20 +18,27 @@ use astroport::asset::{
 };
 use astroport::factory::PairType;
 use astroport::generator::Cw20HookMsg as GeneratorHookMsg;
+use astroport::observation::{query_observation, PrecommitObservation, OBSERVATIONS_SIZE};
 use astroport::pair::{
-    ConfigResponse, FeeShareConfig, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
+    ConfigResponse, CurrentFeeResponse, FeeShareConfig, XYKPoolConfig, XYKPoolParams,
+    XYKPoolUpdateParams, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
 };
 use astroport::pair::{
     CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse,
     QueryMsg, ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
 };
-use astroport::querier::{query_factory_config, query_fee_info, query_supply};
+use astroport::querier::{query_factory_config, query_supply};
 use astroport::{token::InstantiateMsg as TokenInstantiateMsg, U256};
+use astroport_circular_buffer::BufferManager;
 use cw_utils::parse_instantiate_response_data;
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::limit_orders::{
+    cancel_limit_order, claim_limit_order, fill_limit_orders, place_limit_order, query_limit_order,
+    query_limit_orders, remove_expired_limit_orders,
+};
+use crate::state::{Config, BALANCES, CONFIG, OBSERVATIONS, ORDER_RESERVES};
+use crate::utils::{accumulate_swap_sizes, query_pair_fee_info, validate_dynamic_fee_params};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-pair";
@@ -60,10 +67,16 @@ pub fn instantiate(
     }
 
     let mut track_asset_balances = false;
+    let mut dynamic_fee = None;
 
     if let Some(init_params) = msg.init_params {
         let params: XYKPoolParams = from_binary(&init_params)?;
         track_asset_balances = params.track_asset_balances.unwrap_or_default();
+
+        if let Some(dynamic_fee_params) = params.dynamic_fee {
+            validate_dynamic_fee_params(&dynamic_fee_params)?;
+            dynamic_fee = Some(dynamic_fee_params);
+        }
     }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -74,6 +87,7 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         },
         factory_addr: deps.api.addr_validate(msg.factory_addr.as_str())?,
         block_time_last: 0,
@@ -81,6 +95,7 @@ pub fn instantiate(
         price1_cumulative_last: Uint128::zero(),
         track_asset_balances,
         fee_share: None,
+        dynamic_fee,
     };
 
     if track_asset_balances {
@@ -91,6 +106,8 @@ pub fn instantiate(
 
     CONFIG.save(deps.storage, &config)?;
 
+    BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
     // Create the LP token contract
@@ -182,6 +199,19 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             max_spread,
 ///             to,
 ///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::PlaceLimitOrder {
+///             offer_asset,
+///             min_price,
+///             expiry,
+///         }** Places a limit order which is filled during swaps.
+///
+/// * **ExecuteMsg::CancelLimitOrder { order_id }** Cancels a limit order and refunds its assets.
+///
+/// * **ExecuteMsg::ClaimLimitOrder { order_id }** Sends the filled amount of a limit order to its owner.
+///
+/// * **ExecuteMsg::RemoveExpiredLimitOrders { offer_asset_info, limit }** Removes expired limit orders
+/// from the order book.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -231,6 +261,27 @@ pub fn execute(
             )
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
+        ExecuteMsg::PlaceLimitOrder {
+            offer_asset,
+            min_price,
+            expiry,
+        } => {
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Cw20DirectSwap {});
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            place_limit_order(deps, env, info.sender, offer_asset, min_price, expiry)
+        }
+        ExecuteMsg::CancelLimitOrder { order_id } => {
+            cancel_limit_order(deps, info.sender, order_id)
+        }
+        ExecuteMsg::ClaimLimitOrder { order_id } => claim_limit_order(deps, info.sender, order_id),
+        ExecuteMsg::RemoveExpiredLimitOrders {
+            offer_asset_info,
+            limit,
+        } => remove_expired_limit_orders(deps, env, offer_asset_info, limit),
         _ => Err(ContractError::NonSupported {}),
     }
 }
@@ -292,6 +343,32 @@ pub fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::WithdrawSingleAsset { .. } => {
+            Err(StdError::generic_err("Single asset withdraw is currently disabled").into())
+        }
+        Cw20HookMsg::PlaceLimitOrder { min_price, expiry } => {
+            let config = CONFIG.load(deps.storage)?;
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            };
+
+            // Only asset contract can execute this message
+            if !config.pair_info.asset_infos.contains(&offer_asset.info) {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            place_limit_order(
+                deps,
+                env,
+                Addr::unchecked(cw20_msg.sender),
+                offer_asset,
+                min_price,
+                expiry,
+            )
+        }
     }
 }
 
@@ -329,9 +406,7 @@ pub fn provide_liquidity(
     let mut config = CONFIG.load(deps.storage)?;
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
-    let mut pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let mut pools = query_pools(deps.querier, deps.storage, &config)?;
     let deposits = [
         assets
             .iter()
@@ -527,7 +602,7 @@ pub fn withdraw_liquidity(
         return Err(ContractError::Unauthorized {});
     }
 
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, deps.storage, &config)?;
 
     let refund_assets = if assets.is_empty() {
         // Usual withdraw (balanced)
@@ -631,9 +706,7 @@ pub fn swap(
     let mut config = CONFIG.load(deps.storage)?;
 
     // If the asset balance is already increased, we should subtract the user deposit from the pool amount
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?
+    let pools = query_pools(deps.querier, deps.storage, &config)?
         .into_iter()
         .map(|mut p| {
             if p.info.equal(&offer_asset.info) {
@@ -656,12 +729,8 @@ pub fn swap(
         return Err(ContractError::AssetMismatch {});
     }
 
-    // Get fee info from the factory
-    let fee_info = query_fee_info(
-        &deps.querier,
-        &config.factory_addr,
-        config.pair_info.pair_type.clone(),
-    )?;
+    // Get fee info from the factory, the total fee rate is replaced if the dynamic fee is enabled
+    let (fee_info, _) = query_pair_fee_info(&deps.querier, deps.storage, &env, &config)?;
 
     let offer_amount = offer_asset.amount;
 
@@ -719,7 +788,7 @@ pub fn swap(
 
     // Compute the Maker fee
     let mut maker_fee_amount = Uint128::zero();
-    if let Some(fee_address) = fee_info.fee_address {
+    if let Some(fee_address) = &fee_info.fee_address {
         if let Some(f) = calculate_maker_fee(
             &ask_pool.info,
             fees_commission_amount,
@@ -730,19 +799,46 @@ pub fn swap(
         }
     }
 
+    let ask_ind = if ask_pool.info.equal(&pools[0].info) {
+        0
+    } else {
+        1
+    };
+    let mut new_pools = pools.clone();
+    new_pools[1 - ask_ind].amount = offer_pool.amount + offer_amount;
+    new_pools[ask_ind].amount =
+        ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount;
+
+    // The ask asset price went up, so the limit orders selling it may be filled now.
+    // The orders are filled against the pool after the swap, thus the swap itself is not affected.
+    let (fill_messages, filled_orders) = fill_limit_orders(
+        deps.storage,
+        &env,
+        &config,
+        &fee_info,
+        &mut new_pools,
+        ask_ind,
+    )?;
+    messages.extend(fill_messages);
+
     if config.track_asset_balances {
-        BALANCES.save(
-            deps.storage,
-            &offer_pool.info,
-            &(offer_pool.amount + offer_amount),
-            env.block.height,
-        )?;
-        BALANCES.save(
-            deps.storage,
-            &ask_pool.info,
-            &(ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount),
-            env.block.height,
-        )?;
+        for pool in &new_pools {
+            BALANCES.save(deps.storage, &pool.info, &pool.amount, env.block.height)?;
+        }
+    }
+
+    if config.dynamic_fee.is_some() {
+        // Store observation from precommit data
+        accumulate_swap_sizes(deps.storage, &env)?;
+
+        // Store the pre-trade spot price in precommit observation. The first pool asset is the base one.
+        // Only the first swap in a block is recorded, so the price can't be moved within the block.
+        // This data will be reflected in observations on the next swap.
+        let recorded = PrecommitObservation::may_load(deps.storage)?
+            .map_or(false, |obs| obs.precommit_ts == env.block.time.seconds());
+        if !recorded && !pools[0].amount.is_zero() && !pools[1].amount.is_zero() {
+            PrecommitObservation::save(deps.storage, &env, pools[0].amount, pools[1].amount)?;
+        }
     }
 
     // Accumulate prices for the assets in the pool
@@ -755,25 +851,39 @@ pub fn swap(
         CONFIG.save(deps.storage, &config)?;
     }
 
+    let mut attributes = vec![
+        attr("action", "swap"),
+        attr("sender", sender),
+        attr("receiver", receiver),
+        attr("offer_asset", offer_asset.info.to_string()),
+        attr("ask_asset", ask_pool.info.to_string()),
+        attr("offer_amount", offer_amount),
+        attr("return_amount", return_amount),
+        attr("spread_amount", spread_amount),
+        attr("commission_amount", commission_amount),
+        attr("maker_fee_amount", maker_fee_amount),
+        attr("fee_share_amount", fee_share_amount),
+        attr("total_fee_rate", fee_info.total_fee_rate.to_string()),
+    ];
+    if !filled_orders.is_empty() {
+        attributes.push(attr(
+            "filled_limit_orders",
+            filled_orders
+                .iter()
+                .map(|order_id| order_id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+    }
+
     Ok(Response::new()
         .add_messages(
             // 1. send collateral tokens from the contract to a user
             // 2. send inactive commission fees to the Maker contract
+            // 3. send commission fees charged on limit order fills
             messages,
         )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
-            attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("fee_share_amount", fee_share_amount),
-        ]))
+        .add_attributes(attributes))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -801,9 +911,7 @@ pub fn update_config(
             }
             config.track_asset_balances = true;
 
-            let pools = config
-                .pair_info
-                .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+            let pools = query_pools(deps.querier, deps.storage, &config)?;
 
             for pool in pools.iter() {
                 BALANCES.save(deps.storage, &pool.info, &pool.amount, env.block.height)?;
@@ -854,6 +962,28 @@ pub fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        XYKPoolUpdateParams::UpdateDynamicFee { params } => {
+            validate_dynamic_fee_params(&params)?;
+
+            response.attributes.extend([
+                attr("action", "update_dynamic_fee"),
+                attr("min_fee_bps", params.min_fee_bps.to_string()),
+                attr("max_fee_bps", params.max_fee_bps.to_string()),
+                attr("max_volatility_bps", params.max_volatility_bps.to_string()),
+                attr("window", params.window.to_string()),
+            ]);
+
+            config.dynamic_fee = Some(params);
+            CONFIG.save(deps.storage, &config)?;
+        }
+        XYKPoolUpdateParams::DisableDynamicFee => {
+            // The factory fee is charged again
+            config.dynamic_fee = None;
+            CONFIG.save(deps.storage, &config)?;
+            response
+                .attributes
+                .push(attr("action", "disable_dynamic_fee"));
+        }
     }
 
     Ok(response)
@@ -947,6 +1077,16 @@ pub fn calculate_maker_fee(
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified asset that was in the pool
 /// just preceeding the moment of the specified block height creation.
+///
+/// * **QueryMsg::Observe { seconds_ago }** Returns the price observation made `seconds_ago` seconds ago.
+/// Observations are only recorded while the dynamic fee is enabled.
+///
+/// * **QueryMsg::CurrentFee {}** Returns the swap fee currently charged by the pool using a [`CurrentFeeResponse`] object.
+///
+/// * **QueryMsg::LimitOrder { order_id }** Returns a limit order.
+///
+/// * **QueryMsg::LimitOrders { offer_asset_info, start_after, limit }** Returns the resting limit orders
+/// which sell the specified asset sorted by the limit price.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -954,10 +1094,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Pool {} => to_binary(&query_pool(deps)?),
         QueryMsg::Share { amount } => to_binary(&query_share(deps, amount)?),
         QueryMsg::Simulation { offer_asset, .. } => {
-            to_binary(&query_simulation(deps, offer_asset)?)
+            to_binary(&query_simulation(deps, env, offer_asset)?)
         }
         QueryMsg::ReverseSimulation { ask_asset, .. } => {
-            to_binary(&query_reverse_simulation(deps, ask_asset)?)
+            to_binary(&query_reverse_simulation(deps, env, ask_asset)?)
         }
         QueryMsg::CumulativePrices {} => to_binary(&query_cumulative_prices(deps, env)?),
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
@@ -965,6 +1105,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_info,
             block_height,
         } => to_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::Observe { seconds_ago } => {
+            to_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
+        }
+        QueryMsg::CurrentFee {} => to_binary(&query_current_fee(deps, env)?),
+        QueryMsg::LimitOrder { order_id } => to_binary(&query_limit_order(deps, order_id)?),
+        QueryMsg::LimitOrders {
+            offer_asset_info,
+            start_after,
+            limit,
+        } => to_binary(&query_limit_orders(
+            deps,
+            offer_asset_info,
+            start_after,
+            limit,
+        )?),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
@@ -973,7 +1128,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, deps.storage, &config)?;
 
     let resp = PoolResponse {
         assets,
@@ -989,7 +1144,7 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
 /// * **amount** is the amount of LP tokens for which we calculate associated amounts of assets.
 pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     let config = CONFIG.load(deps.storage)?;
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, deps.storage, &config)?;
     let refund_assets = get_share_in_assets(&pools, amount, total_share);
 
     Ok(refund_assets)
@@ -998,12 +1153,10 @@ pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
 ///
 /// * **offer_asset** is the asset to swap as well as an amount of the said asset.
-pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationResponse> {
+pub fn query_simulation(deps: Deps, env: Env, offer_asset: Asset) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let pools = query_pools(deps.querier, deps.storage, &config)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -1020,11 +1173,7 @@ pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationR
     }
 
     // Get fee info from the factory contract
-    let fee_info = query_fee_info(
-        &deps.querier,
-        config.factory_addr,
-        config.pair_info.pair_type,
-    )?;
+    let (fee_info, _) = query_pair_fee_info(&deps.querier, deps.storage, &env, &config)?;
 
     let (return_amount, spread_amount, commission_amount) = compute_swap(
         offer_pool.amount,
@@ -1046,13 +1195,12 @@ pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationR
 /// assets to receive from the swap.
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: Env,
     ask_asset: Asset,
 ) -> StdResult<ReverseSimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let pools = query_pools(deps.querier, deps.storage, &config)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -1069,11 +1217,7 @@ pub fn query_reverse_simulation(
     }
 
     // Get fee info from factory
-    let fee_info = query_fee_info(
-        &deps.querier,
-        config.factory_addr,
-        config.pair_info.pair_type,
-    )?;
+    let (fee_info, _) = query_pair_fee_info(&deps.querier, deps.storage, &env, &config)?;
 
     let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
         offer_pool.amount,
@@ -1092,7 +1236,7 @@ pub fn query_reverse_simulation(
 /// Returns information about cumulative prices for the assets in the pool using a [`CumulativePricesResponse`] object.
 pub fn query_cumulative_prices(deps: Deps, env: Env) -> StdResult<CumulativePricesResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, deps.storage, &config)?;
 
     let mut price0_cumulative_last = config.price0_cumulative_last;
     let mut price1_cumulative_last = config.price1_cumulative_last;
@@ -1137,12 +1281,26 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         params: Some(to_binary(&XYKPoolConfig {
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            dynamic_fee: config.dynamic_fee,
         })?),
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
     })
 }
 
+/// Returns the swap fee currently charged by the pool in a [`CurrentFeeResponse`] object.
+/// The price volatility is returned only if the dynamic fee is enabled.
+pub fn query_current_fee(deps: Deps, env: Env) -> StdResult<CurrentFeeResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (fee_info, volatility) = query_pair_fee_info(&deps.querier, deps.storage, &env, &config)?;
+
+    Ok(CurrentFeeResponse {
+        total_fee_rate: fee_info.total_fee_rate,
+        maker_fee_rate: fee_info.maker_fee_rate,
+        volatility,
+    })
+}
+
 /// Returns the balance of the specified asset that was in the pool
 /// just preceeding the moment of the specified block height creation.
 /// It will return None (null) if the balance was not tracked up to the specified block height
@@ -1333,12 +1491,17 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             "1.0.0" | "1.0.1" | "1.1.0" | "1.2.0" => {
                 migration::add_asset_balances_tracking_flag(deps.storage)?;
             }
-            "1.3.0" | "1.3.1" => {}
+            "1.3.0" | "1.3.1" | "1.4.0" | "1.5.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    // Pairs migrated from an earlier version of this contract already have the buffer
+    if OBSERVATIONS.state().may_load(deps.storage)?.is_none() {
+        BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default().add_attributes([
@@ -1352,11 +1515,34 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
     ]))
 }
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub fn pool_info(querier: QuerierWrapper, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
-    let pools = config
+/// Returns the amounts of assets in the pool. Assets held on behalf of limit orders are not
+/// part of the pool liquidity and are excluded.
+pub fn query_pools(
+    querier: QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+) -> StdResult<Vec<Asset>> {
+    config
         .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
+        .query_pools(&querier, &config.pair_info.contract_addr)?
+        .into_iter()
+        .map(|mut pool| {
+            let reserved = ORDER_RESERVES
+                .may_load(storage, &pool.info)?
+                .unwrap_or_default();
+            pool.amount = pool.amount.checked_sub(reserved)?;
+            Ok(pool)
+        })
+        .collect()
+}
+
+/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
+pub fn pool_info(
+    querier: QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+) -> StdResult<(Vec<Asset>, Uint128)> {
+    let pools = query_pools(querier, storage, config)?;
     let total_share = query_supply(&querier, &config.pair_info.liquidity_token)?;
 
     Ok((pools, total_share))
@@ -1,13 +1,22 @@
-use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
+use astroport::{
+    asset::MINIMUM_LIQUIDITY_AMOUNT,
+    pair::{MAX_DYNAMIC_FEE_BPS, MAX_FEE_SHARE_BPS},
+};
+use astroport_circular_buffer::error::BufferError;
 use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
+use crate::limit_orders::MIN_LIMIT_ORDER_AMOUNT;
+
 /// This enum describes pair contract errors
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    CircularBuffer(#[from] BufferError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -58,6 +67,27 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error(
+        "Invalid dynamic fee params: min fee must not exceed max fee, max fee must not exceed {} bps, max volatility and window must be positive",
+        MAX_DYNAMIC_FEE_BPS
+    )]
+    DynamicFeeParamsInvalid {},
+
+    #[error("Limit order price must be positive")]
+    InvalidLimitOrderPrice {},
+
+    #[error("Limit order amount must be at least {}", MIN_LIMIT_ORDER_AMOUNT)]
+    LimitOrderTooSmall {},
+
+    #[error("Limit order expiry must be in the future")]
+    InvalidLimitOrderExpiry {},
+
+    #[error("Limit order {order_id} not found")]
+    LimitOrderNotFound { order_id: u64 },
+
+    #[error("Limit order {order_id} has nothing to claim")]
+    LimitOrderNothingToClaim { order_id: u64 },
 }
 
 impl From<OverflowError> for ContractError {
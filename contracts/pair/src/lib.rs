@@ -2,8 +2,10 @@ pub mod contract;
 pub mod state;
 
 pub mod error;
+pub mod limit_orders;
 
 mod migration;
+mod utils;
 
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,505 @@
+use cosmwasm_std::{
+    attr, Addr, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Fraction, Order,
+    Response, StdError, StdResult, Storage, Uint128, Uint256,
+};
+use cw_storage_plus::Bound;
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::pair::LimitOrderResponse;
+use astroport::querier::FeeInfo;
+
+use crate::error::ContractError;
+use crate::state::{
+    Config, LimitOrder, CONFIG, LIMIT_ORDERS, NEXT_ORDER_ID, ORDER_BOOK, ORDER_RESERVES,
+};
+
+/// The maximum number of limit orders examined during a single swap
+const MAX_ORDER_FILLS: usize = 10;
+/// The maximum number of order book entries read during a single swap including expired orders
+const MAX_ORDER_BOOK_SCAN: usize = MAX_ORDER_FILLS * 3;
+/// The default number of order book entries examined by the expired orders cleanup
+const DEFAULT_CLEANUP_LIMIT: u32 = 30;
+/// The maximum number of order book entries examined by the expired orders cleanup
+const MAX_CLEANUP_LIMIT: u32 = 100;
+/// The minimum offer amount of a limit order
+pub const MIN_LIMIT_ORDER_AMOUNT: Uint128 = Uint128::new(1000);
+/// The default number of limit orders returned by the orderbook query
+const DEFAULT_LIMIT: u32 = 10;
+/// The maximum number of limit orders returned by the orderbook query
+const MAX_LIMIT: u32 = 30;
+
+/// Returns the index of the given asset in the pool.
+fn asset_index(config: &Config, asset_info: &AssetInfo) -> Result<u8, ContractError> {
+    config
+        .pair_info
+        .asset_infos
+        .iter()
+        .position(|info| info.equal(asset_info))
+        .map(|ind| ind as u8)
+        .ok_or(ContractError::AssetMismatch {})
+}
+
+/// Returns the order book key of the given order.
+fn order_book_key(
+    config: &Config,
+    order_id: u64,
+    order: &LimitOrder,
+) -> Result<(u8, u128, u64), ContractError> {
+    let ind = asset_index(config, &order.offer_asset.info)?;
+
+    Ok((ind, order.min_price.atomics().u128(), order_id))
+}
+
+/// Increases or decreases the amount of the asset held on behalf of limit orders.
+fn update_reserves(
+    storage: &mut dyn Storage,
+    asset_info: &AssetInfo,
+    increase: Uint128,
+    decrease: Uint128,
+) -> StdResult<()> {
+    ORDER_RESERVES.update(storage, asset_info, |reserve| -> StdResult<_> {
+        Ok(reserve
+            .unwrap_or_default()
+            .checked_add(increase)?
+            .checked_sub(decrease)?)
+    })?;
+
+    Ok(())
+}
+
+/// Places a limit order which sells the offer asset at an average price not lower than `min_price`.
+/// The order rests in the order book until swaps move the pool price above its limit.
+///
+/// * **owner** is the owner of the order.
+///
+/// * **offer_asset** is the asset to sell. Its funds must be already received by the contract.
+///
+/// * **min_price** is the minimum amount of the ask asset received per unit of the offer asset.
+///
+/// * **expiry** is an optional timestamp in seconds after which the order can't be filled.
+pub fn place_limit_order(
+    deps: DepsMut,
+    env: Env,
+    owner: Addr,
+    offer_asset: Asset,
+    min_price: Decimal,
+    expiry: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let offer_ind = asset_index(&config, &offer_asset.info)?;
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if offer_asset.amount < MIN_LIMIT_ORDER_AMOUNT {
+        return Err(ContractError::LimitOrderTooSmall {});
+    }
+    if min_price.is_zero() {
+        return Err(ContractError::InvalidLimitOrderPrice {});
+    }
+    if matches!(expiry, Some(expiry) if expiry <= env.block.time.seconds()) {
+        return Err(ContractError::InvalidLimitOrderExpiry {});
+    }
+
+    let ask_info = config.pair_info.asset_infos[1 - offer_ind as usize].clone();
+    let order = LimitOrder {
+        owner,
+        offer_asset,
+        filled_asset: Asset {
+            info: ask_info,
+            amount: Uint128::zero(),
+        },
+        min_price,
+        expiry,
+    };
+
+    let order_id = NEXT_ORDER_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_ORDER_ID.save(deps.storage, &(order_id + 1))?;
+
+    LIMIT_ORDERS.save(deps.storage, order_id, &order)?;
+    ORDER_BOOK.save(
+        deps.storage,
+        order_book_key(&config, order_id, &order)?,
+        &Empty {},
+    )?;
+    update_reserves(
+        deps.storage,
+        &order.offer_asset.info,
+        order.offer_asset.amount,
+        Uint128::zero(),
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "place_limit_order"),
+        attr("order_id", order_id.to_string()),
+        attr("owner", &order.owner),
+        attr("offer_asset", order.offer_asset.to_string()),
+        attr("min_price", min_price.to_string()),
+    ]))
+}
+
+/// Cancels a limit order. Both the unfilled offer amount and the unclaimed filled amount
+/// are sent to the order owner.
+pub fn cancel_limit_order(
+    deps: DepsMut,
+    sender: Addr,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let order = LIMIT_ORDERS
+        .may_load(deps.storage, order_id)?
+        .ok_or(ContractError::LimitOrderNotFound { order_id })?;
+
+    if order.owner != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    LIMIT_ORDERS.remove(deps.storage, order_id);
+    ORDER_BOOK.remove(deps.storage, order_book_key(&config, order_id, &order)?);
+
+    let mut messages = vec![];
+    for asset in [&order.offer_asset, &order.filled_asset] {
+        if !asset.amount.is_zero() {
+            update_reserves(deps.storage, &asset.info, Uint128::zero(), asset.amount)?;
+            messages.push(asset.clone().into_msg(&order.owner)?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "cancel_limit_order"),
+        attr("order_id", order_id.to_string()),
+        attr("refund_asset", order.offer_asset.to_string()),
+        attr("filled_asset", order.filled_asset.to_string()),
+    ]))
+}
+
+/// Sends the filled amount of a limit order to its owner. Fully filled orders are removed.
+pub fn claim_limit_order(
+    deps: DepsMut,
+    sender: Addr,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let mut order = LIMIT_ORDERS
+        .may_load(deps.storage, order_id)?
+        .ok_or(ContractError::LimitOrderNotFound { order_id })?;
+
+    if order.owner != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if order.filled_asset.amount.is_zero() {
+        return Err(ContractError::LimitOrderNothingToClaim { order_id });
+    }
+
+    let claimed_asset = order.filled_asset.clone();
+    update_reserves(
+        deps.storage,
+        &claimed_asset.info,
+        Uint128::zero(),
+        claimed_asset.amount,
+    )?;
+
+    if order.offer_asset.amount.is_zero() {
+        LIMIT_ORDERS.remove(deps.storage, order_id);
+    } else {
+        order.filled_asset.amount = Uint128::zero();
+        LIMIT_ORDERS.save(deps.storage, order_id, &order)?;
+    }
+
+    Ok(Response::new()
+        .add_message(claimed_asset.clone().into_msg(&order.owner)?)
+        .add_attributes([
+            attr("action", "claim_limit_order"),
+            attr("order_id", order_id.to_string()),
+            attr("claimed_asset", claimed_asset.to_string()),
+        ]))
+}
+
+/// Removes expired limit orders which sell the specified asset from the order book.
+/// Removed orders can't be filled anymore. Their owners get the funds back by cancelling them.
+/// Anyone can execute this.
+///
+/// * **limit** is the maximum number of order book entries examined.
+pub fn remove_expired_limit_orders(
+    deps: DepsMut,
+    env: Env,
+    offer_asset_info: AssetInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let offer_ind = asset_index(&config, &offer_asset_info)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CLEANUP_LIMIT)
+        .min(MAX_CLEANUP_LIMIT) as usize;
+
+    let mut expired = vec![];
+    for key in ORDER_BOOK
+        .sub_prefix(offer_ind)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+    {
+        let (price_key, order_id) = key?;
+        let order = LIMIT_ORDERS.load(deps.storage, order_id)?;
+        if matches!(order.expiry, Some(expiry) if expiry <= env.block.time.seconds()) {
+            expired.push((offer_ind, price_key, order_id));
+        }
+    }
+    for book_key in &expired {
+        ORDER_BOOK.remove(deps.storage, *book_key);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "remove_expired_limit_orders"),
+        attr("removed_orders", expired.len().to_string()),
+    ]))
+}
+
+/// Returns the amount of the ask asset received for selling `offer_amount` to the pool as well as
+/// the commission charged. Unlike a regular swap, the return amount is rounded down only once,
+/// so the order always receives at least `offer_amount * min_price` while it is within its limit.
+fn compute_order_fill(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    commission_rate: Decimal,
+) -> StdResult<(Uint128, Uint128)> {
+    let new_offer_pool = Uint256::from(offer_pool) + Uint256::from(offer_amount);
+    let fee_complement = Decimal256::one() - Decimal256::from(commission_rate);
+
+    // ask_amount = ask_pool * offer_amount / (offer_pool + offer_amount)
+    let ask_amount = Uint256::from(ask_pool).multiply_ratio(offer_amount, new_offer_pool);
+    let return_amount = (Uint256::from(ask_pool) * Uint256::from(offer_amount)).multiply_ratio(
+        fee_complement.numerator(),
+        new_offer_pool * fee_complement.denominator(),
+    );
+
+    Ok((
+        return_amount.try_into()?,
+        (ask_amount - return_amount).try_into()?,
+    ))
+}
+
+/// Fills resting limit orders which sell the asset with index `offer_ind` as long as the pool
+/// price after the fee is not lower than their limits. Orders are filled against the pool curve
+/// in the order of their limit prices. Expired orders are removed from the order book.
+/// Unfilled remainders too small to be filled are refunded to their owners.
+///
+/// * **pools** contains the pool liquidity which is updated with the filled amounts.
+///
+/// Returns the messages transferring the fees charged on fills and the refunds as well as
+/// the filled order identifiers.
+pub(crate) fn fill_limit_orders(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    fee_info: &FeeInfo,
+    pools: &mut [Asset],
+    offer_ind: usize,
+) -> Result<(Vec<CosmosMsg>, Vec<u64>), ContractError> {
+    let ask_ind = 1 - offer_ind;
+    let fee_complement = Decimal256::one() - Decimal256::from(fee_info.total_fee_rate);
+
+    // Expired orders are skipped before the fills limit is applied. The total number of entries
+    // read is bounded as well, the remaining expired orders are removed with RemoveExpiredLimitOrders.
+    let mut expired = vec![];
+    let mut candidates = vec![];
+    for key in ORDER_BOOK
+        .sub_prefix(offer_ind as u8)
+        .keys(storage, None, None, Order::Ascending)
+        .take(MAX_ORDER_BOOK_SCAN)
+    {
+        let (price_key, order_id) = key?;
+        let order = LIMIT_ORDERS.load(storage, order_id)?;
+        if matches!(order.expiry, Some(expiry) if expiry <= env.block.time.seconds()) {
+            expired.push((offer_ind as u8, price_key, order_id));
+        } else {
+            candidates.push(((offer_ind as u8, price_key, order_id), order));
+            if candidates.len() == MAX_ORDER_FILLS {
+                break;
+            }
+        }
+    }
+    for book_key in expired {
+        ORDER_BOOK.remove(storage, book_key);
+    }
+
+    let mut messages = vec![];
+    let mut filled_orders = vec![];
+    let mut total_fee_share_amount = Uint128::zero();
+    let mut total_maker_fee_amount = Uint128::zero();
+
+    for (book_key, mut order) in candidates {
+        let order_id = book_key.2;
+
+        // The average price of a fill x is (1 - fee) * ask_pool / (offer_pool + x).
+        // Thus the order can sell up to (1 - fee) * ask_pool / min_price - offer_pool.
+        let max_fill_amount = Uint256::from(pools[ask_ind].amount)
+            .multiply_ratio(
+                fee_complement.numerator(),
+                Decimal256::from(order.min_price).numerator(),
+            )
+            .saturating_sub(Uint256::from(pools[offer_ind].amount));
+        let fill_amount: Uint128 = max_fill_amount
+            .min(Uint256::from(order.offer_asset.amount))
+            .try_into()?;
+
+        // The book is sorted by the limit price, so the next orders can't be filled either
+        if fill_amount.is_zero() {
+            break;
+        }
+
+        let (return_amount, commission_amount) = compute_order_fill(
+            pools[offer_ind].amount,
+            pools[ask_ind].amount,
+            fill_amount,
+            fee_info.total_fee_rate,
+        )?;
+        if return_amount.is_zero() || return_amount < fill_amount * order.min_price {
+            if fill_amount < order.offer_asset.amount {
+                // The order is limited by its price, so the next orders can't be filled either
+                break;
+            }
+            if order.offer_asset.amount >= MIN_LIMIT_ORDER_AMOUNT {
+                continue;
+            }
+
+            // The dust remainder can't be filled at any price thus it is refunded
+            let refund_asset = order.offer_asset.clone();
+            update_reserves(
+                storage,
+                &refund_asset.info,
+                Uint128::zero(),
+                refund_asset.amount,
+            )?;
+            messages.push(refund_asset.into_msg(&order.owner)?);
+
+            ORDER_BOOK.remove(storage, book_key);
+            if order.filled_asset.amount.is_zero() {
+                LIMIT_ORDERS.remove(storage, order_id);
+            } else {
+                order.offer_asset.amount = Uint128::zero();
+                LIMIT_ORDERS.save(storage, order_id, &order)?;
+            }
+            continue;
+        }
+
+        // Split the commission the same way as for regular swaps
+        let fee_share_amount = config
+            .fee_share
+            .as_ref()
+            .map(|fee_share| commission_amount * Decimal::from_ratio(fee_share.bps, 10000u16))
+            .unwrap_or_default();
+        let maker_fee_amount = if fee_info.fee_address.is_some() {
+            (commission_amount - fee_share_amount) * fee_info.maker_fee_rate
+        } else {
+            Uint128::zero()
+        };
+        total_fee_share_amount += fee_share_amount;
+        total_maker_fee_amount += maker_fee_amount;
+
+        pools[offer_ind].amount += fill_amount;
+        pools[ask_ind].amount = pools[ask_ind]
+            .amount
+            .checked_sub(return_amount + fee_share_amount + maker_fee_amount)?;
+
+        update_reserves(
+            storage,
+            &order.offer_asset.info,
+            Uint128::zero(),
+            fill_amount,
+        )?;
+        update_reserves(
+            storage,
+            &order.filled_asset.info,
+            return_amount,
+            Uint128::zero(),
+        )?;
+
+        order.offer_asset.amount -= fill_amount;
+        order.filled_asset.amount += return_amount;
+        if order.offer_asset.amount.is_zero() {
+            ORDER_BOOK.remove(storage, book_key);
+        }
+        LIMIT_ORDERS.save(storage, order_id, &order)?;
+
+        filled_orders.push(order_id);
+    }
+
+    let ask_info = &pools[ask_ind].info;
+    if let Some(fee_share) = &config.fee_share {
+        if !total_fee_share_amount.is_zero() {
+            messages.push(
+                ask_info
+                    .with_balance(total_fee_share_amount)
+                    .into_msg(&fee_share.recipient)?,
+            );
+        }
+    }
+    if let Some(fee_address) = &fee_info.fee_address {
+        if !total_maker_fee_amount.is_zero() {
+            messages.push(
+                ask_info
+                    .with_balance(total_maker_fee_amount)
+                    .into_msg(fee_address)?,
+            );
+        }
+    }
+
+    Ok((messages, filled_orders))
+}
+
+/// Returns a limit order in a [`LimitOrderResponse`] object.
+pub fn query_limit_order(deps: Deps, order_id: u64) -> StdResult<LimitOrderResponse> {
+    let order = LIMIT_ORDERS.load(deps.storage, order_id)?;
+
+    Ok(limit_order_response(order_id, order))
+}
+
+/// Returns the resting limit orders which sell the specified asset sorted by their limit prices.
+///
+/// * **start_after** is an optional order identifier to start reading after.
+///
+/// * **limit** is the maximum number of orders returned.
+pub fn query_limit_orders(
+    deps: Deps,
+    offer_asset_info: AssetInfo,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LimitOrderResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let offer_ind = asset_index(&config, &offer_asset_info)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let start = start_after
+        .map(|order_id| -> StdResult<_> {
+            let order = LIMIT_ORDERS.load(deps.storage, order_id)?;
+            Ok(Bound::exclusive((
+                order.min_price.atomics().u128(),
+                order_id,
+            )))
+        })
+        .transpose()?;
+
+    ORDER_BOOK
+        .sub_prefix(offer_ind)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            let (_, order_id) = key?;
+            let order = LIMIT_ORDERS.load(deps.storage, order_id)?;
+            Ok(limit_order_response(order_id, order))
+        })
+        .collect()
+}
+
+fn limit_order_response(order_id: u64, order: LimitOrder) -> LimitOrderResponse {
+    LimitOrderResponse {
+        order_id,
+        owner: order.owner,
+        offer_asset: order.offer_asset,
+        filled_asset: order.filled_asset,
+        min_price: order.min_price,
+        expiry: order.expiry,
+    }
+}
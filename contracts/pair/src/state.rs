@@ -1,10 +1,12 @@
 use astroport::{
-    asset::{AssetInfo, PairInfo},
-    pair::FeeShareConfig,
+    asset::{Asset, AssetInfo, PairInfo},
+    observation::Observation,
+    pair::{DynamicFeeParams, FeeShareConfig},
 };
+use astroport_circular_buffer::CircularBuffer;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, SnapshotMap};
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap};
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -23,6 +25,8 @@ pub struct Config {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The dynamic swap fee parameters. The factory fee is charged if not set
+    pub dynamic_fee: Option<DynamicFeeParams>,
 }
 
 /// Stores the config struct at the given key
@@ -35,3 +39,36 @@ pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances_change",
     cw_storage_plus::Strategy::EveryBlock,
 );
+
+/// Circular buffer to store trade size observations
+pub const OBSERVATIONS: CircularBuffer<Observation> =
+    CircularBuffer::new("observations_state", "observations_buffer");
+
+/// This structure describes a limit order resting on the pool.
+#[cw_serde]
+pub struct LimitOrder {
+    /// The order owner
+    pub owner: Addr,
+    /// The offer asset amount which is not filled yet
+    pub offer_asset: Asset,
+    /// The ask asset amount received from fills which is not claimed yet
+    pub filled_asset: Asset,
+    /// The minimum amount of the ask asset received per unit of the offer asset
+    pub min_price: Decimal,
+    /// The timestamp in seconds after which the order can't be filled
+    pub expiry: Option<u64>,
+}
+
+/// Stores limit orders by their identifiers
+pub const LIMIT_ORDERS: Map<u64, LimitOrder> = Map::new("limit_orders");
+
+/// The order book. Resting orders are keyed by the index of the offer asset in the pool,
+/// the limit price atomics and the order identifier, so each side is sorted by the limit price.
+pub const ORDER_BOOK: Map<(u8, u128, u64), Empty> = Map::new("order_book");
+
+/// The identifier of the next limit order
+pub const NEXT_ORDER_ID: Item<u64> = Item::new("next_order_id");
+
+/// Pool asset amounts held by the contract on behalf of limit orders.
+/// They are excluded from the pool liquidity.
+pub const ORDER_RESERVES: Map<&AssetInfo, Uint128> = Map::new("order_reserves");
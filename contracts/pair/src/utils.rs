@@ -0,0 +1,159 @@
+use cosmwasm_std::{Decimal, Env, Fraction, QuerierWrapper, StdResult, Storage};
+
+use astroport::observation::{
+    safe_sma_buffer_not_full, safe_sma_calculation, Observation, PrecommitObservation,
+};
+use astroport::pair::{DynamicFeeParams, MAX_DYNAMIC_FEE_BPS};
+use astroport::querier::{query_fee_info, FeeInfo};
+use astroport_circular_buffer::error::BufferResult;
+use astroport_circular_buffer::BufferManager;
+
+use crate::error::ContractError;
+use crate::state::{Config, OBSERVATIONS};
+
+/// The maximum number of the latest observations used to measure the price volatility
+const MAX_VOLATILITY_OBSERVATIONS: u32 = 30;
+
+/// Checks that the dynamic fee parameters are consistent.
+pub(crate) fn validate_dynamic_fee_params(params: &DynamicFeeParams) -> Result<(), ContractError> {
+    if params.min_fee_bps > params.max_fee_bps
+        || params.max_fee_bps > MAX_DYNAMIC_FEE_BPS
+        || params.max_volatility_bps == 0
+        || params.window == 0
+    {
+        return Err(ContractError::DynamicFeeParamsInvalid {});
+    }
+
+    Ok(())
+}
+
+/// Calculate and save price moving average
+pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResult<()> {
+    if let Some(PrecommitObservation {
+        base_amount,
+        quote_amount,
+        precommit_ts,
+    }) = PrecommitObservation::may_load(storage)?
+    {
+        let mut buffer = BufferManager::new(storage, OBSERVATIONS)?;
+        let observed_price = Decimal::from_ratio(base_amount, quote_amount);
+
+        let new_observation;
+        if let Some(last_obs) = buffer.read_last(storage)? {
+            // Skip saving observation if it has been already saved
+            if last_obs.ts < precommit_ts {
+                // Since this is circular buffer the next index contains the oldest value
+                let count = buffer.capacity();
+                if let Some(oldest_obs) = buffer.read_single(storage, buffer.head() + 1)? {
+                    let price_sma = safe_sma_calculation(
+                        last_obs.price_sma,
+                        oldest_obs.price,
+                        count,
+                        observed_price,
+                    )?;
+                    new_observation = Observation {
+                        ts: precommit_ts,
+                        price: observed_price,
+                        price_sma,
+                    };
+                } else {
+                    // Buffer is not full yet
+                    let count = buffer.head();
+                    let price_sma =
+                        safe_sma_buffer_not_full(last_obs.price_sma, count, observed_price)?;
+                    new_observation = Observation {
+                        ts: precommit_ts,
+                        price: observed_price,
+                        price_sma,
+                    };
+                }
+
+                buffer.instant_push(storage, &new_observation)?
+            }
+        } else {
+            // Buffer is empty
+            if env.block.time.seconds() > precommit_ts {
+                new_observation = Observation {
+                    ts: precommit_ts,
+                    price: observed_price,
+                    price_sma: observed_price,
+                };
+
+                buffer.instant_push(storage, &new_observation)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Measures the price volatility as the spread between the highest and the lowest pre-trade spot price
+/// observed within the last `window` seconds relative to the lowest one.
+/// The pending (not yet committed) observation is taken into account as well, but only
+/// [`MAX_VOLATILITY_OBSERVATIONS`] latest committed observations are read.
+pub fn compute_volatility(storage: &dyn Storage, env: &Env, window: u64) -> StdResult<Decimal> {
+    let since = env.block.time.seconds().saturating_sub(window);
+    let mut prices = vec![];
+
+    if let Some(obs) = PrecommitObservation::may_load(storage)? {
+        if obs.precommit_ts >= since {
+            prices.push(Decimal::from_ratio(obs.base_amount, obs.quote_amount));
+        }
+    }
+
+    let buffer = BufferManager::new(storage, OBSERVATIONS)?;
+    let newest_ind = buffer.head() + buffer.capacity() - 1;
+    for i in 0..MAX_VOLATILITY_OBSERVATIONS.min(buffer.capacity()) {
+        match buffer.read_single(storage, newest_ind - i)? {
+            Some(obs) if obs.ts >= since => prices.push(obs.price),
+            _ => break,
+        }
+    }
+
+    match (prices.iter().min(), prices.iter().max()) {
+        (Some(min), Some(max)) if !min.is_zero() => Ok(Decimal::from_ratio(
+            max.numerator() - min.numerator(),
+            min.numerator(),
+        )),
+        _ => Ok(Decimal::zero()),
+    }
+}
+
+/// Returns the swap fee rate for the given price volatility. The fee grows linearly from
+/// `min_fee_bps` to `max_fee_bps` and stays at the maximum once the volatility reaches `max_volatility_bps`.
+pub fn dynamic_fee_rate(params: &DynamicFeeParams, volatility: Decimal) -> Decimal {
+    let max_volatility = Decimal::from_ratio(params.max_volatility_bps, 10000u16);
+    let volatility_ratio = Decimal::from_ratio(
+        volatility.min(max_volatility).numerator(),
+        max_volatility.numerator(),
+    );
+
+    Decimal::from_ratio(params.min_fee_bps, 10000u16)
+        + Decimal::from_ratio(params.max_fee_bps - params.min_fee_bps, 10000u16) * volatility_ratio
+}
+
+/// Returns the fee info of the pair. If the dynamic fee is enabled, the factory total fee rate
+/// is replaced with the one derived from the current price volatility which is returned as well.
+pub fn query_pair_fee_info(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+) -> StdResult<(FeeInfo, Option<Decimal>)> {
+    let mut fee_info = query_fee_info(
+        querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+
+    let volatility = match &config.dynamic_fee {
+        Some(params) => {
+            let volatility = compute_volatility(storage, env, params.window)?;
+            fee_info.total_fee_rate = dynamic_fee_rate(params, volatility);
+            Some(volatility)
+        }
+        None => None,
+    };
+
+    Ok((fee_info, volatility))
+}
@@ -2,6 +2,7 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::str::FromStr;
 
 use astroport::asset::{native_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::factory::{
@@ -9,9 +10,10 @@ use astroport::factory::{
     QueryMsg as FactoryQueryMsg,
 };
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, FeeShareConfig,
-    InstantiateMsg, PoolResponse, QueryMsg, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    MAX_FEE_SHARE_BPS, TWAP_PRECISION,
+    ConfigResponse, CumulativePricesResponse, CurrentFeeResponse, Cw20HookMsg, DynamicFeeParams,
+    ExecuteMsg, FeeShareConfig, InstantiateMsg, LimitOrderResponse, PoolResponse, QueryMsg,
+    XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams, MAX_DYNAMIC_FEE_BPS, MAX_FEE_SHARE_BPS,
+    TWAP_PRECISION,
 };
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport_mocks::cw_multi_test::{App, BasicApp, ContractWrapper, Executor};
@@ -80,6 +82,8 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
             total_fee_bps: 0,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: Some(String::from("generator")),
@@ -342,6 +346,7 @@ fn test_provide_and_withdraw_liquidity() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -484,6 +489,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             total_fee_bps: 0,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -874,6 +881,8 @@ fn asset_balances_tracking_works_correctly() {
             total_fee_bps: 0,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1105,6 +1114,7 @@ fn asset_balances_tracking_works_correctly() {
         init_params: Some(
             to_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                dynamic_fee: None,
             })
             .unwrap(),
         ),
@@ -1457,6 +1467,7 @@ fn update_pair_config() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -1498,6 +1509,7 @@ fn update_pair_config() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: true,
                     fee_share: None,
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -1588,6 +1600,7 @@ fn enable_disable_fee_sharing() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -1660,6 +1673,7 @@ fn enable_disable_fee_sharing() {
                         bps: fee_share_bps,
                         recipient: Addr::unchecked(fee_share_contract),
                     }),
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -1689,6 +1703,7 @@ fn enable_disable_fee_sharing() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    dynamic_fee: None,
                 })
                 .unwrap()
             ),
@@ -1698,6 +1713,780 @@ fn enable_disable_fee_sharing() {
     );
 }
 
+#[test]
+fn dynamic_fee_follows_volatility() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    // Factory fee is 0
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentFeeResponse {
+            total_fee_rate: Decimal::zero(),
+            maker_fee_rate: Decimal::zero(),
+            volatility: None,
+        }
+    );
+
+    let params = DynamicFeeParams {
+        min_fee_bps: 10,
+        max_fee_bps: 100,
+        max_volatility_bps: 1000,
+        window: 600,
+    };
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::UpdateDynamicFee {
+            params: params.clone(),
+        })
+        .unwrap(),
+    };
+    let err = router
+        .execute_contract(
+            Addr::unchecked("not_owner"),
+            pair_instance.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::Unauthorized {}
+    );
+
+    for wrong_params in [
+        DynamicFeeParams {
+            min_fee_bps: 200,
+            ..params.clone()
+        },
+        DynamicFeeParams {
+            max_fee_bps: MAX_DYNAMIC_FEE_BPS + 1,
+            ..params.clone()
+        },
+        DynamicFeeParams {
+            max_volatility_bps: 0,
+            ..params.clone()
+        },
+        DynamicFeeParams {
+            window: 0,
+            ..params.clone()
+        },
+    ] {
+        let msg = ExecuteMsg::UpdateConfig {
+            params: to_binary(&XYKPoolUpdateParams::UpdateDynamicFee {
+                params: wrong_params,
+            })
+            .unwrap(),
+        };
+        let err = router
+            .execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ContractError>().unwrap(),
+            &ContractError::DynamicFeeParamsInvalid {}
+        );
+    }
+
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        res.params,
+        Some(
+            to_binary(&XYKPoolConfig {
+                track_asset_balances: false,
+                fee_share: None,
+                dynamic_fee: Some(params.clone()),
+            })
+            .unwrap()
+        )
+    );
+
+    // No observations yet, the minimum fee is charged
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentFeeResponse {
+            total_fee_rate: Decimal::permille(1),
+            maker_fee_rate: Decimal::zero(),
+            volatility: Some(Decimal::zero()),
+        }
+    );
+
+    let swap = |router: &mut App, denom: &str, amount: u128| {
+        let msg = ExecuteMsg::Swap {
+            offer_asset: native_asset_info(denom.to_string()).with_balance(amount),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+        };
+        router
+            .execute_contract(
+                owner.clone(),
+                pair_instance.clone(),
+                &msg,
+                &[Coin::new(amount, denom)],
+            )
+            .unwrap()
+    };
+
+    let res = swap(&mut router, "uusd", 1_000000);
+    assert_eq!(res.events[1].attributes[7], attr("return_amount", "989109"));
+    assert_eq!(
+        res.events[1].attributes[9],
+        attr("commission_amount", "990")
+    );
+    assert_eq!(
+        res.events[1].attributes[12],
+        attr("total_fee_rate", "0.001")
+    );
+
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(10);
+    });
+
+    // A single observed price doesn't change the fee
+    let res = swap(&mut router, "uluna", 8_000000);
+    assert_eq!(
+        res.events[1].attributes[7],
+        attr("return_amount", "7543083")
+    );
+    assert_eq!(
+        res.events[1].attributes[12],
+        attr("total_fee_rate", "0.001")
+    );
+
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentFeeResponse {
+            total_fee_rate: Decimal::from_str("0.002808082001807255").unwrap(),
+            maker_fee_rate: Decimal::zero(),
+            volatility: Some(Decimal::from_str("0.020089800020080619").unwrap()),
+        }
+    );
+
+    let res = swap(&mut router, "uusd", 1_000000);
+    assert_eq!(
+        res.events[1].attributes[7],
+        attr("return_amount", "1129725")
+    );
+    assert_eq!(
+        res.events[1].attributes[9],
+        attr("commission_amount", "3181")
+    );
+    assert_eq!(
+        res.events[1].attributes[12],
+        attr("total_fee_rate", "0.002808082001807255")
+    );
+
+    // Only the spot price before the first swap in a block is observed
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(
+        res.volatility,
+        Some(Decimal::from_str("0.020089800020080619").unwrap())
+    );
+
+    // All observations are out of the window
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(700);
+    });
+
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(res.total_fee_rate, Decimal::permille(1));
+    assert_eq!(res.volatility, Some(Decimal::zero()));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::DisableDynamicFee).unwrap(),
+    };
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let res: CurrentFeeResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CurrentFee {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentFeeResponse {
+            total_fee_rate: Decimal::zero(),
+            maker_fee_rate: Decimal::zero(),
+            volatility: None,
+        }
+    );
+}
+
+#[test]
+fn limit_orders_are_filled_by_swaps() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+    router
+        .send_tokens(
+            owner.clone(),
+            alice.clone(),
+            &[Coin::new(10_000000, "uluna")],
+        )
+        .unwrap();
+
+    // Factory fee is 0
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let place_order = |router: &mut App, amount: u128, min_price: Decimal, expiry: Option<u64>| {
+        let msg = ExecuteMsg::PlaceLimitOrder {
+            offer_asset: native_asset_info("uluna".to_string()).with_balance(amount),
+            min_price,
+            expiry,
+        };
+        router.execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &msg,
+            &[Coin::new(amount, "uluna")],
+        )
+    };
+
+    let err = place_order(&mut router, 1_000000, Decimal::zero(), None).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::InvalidLimitOrderPrice {}
+    );
+    let now = router.block_info().time.seconds();
+    let err = place_order(&mut router, 1_000000, Decimal::one(), Some(now)).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::InvalidLimitOrderExpiry {}
+    );
+
+    place_order(
+        &mut router,
+        2_000000,
+        Decimal::from_str("1.2").unwrap(),
+        None,
+    )
+    .unwrap();
+    place_order(
+        &mut router,
+        1_000000,
+        Decimal::from_str("1.05").unwrap(),
+        None,
+    )
+    .unwrap();
+
+    // Escrowed funds are not a part of the pool liquidity
+    let res: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(res.assets[1].amount, Uint128::new(100_000_000));
+
+    // Orders are sorted by their limit prices
+    let res: Vec<LimitOrderResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::LimitOrders {
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.iter().map(|order| order.order_id).collect::<Vec<_>>(),
+        vec![1, 0]
+    );
+    let res: Vec<LimitOrderResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::LimitOrders {
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].order_id, 0);
+    router
+        .wrap()
+        .query_wasm_smart::<Vec<LimitOrderResponse>>(
+            &pair_instance,
+            &QueryMsg::LimitOrders {
+                offer_asset_info: native_asset_info("ucny".to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap_err();
+
+    let swap = |router: &mut App, amount: u128| {
+        let msg = ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(amount),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+        };
+        router
+            .execute_contract(
+                owner.clone(),
+                pair_instance.clone(),
+                &msg,
+                &[Coin::new(amount, "uusd")],
+            )
+            .unwrap()
+    };
+
+    // The taker receives the same amount as without resting orders
+    let res = swap(&mut router, 5_000000);
+    assert_eq!(
+        res.events[1].attributes[7],
+        attr("return_amount", "4761904")
+    );
+    assert_eq!(
+        res.events[1].attributes[13],
+        attr("filled_limit_orders", "1")
+    );
+
+    let res: LimitOrderResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::LimitOrder { order_id: 1 })
+        .unwrap();
+    assert_eq!(
+        res,
+        LimitOrderResponse {
+            order_id: 1,
+            owner: alice.clone(),
+            offer_asset: native_asset_info("uluna".to_string()).with_balance(0u128),
+            filled_asset: native_asset_info("uusd".to_string()).with_balance(1091044u128),
+            min_price: Decimal::from_str("1.05").unwrap(),
+            expiry: None,
+        }
+    );
+
+    let res: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(res.assets[0].amount, Uint128::new(103_908_956));
+    assert_eq!(res.assets[1].amount, Uint128::new(96_238_096));
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ClaimLimitOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::Unauthorized {}
+    );
+    let err = router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ClaimLimitOrder { order_id: 0 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::LimitOrderNothingToClaim { order_id: 0 }
+    );
+
+    // The fully filled order is removed once claimed
+    router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ClaimLimitOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ClaimLimitOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::LimitOrderNotFound { order_id: 1 }
+    );
+
+    router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::CancelLimitOrder { order_id: 0 },
+            &[],
+        )
+        .unwrap();
+
+    // Expired orders are not filled
+    let now = router.block_info().time.seconds();
+    place_order(&mut router, 500000, Decimal::one(), Some(now + 10)).unwrap();
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(20);
+    });
+
+    let res = swap(&mut router, 1_000000);
+    assert_eq!(res.events[1].attributes[7], attr("return_amount", "917348"));
+    assert_eq!(res.events[1].attributes.len(), 13);
+
+    let res: Vec<LimitOrderResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::LimitOrders {
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(res.is_empty());
+
+    router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::CancelLimitOrder { order_id: 2 },
+            &[],
+        )
+        .unwrap();
+
+    let uluna_balance = router.wrap().query_balance(&alice, "uluna").unwrap();
+    assert_eq!(uluna_balance.amount, Uint128::new(9_000000));
+    let uusd_balance = router.wrap().query_balance(&alice, "uusd").unwrap();
+    assert_eq!(uusd_balance.amount, Uint128::new(1091044));
+}
+
+#[test]
+fn limit_orders_skip_expired_orders() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+    router
+        .send_tokens(
+            owner.clone(),
+            alice.clone(),
+            &[Coin::new(10_000000, "uluna")],
+        )
+        .unwrap();
+
+    // Factory fee is 0
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let place_order = |router: &mut App, amount: u128, expiry: Option<u64>| {
+        let msg = ExecuteMsg::PlaceLimitOrder {
+            offer_asset: native_asset_info("uluna".to_string()).with_balance(amount),
+            min_price: Decimal::one(),
+            expiry,
+        };
+        router.execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &msg,
+            &[Coin::new(amount, "uluna")],
+        )
+    };
+
+    let err = place_order(&mut router, 999, None).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::LimitOrderTooSmall {}
+    );
+
+    // More expired orders than can be examined during a single swap
+    let now = router.block_info().time.seconds();
+    for _ in 0..10 {
+        place_order(&mut router, 1000, Some(now + 10)).unwrap();
+    }
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(20);
+    });
+    place_order(&mut router, 1_000000, None).unwrap();
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(5_000000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        to: None,
+    };
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &msg,
+            &[Coin::new(5_000000u128, "uusd")],
+        )
+        .unwrap();
+    assert_eq!(
+        res.events[1].attributes[13],
+        attr("filled_limit_orders", "10")
+    );
+
+    let res: LimitOrderResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::LimitOrder { order_id: 10 })
+        .unwrap();
+    assert!(res.offer_asset.amount.is_zero());
+    assert!(res.filled_asset.amount >= Uint128::new(1_000000));
+
+    // Expired orders are removed from the order book
+    let res: Vec<LimitOrderResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::LimitOrders {
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(res.is_empty());
+}
+
+#[test]
+fn limit_orders_scan_is_bounded() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+    router
+        .send_tokens(
+            owner.clone(),
+            alice.clone(),
+            &[Coin::new(10_000000, "uluna")],
+        )
+        .unwrap();
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let place_order = |router: &mut App, amount: u128, expiry: Option<u64>| {
+        let msg = ExecuteMsg::PlaceLimitOrder {
+            offer_asset: native_asset_info("uluna".to_string()).with_balance(amount),
+            min_price: Decimal::one(),
+            expiry,
+        };
+        router.execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &msg,
+            &[Coin::new(amount, "uluna")],
+        )
+    };
+    let swap = |router: &mut App| {
+        let msg = ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(5_000000u128),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+        };
+        router.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &msg,
+            &[Coin::new(5_000000u128, "uusd")],
+        )
+    };
+    let query_orders = |router: &App| -> Vec<LimitOrderResponse> {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &pair_instance,
+                &QueryMsg::LimitOrders {
+                    offer_asset_info: native_asset_info("uluna".to_string()),
+                    start_after: None,
+                    limit: Some(30),
+                },
+            )
+            .unwrap()
+    };
+
+    // More expired orders than a single swap reads
+    let now = router.block_info().time.seconds();
+    for _ in 0..45 {
+        place_order(&mut router, 1000, Some(now + 10)).unwrap();
+    }
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(20);
+    });
+    place_order(&mut router, 1_000000, None).unwrap();
+
+    // The swap succeeds. It removes the expired orders it read but doesn't reach the valid order.
+    let res = swap(&mut router).unwrap();
+    assert!(!res.events[1]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "filled_limit_orders"));
+    let res: LimitOrderResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::LimitOrder { order_id: 45 })
+        .unwrap();
+    assert_eq!(res.offer_asset.amount.u128(), 1_000000);
+    assert_eq!(query_orders(&router).len(), 16);
+
+    // Anyone can remove the remaining expired orders
+    let res = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            pair_instance.clone(),
+            &ExecuteMsg::RemoveExpiredLimitOrders {
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(res.events[1].attributes[2], attr("removed_orders", "15"));
+    let orders = query_orders(&router);
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].order_id, 45);
+
+    let res = swap(&mut router).unwrap();
+    assert!(res.events[1]
+        .attributes
+        .contains(&attr("filled_limit_orders", "45")));
+
+    // Removed orders can still be cancelled by their owner
+    router
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::CancelLimitOrder { order_id: 0 },
+            &[],
+        )
+        .unwrap();
+}
+
 #[test]
 fn provide_liquidity_with_autostaking_to_generator() {
     let astroport = astroport_address();
@@ -1983,6 +2772,8 @@ fn test_fee_share(
             total_fee_bps,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -2015,6 +2806,7 @@ fn test_fee_share(
         init_params: Some(
             to_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                dynamic_fee: None,
             })
             .unwrap(),
         ),
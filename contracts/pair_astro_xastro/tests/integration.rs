@@ -93,6 +93,8 @@ fn instantiate_factory_contract(app: &mut App, owner: Addr, pair_code_id: u64) -
             pair_type: PairType::Custom("bonded".to_string()),
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: 0,
         fee_address: None,
@@ -146,8 +148,10 @@ fn instantiate_staking(app: &mut App, owner: Addr, token_instance: &Addr) -> (Ad
     let msg = StakingInstantiateMsg {
         owner: owner.to_string(),
         token_code_id: xastro_code_id,
-        deposit_token_addr: token_instance.to_string(),
+        deposit_token: AssetInfo::cw20(token_instance.clone()),
         marketing: None,
+        unbonding_period: None,
+        tokenfactory_subdenom: None,
     };
 
     let staking_instance = app
@@ -166,7 +170,10 @@ fn instantiate_staking(app: &mut App, owner: Addr, token_instance: &Addr) -> (Ad
         .query_wasm_smart(&staking_instance, &StakingQueryMsg::Config {})
         .unwrap();
 
-    (staking_instance, resp.share_token_addr)
+    (
+        staking_instance,
+        Addr::unchecked(resp.share_token.to_string()),
+    )
 }
 
 fn instantiate_astroport(mut router: &mut App, owner: &Addr) -> AstroportContracts {
@@ -736,7 +743,8 @@ fn test_queries() {
             ],
             contract_addr: contracts.pair_instance.clone(),
             liquidity_token: Addr::unchecked(""),
-            pair_type: PairType::Custom("Bonded".to_string())
+            pair_type: PairType::Custom("Bonded".to_string()),
+            pool_key: None,
         }
     );
 }
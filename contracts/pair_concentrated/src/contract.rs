@@ -36,14 +36,15 @@ use astroport_pcl_common::state::{
 use astroport_pcl_common::utils::{
     assert_max_spread, assert_slippage_tolerance, before_swap_check, calc_provide_fee,
     check_asset_infos, check_assets, check_cw20_in_pool, check_pair_registered, compute_swap,
-    get_share_in_assets, mint_liquidity_token_message,
+    get_share_in_assets, mint_liquidity_token_message, query_pools,
 };
-use astroport_pcl_common::{calc_d, get_xcp};
+use astroport_pcl_common::utils_n_coins::{price_scale, to_internal_repr};
+use astroport_pcl_common::{assert_safe_pool_volumes, calc_d, get_xcp};
 
 use crate::error::ContractError;
 use crate::migration::migrate_config;
 use crate::state::{BALANCES, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL};
-use crate::utils::{accumulate_swap_sizes, query_pools};
+use crate::utils::{accumulate_swap_sizes, compute_single_asset_withdraw};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -114,6 +115,7 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("concentrated".to_string()),
+            pool_key: None,
         },
         factory_addr,
         pool_params,
@@ -308,6 +310,12 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::PlaceLimitOrder { .. }
+        | ExecuteMsg::CancelLimitOrder { .. }
+        | ExecuteMsg::ClaimLimitOrder { .. }
+        | ExecuteMsg::RemoveExpiredLimitOrders { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
     }
 }
 
@@ -351,6 +359,21 @@ fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::WithdrawSingleAsset {
+            asset_info,
+            min_amount,
+        } => withdraw_single_asset(
+            deps,
+            env,
+            info,
+            Addr::unchecked(cw20_msg.sender),
+            cw20_msg.amount,
+            asset_info,
+            min_amount,
+        ),
+        Cw20HookMsg::PlaceLimitOrder { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
     }
 }
 
@@ -539,8 +562,9 @@ pub fn provide_liquidity(
         .map(|(ind, pool)| pool.amount + deposits[ind])
         .collect_vec();
     new_xp[1] *= config.pool_state.price_state.price_scale;
-
     let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    assert_safe_pool_volumes(&new_xp, &amp_gamma)?;
+
     let new_d = calc_d(&new_xp, &amp_gamma)?;
 
     let share = if total_share.is_zero() {
@@ -835,6 +859,127 @@ fn withdraw_liquidity(
     ]))
 }
 
+/// Withdraw liquidity from the pool into a single asset.
+///
+/// * **sender** address that will receive the asset back from the pair contract
+///
+/// * **amount** amount of provided LP tokens
+///
+/// * **asset_info** asset to withdraw
+///
+/// * **min_amount** minimum amount of the asset to receive
+fn withdraw_single_asset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    amount: Uint128,
+    asset_info: AssetInfo,
+    min_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.pair_info.liquidity_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(
+        deps.querier,
+        &config.pair_info.contract_addr,
+        &config,
+        &precisions,
+    )?;
+    let (ind, _) = pools
+        .iter()
+        .find_position(|pool| pool.info.equal(&asset_info))
+        .ok_or_else(|| ContractError::InvalidAsset(asset_info.to_string()))?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let burn_amount = amount
+        .saturating_sub(Uint128::one())
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+    let total_share_dec = total_share.to_decimal256(LP_TOKEN_PRECISION)?;
+
+    let price_scales = [config.pool_state.price_state.price_scale];
+    let mut xs = to_internal_repr(&pools.iter().map(|a| a.amount).collect_vec(), &price_scales);
+
+    let withdraw_amount =
+        compute_single_asset_withdraw(&xs, burn_amount, total_share_dec, ind, &config, &env)?;
+
+    let precision = precisions.get_precision(&asset_info)?;
+    let refund_asset = asset_info.with_balance(withdraw_amount.to_uint(precision)?);
+
+    if let Some(min_amount) = min_amount {
+        if refund_asset.amount < min_amount {
+            return Err(ContractError::WithdrawAmountTooSmall {
+                amount: refund_asset.amount,
+                min_amount,
+            });
+        }
+    }
+
+    // One-sided withdraw diverges from the balanced one thus the price must be updated
+    let share_ratio = burn_amount / total_share_dec;
+    let balanced_share = [pools[0].amount * share_ratio, pools[1].amount * share_ratio];
+    let mut withdrawn = [Decimal256::zero(); 2];
+    withdrawn[ind] = withdraw_amount;
+    let assets_diff = [
+        withdrawn[0].diff(balanced_share[0]),
+        withdrawn[1].diff(balanced_share[1]),
+    ];
+
+    xs[ind] -= withdraw_amount * price_scale(&price_scales, ind);
+    let new_total_share = (total_share - amount).to_decimal256(LP_TOKEN_PRECISION)?;
+
+    if assets_diff[0] >= MIN_TRADE_SIZE && assets_diff[1] >= MIN_TRADE_SIZE {
+        let last_price = assets_diff[0] / assets_diff[1];
+        config.pool_state.update_price(
+            &config.pool_params,
+            &env,
+            new_total_share,
+            &xs,
+            last_price,
+        )?;
+    } else {
+        let amp_gamma = config.pool_state.get_amp_gamma(&env);
+        let d = calc_d(&xs, &amp_gamma)?;
+        config.pool_state.price_state.xcp_profit_real =
+            get_xcp(d, config.pool_state.price_state.price_scale) / new_total_share;
+    }
+
+    let messages: Vec<CosmosMsg> = vec![
+        refund_asset.clone().into_msg(&sender)?,
+        wasm_execute(
+            &config.pair_info.liquidity_token,
+            &Cw20ExecuteMsg::Burn { amount },
+            vec![],
+        )?
+        .into(),
+    ];
+
+    if config.track_asset_balances {
+        BALANCES.save(
+            deps.storage,
+            &asset_info,
+            &pools[ind]
+                .amount
+                .to_uint(precision)?
+                .checked_sub(refund_asset.amount)?,
+            env.block.height,
+        )?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "withdraw_single_asset"),
+        attr("sender", sender),
+        attr("withdrawn_share", amount),
+        attr("refund_asset", refund_asset.to_string()),
+    ]))
+}
+
 /// Performs an swap operation with the specified parameters. The trader must approve the
 /// pool contract to transfer offer assets from their wallet.
 ///
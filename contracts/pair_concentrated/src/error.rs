@@ -1,4 +1,4 @@
-use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
@@ -50,6 +50,12 @@ pub enum ContractError {
     #[error("The asset {0} does not belong to the pair")]
     InvalidAsset(String),
 
+    #[error("Withdrawn amount {amount} is less than the minimum amount {min_amount}")]
+    WithdrawAmountTooSmall {
+        amount: Uint128,
+        min_amount: Uint128,
+    },
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
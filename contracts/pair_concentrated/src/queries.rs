@@ -19,13 +19,15 @@ use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
 use astroport_pcl_common::state::Precisions;
 use astroport_pcl_common::utils::{
-    before_swap_check, compute_offer_amount, compute_swap, get_share_in_assets,
+    before_swap_check, compute_offer_amount, compute_swap, get_share_in_assets, pool_info,
+    query_pools,
 };
+use astroport_pcl_common::utils_n_coins::to_internal_repr;
 use astroport_pcl_common::{calc_d, get_xcp};
 
 use crate::state::{BALANCES, CONFIG, OBSERVATIONS};
 
-use crate::utils::{pool_info, query_pools};
+use crate::utils::compute_single_asset_withdraw;
 
 /// Exposes all the queries available in the contract.
 ///
@@ -50,6 +52,9 @@ use crate::utils::{pool_info, query_pools};
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified
 /// asset that was in the pool just preceding the moment of the specified block height creation.
+///
+/// * **QueryMsg::SimulateSingleAssetWithdraw { lp_amount, asset_info }** Returns the asset received for
+/// burning the specified amount of LP tokens into a single asset.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -79,6 +84,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_info,
             block_height,
         } => to_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::SimulateSingleAssetWithdraw {
+            lp_amount,
+            asset_info,
+        } => to_binary(
+            &query_single_asset_withdraw(deps, env, lp_amount, asset_info)
+                .map_err(|err| StdError::generic_err(format!("{err}")))?,
+        ),
     }
 }
 
@@ -230,7 +242,7 @@ pub fn query_lp_price(deps: Deps, env: Env) -> StdResult<Decimal256> {
             .collect_vec();
         ixs[1] *= config.pool_state.price_state.price_scale;
         let amp_gamma = config.pool_state.get_amp_gamma(&env);
-        let d = calc_d(&ixs, &amp_gamma)?;
+        let d = calc_d(&ixs, &amp_gamma).map_err(|err| StdError::generic_err(err.to_string()))?;
         let xcp = get_xcp(d, config.pool_state.price_state.price_scale);
 
         Ok(xcp / total_lp)
@@ -290,7 +302,7 @@ pub fn query_compute_d(deps: Deps, env: Env) -> StdResult<Decimal256> {
     xs[1] *= config.pool_state.price_state.price_scale;
 
     let amp_gamma = config.pool_state.get_amp_gamma(&env);
-    calc_d(&xs, &amp_gamma)
+    calc_d(&xs, &amp_gamma).map_err(|err| StdError::generic_err(err.to_string()))
 }
 
 /// Returns the balance of the specified asset that was in the pool
@@ -304,6 +316,49 @@ pub fn query_asset_balances_at(
     BALANCES.may_load_at_height(deps.storage, &asset_info, block_height.u64())
 }
 
+/// Returns the asset received for burning LP tokens into a single asset.
+///
+/// * **lp_amount** is the amount of LP tokens to burn.
+///
+/// * **asset_info** is the asset to withdraw.
+pub fn query_single_asset_withdraw(
+    deps: Deps,
+    env: Env,
+    lp_amount: Uint128,
+    asset_info: AssetInfo,
+) -> Result<Asset, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(
+        deps.querier,
+        &config.pair_info.contract_addr,
+        &config,
+        &precisions,
+    )?;
+    let (ind, _) = pools
+        .iter()
+        .find_position(|pool| pool.info.equal(&asset_info))
+        .ok_or_else(|| ContractError::InvalidAsset(asset_info.to_string()))?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+    let lp_amount = lp_amount
+        .saturating_sub(Uint128::one())
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+
+    let xs = to_internal_repr(
+        &pools.iter().map(|a| a.amount).collect_vec(),
+        &[config.pool_state.price_state.price_scale],
+    );
+
+    let amount = compute_single_asset_withdraw(&xs, lp_amount, total_share, ind, &config, &env)?;
+
+    Ok(Asset {
+        amount: amount.to_uint(precisions.get_precision(&asset_info)?)?,
+        info: asset_info,
+    })
+}
+
 #[cfg(test)]
 mod testing {
     use std::error::Error;
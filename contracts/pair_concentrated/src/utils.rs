@@ -1,46 +1,48 @@
-use cosmwasm_std::{Addr, Decimal, Env, QuerierWrapper, StdResult, Storage, Uint128};
+use cosmwasm_std::{Decimal, Decimal256, Env, StdError, Storage};
 
-use astroport::asset::{Asset, DecimalAsset};
 use astroport::observation::{safe_sma_buffer_not_full, safe_sma_calculation};
 use astroport::observation::{Observation, PrecommitObservation};
-use astroport::querier::query_supply;
 use astroport_circular_buffer::error::BufferResult;
 use astroport_circular_buffer::BufferManager;
-use astroport_pcl_common::state::{Config, Precisions};
+use astroport_pcl_common::consts::TWO;
+use astroport_pcl_common::state::Config;
+use astroport_pcl_common::utils_n_coins::price_scale;
+use astroport_pcl_common::{calc_d, calc_y};
 
 use crate::error::ContractError;
 use crate::state::OBSERVATIONS;
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub(crate) fn pool_info(
-    querier: QuerierWrapper,
+/// Returns the amount of the asset with index `ind` received for burning `lp_amount` LP tokens.
+/// One-sided withdraw is equivalent to a balanced withdraw followed by a swap into the asset,
+/// thus the burnt part of the invariant (D) is charged with the half of the current fee rate.
+/// Charged fee stays in the pool.
+/// * `xs` - internal repr of pools
+pub(crate) fn compute_single_asset_withdraw(
+    xs: &[Decimal256],
+    lp_amount: Decimal256,
+    total_share: Decimal256,
+    ind: usize,
     config: &Config,
-) -> StdResult<(Vec<Asset>, Uint128)> {
-    let pools = config
-        .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
-    let total_share = query_supply(&querier, &config.pair_info.liquidity_token)?;
+    env: &Env,
+) -> Result<Decimal256, ContractError> {
+    if lp_amount.is_zero() {
+        return Err(StdError::generic_err("Nothing to withdraw").into());
+    }
+    if lp_amount >= total_share {
+        return Err(StdError::generic_err("Not enough liquidity in the pool").into());
+    }
 
-    Ok((pools, total_share))
-}
+    let amp_gamma = config.pool_state.get_amp_gamma(env);
+    let d = calc_d(xs, &amp_gamma)?;
 
-/// Returns current pool's volumes where amount is in [`Decimal256`] form.
-pub(crate) fn query_pools(
-    querier: QuerierWrapper,
-    addr: &Addr,
-    config: &Config,
-    precisions: &Precisions,
-) -> Result<Vec<DecimalAsset>, ContractError> {
-    config
-        .pair_info
-        .query_pools(&querier, addr)?
-        .into_iter()
-        .map(|asset| {
-            asset
-                .to_decimal_asset(precisions.get_precision(&asset.info)?)
-                .map_err(Into::into)
-        })
-        .collect()
+    let fee_rate = config.pool_params.fee(xs) / TWO;
+    let burnt_d = d * lp_amount / total_share;
+    let new_d = d - burnt_d * (Decimal256::one() - fee_rate);
+
+    let new_y = calc_y(xs, new_d, &amp_gamma, ind)?;
+    let price_scales = [config.pool_state.price_state.price_scale];
+
+    Ok(xs[ind].saturating_sub(new_y) / price_scale(&price_scales, ind))
 }
 
 /// Calculate and save price moving average
@@ -231,6 +231,8 @@ impl Helper {
                 pair_type: pair_type.clone(),
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             }],
             token_code_id,
             generator_address: None,
@@ -321,6 +323,41 @@ impl Helper {
             .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
     }
 
+    pub fn withdraw_single_asset(
+        &mut self,
+        sender: &Addr,
+        amount: u128,
+        asset_info: &AssetInfo,
+        min_amount: Option<Uint128>,
+    ) -> AnyResult<AppResponse> {
+        let msg = Cw20ExecuteMsg::Send {
+            contract: self.pair_addr.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::WithdrawSingleAsset {
+                asset_info: asset_info.clone(),
+                min_amount,
+            })
+            .unwrap(),
+        };
+
+        self.app
+            .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
+    }
+
+    pub fn simulate_single_asset_withdraw(
+        &self,
+        lp_amount: u128,
+        asset_info: &AssetInfo,
+    ) -> StdResult<Asset> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::SimulateSingleAssetWithdraw {
+                lp_amount: lp_amount.into(),
+                asset_info: asset_info.clone(),
+            },
+        )
+    }
+
     pub fn swap(
         &mut self,
         sender: &Addr,
@@ -394,6 +394,97 @@ fn provide_and_withdraw() {
     assert_eq!(26653_440612, helper.coin_balance(&test_coins[1], &user2));
 }
 
+#[test]
+fn withdraw_single_asset() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::cw20("USDX")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = Addr::unchecked("user");
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(10_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(10_000_000000u128),
+    ];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+    let user_lp = helper.token_balance(&helper.lp_token, &user);
+
+    let wrong_coin = native_asset_info("random-coin".to_string());
+    let err = helper
+        .withdraw_single_asset(&user, user_lp, &wrong_coin, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidAsset(wrong_coin.to_string()),
+        err.downcast().unwrap()
+    );
+
+    let lp_amount = user_lp / 2;
+    let uusd = helper.assets[&test_coins[0]].clone();
+    let sim_asset = helper
+        .simulate_single_asset_withdraw(lp_amount, &uusd)
+        .unwrap();
+    assert_eq!(sim_asset.info, uusd);
+
+    // One-sided withdraw yields more than the balanced share of the asset
+    // but less than the value of the whole balanced share as it is charged with fees
+    let share = helper.query_share(lp_amount).unwrap();
+    assert!(sim_asset.amount > share[0].amount);
+    assert!(sim_asset.amount < share[0].amount + share[1].amount);
+
+    let err = helper
+        .withdraw_single_asset(
+            &user,
+            lp_amount,
+            &uusd,
+            Some(sim_asset.amount + Uint128::one()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WithdrawAmountTooSmall {
+            amount: sim_asset.amount,
+            min_amount: sim_asset.amount + Uint128::one(),
+        },
+        err.downcast().unwrap()
+    );
+
+    let lp_price_before = helper.query_lp_price().unwrap();
+    helper
+        .withdraw_single_asset(&user, lp_amount, &uusd, Some(sim_asset.amount))
+        .unwrap();
+    assert_eq!(
+        helper.coin_balance(&test_coins[0], &user),
+        sim_asset.amount.u128()
+    );
+    assert_eq!(
+        helper.token_balance(&helper.lp_token, &user),
+        user_lp - lp_amount
+    );
+    assert!(helper.query_lp_price().unwrap() >= lp_price_before);
+
+    // Withdraw the rest into the cw20 token
+    helper.app.next_block(600);
+    let usdx = helper.assets[&test_coins[1]].clone();
+    let sim_asset = helper
+        .simulate_single_asset_withdraw(user_lp - lp_amount, &usdx)
+        .unwrap();
+    helper
+        .withdraw_single_asset(&user, user_lp - lp_amount, &usdx, None)
+        .unwrap();
+    assert_eq!(
+        helper.coin_balance(&test_coins[1], &user),
+        sim_asset.amount.u128()
+    );
+    assert_eq!(helper.token_balance(&helper.lp_token, &user), 0);
+}
+
 #[test]
 fn check_imbalanced_provide() {
     let owner = Addr::unchecked("owner");
@@ -770,6 +861,87 @@ fn provides_and_swaps() {
     helper.swap(&user, &offer_asset, None).unwrap();
 }
 
+#[test]
+fn withdraw_from_large_pool() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    // 1e13 of each asset. With amp 40 and gamma 0.000145 pools may grow up to about 2.43e13
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(10_000_000_000_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(10_000_000_000_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    helper.app.next_block(1000);
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000_000_000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+
+    // Pool volumes must not overflow the invariant
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(10_000_000_000_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(10_000_000_000_000_000000u128),
+    ];
+    let err = helper.provide_liquidity(&owner, &assets).unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::UnsafePoolVolumes {}),
+        err.downcast().unwrap()
+    );
+
+    // Balanced withdraw is never limited
+    let owner_lp = helper.token_balance(&helper.lp_token, &owner);
+    helper.withdraw_liquidity(&owner, owner_lp, vec![]).unwrap();
+    assert_eq!(helper.token_balance(&helper.lp_token, &owner), 0);
+}
+
+#[test]
+fn existing_pool_above_former_volume_limit() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(1_000_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(1_000_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    // Pools which had grown beyond the former fixed limit (5e12) before the upgrade keep working
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(5_000_000_000_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(5_000_000_000_000_000000u128),
+    ];
+    let pair_addr = helper.pair_addr.clone();
+    helper.give_me_money(&assets, &pair_addr);
+
+    helper.app.next_block(1000);
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000_000_000000u128);
+    helper.simulate_swap(&offer_asset, None).unwrap();
+    helper
+        .simulate_reverse_swap(
+            &helper.assets[&test_coins[1]].with_balance(1_000_000000u128),
+            None,
+        )
+        .unwrap();
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+    assert!(helper.coin_balance(&test_coins[1], &user) > 0);
+
+    let owner_lp = helper.token_balance(&helper.lp_token, &owner);
+    helper.withdraw_liquidity(&owner, owner_lp, vec![]).unwrap();
+    assert_eq!(helper.token_balance(&helper.lp_token, &owner), 0);
+}
+
 #[test]
 fn check_amp_gamma_change() {
     let owner = Addr::unchecked("owner");
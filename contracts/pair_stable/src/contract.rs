@@ -47,8 +47,9 @@ use crate::state::{
 };
 use crate::utils::{
     accumulate_swap_sizes, adjust_precision, check_asset_infos, check_assets, check_cw20_in_pool,
-    compute_current_amp, compute_swap, determine_base_quote_amount, get_share_in_assets,
-    mint_liquidity_token_message, select_pools, SwapResult,
+    compute_current_amp, compute_imbalanced_withdraw, compute_single_asset_withdraw, compute_swap,
+    determine_base_quote_amount, get_share_in_assets, imbalance_fee_rate,
+    mint_liquidity_token_message, pool_index, select_pools, SwapResult,
 };
 
 /// Contract name that is used for migration.
@@ -57,8 +58,10 @@ const CONTRACT_NAME: &str = "astroport-pair-stable";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID of sub-message.
 const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
-/// Number of assets in the pool.
-const N_COINS: usize = 2;
+/// Minimum number of assets in the pool.
+const MIN_COINS: usize = 2;
+/// Maximum number of assets in the pool.
+const MAX_COINS: usize = 5;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -70,8 +73,11 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     check_asset_infos(deps.api, &msg.asset_infos)?;
 
-    if msg.asset_infos.len() != N_COINS {
-        return Err(ContractError::InvalidNumberOfAssets(N_COINS));
+    if !(MIN_COINS..=MAX_COINS).contains(&msg.asset_infos.len()) {
+        return Err(ContractError::InvalidPoolAssetsLength {
+            min: MIN_COINS,
+            max: MAX_COINS,
+        });
     }
 
     if msg.init_params.is_none() {
@@ -96,6 +102,7 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Stable {},
+            pool_key: None,
         },
         factory_addr,
         block_time_last: 0,
@@ -268,6 +275,12 @@ pub fn execute(
             })
             .map_err(|e| e.into())
         }
+        ExecuteMsg::PlaceLimitOrder { .. }
+        | ExecuteMsg::CancelLimitOrder { .. }
+        | ExecuteMsg::ClaimLimitOrder { .. }
+        | ExecuteMsg::RemoveExpiredLimitOrders { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
     }
 }
 
@@ -311,11 +324,27 @@ pub fn receive_cw20(
         }
         Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
             deps,
+            env,
             info,
             Addr::unchecked(cw20_msg.sender),
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::WithdrawSingleAsset {
+            asset_info,
+            min_amount,
+        } => withdraw_single_asset(
+            deps,
+            env,
+            info,
+            Addr::unchecked(cw20_msg.sender),
+            cw20_msg.amount,
+            asset_info,
+            min_amount,
+        ),
+        Cw20HookMsg::PlaceLimitOrder { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
     }
 }
 
@@ -344,7 +373,7 @@ pub fn provide_liquidity(
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
 
-    if assets.len() != config.pair_info.asset_infos.len() {
+    if assets.len() > config.pair_info.asset_infos.len() {
         return Err(ContractError::InvalidNumberOfAssets(
             config.pair_info.asset_infos.len(),
         ));
@@ -438,7 +467,7 @@ pub fn provide_liquidity(
     let amp = compute_current_amp(&config, &env)?;
 
     // Invariant (D) after deposit added
-    let new_balances = assets_collection
+    let mut new_balances = assets_collection
         .iter()
         .map(|(deposit, pool)| Ok(pool + deposit.amount))
         .collect::<StdResult<Vec<_>>>()?;
@@ -474,8 +503,22 @@ pub fn provide_liquidity(
             .collect_vec();
         let init_d = compute_d(amp, &old_balances)?;
 
+        // Charge the difference between ideal and real balances
+        let fee_info = query_fee_info(
+            &deps.querier,
+            &config.factory_addr,
+            config.pair_info.pair_type.clone(),
+        )?;
+        let fee_rate = imbalance_fee_rate(fee_info.total_fee_rate, old_balances.len());
+        for (new_balance, old_balance) in new_balances.iter_mut().zip(&old_balances) {
+            let ideal_balance = deposit_d.checked_multiply_ratio(*old_balance, init_d)?;
+            let difference = ideal_balance.abs_diff(*new_balance);
+            *new_balance = new_balance.checked_sub(fee_rate.checked_mul(difference)?)?;
+        }
+        let after_fee_d = compute_d(amp, &new_balances)?;
+
         let share = Decimal256::with_precision(total_share, config.greatest_precision)?
-            .checked_multiply_ratio(deposit_d.saturating_sub(init_d), init_d)?
+            .checked_multiply_ratio(after_fee_d.saturating_sub(init_d), init_d)?
             .to_uint128_with_precision(config.greatest_precision)?;
 
         if share.is_zero() {
@@ -509,8 +552,13 @@ pub fn provide_liquidity(
 /// * **sender** is the address that will receive assets back from the pair contract.
 ///
 /// * **amount** is the amount of LP tokens to burn.
+///
+/// * **assets** is the exact amount of assets to withdraw. If it is empty, the assets are
+/// withdrawn in the pool proportion. Otherwise only the LP tokens needed for the imbalanced
+/// withdrawal are burnt and the rest are returned to the sender.
 pub fn withdraw_liquidity(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     amount: Uint128,
@@ -522,24 +570,57 @@ pub fn withdraw_liquidity(
         return Err(ContractError::Unauthorized {});
     }
 
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let mut messages = vec![];
 
-    let refund_assets = if assets.is_empty() {
+    let (refund_assets, burn_amount) = if assets.is_empty() {
         // Usual withdraw (balanced)
-        get_share_in_assets(&pools, amount, total_share)
+        let (pools, total_share) = pool_info(deps.querier, &config)?;
+        (get_share_in_assets(&pools, amount, total_share), amount)
     } else {
-        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+        let burn_amount = compute_imbalanced_withdraw(deps.as_ref(), &env, &config, &assets)?;
+        if burn_amount > amount {
+            return Err(StdError::generic_err(format!(
+                "Not enough LP tokens. You need {burn_amount} LP tokens."
+            ))
+            .into());
+        }
+
+        if burn_amount < amount {
+            // Return unused LP tokens back to the sender
+            messages.push(
+                wasm_execute(
+                    &config.pair_info.liquidity_token,
+                    &Cw20ExecuteMsg::Transfer {
+                        recipient: sender.to_string(),
+                        amount: amount - burn_amount,
+                    },
+                    vec![],
+                )?
+                .into(),
+            );
+        }
+
+        let refund_assets = assets
+            .into_iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .collect_vec();
+
+        (refund_assets, burn_amount)
     };
 
-    let mut messages = refund_assets
-        .clone()
-        .into_iter()
-        .map(|asset| asset.into_msg(&sender))
-        .collect::<StdResult<Vec<_>>>()?;
+    messages.extend(
+        refund_assets
+            .clone()
+            .into_iter()
+            .map(|asset| asset.into_msg(&sender))
+            .collect::<StdResult<Vec<_>>>()?,
+    );
     messages.push(
         wasm_execute(
             &config.pair_info.liquidity_token,
-            &Cw20ExecuteMsg::Burn { amount },
+            &Cw20ExecuteMsg::Burn {
+                amount: burn_amount,
+            },
             vec![],
         )?
         .into(),
@@ -548,11 +629,64 @@ pub fn withdraw_liquidity(
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "withdraw_liquidity"),
         attr("sender", sender),
-        attr("withdrawn_share", amount),
+        attr("withdrawn_share", burn_amount),
         attr("refund_assets", refund_assets.iter().join(", ")),
     ]))
 }
 
+/// Withdraw liquidity from the pool into a single asset.
+/// * **sender** is the address that will receive the asset back from the pair contract.
+///
+/// * **amount** is the amount of LP tokens to burn.
+///
+/// * **asset_info** is the asset to withdraw.
+///
+/// * **min_amount** is the minimum amount of the asset to receive.
+pub fn withdraw_single_asset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    amount: Uint128,
+    asset_info: AssetInfo,
+    min_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.pair_info.liquidity_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let refund_asset =
+        compute_single_asset_withdraw(deps.as_ref(), &env, &config, amount, &asset_info)?;
+
+    if let Some(min_amount) = min_amount {
+        if refund_asset.amount < min_amount {
+            return Err(ContractError::WithdrawAmountTooSmall {
+                amount: refund_asset.amount,
+                min_amount,
+            });
+        }
+    }
+
+    let messages: Vec<CosmosMsg> = vec![
+        refund_asset.clone().into_msg(&sender)?,
+        wasm_execute(
+            &config.pair_info.liquidity_token,
+            &Cw20ExecuteMsg::Burn { amount },
+            vec![],
+        )?
+        .into(),
+    ];
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "withdraw_single_asset"),
+        attr("sender", sender),
+        attr("withdrawn_share", amount),
+        attr("refund_asset", refund_asset.to_string()),
+    ]))
+}
+
 /// Performs an swap operation with the specified parameters.
 ///
 /// * **sender** is the sender of the swap operation.
@@ -708,9 +842,11 @@ pub fn swap(
         && return_amount.to_decimal256(ask_precision)? >= MIN_TRADE_SIZE
     {
         // Store time series data
-        let (base_amount, quote_amount) =
-            determine_base_quote_amount(&pools, &offer_asset, return_amount)?;
-        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+        if let Some((base_amount, quote_amount)) =
+            determine_base_quote_amount(&pools, &offer_asset, &ask_pool.info, return_amount)?
+        {
+            PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+        }
     }
 
     Ok(Response::new()
@@ -777,6 +913,12 @@ pub fn calculate_maker_fee(
 /// pool using a [`CumulativePricesResponse`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::SimulateImbalancedWithdraw { assets }** Returns the amount of LP tokens that must be burnt
+/// to withdraw the specified assets.
+///
+/// * **QueryMsg::SimulateSingleAssetWithdraw { lp_amount, asset_info }** Returns the asset received for
+/// burning the specified amount of LP tokens into a single asset.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -804,6 +946,15 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::Config {} => to_binary(&query_config(deps, env)?),
         QueryMsg::QueryComputeD {} => to_binary(&query_compute_d(deps, env)?),
+        QueryMsg::SimulateImbalancedWithdraw { assets } => {
+            to_binary(&query_imbalanced_withdraw(deps, env, assets)?)
+        }
+        QueryMsg::SimulateSingleAssetWithdraw {
+            lp_amount,
+            asset_info,
+        } => to_binary(&query_single_asset_withdraw(
+            deps, env, lp_amount, asset_info,
+        )?),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
@@ -964,11 +1115,17 @@ pub fn query_reverse_simulation(
     .ok_or_else(|| StdError::generic_err("The pool must have less than 100% fee!"))?
     .checked_mul(Decimal256::with_precision(ask_asset.amount, ask_precision)?)?;
 
+    let ask_ind = pool_index(&pools, &ask_pool.info)
+        .map_err(|err| StdError::generic_err(format!("{err}")))?;
+    let offer_ind = pool_index(&pools, &offer_pool.info)
+        .map_err(|err| StdError::generic_err(format!("{err}")))?;
     let xp = pools.into_iter().map(|pool| pool.amount).collect_vec();
     let new_offer_pool_amount = calc_y(
         compute_current_amp(&config, &env)?,
         ask_pool.amount - before_commission,
         &xp,
+        ask_ind,
+        offer_ind,
         config.greatest_precision,
     )?;
 
@@ -989,6 +1146,33 @@ pub fn query_reverse_simulation(
     })
 }
 
+/// Returns the amount of LP tokens that must be burnt to withdraw the specified assets.
+///
+/// * **assets** is the exact amount of assets to withdraw.
+pub fn query_imbalanced_withdraw(deps: Deps, env: Env, assets: Vec<Asset>) -> StdResult<Uint128> {
+    let config = CONFIG.load(deps.storage)?;
+
+    compute_imbalanced_withdraw(deps, &env, &config, &assets)
+        .map_err(|err| StdError::generic_err(format!("{err}")))
+}
+
+/// Returns the asset received for burning LP tokens into a single asset.
+///
+/// * **lp_amount** is the amount of LP tokens to burn.
+///
+/// * **asset_info** is the asset to withdraw.
+pub fn query_single_asset_withdraw(
+    deps: Deps,
+    env: Env,
+    lp_amount: Uint128,
+    asset_info: AssetInfo,
+) -> StdResult<Asset> {
+    let config = CONFIG.load(deps.storage)?;
+
+    compute_single_asset_withdraw(deps, &env, &config, lp_amount, &asset_info)
+        .map_err(|err| StdError::generic_err(format!("{err}")))
+}
+
 /// Returns the pair contract configuration in a [`ConfigResponse`] object.
 pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
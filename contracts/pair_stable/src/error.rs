@@ -1,4 +1,6 @@
-use cosmwasm_std::{CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{
+    CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError, Uint128,
+};
 use thiserror::Error;
 
 use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
@@ -81,6 +83,9 @@ pub enum ContractError {
     #[error("Invalid number of assets. This pair support only {0} assets")]
     InvalidNumberOfAssets(usize),
 
+    #[error("Invalid number of assets. The stable pool supports from {min} to {max} assets")]
+    InvalidPoolAssetsLength { min: usize, max: usize },
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
@@ -95,6 +100,12 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error("Withdrawn amount {amount} is less than the minimum amount {min_amount}")]
+    WithdrawAmountTooSmall {
+        amount: Uint128,
+        min_amount: Uint128,
+    },
 }
 
 impl From<OverflowError> for ContractError {
@@ -9,8 +9,6 @@ pub const MAX_AMP: u64 = 1_000_000;
 pub const MAX_AMP_CHANGE: u64 = 10;
 pub const MIN_AMP_CHANGING_TIME: u64 = 86400;
 pub const AMP_PRECISION: u64 = 100;
-/// N = 2
-pub const N_COINS: Decimal256 = Decimal256::raw(2000000000000000000);
 /// 1e-6
 pub const TOL: Decimal256 = Decimal256::raw(1000000000000);
 
@@ -21,11 +19,13 @@ pub const TOL: Decimal256 = Decimal256::raw(1000000000000);
 /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
 ///
 pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
-    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
-    let amount_a_times_coins = pools[0] * N_COINS;
-    let amount_b_times_coins = pools[1] * N_COINS;
+    let n_coins = Decimal256::from_ratio(pools.len() as u128, 1u8);
+    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * n_coins;
 
-    let sum_x = pools[0].checked_add(pools[1])?; // sum(x_i), a.k.a S
+    // sum(x_i), a.k.a S
+    let sum_x = pools
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, x| acc.checked_add(*x))?;
     if sum_x.is_zero() {
         Ok(Decimal256::zero())
     } else {
@@ -34,9 +34,14 @@ pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
 
         // Newton's method to approximate D
         for _ in 0..ITERATIONS {
-            let d_product = d.pow(3) / (amount_a_times_coins * amount_b_times_coins);
+            // D**(n+1) / (n**n * prod(x_i)), accumulated pool by pool to avoid overflowing D**(n+1)
+            let d_product = pools.iter().try_fold(d, |acc, pool| {
+                acc.checked_mul(d)?
+                    .checked_div(pool.checked_mul(n_coins)?)
+                    .map_err(|e| StdError::generic_err(e.to_string()))
+            })?;
             d_previous = d;
-            d = calculate_step(d, leverage, sum_x, d_product)?;
+            d = calculate_step(d, leverage, sum_x, d_product, n_coins)?;
             // Equality with the precision of 1e-6
             if d.abs_diff(d_previous) <= TOL {
                 return Ok(d);
@@ -59,14 +64,15 @@ fn calculate_step(
     leverage: Decimal256,
     sum_x: Decimal256,
     d_product: Decimal256,
+    n_coins: Decimal256,
 ) -> StdResult<Decimal256> {
     let leverage_mul = leverage.checked_mul(sum_x)?;
-    let d_p_mul = d_product.checked_mul(N_COINS)?;
+    let d_p_mul = d_product.checked_mul(n_coins)?;
 
     let l_val = leverage_mul.checked_add(d_p_mul)?.checked_mul(initial_d)?;
 
     let leverage_sub = initial_d.checked_mul(leverage - Decimal256::one())?;
-    let n_coins_sum = d_product.checked_mul(N_COINS.checked_add(Decimal256::one())?)?;
+    let n_coins_sum = d_product.checked_mul(n_coins.checked_add(Decimal256::one())?)?;
 
     let r_val = leverage_sub.checked_add(n_coins_sum)?;
 
@@ -77,27 +83,70 @@ fn calculate_step(
 
 /// Compute the swap amount `y` in proportion to `x`.
 ///
-/// * **Solve for y**
-///
-/// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
-///
-/// y**2 + b*y = c
+/// `x_ind` is the index of the pool whose balance becomes `new_amount` and `y_ind` is the index
+/// of the pool whose new balance is solved for. All other pools keep their balances from `xp`.
 pub(crate) fn calc_y(
     amp: Uint64,
     new_amount: Decimal256,
     xp: &[Decimal256],
+    x_ind: usize,
+    y_ind: usize,
     target_precision: u8,
 ) -> StdResult<Uint128> {
+    if x_ind == y_ind || x_ind >= xp.len() {
+        return Err(StdError::generic_err("Invalid pool indexes"));
+    }
+
     let d = compute_d(amp, xp)?;
-    let leverage = Decimal256::from_ratio(amp, 1u8) * N_COINS;
+    let mut new_xp = xp.to_vec();
+    new_xp[x_ind] = new_amount;
+
+    calc_y_d(amp, d, &new_xp, y_ind)?.to_uint128_with_precision(target_precision)
+}
+
+/// Compute the balance `y` of the pool at `y_ind` which keeps the invariant equal to `d`.
+/// The balance of the pool at `y_ind` in `xp` is ignored.
+///
+/// * **Solve for y**
+///
+/// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
+///
+/// y**2 + b*y = c
+pub(crate) fn calc_y_d(
+    amp: Uint64,
+    d: Decimal256,
+    xp: &[Decimal256],
+    y_ind: usize,
+) -> StdResult<Decimal256> {
+    if y_ind >= xp.len() {
+        return Err(StdError::generic_err("Invalid pool indexes"));
+    }
+
+    let n_coins = Decimal256::from_ratio(xp.len() as u128, 1u8);
+    let leverage = Decimal256::from_ratio(amp, 1u8) * n_coins;
     let amp_prec = Decimal256::from_ratio(AMP_PRECISION, 1u8);
 
-    let c = d.checked_pow(3)?.checked_mul(amp_prec)?
-        / new_amount
-            .checked_mul(N_COINS * N_COINS)?
-            .checked_mul(leverage)?;
+    // sum' and D**(n+1) / (n**n * prod') over every pool except the one being solved for
+    let mut sum = Decimal256::zero();
+    let mut c = d;
+    for (ind, amount) in xp.iter().enumerate() {
+        if ind == y_ind {
+            continue;
+        }
+        sum = sum.checked_add(*amount)?;
+        c = c
+            .checked_mul(d)?
+            .checked_div(amount.checked_mul(n_coins)?)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+    }
+
+    let c = c
+        .checked_mul(d)?
+        .checked_mul(amp_prec)?
+        .checked_div(leverage.checked_mul(n_coins)?)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
 
-    let b = new_amount.checked_add(d.checked_mul(amp_prec)? / leverage)?;
+    let b = sum.checked_add(d.checked_mul(amp_prec)? / leverage)?;
 
     // Solve for y by approximating: y**2 + b*y = c
     let mut y_prev;
@@ -107,10 +156,10 @@ pub(crate) fn calc_y(
         y = y
             .checked_pow(2)?
             .checked_add(c)?
-            .checked_div(y.checked_mul(N_COINS)?.checked_add(b)?.checked_sub(d)?)
+            .checked_div(y.checked_add(y)?.checked_add(b)?.checked_sub(d)?)
             .map_err(|e| StdError::generic_err(e.to_string()))?;
         if y.abs_diff(y_prev) <= TOL {
-            return y.to_uint128_with_precision(target_precision);
+            return Ok(y);
         }
     }
 
@@ -97,6 +97,9 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                pool_manager: None,
+                                pool_creation_fee: None,
+                                listing_policy: None,
                             })
                             .into(),
                         ),
@@ -164,6 +167,9 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                pool_manager: None,
+                                pool_creation_fee: None,
+                                listing_policy: None,
                             })
                             .into(),
                         )
@@ -1,8 +1,8 @@
 use std::cmp::Ordering;
 
 use cosmwasm_std::{
-    to_binary, wasm_execute, Addr, Api, CosmosMsg, Decimal, Env, QuerierWrapper, StdResult,
-    Storage, Uint128, Uint64,
+    to_binary, wasm_execute, Addr, Api, CosmosMsg, Decimal, Decimal256, Deps, Env, QuerierWrapper,
+    StdError, StdResult, Storage, Uint128, Uint64,
 };
 use cw20::Cw20ExecuteMsg;
 use itertools::Itertools;
@@ -11,12 +11,12 @@ use astroport::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset};
 use astroport::observation::{
     safe_sma_buffer_not_full, safe_sma_calculation, Observation, PrecommitObservation,
 };
-use astroport::querier::query_factory_config;
+use astroport::querier::{query_factory_config, query_fee_info, query_supply};
 use astroport_circular_buffer::error::BufferResult;
 use astroport_circular_buffer::BufferManager;
 
 use crate::error::ContractError;
-use crate::math::calc_y;
+use crate::math::{calc_y, calc_y_d, compute_d};
 use crate::state::{get_precision, Config, OBSERVATIONS};
 
 /// Helper function to check if the given asset infos are valid.
@@ -245,6 +245,17 @@ pub(crate) fn get_share_in_assets(
         .collect()
 }
 
+/// Returns the position of the pool with the specified asset.
+pub(crate) fn pool_index(
+    pools: &[DecimalAsset],
+    asset_info: &AssetInfo,
+) -> Result<usize, ContractError> {
+    pools
+        .iter()
+        .position(|pool| pool.info == *asset_info)
+        .ok_or_else(|| ContractError::InvalidAsset(asset_info.to_string()))
+}
+
 /// Structure for internal use which represents swap result.
 pub(crate) struct SwapResult {
     pub return_amount: Uint128,
@@ -276,6 +287,8 @@ pub(crate) fn compute_swap(
         compute_current_amp(config, env)?,
         offer_pool.amount + offer_asset.amount,
         &xp,
+        pool_index(pools, &offer_pool.info)?,
+        pool_index(pools, &ask_pool.info)?,
         token_precision,
     )?;
 
@@ -293,6 +306,161 @@ pub(crate) fn compute_swap(
     })
 }
 
+/// Returns the fee rate charged on the imbalance of a provide or withdrawal: `fee * n / (4 * (n - 1))`.
+pub fn imbalance_fee_rate(total_fee_rate: Decimal, n_coins: usize) -> Decimal256 {
+    Decimal256::new(total_fee_rate.atomics().into())
+        * Decimal256::from_ratio(n_coins as u128, 4 * (n_coins as u128 - 1))
+}
+
+/// Returns the amount of LP tokens that must be burnt to withdraw the specified assets.
+/// The difference between the withdrawn assets and the pool proportion is charged with an
+/// imbalance fee which stays in the pool.
+///
+/// * **assets** assets to withdraw. Omitted pool assets are not withdrawn.
+pub(crate) fn compute_imbalanced_withdraw(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    assets: &[Asset],
+) -> Result<Uint128, ContractError> {
+    if assets.len() > config.pair_info.asset_infos.len() {
+        return Err(ContractError::InvalidNumberOfAssets(
+            config.pair_info.asset_infos.len(),
+        ));
+    }
+    check_assets(deps.api, assets)?;
+    if assets.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let pools = config.pair_info.query_pools_decimal(
+        &deps.querier,
+        &config.pair_info.contract_addr,
+        &config.factory_addr,
+    )?;
+    for asset in assets {
+        pool_index(&pools, &asset.info)?;
+    }
+
+    let old_balances = pools.iter().map(|pool| pool.amount).collect_vec();
+    let mut new_balances = pools
+        .iter()
+        .map(|pool| {
+            let withdraw_amount = assets
+                .iter()
+                .find(|asset| asset.info == pool.info)
+                .map(|asset| asset.amount)
+                .unwrap_or_default();
+            let precision = get_precision(deps.storage, &pool.info)?;
+            Ok(pool
+                .amount
+                .checked_sub(Decimal256::with_precision(withdraw_amount, precision)?)?)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let amp = compute_current_amp(config, env)?;
+
+    // Initial invariant (D) and invariant after assets withdrawn
+    let init_d = compute_d(amp, &old_balances)?;
+    let withdraw_d = compute_d(amp, &new_balances)?;
+
+    // Charge the difference between ideal and real balances
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let fee_rate = imbalance_fee_rate(fee_info.total_fee_rate, pools.len());
+    for (new_balance, old_balance) in new_balances.iter_mut().zip(&old_balances) {
+        let ideal_balance = withdraw_d.checked_multiply_ratio(*old_balance, init_d)?;
+        let difference = ideal_balance.abs_diff(*new_balance);
+        *new_balance = new_balance.checked_sub(fee_rate.checked_mul(difference)?)?;
+    }
+    let after_fee_d = compute_d(amp, &new_balances)?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    // Round up to keep rounding errors in favour of the pool
+    let burn_amount = Decimal256::with_precision(total_share, config.greatest_precision)?
+        .checked_multiply_ratio(init_d.checked_sub(after_fee_d)?, init_d)?
+        .to_uint128_with_precision(config.greatest_precision)?
+        .checked_add(Uint128::one())?;
+
+    Ok(burn_amount)
+}
+
+/// Returns the asset received for burning `lp_amount` LP tokens into a single asset.
+/// The new balance of the asset is computed against the reduced invariant (D) and the
+/// imbalance of the withdrawal is charged with a fee which stays in the pool.
+///
+/// * **lp_amount** amount of LP tokens to burn.
+///
+/// * **asset_info** asset to withdraw.
+pub(crate) fn compute_single_asset_withdraw(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    lp_amount: Uint128,
+    asset_info: &AssetInfo,
+) -> Result<Asset, ContractError> {
+    if lp_amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let pools = config.pair_info.query_pools_decimal(
+        &deps.querier,
+        &config.pair_info.contract_addr,
+        &config.factory_addr,
+    )?;
+    let ind = pool_index(&pools, asset_info)?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    if lp_amount >= total_share {
+        return Err(StdError::generic_err("Not enough liquidity in the pool").into());
+    }
+
+    let xp = pools.iter().map(|pool| pool.amount).collect_vec();
+    let amp = compute_current_amp(config, env)?;
+
+    // Initial invariant (D) and invariant reduced by the share of burnt LP tokens
+    let init_d = compute_d(amp, &xp)?;
+    let new_d = init_d.checked_multiply_ratio(
+        Decimal256::from_ratio(total_share - lp_amount, 1u8),
+        Decimal256::from_ratio(total_share, 1u8),
+    )?;
+    let new_y = calc_y_d(amp, new_d, &xp, ind)?;
+
+    // Charge the difference between ideal and real balances
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let fee_rate = imbalance_fee_rate(fee_info.total_fee_rate, pools.len());
+    let xp_reduced = xp
+        .iter()
+        .enumerate()
+        .map(|(i, balance)| {
+            let ideal_balance = balance.checked_multiply_ratio(new_d, init_d)?;
+            let difference = if i == ind {
+                ideal_balance.abs_diff(new_y)
+            } else {
+                ideal_balance.abs_diff(*balance)
+            };
+            Ok(balance.checked_sub(fee_rate.checked_mul(difference)?)?)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let precision = get_precision(deps.storage, asset_info)?;
+    let amount = xp_reduced[ind]
+        .checked_sub(calc_y_d(amp, new_d, &xp_reduced, ind)?)?
+        .to_uint128_with_precision(precision)?;
+
+    Ok(Asset {
+        info: asset_info.clone(),
+        amount,
+    })
+}
+
 /// Calculate and save price moving average
 pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResult<()> {
     if let Some(PrecommitObservation {
@@ -353,23 +521,24 @@ pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResu
     Ok(())
 }
 
-/// Internal function to determine which asset is base one, which is quote one
+/// Internal function to determine which asset is base one, which is quote one.
+///
+/// The oracle tracks the price of the first pool asset against the second one, so swaps
+/// involving other assets of an N-coin pool are not observed and `None` is returned.
 pub(crate) fn determine_base_quote_amount(
     pools: &[DecimalAsset],
     offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
     return_amount: Uint128,
-) -> Result<(Uint128, Uint128), ContractError> {
-    let offer_index = pools
-        .iter()
-        .find_position(|asset| asset.info == offer_asset.info)
-        .ok_or_else(|| ContractError::InvalidAsset(offer_asset.info.to_string()))?
-        .0;
-
-    let (base_amount, quote_amount) = if offer_index == 0 {
-        (offer_asset.amount, return_amount)
-    } else {
-        (return_amount, offer_asset.amount)
+) -> Result<Option<(Uint128, Uint128)>, ContractError> {
+    let offer_index = pool_index(pools, &offer_asset.info)?;
+    let ask_index = pool_index(pools, ask_asset_info)?;
+
+    let amounts = match (offer_index, ask_index) {
+        (0, 1) => Some((offer_asset.amount, return_amount)),
+        (1, 0) => Some((return_amount, offer_asset.amount)),
+        _ => None,
     };
 
-    Ok((base_amount, quote_amount))
+    Ok(amounts)
 }
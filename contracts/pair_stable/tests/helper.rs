@@ -188,6 +188,8 @@ impl Helper {
                 pair_type: PairType::Stable {},
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             }],
             token_code_id,
             generator_address: None,
@@ -265,6 +267,27 @@ impl Helper {
             .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
     }
 
+    pub fn withdraw_single_asset(
+        &mut self,
+        sender: &Addr,
+        amount: u128,
+        asset_info: AssetInfo,
+        min_amount: Option<u128>,
+    ) -> AnyResult<AppResponse> {
+        let msg = Cw20ExecuteMsg::Send {
+            contract: self.pair_addr.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::WithdrawSingleAsset {
+                asset_info,
+                min_amount: min_amount.map(Uint128::from),
+            })
+            .unwrap(),
+        };
+
+        self.app
+            .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
+    }
+
     pub fn swap(
         &mut self,
         sender: &Addr,
@@ -338,6 +361,29 @@ impl Helper {
         )
     }
 
+    pub fn simulate_imbalanced_withdraw(&self, assets: &[Asset]) -> StdResult<Uint128> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::SimulateImbalancedWithdraw {
+                assets: assets.to_vec(),
+            },
+        )
+    }
+
+    pub fn simulate_single_asset_withdraw(
+        &self,
+        lp_amount: u128,
+        asset_info: AssetInfo,
+    ) -> StdResult<Asset> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::SimulateSingleAssetWithdraw {
+                lp_amount: Uint128::from(lp_amount),
+                asset_info,
+            },
+        )
+    }
+
     pub fn query_prices(&self) -> StdResult<CumulativePricesResponse> {
         self.app
             .wrap()
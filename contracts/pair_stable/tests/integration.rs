@@ -129,6 +129,8 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
             pair_type: PairType::Stable {},
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: None,
@@ -492,6 +494,8 @@ fn provide_lp_for_single_token() {
             pair_type: PairType::Stable {},
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -826,6 +830,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             pair_type: PairType::Stable {},
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1903,6 +1909,8 @@ fn test_fee_share(
             pair_type: PairType::Stable {},
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -3,7 +3,7 @@
 use cosmwasm_std::{Addr, Decimal, StdError};
 use std::str::FromStr;
 
-use astroport::asset::AssetInfoExt;
+use astroport::asset::{native_asset_info, AssetInfoExt};
 use astroport::cosmwasm_ext::AbsDiff;
 use astroport::observation::OracleObservation;
 use astroport_pair_stable::error::ContractError;
@@ -13,7 +13,6 @@ use crate::helper::{f64_to_dec, Helper, TestCoin};
 
 mod helper;
 
-#[ignore]
 #[test]
 fn provide_and_withdraw_no_fee() {
     let owner = Addr::unchecked("owner");
@@ -224,7 +223,6 @@ fn swap_different_precisions() {
     assert_eq!(99_94902, helper.coin_balance(&test_coins[1], &user));
 }
 
-#[ignore]
 #[test]
 fn check_swaps() {
     let owner = Addr::unchecked("owner");
@@ -274,6 +272,73 @@ fn check_swaps() {
     assert_eq!(99_949011, helper.coin_balance(&test_coins[1], &user));
 }
 
+#[test]
+fn check_n_coin_swaps() {
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+
+    // Reference values were generated offline by solving the invariant with exact rational
+    // arithmetic: 1007.085457 USDT for the 4-coin pool and 2544.134064 USDD for the 5-coin pool
+    // (before fees). Newton's method converges within 1e-6, hence the last digit may differ.
+    let cases = [
+        (
+            vec![100_000u128, 120_000, 80_000, 150_000],
+            (2usize, 3usize),
+            1000u128,
+            1006_581916u128,
+        ),
+        (
+            vec![50_000u128, 75_000, 100_000, 125_000, 150_000],
+            (0usize, 4usize),
+            2500u128,
+            2542_861998u128,
+        ),
+    ];
+
+    for (balances, (offer_ind, ask_ind), offer_amount, expected_return) in cases {
+        let test_coins = ["FOO", "BAR", "USDC", "USDT", "USDD"][..balances.len()]
+            .iter()
+            .map(|name| TestCoin::cw20(name))
+            .collect::<Vec<_>>();
+
+        let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+        let assets = test_coins
+            .iter()
+            .zip(&balances)
+            .map(|(coin, balance)| helper.assets[coin].with_balance(balance * 1_000000))
+            .collect::<Vec<_>>();
+        helper.provide_liquidity(&owner, &assets).unwrap();
+
+        let offer_asset =
+            helper.assets[&test_coins[offer_ind]].with_balance(offer_amount * 1_000000);
+        let ask_asset_info = helper.assets[&test_coins[ask_ind]].clone();
+
+        let sim_resp = helper
+            .simulate_swap(&offer_asset, Some(ask_asset_info.clone()))
+            .unwrap();
+        assert_eq!(expected_return, sim_resp.return_amount.u128());
+
+        let reverse_sim_resp = helper
+            .simulate_reverse_swap(
+                &ask_asset_info.with_balance(sim_resp.return_amount),
+                Some(offer_asset.info.clone()),
+            )
+            .unwrap();
+        assert_eq!(offer_asset.amount, reverse_sim_resp.offer_amount);
+
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper
+            .swap(&user, &offer_asset, Some(ask_asset_info))
+            .unwrap();
+        assert_eq!(0, helper.coin_balance(&test_coins[offer_ind], &user));
+        assert_eq!(
+            expected_return,
+            helper.coin_balance(&test_coins[ask_ind], &user)
+        );
+    }
+}
+
 #[test]
 fn check_wrong_initializations() {
     let owner = Addr::unchecked("owner");
@@ -281,7 +346,7 @@ fn check_wrong_initializations() {
     let err = Helper::new(&owner, vec![TestCoin::native("uluna")], 100u64, None).unwrap_err();
 
     assert_eq!(
-        ContractError::InvalidNumberOfAssets(2),
+        ContractError::InvalidPoolAssetsLength { min: 2, max: 5 },
         err.downcast().unwrap()
     );
 
@@ -301,7 +366,7 @@ fn check_wrong_initializations() {
     .unwrap_err();
 
     assert_eq!(
-        ContractError::InvalidNumberOfAssets(2),
+        ContractError::InvalidPoolAssetsLength { min: 2, max: 5 },
         err.downcast().unwrap()
     );
 
@@ -328,7 +393,6 @@ fn check_wrong_initializations() {
     .unwrap();
 }
 
-#[ignore]
 #[test]
 fn check_withdraw_charges_fees() {
     let owner = Addr::unchecked("owner");
@@ -400,6 +464,159 @@ fn check_withdraw_charges_fees() {
     );
 }
 
+#[test]
+fn check_single_asset_withdraw() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![
+        TestCoin::native("uluna"),
+        TestCoin::cw20("USDC"),
+        TestCoin::cw20("USDD"),
+    ];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000_000000u128),
+        helper.assets[&test_coins[2]].with_balance(100_000_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = Addr::unchecked("user");
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000000u128),
+        helper.assets[&test_coins[2]].with_balance(100_000000u128),
+    ];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+    let lp_amount = helper.token_balance(&helper.lp_token, &user);
+    assert_eq!(300_000000, lp_amount);
+
+    let err = helper
+        .simulate_single_asset_withdraw(lp_amount, native_asset_info("random".to_string()))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: Generic error: The asset random does not belong to the pair"
+        )
+    );
+
+    // 300 x USDC minus the imbalance fee
+    let refund_asset = helper
+        .simulate_single_asset_withdraw(lp_amount, helper.assets[&test_coins[1]].clone())
+        .unwrap();
+    assert_eq!(299_924997, refund_asset.amount.u128());
+
+    let err = helper
+        .withdraw_single_asset(
+            &user,
+            lp_amount,
+            helper.assets[&test_coins[1]].clone(),
+            Some(300_000000),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Withdrawn amount 299924997 is less than the minimum amount 300000000"
+    );
+
+    helper
+        .withdraw_single_asset(
+            &user,
+            lp_amount,
+            helper.assets[&test_coins[1]].clone(),
+            Some(299_900000),
+        )
+        .unwrap();
+    assert_eq!(0, helper.token_balance(&helper.lp_token, &user));
+    assert_eq!(0, helper.coin_balance(&test_coins[0], &user));
+    assert_eq!(
+        refund_asset.amount.u128(),
+        helper.coin_balance(&test_coins[1], &user)
+    );
+    assert_eq!(0, helper.coin_balance(&test_coins[2], &user));
+
+    // Imbalanced withdraw simulation matches the amount of burnt LP tokens
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000000u128),
+        helper.assets[&test_coins[2]].with_balance(50_000000u128),
+    ];
+    let burn_amount = helper.simulate_imbalanced_withdraw(&assets).unwrap();
+    let owner_lp_amount = helper.token_balance(&helper.lp_token, &owner);
+    helper
+        .withdraw_liquidity(&owner, burn_amount.u128() + 1000, assets)
+        .unwrap();
+    assert_eq!(
+        owner_lp_amount - burn_amount.u128(),
+        helper.token_balance(&helper.lp_token, &owner)
+    );
+}
+
+#[test]
+fn n_coin_pool_observes_first_two_assets_only() {
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+
+    let test_coins = vec![
+        TestCoin::native("uusd"),
+        TestCoin::cw20("USDX"),
+        TestCoin::cw20("USDY"),
+    ];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(100_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block(1000);
+
+    // Swaps which don't involve both of the first two assets are not observed
+    for (offer_ind, ask_ind) in [(1, 2), (2, 0)] {
+        let offer_asset = helper.assets[&test_coins[offer_ind]].with_balance(1000_000000u128);
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper
+            .swap(
+                &user,
+                &offer_asset,
+                Some(helper.assets[&test_coins[ask_ind]].clone()),
+            )
+            .unwrap();
+        helper.app.next_block(1000);
+
+        let err = helper.query_observe(0).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Querier contract error: Generic error: Buffer is empty")
+        );
+    }
+
+    // The price of the first asset in terms of the second one is observed in both directions
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(1000_000000u128);
+    let ask_asset_info = helper.assets[&test_coins[0]].clone();
+    let return_amount = helper
+        .simulate_swap(&offer_asset, Some(ask_asset_info.clone()))
+        .unwrap()
+        .return_amount;
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper
+        .swap(&user, &offer_asset, Some(ask_asset_info))
+        .unwrap();
+    helper.app.next_block(1000);
+
+    assert_eq!(
+        helper.query_observe(0).unwrap(),
+        OracleObservation {
+            timestamp: helper.app.block_info().time.seconds(),
+            price: Decimal::from_ratio(return_amount, offer_asset.amount)
+        }
+    );
+}
+
 #[test]
 fn check_pool_prices() {
     let owner = Addr::unchecked("owner");
@@ -0,0 +1,818 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, from_binary, wasm_execute, wasm_instantiate, Addr, Binary, CosmosMsg, Decimal,
+    Decimal256, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg,
+    SubMsgResponse, SubMsgResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw_utils::parse_instantiate_response_data;
+use itertools::Itertools;
+
+use astroport::asset::AssetInfoExt;
+use astroport::asset::{
+    addr_opt_validate, format_lp_token_name, token_asset, Asset, AssetInfo, CoinsExt,
+    Decimal256Ext, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+};
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
+use astroport::factory::PairType;
+use astroport::pair::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, MIN_TRADE_SIZE};
+use astroport::pair_concentrated::UpdatePoolParams;
+use astroport::pair_tricrypto::{TricryptoPoolParams, TricryptoPoolUpdateParams};
+use astroport::querier::{query_factory_config, query_fee_info, query_supply};
+use astroport::token::InstantiateMsg as TokenInstantiateMsg;
+use astroport_pcl_common::state::{
+    AmpGamma, Config, MultiPriceState, PoolParams, PoolState, Precisions,
+};
+use astroport_pcl_common::utils::{
+    assert_max_spread, before_swap_check, check_asset_infos, check_cw20_in_pool,
+    check_pair_registered, get_share_in_assets, mint_liquidity_token_message, query_pools,
+};
+use astroport_pcl_common::utils_n_coins::{
+    assert_slippage_tolerance, calc_last_prices, calc_provide_fee, compute_swap, select_pools,
+    to_internal_repr,
+};
+use astroport_pcl_common::{assert_safe_pool_volumes, calc_d, get_xcp_n};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, OWNERSHIP_PROPOSAL};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// A `reply` call code ID used for sub-messages.
+const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+/// An LP token's precision.
+pub(crate) const LP_TOKEN_PRECISION: u8 = 6;
+/// The number of assets in the pool.
+pub(crate) const N_COINS: usize = 3;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.asset_infos.len() != N_COINS {
+        return Err(ContractError::InvalidNumberOfAssets(N_COINS));
+    }
+
+    check_asset_infos(deps.api, &msg.asset_infos)?;
+
+    let params: TricryptoPoolParams = from_binary(
+        &msg.init_params
+            .ok_or(ContractError::InitParamsNotFound {})?,
+    )?;
+
+    if params.price_scales.len() != N_COINS - 1 {
+        return Err(StdError::generic_err(format!(
+            "price_scales must contain exactly {} elements",
+            N_COINS - 1
+        ))
+        .into());
+    }
+
+    if params.price_scales.iter().any(Decimal::is_zero) {
+        return Err(StdError::generic_err("Initial price scale can not be zero").into());
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let factory_addr = deps.api.addr_validate(&msg.factory_addr)?;
+
+    Precisions::store_precisions(deps.branch(), &msg.asset_infos, &factory_addr)?;
+
+    let mut pool_params = PoolParams::default();
+    pool_params.update_params(UpdatePoolParams {
+        mid_fee: Some(params.mid_fee),
+        out_fee: Some(params.out_fee),
+        fee_gamma: Some(params.fee_gamma),
+        repeg_profit_threshold: Some(params.repeg_profit_threshold),
+        min_price_scale_delta: Some(params.min_price_scale_delta),
+        ma_half_time: Some(params.ma_half_time),
+    })?;
+
+    let price_scales = params
+        .price_scales
+        .into_iter()
+        .map(Decimal256::from)
+        .collect_vec();
+
+    let pool_state = PoolState {
+        initial: AmpGamma::default(),
+        future: AmpGamma::new(params.amp, params.gamma)?,
+        future_time: env.block.time.seconds(),
+        initial_time: 0,
+        price_state: MultiPriceState {
+            oracle_prices: price_scales.clone(),
+            last_prices: price_scales.clone(),
+            price_scales,
+            last_price_update: env.block.time.seconds(),
+            xcp_profit: Decimal256::zero(),
+            xcp_profit_real: Decimal256::zero(),
+        },
+    };
+
+    let config = Config {
+        pair_info: PairInfo {
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: Addr::unchecked(""),
+            asset_infos: msg.asset_infos.clone(),
+            pair_type: PairType::Custom("tricrypto".to_string()),
+            pool_key: None,
+        },
+        factory_addr,
+        pool_params,
+        pool_state,
+        owner: None,
+        track_asset_balances: false,
+        fee_share: None,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
+
+    // Create LP token
+    let sub_msg = SubMsg::reply_on_success(
+        wasm_instantiate(
+            msg.token_code_id,
+            &TokenInstantiateMsg {
+                name: token_name,
+                symbol: "uLP".to_string(),
+                decimals: LP_TOKEN_PRECISION,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: env.contract.address.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            },
+            vec![],
+            String::from("Astroport LP token"),
+        )?,
+        INSTANTIATE_TOKEN_REPLY_ID,
+    );
+
+    Ok(Response::new().add_submessage(sub_msg))
+}
+
+/// The entry point to the contract for processing replies from submessages.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }),
+        } => {
+            let mut config = CONFIG.load(deps.storage)?;
+
+            if config.pair_info.liquidity_token != Addr::unchecked("") {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let init_response = parse_instantiate_response_data(data.as_slice())
+                .map_err(|e| StdError::generic_err(format!("{e}")))?;
+            config.pair_info.liquidity_token =
+                deps.api.addr_validate(&init_response.contract_address)?;
+            CONFIG.save(deps.storage, &config)?;
+            Ok(Response::new()
+                .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+/// Exposes all the execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::UpdateConfig { params: Binary }** Updates the pool parameters.
+///
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
+/// it depending on the received template.
+///
+/// * **ExecuteMsg::ProvideLiquidity {
+///             assets,
+///             slippage_tolerance,
+///             auto_stake,
+///             receiver,
+///         }** Provides liquidity in the pair with the specified input parameters.
+///
+/// * **ExecuteMsg::Swap {
+///             offer_asset,
+///             ask_asset_info,
+///             belief_price,
+///             max_spread,
+///             to,
+///         }** Performs a swap operation with the specified parameters.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        } => provide_liquidity(
+            deps,
+            env,
+            info,
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        ),
+        ExecuteMsg::Swap {
+            offer_asset,
+            ask_asset_info,
+            belief_price,
+            max_spread,
+            to,
+        } => {
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Cw20DirectSwap {});
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            if !config.pair_info.asset_infos.contains(&offer_asset.info) {
+                return Err(ContractError::InvalidAsset(offer_asset.info.to_string()));
+            }
+
+            let to_addr = addr_opt_validate(deps.api, &to)?;
+
+            swap(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                ask_asset_info,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner.unwrap_or(factory_config.owner),
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+
+            drop_ownership_proposal(
+                deps,
+                info,
+                config.owner.unwrap_or(factory_config.owner),
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut config| {
+                    config.owner = Some(new_owner);
+                    Ok(config)
+                })?;
+
+                Ok(())
+            })
+            .map_err(Into::into)
+        }
+        ExecuteMsg::PlaceLimitOrder { .. }
+        | ExecuteMsg::CancelLimitOrder { .. }
+        | ExecuteMsg::ClaimLimitOrder { .. }
+        | ExecuteMsg::RemoveExpiredLimitOrders { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
+    }
+}
+
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+///
+/// * **cw20_msg** CW20 receive message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Swap {
+            ask_asset_info,
+            belief_price,
+            max_spread,
+            to,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            // Only asset contract can execute this message
+            check_cw20_in_pool(&config, &info.sender)?;
+
+            let to_addr = addr_opt_validate(deps.api, &to)?;
+            swap(
+                deps,
+                env,
+                Addr::unchecked(cw20_msg.sender),
+                token_asset(info.sender, cw20_msg.amount),
+                ask_asset_info,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
+            deps,
+            env,
+            info,
+            Addr::unchecked(cw20_msg.sender),
+            cw20_msg.amount,
+            assets,
+        ),
+        Cw20HookMsg::WithdrawSingleAsset { .. } => {
+            Err(StdError::generic_err("Single asset withdraw is currently disabled").into())
+        }
+        Cw20HookMsg::PlaceLimitOrder { .. } => {
+            Err(StdError::generic_err("Limit orders are not supported by this pool").into())
+        }
+    }
+}
+
+/// Provides liquidity in the pair with the specified input parameters.
+///
+/// * **assets** is an array with assets available in the pool. Omitted assets are treated as zero deposits.
+///
+/// * **slippage_tolerance** is an optional parameter which is used to specify how much
+/// the pool price can move until the provide liquidity transaction goes through.
+///
+/// * **auto_stake** is an optional parameter which determines whether the LP tokens minted after
+/// liquidity provision are automatically staked in the Generator contract on behalf of the LP token receiver.
+///
+/// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
+/// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
+///
+/// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
+pub fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    auto_stake: Option<bool>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if !check_pair_registered(
+        deps.querier,
+        &config.factory_addr,
+        &config.pair_info.asset_infos,
+    )? {
+        return Err(ContractError::PairIsNotRegistered {});
+    }
+
+    if assets.is_empty() {
+        return Err(StdError::generic_err("Nothing to provide").into());
+    } else if assets.len() > N_COINS {
+        return Err(ContractError::InvalidNumberOfAssets(N_COINS));
+    }
+
+    // Also checks that all assets belong to the pool and are not duplicated
+    info.funds
+        .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
+
+    let precisions = Precisions::new(deps.storage)?;
+    let mut pools = query_pools(deps.querier, &env.contract.address, &config, &precisions)?;
+
+    // Deposits are sorted in the same order as pools
+    let deposits = pools
+        .iter()
+        .map(|pool| {
+            let amount = assets
+                .iter()
+                .find(|asset| asset.info == pool.info)
+                .map(|asset| asset.amount)
+                .unwrap_or_default();
+            Decimal256::with_precision(amount, precisions.get_precision(&pool.info)?)
+                .map_err(Into::into)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+
+    // Initial provide can not be one-sided
+    if total_share.is_zero() && deposits.iter().any(Decimal256::is_zero) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut messages = vec![];
+    for (i, pool) in pools.iter_mut().enumerate() {
+        // If the asset is a token contract, then we need to execute a TransferFrom msg to receive assets
+        match &pool.info {
+            AssetInfo::Token { contract_addr } => {
+                if !deposits[i].is_zero() {
+                    messages.push(CosmosMsg::Wasm(wasm_execute(
+                        contract_addr,
+                        &Cw20ExecuteMsg::TransferFrom {
+                            owner: info.sender.to_string(),
+                            recipient: env.contract.address.to_string(),
+                            amount: deposits[i].to_uint(precisions.get_precision(&pool.info)?)?,
+                        },
+                        vec![],
+                    )?))
+                }
+            }
+            AssetInfo::NativeToken { .. } => {
+                // If the asset is native token, the pool balance is already increased
+                // To calculate the total amount of deposits properly, we should subtract the user deposit from the pool
+                pool.amount = pool.amount.checked_sub(deposits[i])?;
+            }
+        }
+    }
+
+    let new_xs = pools
+        .iter()
+        .zip(&deposits)
+        .map(|(pool, deposit)| pool.amount + *deposit)
+        .collect_vec();
+    let new_xp = to_internal_repr(&new_xs, &config.pool_state.price_state.price_scales);
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    assert_safe_pool_volumes(&new_xp, &amp_gamma)?;
+
+    let new_d = calc_d(&new_xp, &amp_gamma)?;
+
+    let share = if total_share.is_zero() {
+        let xcp = get_xcp_n(new_d, &config.pool_state.price_state.price_scales)?;
+        let mint_amount = xcp
+            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT.to_decimal256(LP_TOKEN_PRECISION)?)
+            .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+
+        messages.extend(mint_liquidity_token_message(
+            deps.querier,
+            &config,
+            &env.contract.address,
+            &env.contract.address,
+            MINIMUM_LIQUIDITY_AMOUNT,
+            false,
+        )?);
+
+        // share cannot become zero after minimum liquidity subtraction
+        if mint_amount.is_zero() {
+            return Err(ContractError::MinimumLiquidityAmountError {});
+        }
+
+        config.pool_state.price_state.xcp_profit_real = Decimal256::one();
+        config.pool_state.price_state.xcp_profit = Decimal256::one();
+
+        mint_amount
+    } else {
+        let old_xp = to_internal_repr(
+            &pools.iter().map(|a| a.amount).collect_vec(),
+            &config.pool_state.price_state.price_scales,
+        );
+        let old_d = calc_d(&old_xp, &amp_gamma)?;
+        let share = (total_share * new_d / old_d).saturating_sub(total_share);
+
+        let ideposits = to_internal_repr(&deposits, &config.pool_state.price_state.price_scales);
+
+        share * (Decimal256::one() - calc_provide_fee(&ideposits, &new_xp, &config.pool_params))
+    };
+
+    // calculate accrued share
+    let share_ratio = share / (total_share + share);
+    let imbalanced = new_xs
+        .iter()
+        .zip(&deposits)
+        .any(|(x, deposit)| deposit.diff(*x * share_ratio) >= MIN_TRADE_SIZE);
+
+    let mut slippage = Decimal256::zero();
+
+    // If deposit doesn't diverge too much from the balanced share, we don't update the price
+    if imbalanced {
+        slippage = assert_slippage_tolerance(
+            &deposits,
+            share,
+            &config.pool_state.price_state,
+            slippage_tolerance,
+        )?;
+
+        let last_prices = calc_last_prices(&new_xs, &config, &env)?;
+        config.pool_state.update_price(
+            &config.pool_params,
+            &env,
+            total_share + share,
+            &new_xp,
+            &last_prices,
+        )?;
+    }
+
+    let share_uint128 = share.to_uint(LP_TOKEN_PRECISION)?;
+
+    // Mint LP tokens for the sender or for the receiver (if set)
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let auto_stake = auto_stake.unwrap_or(false);
+    messages.extend(mint_liquidity_token_message(
+        deps.querier,
+        &config,
+        &env.contract.address,
+        &receiver,
+        share_uint128,
+        auto_stake,
+    )?);
+
+    CONFIG.save(deps.storage, &config)?;
+
+    let attrs = vec![
+        attr("action", "provide_liquidity"),
+        attr("sender", info.sender),
+        attr("receiver", receiver),
+        attr("assets", assets.iter().join(", ")),
+        attr("share", share_uint128),
+        attr("slippage", slippage.to_string()),
+    ];
+
+    Ok(Response::new().add_messages(messages).add_attributes(attrs))
+}
+
+/// Withdraw liquidity from the pool.
+///
+/// * **sender** address that will receive assets back from the pair contract
+///
+/// * **amount** amount of provided LP tokens
+///
+/// * **assets** defines number of coins a user wants to withdraw per each asset.
+fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    amount: Uint128,
+    assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.pair_info.liquidity_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(
+        deps.querier,
+        &config.pair_info.contract_addr,
+        &config,
+        &precisions,
+    )?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+
+    let refund_assets = if assets.is_empty() {
+        // Usual withdraw (balanced)
+        get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share)
+    } else {
+        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+    };
+
+    // decrease XCP
+    let xs = pools
+        .iter()
+        .zip(&refund_assets)
+        .map(|(pool, refund)| pool.amount - refund.amount)
+        .collect_vec();
+    let xp = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    let d = calc_d(&xp, &amp_gamma)?;
+    config.pool_state.price_state.xcp_profit_real =
+        get_xcp_n(d, &config.pool_state.price_state.price_scales)?
+            / (total_share - amount).to_decimal256(LP_TOKEN_PRECISION)?;
+
+    let refund_assets = refund_assets
+        .into_iter()
+        .map(|asset| {
+            let prec = precisions.get_precision(&asset.info).unwrap();
+
+            Ok(Asset {
+                info: asset.info,
+                amount: asset.amount.to_uint(prec)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut messages = refund_assets
+        .iter()
+        .cloned()
+        .map(|asset| asset.into_msg(&sender))
+        .collect::<StdResult<Vec<_>>>()?;
+    messages.push(
+        wasm_execute(
+            &config.pair_info.liquidity_token,
+            &Cw20ExecuteMsg::Burn { amount },
+            vec![],
+        )?
+        .into(),
+    );
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "withdraw_liquidity"),
+        attr("sender", sender),
+        attr("withdrawn_share", amount),
+        attr("refund_assets", refund_assets.iter().join(", ")),
+    ]))
+}
+
+/// Performs an swap operation with the specified parameters. The trader must approve the
+/// pool contract to transfer offer assets from their wallet.
+///
+/// * **sender** is the sender of the swap operation.
+///
+/// * **offer_asset** proposed asset for swapping.
+///
+/// * **ask_asset_info** the asset to swap to. It must be specified as the pool contains three assets.
+///
+/// * **belief_price** is used to calculate the maximum swap spread.
+///
+/// * **max_spread** sets the maximum spread of the swap operation.
+///
+/// * **to** sets the recipient of the swap operation.
+#[allow(clippy::too_many_arguments)]
+fn swap(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let precisions = Precisions::new(deps.storage)?;
+    let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
+    let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let mut pools = query_pools(deps.querier, &env.contract.address, &config, &precisions)?;
+
+    let (offer_ind, ask_ind) =
+        select_pools(Some(&offer_asset.info), ask_asset_info.as_ref(), &pools)?;
+    let ask_asset_prec = precisions.get_precision(&pools[ask_ind].info)?;
+
+    pools[offer_ind].amount -= offer_asset_dec.amount;
+
+    before_swap_check(&pools, offer_asset_dec.amount)?;
+
+    let mut xs = pools.iter().map(|asset| asset.amount).collect_vec();
+
+    // Get fee info from the factory
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let mut maker_fee_share = Decimal256::zero();
+    if fee_info.fee_address.is_some() {
+        maker_fee_share = fee_info.maker_fee_rate.into();
+    }
+
+    let swap_result = compute_swap(
+        &xs,
+        offer_asset_dec.amount,
+        offer_ind,
+        ask_ind,
+        &config,
+        &env,
+        maker_fee_share,
+    )?;
+    xs[offer_ind] += offer_asset_dec.amount;
+    xs[ask_ind] -= swap_result.dy + swap_result.maker_fee;
+
+    let return_amount = swap_result.dy.to_uint(ask_asset_prec)?;
+    let spread_amount = swap_result.spread_fee.to_uint(ask_asset_prec)?;
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_asset.amount,
+        return_amount,
+        spread_amount,
+    )?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+
+    // Skip very small trade sizes which could significantly mess up the price due to rounding errors,
+    // especially if token precisions are 18.
+    if (swap_result.dy + swap_result.maker_fee) >= MIN_TRADE_SIZE
+        && offer_asset_dec.amount >= MIN_TRADE_SIZE
+    {
+        let last_prices = calc_last_prices(&xs, &config, &env)?;
+
+        // update_price() works only with internal representation
+        let xp = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+        config.pool_state.update_price(
+            &config.pool_params,
+            &env,
+            total_share,
+            &xp,
+            &last_prices,
+        )?;
+    }
+
+    let receiver = to.unwrap_or_else(|| sender.clone());
+
+    let mut messages = vec![Asset {
+        info: pools[ask_ind].info.clone(),
+        amount: return_amount,
+    }
+    .into_msg(&receiver)?];
+
+    // Send the maker fee
+    let mut maker_fee = Uint128::zero();
+    if let Some(fee_address) = fee_info.fee_address {
+        maker_fee = swap_result.maker_fee.to_uint(ask_asset_prec)?;
+        if !maker_fee.is_zero() {
+            let fee = pools[ask_ind].info.with_balance(maker_fee);
+            messages.push(fee.into_msg(fee_address)?);
+        }
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "swap"),
+        attr("sender", sender),
+        attr("receiver", receiver),
+        attr("offer_asset", offer_asset_dec.info.to_string()),
+        attr("ask_asset", pools[ask_ind].info.to_string()),
+        attr("offer_amount", offer_asset.amount),
+        attr("return_amount", return_amount),
+        attr("spread_amount", spread_amount),
+        attr(
+            "commission_amount",
+            swap_result.total_fee.to_uint(ask_asset_prec)?,
+        ),
+        attr("maker_fee_amount", maker_fee),
+    ]))
+}
+
+/// Updates the pool configuration with the specified parameters in the `params` variable.
+///
+/// * **params** new parameter values in [`Binary`] form.
+fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+
+    let owner = config.owner.as_ref().unwrap_or(&factory_config.owner);
+    if info.sender != *owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let action = match from_binary::<TricryptoPoolUpdateParams>(&params)? {
+        TricryptoPoolUpdateParams::Update(update_params) => {
+            config.pool_params.update_params(update_params)?;
+            "update_params"
+        }
+        TricryptoPoolUpdateParams::Promote(promote_params) => {
+            config.pool_state.promote_params(&env, promote_params)?;
+            "promote_params"
+        }
+        TricryptoPoolUpdateParams::StopChangingAmpGamma {} => {
+            config.pool_state.stop_promotion(&env);
+            "stop_changing_amp_gamma"
+        }
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", action))
+}
@@ -0,0 +1,5 @@
+pub mod contract;
+pub mod state;
+
+pub mod error;
+pub mod queries;
@@ -0,0 +1,10 @@
+use cw_storage_plus::Item;
+
+use astroport::common::OwnershipProposal;
+use astroport_pcl_common::state::{Config, MultiPriceState};
+
+/// Stores pool parameters and state.
+pub const CONFIG: Item<Config<MultiPriceState>> = Item::new("config");
+
+/// Stores the latest contract ownership transfer proposal
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
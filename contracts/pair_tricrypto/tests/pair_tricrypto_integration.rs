@@ -0,0 +1,503 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{Addr, Decimal, Uint128};
+
+use astroport::asset::{native_asset_info, AssetInfoExt, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::pair::{ExecuteMsg, ReverseSimulationResponse, SimulationResponse};
+use astroport::pair_concentrated::{PromoteParams, UpdatePoolParams};
+use astroport::pair_tricrypto::{TricryptoPoolParams, TricryptoPoolUpdateParams};
+use astroport_mocks::cw_multi_test::Executor;
+use astroport_pair_tricrypto::error::ContractError;
+use astroport_pcl_common::consts::{AMP_MAX, AMP_MIN};
+use astroport_pcl_common::error::PclError;
+
+use crate::helper::{
+    common_tricrypto_params, dec_to_f64, f64_to_dec, AppExtension, Helper, TestCoin,
+};
+
+mod helper;
+
+fn test_coins() -> Vec<TestCoin> {
+    vec![
+        TestCoin::native("uusd"),
+        TestCoin::native("uluna"),
+        TestCoin::cw20("ETH"),
+    ]
+}
+
+#[test]
+fn check_wrong_initialization() {
+    let owner = Addr::unchecked("owner");
+
+    let err = Helper::new(
+        &owner,
+        vec![TestCoin::native("uluna"), TestCoin::cw20("ASTRO")],
+        common_tricrypto_params(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidNumberOfAssets(3),
+        err.downcast().unwrap()
+    );
+
+    let mut wrong_params = common_tricrypto_params();
+    wrong_params.amp = Decimal::zero();
+
+    let err = Helper::new(&owner, test_coins(), wrong_params).unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::IncorrectPoolParam(
+            "amp".to_string(),
+            AMP_MIN.to_string(),
+            AMP_MAX.to_string()
+        )),
+        err.downcast().unwrap(),
+    );
+
+    let wrong_params = TricryptoPoolParams {
+        price_scales: vec![Decimal::one()],
+        ..common_tricrypto_params()
+    };
+
+    let err = Helper::new(&owner, test_coins(), wrong_params).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: price_scales must contain exactly 2 elements",
+    );
+
+    let wrong_params = TricryptoPoolParams {
+        price_scales: vec![Decimal::one(), Decimal::zero()],
+        ..common_tricrypto_params()
+    };
+
+    let err = Helper::new(&owner, test_coins(), wrong_params).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Initial price scale can not be zero",
+    );
+
+    // check instantiation with valid params
+    Helper::new(&owner, test_coins(), common_tricrypto_params()).unwrap();
+}
+
+#[test]
+fn provide_and_withdraw() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = test_coins();
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_tricrypto_params()).unwrap();
+
+    // checking LP token virtual price on an empty pool
+    let lp_price = helper.query_lp_price().unwrap();
+    assert!(
+        lp_price.is_zero(),
+        "LP price must be zero before any provide"
+    );
+
+    let user1 = Addr::unchecked("user1");
+
+    let random_coin = native_asset_info("random-coin".to_string()).with_balance(100u8);
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(100_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.give_me_money(&assets, &user1);
+    helper.give_me_money(&[random_coin.clone()], &user1);
+
+    let err = helper
+        .provide_liquidity(
+            &user1,
+            &[
+                random_coin.clone(),
+                helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: Asset random-coin is not in the pool",
+        err.root_cause().to_string()
+    );
+
+    let err = helper.provide_liquidity(&user1, &[]).unwrap_err();
+    assert_eq!(
+        "Generic error: Nothing to provide",
+        err.root_cause().to_string()
+    );
+
+    // Try to provide 4 assets
+    let err = helper
+        .provide_liquidity(
+            &user1,
+            &[
+                random_coin,
+                helper.assets[&test_coins[0]].with_balance(1u8),
+                helper.assets[&test_coins[1]].with_balance(1u8),
+                helper.assets[&test_coins[2]].with_balance(1u8),
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidNumberOfAssets(3),
+        err.downcast().unwrap()
+    );
+
+    // Initial provide can't omit any asset
+    let err = helper
+        .provide_liquidity(
+            &user1,
+            &[
+                helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+                helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidZeroAmount {}, err.downcast().unwrap());
+
+    helper.provide_liquidity(&user1, &assets).unwrap();
+
+    assert_eq!(99999_999000, helper.token_balance(&helper.lp_token, &user1));
+    for coin in &test_coins {
+        assert_eq!(0, helper.coin_balance(coin, &user1));
+    }
+
+    assert_eq!(
+        helper
+            .query_share(helper.token_balance(&helper.lp_token, &user1))
+            .unwrap(),
+        test_coins
+            .iter()
+            .map(|coin| helper.assets[coin].with_balance(99999_998999u128))
+            .collect::<Vec<_>>()
+    );
+
+    // Balanced provide is not charged with fees
+    let user2 = Addr::unchecked("user2");
+    helper.give_me_money(&assets, &user2);
+    helper.provide_liquidity(&user2, &assets).unwrap();
+    assert_eq!(
+        99999_999000 + MINIMUM_LIQUIDITY_AMOUNT.u128(),
+        helper.token_balance(&helper.lp_token, &user2)
+    );
+
+    // After initial provide one-sided provide is allowed
+    let user3 = Addr::unchecked("user3");
+    let assets = vec![helper.assets[&test_coins[0]].with_balance(100_000_000000u128)];
+    helper.give_me_money(&assets, &user3);
+    helper.provide_liquidity(&user3, &assets).unwrap();
+    // LP amount is less than for prev users as provide is imbalanced
+    assert_eq!(29423_452185, helper.token_balance(&helper.lp_token, &user3));
+
+    // check that imbalanced withdraw is currently disabled
+    let withdraw_assets = vec![helper.assets[&test_coins[0]].with_balance(10_000_000000u128)];
+    let err = helper
+        .withdraw_liquidity(&user1, 9999_999900, withdraw_assets)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Imbalanced withdraw is currently disabled"
+    );
+
+    // user1 withdraws 1/10 of the LP tokens
+    helper
+        .withdraw_liquidity(&user1, 9999_999900, vec![])
+        .unwrap();
+
+    assert_eq!(
+        99999_999000 - 9999_999900,
+        helper.token_balance(&helper.lp_token, &user1)
+    );
+    assert_eq!(13076_256769, helper.coin_balance(&test_coins[0], &user1));
+    assert_eq!(8717_504512, helper.coin_balance(&test_coins[1], &user1));
+    assert_eq!(8717_504512, helper.coin_balance(&test_coins[2], &user1));
+}
+
+#[test]
+fn check_swaps_simple() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = test_coins();
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_tricrypto_params()).unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    let ask_asset_info = helper.assets[&test_coins[1]].clone();
+    helper.give_me_money(&[offer_asset.clone()], &user);
+
+    // Check swap does not work if pool is empty
+    let err = helper
+        .swap(&user, &offer_asset, &ask_asset_info, None)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: One of the pools is empty"
+    );
+
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(100_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    // The ask asset must be specified
+    let err = helper
+        .swap_full_params(&user, &offer_asset, None, None, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::VariableAssetMissed {}),
+        err.downcast().unwrap()
+    );
+
+    let err = helper
+        .swap(&user, &offer_asset, &offer_asset.info, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::SameAssets {}),
+        err.downcast().unwrap()
+    );
+
+    // Try to swap a wrong asset
+    let wrong_coin = native_asset_info("random-coin".to_string());
+    let wrong_asset = wrong_coin.with_balance(100_000000u128);
+    helper.give_me_money(&[wrong_asset.clone()], &user);
+    let err = helper
+        .swap(&user, &wrong_asset, &ask_asset_info, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidAsset(wrong_coin.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // trying to swap cw20 without calling Cw20::Send method
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: helper.assets[&test_coins[2]].with_balance(1u8),
+                ask_asset_info: Some(ask_asset_info.clone()),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Cw20DirectSwap {}, err.downcast().unwrap());
+
+    let d = helper.query_d().unwrap();
+    assert_eq!(dec_to_f64(d), 300000f64);
+
+    // The pool is balanced thus swaps between any pair of assets are equal
+    let sim_resp = helper
+        .simulate_swap(
+            &helper.assets[&test_coins[2]].with_balance(100_000000u128),
+            Some(helper.assets[&test_coins[0]].clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        sim_resp,
+        SimulationResponse {
+            return_amount: Uint128::new(99_738897),
+            spread_amount: Uint128::new(280),
+            commission_amount: Uint128::new(260821),
+        }
+    );
+
+    let sim_resp = helper
+        .simulate_swap(
+            &helper.assets[&test_coins[1]].with_balance(1000_000000u128),
+            Some(helper.assets[&test_coins[2]].clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        sim_resp,
+        SimulationResponse {
+            return_amount: Uint128::new(996_746343),
+            spread_amount: Uint128::new(78178),
+            commission_amount: Uint128::new(3175477),
+        }
+    );
+
+    let rev_sim_resp = helper
+        .simulate_reverse_swap(
+            &ask_asset_info.with_balance(99_000000u128),
+            Some(offer_asset.info.clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        rev_sim_resp,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::new(99_447791),
+            spread_amount: Uint128::new(277),
+            commission_amount: Uint128::new(447513),
+        }
+    );
+
+    let err = helper
+        .simulate_swap(&offer_asset, None)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Ask or offer asset is missed"),
+        "Unexpected error: {err}"
+    );
+
+    helper
+        .swap(&user, &offer_asset, &ask_asset_info, None)
+        .unwrap();
+    assert_eq!(0, helper.coin_balance(&test_coins[0], &user));
+    assert_eq!(99_738897, helper.coin_balance(&test_coins[1], &user));
+    // Half of the fee is sent to the Maker
+    assert_eq!(
+        130410,
+        helper.coin_balance(&test_coins[1], &helper.fake_maker)
+    );
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(90_000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let err = helper
+        .swap(&user, &offer_asset, &ask_asset_info, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::MaxSpreadAssertion {}),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn check_swaps_with_price_update() {
+    let owner = Addr::unchecked("owner");
+    let half = Decimal::from_ratio(1u8, 2u8);
+    let test_coins = test_coins();
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_tricrypto_params()).unwrap();
+
+    helper.app.next_block(1000);
+
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(100_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    helper.app.next_block(1000);
+
+    let user1 = Addr::unchecked("user1");
+    let mut prev_vlp_price = helper.query_lp_price().unwrap();
+
+    let mut swap = |helper: &mut Helper, offer_ind: usize, ask_ind: usize| {
+        let offer_asset = helper.assets[&test_coins[offer_ind]].with_balance(10_000_000000u128);
+        let ask_asset_info = helper.assets[&test_coins[ask_ind]].clone();
+        helper.give_me_money(&[offer_asset.clone()], &user1);
+        helper
+            .swap(&user1, &offer_asset, &ask_asset_info, Some(half))
+            .unwrap();
+        let new_vlp_price = helper.query_lp_price().unwrap();
+        assert!(
+            new_vlp_price >= prev_vlp_price,
+            "new_vlp_price <= prev_vlp_price ({new_vlp_price} <= {prev_vlp_price})",
+        );
+        prev_vlp_price = new_vlp_price;
+        helper.app.next_block(1000);
+    };
+
+    // ETH is being bought thus its price grows
+    for _ in 0..4 {
+        swap(&mut helper, 0, 2);
+    }
+
+    let config = helper.query_pool_config().unwrap();
+    assert!(
+        config.price_scales[1] > Decimal::one(),
+        "ETH price scale was not repegged: {:?}",
+        config.price_scales
+    );
+    assert!(config.oracle_prices[1] > config.price_scales[1]);
+
+    for _ in 0..4 {
+        swap(&mut helper, 2, 0);
+    }
+}
+
+#[test]
+fn check_unsupported_operations() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = test_coins();
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_tricrypto_params()).unwrap();
+
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(100_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::PlaceLimitOrder {
+                offer_asset,
+                min_price: Decimal::one(),
+                expiry: None,
+            },
+            &[cosmwasm_std::coin(100_000000u128, "uusd")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Limit orders are not supported by this pool"
+    );
+}
+
+#[test]
+fn check_update_config() {
+    let owner = Addr::unchecked("owner");
+
+    let mut helper = Helper::new(&owner, test_coins(), common_tricrypto_params()).unwrap();
+
+    let action = TricryptoPoolUpdateParams::Update(UpdatePoolParams {
+        mid_fee: Some(f64_to_dec(0.003)),
+        out_fee: None,
+        fee_gamma: None,
+        repeg_profit_threshold: None,
+        min_price_scale_delta: None,
+        ma_half_time: None,
+    });
+
+    let err = helper
+        .update_config(&Addr::unchecked("random_user"), &action)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    helper.update_config(&owner, &action).unwrap();
+    assert_eq!(
+        helper.query_pool_config().unwrap().mid_fee,
+        f64_to_dec(0.003)
+    );
+
+    helper.app.next_block(86400);
+
+    let block_time = helper.app.block_info().time.seconds();
+    let action = TricryptoPoolUpdateParams::Promote(PromoteParams {
+        next_amp: f64_to_dec(44f64),
+        next_gamma: f64_to_dec(0.00015),
+        future_time: block_time + 86400,
+    });
+    helper.update_config(&owner, &action).unwrap();
+
+    helper.app.next_block(86400 / 2);
+    let amp_gamma = helper.query_amp_gamma().unwrap();
+    assert_eq!(dec_to_f64(amp_gamma.amp), 42f64);
+    assert_eq!(dec_to_f64(amp_gamma.gamma), 0.0001475);
+
+    helper
+        .update_config(&owner, &TricryptoPoolUpdateParams::StopChangingAmpGamma {})
+        .unwrap();
+    helper.app.next_block(86400);
+    let amp_gamma = helper.query_amp_gamma().unwrap();
+    assert_eq!(dec_to_f64(amp_gamma.amp), 42f64);
+    assert_eq!(dec_to_f64(amp_gamma.gamma), 0.0001475);
+}
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, wasm_execute, Addr, DepsMut, Env, MessageInfo, Reply, ReplyOn,
+    coin, from_binary, to_binary, wasm_execute, Addr, DepsMut, Env, MessageInfo, Reply, ReplyOn,
     Response, StdError, StdResult, SubMsg, Uint128,
 };
 use cw20::{Cw20ExecuteMsg, Expiration};
@@ -11,14 +11,17 @@ use astroport::factory::PairType;
 use astroport::liquidity_manager::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
 use astroport::pair::{
     Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
-    QueryMsg,
+    QueryMsg, MAX_ALLOWED_SLIPPAGE,
 };
 use astroport::querier::query_supply;
 use astroport_pair::contract::get_share_in_assets;
 
 use crate::error::ContractError;
 use crate::state::{ActionParams, Config, ReplyData, CONFIG, REPLY_DATA};
-use crate::utils::{query_cw20_minter, query_lp_amount, xyk_provide_simulation};
+use crate::utils::{
+    query_cw20_minter, query_lp_amount, query_pair_fee_info, swap_msg, xyk_maker_fee_rate,
+    xyk_provide_simulation, xyk_zap_swap_amount,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -55,6 +58,25 @@ pub fn execute(
             let pair_addr = deps.api.addr_validate(&pair_addr)?;
             provide_liquidity(deps, info, env, pair_addr, msg, min_lp_to_receive)
         }
+        ExecuteMsg::ZapIn {
+            pair_addr,
+            offer_asset,
+            min_lp_to_receive,
+            auto_stake,
+            receiver,
+        } => {
+            let pair_addr = deps.api.addr_validate(&pair_addr)?;
+            zap_in(
+                deps,
+                info,
+                env,
+                pair_addr,
+                offer_asset,
+                min_lp_to_receive,
+                auto_stake,
+                receiver,
+            )
+        }
         ExecuteMsg::Receive(cw20_msg) => match from_binary(&cw20_msg.msg)? {
             Cw20HookMsg::WithdrawLiquidity {
                 pair_msg: msg,
@@ -67,6 +89,18 @@ pub fn execute(
                 msg,
                 min_assets_to_receive,
             ),
+            Cw20HookMsg::ZapOut {
+                ask_asset_info,
+                min_out,
+            } => zap_out(
+                deps,
+                env,
+                info.sender,
+                Addr::unchecked(cw20_msg.sender),
+                cw20_msg.amount,
+                ask_asset_info,
+                min_out,
+            ),
             _ => Err(ContractError::UnsupportedCw20HookMsg {}),
         },
     }
@@ -74,6 +108,9 @@ pub fn execute(
 
 const WITHDRAW_LIQUIDITY_REPLY_ID: u64 = 1;
 const PROVIDE_LIQUIDITY_REPLY_ID: u64 = 2;
+const ZAP_IN_SWAP_REPLY_ID: u64 = 3;
+const ZAP_OUT_WITHDRAW_REPLY_ID: u64 = 4;
+const ZAP_OUT_SWAP_REPLY_ID: u64 = 5;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
@@ -150,6 +187,178 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
             }
             _ => Err(ContractError::InvalidReplyData {}),
         },
+        ZAP_IN_SWAP_REPLY_ID => match REPLY_DATA.load(deps.storage)? {
+            ReplyData {
+                receiver,
+                params:
+                    ActionParams::ZapIn {
+                        pair_addr,
+                        sender,
+                        auto_stake,
+                        min_lp_to_receive,
+                        balances_before,
+                    },
+            } => {
+                let pair_info: PairInfo = deps
+                    .querier
+                    .query_wasm_smart(&pair_addr, &QueryMsg::Pair {})?;
+                let pools = pair_info.query_pools(&deps.querier, &pair_addr)?;
+                // The remaining offer asset and the swap return
+                let deposits = query_received_assets(
+                    &deps,
+                    &pair_info,
+                    &env.contract.address,
+                    &balances_before,
+                )?;
+
+                // Trim deposits to the pool ratio and return the excess back to the sender
+                let predicted_lp_amount = xyk_provide_simulation(
+                    deps.querier,
+                    &pools,
+                    &pair_info,
+                    None,
+                    deposits.clone(),
+                )?;
+                let pools_with_deposits = pools
+                    .into_iter()
+                    .zip(deposits.iter())
+                    .map(|(mut pool, deposit)| {
+                        pool.amount += deposit.amount;
+                        pool
+                    })
+                    .collect::<Vec<_>>();
+                let total_share = query_supply(&deps.querier, &pair_info.liquidity_token)?;
+                let share = get_share_in_assets(
+                    &pools_with_deposits,
+                    predicted_lp_amount,
+                    total_share + predicted_lp_amount,
+                );
+
+                let mut submessages = vec![];
+                let mut funds = vec![];
+                let mut assets = vec![];
+                for (deposit, share_asset) in deposits.into_iter().zip(share) {
+                    let amount = share_asset.amount.min(deposit.amount);
+                    let excess_amount = deposit.amount - amount;
+                    if !excess_amount.is_zero() {
+                        submessages.push(SubMsg::new(
+                            deposit.info.with_balance(excess_amount).into_msg(&sender)?,
+                        ));
+                    }
+
+                    match &deposit.info {
+                        AssetInfo::Token { contract_addr } if !amount.is_zero() => {
+                            submessages.push(SubMsg::new(wasm_execute(
+                                contract_addr,
+                                &Cw20ExecuteMsg::IncreaseAllowance {
+                                    spender: pair_addr.to_string(),
+                                    amount,
+                                    expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                                },
+                                vec![],
+                            )?));
+                        }
+                        AssetInfo::NativeToken { denom } if !amount.is_zero() => {
+                            funds.push(coin(amount.u128(), denom));
+                        }
+                        _ => {}
+                    }
+
+                    assets.push(deposit.info.with_balance(amount));
+                }
+                funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+                let provide_msg = PairExecuteMsg::ProvideLiquidity {
+                    assets,
+                    slippage_tolerance: None,
+                    auto_stake: Some(auto_stake),
+                    receiver: Some(receiver.clone()),
+                };
+                let mut provide_msg = SubMsg::new(wasm_execute(&pair_addr, &provide_msg, funds)?);
+                if let Some(min_lp_to_receive) = min_lp_to_receive {
+                    enforce_min_lp(
+                        deps,
+                        &mut provide_msg,
+                        &pair_info.liquidity_token,
+                        &receiver,
+                        auto_stake,
+                        min_lp_to_receive,
+                    )?;
+                }
+                submessages.push(provide_msg);
+
+                Ok(Response::new()
+                    .add_submessages(submessages)
+                    .add_attribute("liquidity_manager", "zap_in_swap_done"))
+            }
+            _ => Err(ContractError::InvalidReplyData {}),
+        },
+        ZAP_OUT_WITHDRAW_REPLY_ID => match REPLY_DATA.load(deps.storage)? {
+            ReplyData {
+                receiver,
+                params:
+                    ActionParams::ZapOut {
+                        pair_addr,
+                        ask_asset_info,
+                        min_out,
+                        balances_before,
+                    },
+            } => {
+                let pair_info: PairInfo = deps
+                    .querier
+                    .query_wasm_smart(&pair_addr, &QueryMsg::Pair {})?;
+                let offer_asset = query_received_assets(
+                    &deps,
+                    &pair_info,
+                    &env.contract.address,
+                    &balances_before,
+                )?
+                .into_iter()
+                .find(|asset| !asset.info.equal(&ask_asset_info))
+                .ok_or(ContractError::InvalidReplyData {})?;
+
+                if offer_asset.amount.is_zero() {
+                    zap_out_send(
+                        deps,
+                        env,
+                        receiver,
+                        ask_asset_info,
+                        min_out,
+                        balances_before,
+                    )
+                } else {
+                    let swap_msg = SubMsg::reply_on_success(
+                        swap_msg(&pair_addr, &offer_asset, &ask_asset_info)?,
+                        ZAP_OUT_SWAP_REPLY_ID,
+                    );
+
+                    Ok(Response::new()
+                        .add_submessage(swap_msg)
+                        .add_attribute("liquidity_manager", "zap_out_withdraw_done"))
+                }
+            }
+            _ => Err(ContractError::InvalidReplyData {}),
+        },
+        ZAP_OUT_SWAP_REPLY_ID => match REPLY_DATA.load(deps.storage)? {
+            ReplyData {
+                receiver,
+                params:
+                    ActionParams::ZapOut {
+                        ask_asset_info,
+                        min_out,
+                        balances_before,
+                        ..
+                    },
+            } => zap_out_send(
+                deps,
+                env,
+                receiver,
+                ask_asset_info,
+                min_out,
+                balances_before,
+            ),
+            _ => Err(ContractError::InvalidReplyData {}),
+        },
         _ => Err(StdError::generic_err(format!("Unsupported reply id {}", msg.id)).into()),
     }
 }
@@ -162,6 +371,23 @@ fn provide_liquidity(
     exec_msg: PairExecuteMsg,
     min_lp_to_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    let submessages =
+        provide_liquidity_msgs(deps, info, env, pair_addr, exec_msg, min_lp_to_receive)?;
+
+    Ok(Response::new()
+        .add_submessages(submessages)
+        .add_attribute("contract", "liquidity_manager")
+        .add_attribute("action", "provide_liquidity"))
+}
+
+fn provide_liquidity_msgs(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    pair_addr: Addr,
+    exec_msg: PairExecuteMsg,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<Vec<SubMsg>, ContractError> {
     if let PairExecuteMsg::ProvideLiquidity {
         mut assets,
         slippage_tolerance,
@@ -253,7 +479,9 @@ fn provide_liquidity(
                     vec![]
                 }
             }
-            _ => vec![],
+            PairType::Stable {} => vec![],
+            PairType::Custom(pair_type) if pair_type == "concentrated" => vec![],
+            pair_type => return Err(ContractError::UnsupportedPairType(pair_type.to_string())),
         };
 
         // pull cw20 tokens and increase allowance for pair contract
@@ -299,39 +527,20 @@ fn provide_liquidity(
         let mut provide_msg = SubMsg::new(wasm_execute(&pair_addr, &tweaked_exec_msg, funds)?);
 
         if let Some(min_lp_to_receive) = min_lp_to_receive {
-            let staked_in_generator = auto_stake.unwrap_or(false);
-            let config = CONFIG.load(deps.storage)?;
-            let lp_amount_before = query_lp_amount(
-                deps.querier,
-                pair_info.liquidity_token.to_string(),
-                config.factory_addr,
-                staked_in_generator,
-                &receiver.to_string(),
-            )?;
-
-            REPLY_DATA.save(
-                deps.storage,
-                &ReplyData {
-                    receiver: receiver.to_string(),
-                    params: ActionParams::Provide {
-                        lp_token_addr: pair_info.liquidity_token.to_string(),
-                        lp_amount_before,
-                        min_lp_to_receive,
-                        staked_in_generator,
-                    },
-                },
+            enforce_min_lp(
+                deps,
+                &mut provide_msg,
+                &pair_info.liquidity_token,
+                receiver.as_str(),
+                auto_stake.unwrap_or(false),
+                min_lp_to_receive,
             )?;
-            provide_msg.reply_on = ReplyOn::Success;
-            provide_msg.id = PROVIDE_LIQUIDITY_REPLY_ID;
         } else {
             // no need to reply as user decided not to enforce minimum LP token amount check
         }
         submessages.push(provide_msg);
 
-        Ok(Response::new()
-            .add_submessages(submessages)
-            .add_attribute("contract", "liquidity_manager")
-            .add_attribute("action", "provide_liquidity"))
+        Ok(submessages)
     } else {
         Err(ContractError::UnsupportedExecuteMsg {})
     }
@@ -394,3 +603,282 @@ fn withdraw_liquidity(
         .add_attribute("contract", "liquidity_manager")
         .add_attribute("action", "withdraw_liquidity"))
 }
+
+/// Saves the data for the minimum LP amount check and makes the provide message reply on success.
+fn enforce_min_lp(
+    deps: DepsMut,
+    provide_msg: &mut SubMsg,
+    lp_token_addr: &Addr,
+    receiver: &str,
+    staked_in_generator: bool,
+    min_lp_to_receive: Uint128,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let lp_amount_before = query_lp_amount(
+        deps.querier,
+        lp_token_addr.to_string(),
+        config.factory_addr,
+        staked_in_generator,
+        &receiver.to_string(),
+    )?;
+
+    REPLY_DATA.save(
+        deps.storage,
+        &ReplyData {
+            receiver: receiver.to_string(),
+            params: ActionParams::Provide {
+                lp_token_addr: lp_token_addr.to_string(),
+                lp_amount_before,
+                min_lp_to_receive,
+                staked_in_generator,
+            },
+        },
+    )?;
+    provide_msg.reply_on = ReplyOn::Success;
+    provide_msg.id = PROVIDE_LIQUIDITY_REPLY_ID;
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn zap_in(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    pair_addr: Addr,
+    offer_asset: Asset,
+    min_lp_to_receive: Option<Uint128>,
+    auto_stake: Option<bool>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&pair_addr, &PairQueryMsg::Pair {})?;
+
+    if pair_info.asset_infos.len() != 2 {
+        return Err(ContractError::WrongPoolLength {});
+    }
+    if !pair_info.asset_infos.contains(&offer_asset.info) {
+        return Err(ContractError::AssetNotInPair(offer_asset.info.to_string()));
+    }
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::ZapAmountTooSmall {});
+    }
+    let ask_asset_info = pair_info
+        .asset_infos
+        .iter()
+        .find(|asset_info| !asset_info.equal(&offer_asset.info))
+        .cloned()
+        .ok_or(ContractError::WrongPoolLength {})?;
+
+    let submessages = match &pair_info.pair_type {
+        // Xyk pair mints LP tokens only for the balanced part of a deposit;
+        // Swapping the optimal part of the offer asset first
+        PairType::Xyk {} => {
+            let pools = pair_info.query_pools(&deps.querier, &pair_addr)?;
+            if pools.iter().any(|pool| pool.amount.is_zero()) {
+                return Err(ContractError::ZapEmptyPool {});
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            let config = CONFIG.load(deps.storage)?;
+            let fee_info = query_pair_fee_info(&deps.querier, &config.factory_addr, &pair_info)?;
+            let offer_pool = pools
+                .iter()
+                .find(|pool| pool.info.equal(&offer_asset.info))
+                .map(|pool| pool.amount)
+                .unwrap_or_default();
+            let swap_amount = xyk_zap_swap_amount(
+                offer_pool,
+                offer_asset.amount,
+                fee_info.total_fee_rate,
+                xyk_maker_fee_rate(&fee_info),
+            )?;
+            if swap_amount.is_zero() || swap_amount >= offer_asset.amount {
+                return Err(ContractError::ZapAmountTooSmall {});
+            }
+
+            // Native offer funds are already credited to the contract
+            let mut balances_before =
+                pair_info.query_pools(&deps.querier, &env.contract.address)?;
+            if offer_asset.is_native_token() {
+                for balance in balances_before.iter_mut() {
+                    if balance.info.equal(&offer_asset.info) {
+                        balance.amount = balance
+                            .amount
+                            .checked_sub(offer_asset.amount)
+                            .map_err(StdError::from)?;
+                    }
+                }
+            }
+
+            let mut submessages = vec![];
+            if let AssetInfo::Token { contract_addr } = &offer_asset.info {
+                submessages.push(SubMsg::new(wasm_execute(
+                    contract_addr,
+                    &Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: offer_asset.amount,
+                    },
+                    vec![],
+                )?));
+            }
+            submessages.push(SubMsg::reply_on_success(
+                swap_msg(
+                    &pair_addr,
+                    &offer_asset.info.with_balance(swap_amount),
+                    &ask_asset_info,
+                )?,
+                ZAP_IN_SWAP_REPLY_ID,
+            ));
+
+            let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender.clone());
+            REPLY_DATA.save(
+                deps.storage,
+                &ReplyData {
+                    receiver: receiver.to_string(),
+                    params: ActionParams::ZapIn {
+                        pair_addr,
+                        sender: info.sender,
+                        auto_stake: auto_stake.unwrap_or(false),
+                        min_lp_to_receive,
+                        balances_before,
+                    },
+                },
+            )?;
+
+            submessages
+        }
+        PairType::Custom(pair_type) if pair_type != "concentrated" => {
+            return Err(ContractError::UnsupportedPairType(
+                pair_info.pair_type.to_string(),
+            ))
+        }
+        // Stable and concentrated pools support single-sided provide natively
+        _ => {
+            let provide_msg = PairExecuteMsg::ProvideLiquidity {
+                assets: vec![offer_asset, ask_asset_info.with_balance(0u8)],
+                slippage_tolerance: Some(MAX_ALLOWED_SLIPPAGE.parse()?),
+                auto_stake,
+                receiver,
+            };
+            provide_liquidity_msgs(deps, info, env, pair_addr, provide_msg, min_lp_to_receive)?
+        }
+    };
+
+    Ok(Response::new()
+        .add_submessages(submessages)
+        .add_attribute("contract", "liquidity_manager")
+        .add_attribute("action", "zap_in"))
+}
+
+fn zap_out(
+    deps: DepsMut,
+    env: Env,
+    lp_token_addr: Addr,
+    receiver: Addr,
+    amount: Uint128,
+    ask_asset_info: AssetInfo,
+    min_out: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let pair_addr = query_cw20_minter(deps.querier, lp_token_addr.clone())?;
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&pair_addr, &QueryMsg::Pair {})?;
+
+    if pair_info.asset_infos.len() != 2 {
+        return Err(ContractError::WrongPoolLength {});
+    }
+    if !pair_info.asset_infos.contains(&ask_asset_info) {
+        return Err(ContractError::AssetNotInPair(ask_asset_info.to_string()));
+    }
+
+    let withdraw_msg = wasm_execute(
+        lp_token_addr,
+        &Cw20ExecuteMsg::Send {
+            contract: pair_addr.to_string(),
+            amount,
+            msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+        },
+        vec![],
+    )?;
+    let msg_with_reply = SubMsg::reply_on_success(withdraw_msg, ZAP_OUT_WITHDRAW_REPLY_ID);
+    let balances_before = pair_info.query_pools(&deps.querier, env.contract.address)?;
+
+    REPLY_DATA.save(
+        deps.storage,
+        &ReplyData {
+            receiver: receiver.to_string(),
+            params: ActionParams::ZapOut {
+                pair_addr,
+                ask_asset_info,
+                min_out: min_out.unwrap_or_default(),
+                balances_before,
+            },
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(msg_with_reply)
+        .add_attribute("contract", "liquidity_manager")
+        .add_attribute("action", "zap_out"))
+}
+
+/// Checks the amount of the ask asset received by the contract since the zap started and sends it to the receiver.
+fn zap_out_send(
+    deps: DepsMut,
+    env: Env,
+    receiver: String,
+    ask_asset_info: AssetInfo,
+    min_out: Uint128,
+    balances_before: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let balance_before = balances_before
+        .iter()
+        .find(|asset| asset.info.equal(&ask_asset_info))
+        .map(|asset| asset.amount)
+        .unwrap_or_default();
+    let received = ask_asset_info
+        .query_pool(&deps.querier, env.contract.address)?
+        .checked_sub(balance_before)
+        .map_err(StdError::from)?;
+    if received < min_out {
+        return Err(ContractError::WithdrawSlippageViolation {
+            asset_name: ask_asset_info.to_string(),
+            received,
+            expected: min_out,
+        });
+    }
+
+    let mut response = Response::new().add_attribute("liquidity_manager", "zap_out_check_passed");
+    if !received.is_zero() {
+        response = response.add_message(ask_asset_info.with_balance(received).into_msg(receiver)?);
+    }
+
+    Ok(response)
+}
+
+/// Returns the amounts of the pair assets the contract received since `balances_before` was recorded.
+/// Funds which were held by the contract before are not included.
+fn query_received_assets(
+    deps: &DepsMut,
+    pair_info: &PairInfo,
+    contract_addr: &Addr,
+    balances_before: &[Asset],
+) -> Result<Vec<Asset>, ContractError> {
+    pair_info
+        .query_pools(&deps.querier, contract_addr)?
+        .into_iter()
+        .map(|mut asset| {
+            if let Some(before) = balances_before
+                .iter()
+                .find(|before| before.info.equal(&asset.info))
+            {
+                asset.amount = asset.amount.checked_sub(before.amount)?;
+            }
+            Ok(asset)
+        })
+        .collect::<StdResult<Vec<_>>>()
+        .map_err(Into::into)
+}
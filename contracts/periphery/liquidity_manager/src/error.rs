@@ -38,4 +38,13 @@ pub enum ContractError {
 
     #[error("Liquidity manager supports only pools with 2 assets")]
     WrongPoolLength {},
+
+    #[error("Pair type {0} is not supported")]
+    UnsupportedPairType(String),
+
+    #[error("Zap requires a pool with liquidity")]
+    ZapEmptyPool {},
+
+    #[error("Zap amount is too small")]
+    ZapAmountTooSmall {},
 }
@@ -2,15 +2,20 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_binary, Binary, Deps, Env, StdError, StdResult, Uint128};
 
-use astroport::asset::{Asset, PairInfo};
+use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::factory::PairType;
 use astroport::liquidity_manager::QueryMsg;
-use astroport::pair::{ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg};
+use astroport::pair::{ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, SimulationResponse};
 use astroport::querier::query_supply;
-use astroport_pair::contract::get_share_in_assets;
+use astroport_pair::contract::{compute_swap, get_share_in_assets};
 
 use crate::error::ContractError;
-use crate::utils::{convert_config, stableswap_provide_simulation, xyk_provide_simulation};
+use crate::state::CONFIG;
+use crate::utils::{
+    convert_config, pcl_provide_simulation, pool_manager_provide_simulation,
+    pool_manager_withdraw_simulation, query_pair_fee_info, query_pool_manager_config,
+    stableswap_provide_simulation, xyk_provide_simulation, xyk_zap_in_simulation,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -22,7 +27,17 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SimulateWithdraw {
             pair_addr,
             lp_tokens,
-        } => simulate_withdraw(deps, pair_addr, lp_tokens),
+            asset_infos,
+        } => simulate_withdraw(deps, pair_addr, lp_tokens, asset_infos),
+        QueryMsg::SimulateZapIn {
+            pair_addr,
+            offer_asset,
+        } => simulate_zap_in(deps, env, pair_addr, offer_asset),
+        QueryMsg::SimulateZapOut {
+            pair_addr,
+            lp_tokens,
+            ask_asset_info,
+        } => simulate_zap_out(deps, pair_addr, lp_tokens, ask_asset_info),
     }
 }
 
@@ -45,6 +60,24 @@ fn simulate_provide(
                 )));
             }
             let pair_addr = deps.api.addr_validate(&pair_addr)?;
+
+            // Pools hosted by the pool manager are identified by their assets
+            let asset_infos = assets
+                .iter()
+                .map(|asset| asset.info.clone())
+                .collect::<Vec<_>>();
+            if let Some(pool_config) =
+                query_pool_manager_config(deps.querier, &pair_addr, &asset_infos)?
+            {
+                return to_binary(&pool_manager_provide_simulation(
+                    deps.querier,
+                    &env,
+                    &pair_addr,
+                    &pool_config,
+                    &assets,
+                )?);
+            }
+
             let pair_info: PairInfo = deps
                 .querier
                 .query_wasm_smart(&pair_addr, &PairQueryMsg::Pair {})?;
@@ -114,18 +147,38 @@ fn simulate_provide(
                         .map_err(|err| StdError::generic_err(format!("{err}")))?,
                     )
                 }
-                PairType::Custom(..) => unimplemented!("not implemented yet"),
+                PairType::Custom(pair_type) if pair_type == "concentrated" => to_binary(
+                    &pcl_provide_simulation(deps.querier, &env, &pair_addr, &assets)?,
+                ),
+                pair_type => Err(StdError::generic_err(format!(
+                    "{}",
+                    ContractError::UnsupportedPairType(pair_type.to_string())
+                ))),
             }
         }
         _ => Err(StdError::generic_err("Invalid simulate message")),
     }
 }
 
-fn simulate_withdraw(deps: Deps, pair_addr: String, lp_tokens: Uint128) -> StdResult<Binary> {
+fn simulate_withdraw(
+    deps: Deps,
+    pair_addr: String,
+    lp_tokens: Uint128,
+    asset_infos: Option<Vec<AssetInfo>>,
+) -> StdResult<Binary> {
     let pair_addr = deps.api.addr_validate(&pair_addr)?;
-    let assets: Vec<Asset> = deps
-        .querier
-        .query_wasm_smart(pair_addr, &PairQueryMsg::Share { amount: lp_tokens })?;
+    let assets: Vec<Asset> = match asset_infos {
+        Some(asset_infos) => {
+            let pool_config = query_pool_manager_config(deps.querier, &pair_addr, &asset_infos)?
+                .ok_or_else(|| {
+                    StdError::generic_err(format!("Pool manager {pair_addr} has no such pool"))
+                })?;
+            pool_manager_withdraw_simulation(deps.querier, &pair_addr, &pool_config, lp_tokens)?
+        }
+        None => deps
+            .querier
+            .query_wasm_smart(pair_addr, &PairQueryMsg::Share { amount: lp_tokens })?,
+    };
 
     if assets.len() != 2 {
         return Err(StdError::generic_err(format!(
@@ -137,6 +190,139 @@ fn simulate_withdraw(deps: Deps, pair_addr: String, lp_tokens: Uint128) -> StdRe
     to_binary(&assets)
 }
 
+/// Queries the pair info and checks that the pair has 2 assets including `asset_info`.
+fn query_zap_pair_info(deps: Deps, pair_addr: &str, asset_info: &AssetInfo) -> StdResult<PairInfo> {
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(pair_addr, &PairQueryMsg::Pair {})?;
+
+    if pair_info.asset_infos.len() != 2 {
+        return Err(StdError::generic_err(format!(
+            "{}",
+            ContractError::WrongPoolLength {}
+        )));
+    }
+    if !pair_info.asset_infos.contains(asset_info) {
+        return Err(StdError::generic_err(format!(
+            "{}",
+            ContractError::AssetNotInPair(asset_info.to_string())
+        )));
+    }
+
+    Ok(pair_info)
+}
+
+fn simulate_zap_in(
+    deps: Deps,
+    env: Env,
+    pair_addr: String,
+    offer_asset: Asset,
+) -> StdResult<Binary> {
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+    let pair_info = query_zap_pair_info(deps, pair_addr.as_str(), &offer_asset.info)?;
+
+    let lp_amount = match &pair_info.pair_type {
+        PairType::Xyk {} => {
+            let pools = pair_info.query_pools(&deps.querier, &pair_addr)?;
+            if pools.iter().any(|pool| pool.amount.is_zero()) {
+                return Err(StdError::generic_err(format!(
+                    "{}",
+                    ContractError::ZapEmptyPool {}
+                )));
+            }
+            let factory_addr = CONFIG.load(deps.storage)?.factory_addr;
+            xyk_zap_in_simulation(
+                deps.querier,
+                &factory_addr,
+                &pools,
+                &pair_info,
+                &offer_asset,
+            )
+            .map_err(|err| StdError::generic_err(format!("{err}")))?
+        }
+        PairType::Stable {} => {
+            let pair_config_data = deps
+                .querier
+                .query_wasm_raw(&pair_addr, b"config")?
+                .ok_or_else(|| StdError::generic_err("pair stable config not found"))?;
+            let pair_config = convert_config(deps.querier, pair_config_data)?;
+            stableswap_provide_simulation(deps.querier, env, pair_config, None, vec![offer_asset])
+                .map_err(|err| StdError::generic_err(format!("{err}")))?
+        }
+        PairType::Custom(pair_type) if pair_type == "concentrated" => {
+            pcl_provide_simulation(deps.querier, &env, &pair_addr, &[offer_asset])?
+        }
+        pair_type => {
+            return Err(StdError::generic_err(format!(
+                "{}",
+                ContractError::UnsupportedPairType(pair_type.to_string())
+            )))
+        }
+    };
+
+    to_binary(&lp_amount)
+}
+
+fn simulate_zap_out(
+    deps: Deps,
+    pair_addr: String,
+    lp_tokens: Uint128,
+    ask_asset_info: AssetInfo,
+) -> StdResult<Binary> {
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+    let pair_info = query_zap_pair_info(deps, pair_addr.as_str(), &ask_asset_info)?;
+
+    let withdrawn: Vec<Asset> = deps
+        .querier
+        .query_wasm_smart(&pair_addr, &PairQueryMsg::Share { amount: lp_tokens })?;
+    let (ask_assets, offer_assets): (Vec<_>, Vec<_>) = withdrawn
+        .into_iter()
+        .partition(|asset| asset.info.equal(&ask_asset_info));
+    let ask_amount = ask_assets.iter().map(|asset| asset.amount).sum::<Uint128>();
+    let offer_asset = match offer_assets.into_iter().next() {
+        Some(asset) if !asset.amount.is_zero() => asset,
+        _ => return to_binary(&ask_amount),
+    };
+
+    let return_amount = match &pair_info.pair_type {
+        // Xyk swap is simulated against the pool after the withdrawal
+        PairType::Xyk {} => {
+            let pools = pair_info.query_pools(&deps.querier, &pair_addr)?;
+            let (mut offer_pool, mut ask_pool) = (Uint128::zero(), Uint128::zero());
+            for pool in pools {
+                if pool.info.equal(&ask_asset_info) {
+                    ask_pool = pool.amount - ask_amount;
+                } else {
+                    offer_pool = pool.amount - offer_asset.amount;
+                }
+            }
+            let factory_addr = CONFIG.load(deps.storage)?.factory_addr;
+            let fee_info = query_pair_fee_info(&deps.querier, &factory_addr, &pair_info)?;
+
+            compute_swap(
+                offer_pool,
+                ask_pool,
+                offer_asset.amount,
+                fee_info.total_fee_rate,
+            )?
+            .0
+        }
+        _ => {
+            deps.querier
+                .query_wasm_smart::<SimulationResponse>(
+                    &pair_addr,
+                    &PairQueryMsg::Simulation {
+                        offer_asset,
+                        ask_asset_info: Some(ask_asset_info),
+                    },
+                )?
+                .return_amount
+        }
+    };
+
+    to_binary(&(ask_amount + return_amount))
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -173,6 +359,7 @@ mod tests {
         let withdraw_query_msg = QueryMsg::SimulateWithdraw {
             pair_addr: "wasm1...addr".to_string(),
             lp_tokens: 1000u16.into(),
+            asset_infos: None,
         };
 
         println!(
@@ -2,7 +2,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::Item;
 
-use astroport::asset::Asset;
+use astroport::asset::{Asset, AssetInfo};
 
 #[cw_serde]
 pub struct Config {
@@ -23,6 +23,21 @@ pub enum ActionParams {
         pair_addr: Addr,
         min_assets_to_receive: Vec<Asset>,
     },
+    ZapIn {
+        pair_addr: Addr,
+        sender: Addr,
+        auto_stake: bool,
+        min_lp_to_receive: Option<Uint128>,
+        /// The contract balances of the pair assets before the zap, excluding the offered funds
+        balances_before: Vec<Asset>,
+    },
+    ZapOut {
+        pair_addr: Addr,
+        ask_asset_info: AssetInfo,
+        min_out: Uint128,
+        /// The contract balances of the pair assets before the zap
+        balances_before: Vec<Asset>,
+    },
 }
 
 #[cw_serde]
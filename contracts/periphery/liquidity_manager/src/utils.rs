@@ -1,21 +1,44 @@
 use std::collections::HashMap;
 
 use cosmwasm_std::{
-    from_slice, Addr, Decimal, Decimal256, Env, QuerierWrapper, StdError, StdResult, Uint128,
+    coins, from_slice, to_binary, wasm_execute, Addr, CosmosMsg, Decimal, Decimal256, Env,
+    QuerierWrapper, StdError, StdResult, Uint128,
 };
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::{Item, Map};
 
-use astroport::asset::{Asset, Decimal256Ext, DecimalAsset, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::asset::{
+    Asset, AssetInfo, AssetInfoExt, Decimal256Ext, DecimalAsset, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+};
+use astroport::factory::PairType;
 use astroport::generator::QueryMsg as GeneratorQueryMsg;
 use astroport::liquidity_manager::CompatPairStableConfig;
-use astroport::querier::{query_supply, query_token_balance};
+use astroport::pair::{
+    CurrentFeeResponse, Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg,
+    QueryMsg as PairQueryMsg, MAX_ALLOWED_SLIPPAGE,
+};
+use astroport::querier::{query_fee_info, query_supply, query_token_balance, FeeInfo};
 use astroport::U256;
 use astroport_pair::{
-    contract::assert_slippage_tolerance, error::ContractError as PairContractError,
+    contract::{assert_slippage_tolerance, compute_swap, get_share_in_assets},
+    error::ContractError as PairContractError,
 };
 use astroport_pair_stable::error::ContractError as StableContractError;
 use astroport_pair_stable::math::compute_d;
 use astroport_pair_stable::state::Config as PairStableConfig;
-use astroport_pair_stable::utils::compute_current_amp;
+use astroport_pair_stable::utils::{compute_current_amp, imbalance_fee_rate};
+use astroport_pcl_common::state::Config as PclConfig;
+use astroport_pcl_common::utils::calc_provide_fee;
+use astroport_pcl_common::{calc_d, get_xcp};
+use pool_manager::handlers::generate_key_from_asset_info;
+use pool_manager::state::{PAIR_BALANCES, POOLS};
+
+/// PCL pair config which is used in raw queries
+const PCL_CONFIG: Item<PclConfig> = Item::new("config");
+/// PCL pair and pool manager asset precisions which are used in raw queries
+const PCL_PRECISIONS: Map<String, u8> = Map::new("precisions");
+/// PCL pairs mint LP tokens with 6 decimals
+const PCL_LP_TOKEN_PRECISION: u8 = 6;
 
 pub fn query_lp_amount(
     querier: QuerierWrapper,
@@ -120,7 +143,7 @@ pub fn stableswap_provide_simulation(
     _slippage_tolerance: Option<Decimal>,
     deposits: Vec<Asset>,
 ) -> Result<Uint128, StableContractError> {
-    if deposits.len() != config.pair_info.asset_infos.len() {
+    if deposits.len() > config.pair_info.asset_infos.len() {
         return Err(StableContractError::InvalidNumberOfAssets(
             config.pair_info.asset_infos.len(),
         ));
@@ -202,7 +225,7 @@ pub fn stableswap_provide_simulation(
     let amp = compute_current_amp(&config, &env)?;
 
     // Invariant (D) after deposit added
-    let new_balances = assets_collection
+    let mut new_balances = assets_collection
         .iter()
         .map(|(deposit, pool)| Ok(pool + deposit.amount))
         .collect::<StdResult<Vec<_>>>()?;
@@ -229,8 +252,18 @@ pub fn stableswap_provide_simulation(
             .collect::<Vec<_>>();
         let init_d = compute_d(amp, &old_balances)?;
 
+        // Charge the difference between ideal and real balances
+        let fee_info = query_pair_fee_info(&querier, &config.factory_addr, &config.pair_info)?;
+        let fee_rate = imbalance_fee_rate(fee_info.total_fee_rate, old_balances.len());
+        for (new_balance, old_balance) in new_balances.iter_mut().zip(&old_balances) {
+            let ideal_balance = deposit_d.checked_multiply_ratio(*old_balance, init_d)?;
+            let difference = ideal_balance.abs_diff(*new_balance);
+            *new_balance = new_balance.checked_sub(fee_rate.checked_mul(difference)?)?;
+        }
+        let after_fee_d = compute_d(amp, &new_balances)?;
+
         let share = Decimal256::with_precision(total_share, config.greatest_precision)?
-            .checked_multiply_ratio(deposit_d.saturating_sub(init_d), init_d)?
+            .checked_multiply_ratio(after_fee_d.saturating_sub(init_d), init_d)?
             .to_uint128_with_precision(config.greatest_precision)?;
 
         if share.is_zero() {
@@ -273,3 +306,353 @@ pub fn convert_config(
         fee_share: None,
     })
 }
+
+/// Builds a message which swaps `offer_asset` in the pair `pair_addr`.
+/// The result is checked by the caller so the spread is only limited by the pair's maximum.
+pub fn swap_msg(
+    pair_addr: &Addr,
+    offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<CosmosMsg> {
+    let max_spread = Some(MAX_ALLOWED_SLIPPAGE.parse()?);
+    let msg = match &offer_asset.info {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: pair_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    ask_asset_info: Some(ask_asset_info.clone()),
+                    belief_price: None,
+                    max_spread,
+                    to: None,
+                })?,
+            },
+            vec![],
+        )?,
+        AssetInfo::NativeToken { denom } => wasm_execute(
+            pair_addr,
+            &PairExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: Some(ask_asset_info.clone()),
+                belief_price: None,
+                max_spread,
+                to: None,
+            },
+            coins(offer_asset.amount.u128(), denom),
+        )?,
+    };
+
+    Ok(msg.into())
+}
+
+/// Returns the amount of the offer asset which should be swapped in an XYK pool before providing
+/// so that the remaining offer amount and the swap return match the pool ratio after the swap.
+///
+/// The commission `f` is charged on the return amount and its maker part `m` leaves the pool,
+/// thus the swap amount `s` solves `(a - s) * (R + (f - m) * s) = (1 - f) * s * (R + s)`
+/// where `a` is the offer amount and `R` is the offer pool.
+pub fn xyk_zap_swap_amount(
+    offer_pool: Uint128,
+    offer_amount: Uint128,
+    fee_rate: Decimal,
+    maker_fee_rate: Decimal,
+) -> StdResult<Uint128> {
+    const FEE_SCALE: u128 = 1_000_000;
+
+    let overflow = || StdError::generic_err("Overflow in zap swap amount calculation");
+    let scale = U256::from(FEE_SCALE);
+    let fee = U256::from((fee_rate * Uint128::new(FEE_SCALE)).u128());
+    let maker_fee = U256::from((maker_fee_rate * Uint128::new(FEE_SCALE)).u128());
+    let pool = U256::from(offer_pool.u128());
+    let amount = U256::from(offer_amount.u128());
+
+    // (1 - m) * s^2 + s * (R * (2 - f) - a * (f - m)) - a * R = 0
+    let b_pos = pool.checked_mul(scale * 2 - fee).ok_or_else(overflow)?;
+    let b_neg = amount.checked_mul(fee - maker_fee).ok_or_else(overflow)?;
+    let b_abs = if b_pos > b_neg {
+        b_pos - b_neg
+    } else {
+        b_neg - b_pos
+    };
+    let discriminant = b_abs
+        .checked_mul(b_abs)
+        .and_then(|b2| {
+            amount
+                .checked_mul(pool)?
+                .checked_mul(scale * (scale - maker_fee) * 4)?
+                .checked_add(b2)
+        })
+        .ok_or_else(overflow)?
+        .integer_sqrt();
+    let numerator = if b_pos > b_neg {
+        discriminant - b_abs
+    } else {
+        discriminant + b_abs
+    };
+
+    Ok(Uint128::new(
+        (numerator / ((scale - maker_fee) * 2)).as_u128(),
+    ))
+}
+
+/// Returns the fee charged by the pair. XYK pairs may charge a dynamic fee instead of the one set
+/// in the factory, so their current fee is queried from the pair. Pairs which don't support
+/// the query fall back to the factory fee.
+pub fn query_pair_fee_info(
+    querier: &QuerierWrapper,
+    factory_addr: &Addr,
+    pair_info: &PairInfo,
+) -> StdResult<FeeInfo> {
+    let fee_info = query_fee_info(querier, factory_addr, pair_info.pair_type.clone())?;
+    if !matches!(pair_info.pair_type, PairType::Xyk {}) {
+        return Ok(fee_info);
+    }
+
+    match querier.query_wasm_smart::<CurrentFeeResponse>(
+        &pair_info.contract_addr,
+        &PairQueryMsg::CurrentFee {},
+    ) {
+        Ok(current_fee) => Ok(FeeInfo {
+            total_fee_rate: current_fee.total_fee_rate,
+            maker_fee_rate: current_fee.maker_fee_rate,
+            ..fee_info
+        }),
+        Err(_) => Ok(fee_info),
+    }
+}
+
+/// Returns the maker part of the XYK swap commission which leaves the pool.
+pub fn xyk_maker_fee_rate(fee_info: &FeeInfo) -> Decimal {
+    if fee_info.fee_address.is_some() {
+        fee_info.total_fee_rate * fee_info.maker_fee_rate
+    } else {
+        Decimal::zero()
+    }
+}
+
+/// Simulates swapping the optimal part of `offer_asset` and providing both assets to an XYK pool.
+/// Mirrors the contract logic including trimming deposits to the pool ratio.
+pub fn xyk_zap_in_simulation(
+    querier: QuerierWrapper,
+    factory_addr: &Addr,
+    pools: &[Asset],
+    pair_info: &PairInfo,
+    offer_asset: &Asset,
+) -> Result<Uint128, PairContractError> {
+    let fee_info = query_pair_fee_info(&querier, factory_addr, pair_info)?;
+    let maker_fee_rate = xyk_maker_fee_rate(&fee_info);
+
+    let (offer_ind, ask_ind) = if pools[0].info.equal(&offer_asset.info) {
+        (0, 1)
+    } else {
+        (1, 0)
+    };
+    let (offer_pool, ask_pool) = (pools[offer_ind].amount, pools[ask_ind].amount);
+
+    let swap_amount = xyk_zap_swap_amount(
+        offer_pool,
+        offer_asset.amount,
+        fee_info.total_fee_rate,
+        maker_fee_rate,
+    )?;
+    let (return_amount, _, commission_amount) =
+        compute_swap(offer_pool, ask_pool, swap_amount, fee_info.total_fee_rate)?;
+    let maker_fee = if fee_info.fee_address.is_some() {
+        commission_amount * fee_info.maker_fee_rate
+    } else {
+        Uint128::zero()
+    };
+
+    let mut pools = pools.to_vec();
+    pools[offer_ind].amount += swap_amount;
+    pools[ask_ind].amount = ask_pool.checked_sub(return_amount + maker_fee)?;
+    let mut deposits = pools.clone();
+    deposits[offer_ind].amount = offer_asset.amount - swap_amount;
+    deposits[ask_ind].amount = return_amount;
+
+    let predicted_lp_amount =
+        xyk_provide_simulation(querier, &pools, pair_info, None, deposits.clone())?;
+
+    // Simulate provide again without excess tokens
+    let pools_with_deposits = pools
+        .iter()
+        .zip(deposits.iter())
+        .map(|(pool, deposit)| pool.info.with_balance(pool.amount + deposit.amount))
+        .collect::<Vec<_>>();
+    let total_share = query_supply(&querier, &pair_info.liquidity_token)?;
+    let accrued_share = get_share_in_assets(
+        &pools_with_deposits,
+        predicted_lp_amount,
+        total_share + predicted_lp_amount,
+    );
+
+    xyk_provide_simulation(querier, &pools, pair_info, None, accrued_share)
+}
+
+pub fn pcl_provide_simulation(
+    querier: QuerierWrapper,
+    env: &Env,
+    pair_addr: &Addr,
+    deposits: &[Asset],
+) -> StdResult<Uint128> {
+    let config = PCL_CONFIG.query(&querier, pair_addr.clone())?;
+    let pools = config.pair_info.query_pools(&querier, pair_addr)?;
+
+    pcl_provide_share(querier, env, pair_addr, &config, pools, deposits)
+}
+
+/// Returns the config of a pool hosted by the pool manager `contract_addr` if it exists.
+pub fn query_pool_manager_config(
+    querier: QuerierWrapper,
+    contract_addr: &Addr,
+    asset_infos: &[AssetInfo],
+) -> StdResult<Option<PclConfig>> {
+    if asset_infos.len() != 2 {
+        return Ok(None);
+    }
+
+    POOLS.query(
+        &querier,
+        contract_addr.clone(),
+        generate_key_from_asset_info(&asset_infos.to_vec()),
+    )
+}
+
+fn query_pool_manager_balances(
+    querier: QuerierWrapper,
+    pool_manager: &Addr,
+    config: &PclConfig,
+) -> StdResult<Vec<Asset>> {
+    PAIR_BALANCES
+        .query(
+            &querier,
+            pool_manager.clone(),
+            generate_key_from_asset_info(&config.pair_info.asset_infos),
+        )?
+        .ok_or_else(|| StdError::generic_err("Pool balances not found"))
+}
+
+pub fn pool_manager_provide_simulation(
+    querier: QuerierWrapper,
+    env: &Env,
+    pool_manager: &Addr,
+    config: &PclConfig,
+    deposits: &[Asset],
+) -> StdResult<Uint128> {
+    let pools = query_pool_manager_balances(querier, pool_manager, config)?;
+
+    pcl_provide_share(querier, env, pool_manager, config, pools, deposits)
+}
+
+pub fn pool_manager_withdraw_simulation(
+    querier: QuerierWrapper,
+    pool_manager: &Addr,
+    config: &PclConfig,
+    lp_tokens: Uint128,
+) -> StdResult<Vec<Asset>> {
+    let pools = query_pool_manager_balances(querier, pool_manager, config)?;
+    let total_share = query_supply(&querier, &config.pair_info.liquidity_token)?;
+
+    let share_ratio = if !total_share.is_zero() {
+        Decimal256::from_ratio(lp_tokens.saturating_sub(Uint128::one()), total_share)
+    } else {
+        Decimal256::zero()
+    };
+
+    pools
+        .into_iter()
+        .map(|pool| {
+            let prec = query_pcl_precision(querier, pool_manager, &pool.info)?;
+            let amount = Decimal256::with_precision(pool.amount, prec)? * share_ratio;
+
+            Ok(pool
+                .info
+                .with_balance(amount.to_uint128_with_precision(prec)?))
+        })
+        .collect()
+}
+
+fn query_pcl_precision(
+    querier: QuerierWrapper,
+    contract_addr: &Addr,
+    asset_info: &AssetInfo,
+) -> StdResult<u8> {
+    PCL_PRECISIONS
+        .query(&querier, contract_addr.clone(), asset_info.to_string())?
+        .ok_or_else(|| StdError::generic_err(format!("Asset {asset_info} precision not found")))
+}
+
+/// Calculates the amount of LP tokens minted by a concentrated pool for the given deposits.
+/// `contract_addr` is the contract which stores asset precisions.
+fn pcl_provide_share(
+    querier: QuerierWrapper,
+    env: &Env,
+    contract_addr: &Addr,
+    config: &PclConfig,
+    pool_balances: Vec<Asset>,
+    deposits: &[Asset],
+) -> StdResult<Uint128> {
+    if let Some(asset) = deposits
+        .iter()
+        .find(|asset| !config.pair_info.asset_infos.contains(&asset.info))
+    {
+        return Err(StdError::generic_err(format!(
+            "Asset {} is not in the pair",
+            asset.info
+        )));
+    }
+
+    let mut pools = vec![];
+    let mut deposits_dec = vec![];
+    for pool in pool_balances {
+        let prec = query_pcl_precision(querier, contract_addr, &pool.info)?;
+        let deposit = deposits
+            .iter()
+            .find(|asset| asset.info.equal(&pool.info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        pools.push(Decimal256::with_precision(pool.amount, prec)?);
+        deposits_dec.push(Decimal256::with_precision(deposit, prec)?);
+    }
+
+    let total_share = Decimal256::with_precision(
+        query_supply(&querier, &config.pair_info.liquidity_token)?,
+        PCL_LP_TOKEN_PRECISION,
+    )?;
+    if total_share.is_zero() && (deposits_dec[0].is_zero() || deposits_dec[1].is_zero()) {
+        return Err(StdError::generic_err(
+            "Initial provide can not be one-sided",
+        ));
+    }
+
+    let price_scale = config.pool_state.price_state.price_scale;
+    let amp_gamma = config.pool_state.get_amp_gamma(env);
+
+    let mut new_xp = vec![pools[0] + deposits_dec[0], pools[1] + deposits_dec[1]];
+    new_xp[1] *= price_scale;
+    let new_d =
+        calc_d(&new_xp, &amp_gamma).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let share = if total_share.is_zero() {
+        get_xcp(new_d, price_scale)
+            .checked_sub(Decimal256::with_precision(
+                MINIMUM_LIQUIDITY_AMOUNT,
+                PCL_LP_TOKEN_PRECISION,
+            )?)
+            .map_err(|_| StdError::generic_err("Minimum liquidity amount is not reached"))?
+    } else {
+        let mut old_xp = pools.clone();
+        old_xp[1] *= price_scale;
+        let old_d =
+            calc_d(&old_xp, &amp_gamma).map_err(|err| StdError::generic_err(err.to_string()))?;
+        let share = (total_share * new_d / old_d).saturating_sub(total_share);
+
+        let mut ideposits = deposits_dec;
+        ideposits[1] *= price_scale;
+
+        share * (Decimal256::one() - calc_provide_fee(&ideposits, &new_xp, &config.pool_params))
+    };
+
+    share.to_uint128_with_precision(PCL_LP_TOKEN_PRECISION)
+}
@@ -121,6 +121,17 @@ fn stable_pair_contract() -> Box<dyn Contract<Empty>> {
     )
 }
 
+fn pcl_pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair_concentrated::contract::execute,
+            astroport_pair_concentrated::contract::instantiate,
+            astroport_pair_concentrated::queries::query,
+        )
+        .with_reply_empty(astroport_pair_concentrated::contract::reply),
+    )
+}
+
 fn coin_registry_contract() -> Box<dyn Contract<Empty>> {
     Box::new(ContractWrapper::new_with_empty(
         astroport_native_coin_registry::contract::execute,
@@ -216,11 +227,10 @@ impl Helper {
                 pair_type = PairType::Stable {};
                 inner_params = to_binary(inner).unwrap();
             }
-            PoolParams::Concentrated(_) => {
-                unimplemented!("Concentrated pool is not supported yet");
-                // pair_code_id = app.store_code(pcl_pair_contract());
-                // pair_type = PairType::Custom("concentrated".to_owned());
-                // inner_params = to_binary(inner).unwrap();
+            PoolParams::Concentrated(inner) => {
+                pair_code_id = app.store_code(pcl_pair_contract());
+                pair_type = PairType::Custom("concentrated".to_owned());
+                inner_params = to_binary(inner).unwrap();
             }
         }
 
@@ -259,6 +269,8 @@ impl Helper {
                 pair_type: pair_type.clone(),
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             }],
             token_code_id,
             generator_address: None,
@@ -310,6 +322,9 @@ impl Helper {
                 generator_address: Some(generator.to_string()),
                 whitelist_code_id: None,
                 coin_registry_address: None,
+                pool_manager: None,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
             &[],
         )
@@ -392,6 +407,7 @@ impl Helper {
                 &QueryMsg::SimulateWithdraw {
                     pair_addr: self.pair_addr.to_string(),
                     lp_tokens: lp_tokens_amount.into(),
+                    asset_infos: None,
                 },
             )
             .map_err(Into::into)
@@ -488,6 +504,85 @@ impl Helper {
             .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
     }
 
+    pub fn zap_in(
+        &mut self,
+        sender: &Addr,
+        offer_asset: &Asset,
+        min_lp_receive: Option<Uint128>,
+    ) -> AnyResult<AppResponse> {
+        let funds = offer_asset.mock_coin_sent(
+            &mut self.app,
+            sender,
+            &self.liquidity_manager,
+            SendType::Allowance,
+        );
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.liquidity_manager.clone(),
+            &ExecuteMsg::ZapIn {
+                pair_addr: self.pair_addr.to_string(),
+                offer_asset: offer_asset.clone(),
+                min_lp_to_receive: min_lp_receive,
+                auto_stake: None,
+                receiver: None,
+            },
+            &funds,
+        )
+    }
+
+    pub fn zap_out(
+        &mut self,
+        sender: &Addr,
+        amount: u128,
+        ask_asset_info: &AssetInfo,
+        min_out: Option<Uint128>,
+    ) -> AnyResult<AppResponse> {
+        let msg = Cw20ExecuteMsg::Send {
+            contract: self.liquidity_manager.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::ZapOut {
+                ask_asset_info: ask_asset_info.clone(),
+                min_out,
+            })
+            .unwrap(),
+        };
+
+        self.app
+            .execute_contract(sender.clone(), self.lp_token.clone(), &msg, &[])
+    }
+
+    pub fn simulate_zap_in(&self, offer_asset: &Asset) -> AnyResult<Uint128> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.liquidity_manager,
+                &QueryMsg::SimulateZapIn {
+                    pair_addr: self.pair_addr.to_string(),
+                    offer_asset: offer_asset.clone(),
+                },
+            )
+            .map_err(Into::into)
+    }
+
+    pub fn simulate_zap_out(
+        &self,
+        lp_tokens_amount: impl Into<Uint128>,
+        ask_asset_info: &AssetInfo,
+    ) -> AnyResult<Uint128> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.liquidity_manager,
+                &QueryMsg::SimulateZapOut {
+                    pair_addr: self.pair_addr.to_string(),
+                    lp_tokens: lp_tokens_amount.into(),
+                    ask_asset_info: ask_asset_info.clone(),
+                },
+            )
+            .map_err(Into::into)
+    }
+
     pub fn swap(
         &mut self,
         sender: &Addr,
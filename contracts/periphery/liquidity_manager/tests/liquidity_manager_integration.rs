@@ -1,9 +1,10 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 
-use astroport::asset::{native_asset, AssetInfoExt};
-use astroport::pair::{StablePoolParams, XYKPoolParams};
+use astroport::asset::{native_asset, native_asset_info, AssetInfoExt};
+use astroport::pair::{DynamicFeeParams, StablePoolParams, XYKPoolParams};
+use astroport::pair_concentrated::ConcentratedPoolParams;
 use astroport_liquidity_manager::error::ContractError;
 
 use crate::helper::{f64_to_dec, Helper, PoolParams, TestCoin};
@@ -19,6 +20,7 @@ fn test_xyk() {
         test_coins.clone(),
         PoolParams::Constant(XYKPoolParams {
             track_asset_balances: None,
+            dynamic_fee: None,
         }),
     )
     .unwrap();
@@ -299,7 +301,7 @@ fn test_stableswap_with_manager() {
         .unwrap_err();
 
     assert_eq!(
-        ContractError::ProvideSlippageViolation(199_998620u128.into(), 200_000000u128.into()),
+        ContractError::ProvideSlippageViolation(199_983641u128.into(), 200_000000u128.into()),
         err.downcast().unwrap()
     );
 }
@@ -397,6 +399,7 @@ fn test_withdraw() {
         test_coins.clone(),
         PoolParams::Constant(XYKPoolParams {
             track_asset_balances: None,
+            dynamic_fee: None,
         }),
     )
     .unwrap();
@@ -514,3 +517,388 @@ fn test_onesided_provide_stable() {
         )
         .unwrap();
 }
+
+#[test]
+fn test_zap_xyk() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("TEST")];
+    let mut helper = Helper::new(
+        &owner,
+        test_coins.clone(),
+        PoolParams::Constant(XYKPoolParams {
+            track_asset_balances: None,
+            dynamic_fee: None,
+        }),
+    )
+    .unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+
+    // Zap requires existing liquidity
+    let err = helper.zap_in(&user, &offer_asset, None).unwrap_err();
+    assert_eq!(ContractError::ZapEmptyPool {}, err.downcast().unwrap());
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[
+                helper.assets[&test_coins[0]].with_balance(100_000_000000_u128),
+                helper.assets[&test_coins[1]].with_balance(50_000_000000_u128),
+            ],
+            None,
+        )
+        .unwrap();
+
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    let err = helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount + Uint128::one()))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ProvideSlippageViolation(sim_lp_amount, sim_lp_amount + Uint128::one()),
+        err.downcast().unwrap()
+    );
+    helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+
+    let user_lp_bal = helper.token_balance(&helper.lp_token, &user);
+    assert_eq!(user_lp_bal, sim_lp_amount.u128());
+    // Almost the whole offer amount is provided; only rounding leftovers are returned
+    assert!(helper.coin_balance(&test_coins[0], &user) < 1000);
+    assert!(helper.coin_balance(&test_coins[1], &user) < 1000);
+    // Nothing is left in the liquidity manager
+    for test_coin in &test_coins {
+        assert_eq!(helper.coin_balance(test_coin, &helper.liquidity_manager), 0);
+    }
+
+    // Zap out into the cw20 token
+    let ask_asset_info = helper.assets[&test_coins[1]].clone();
+    let sim_out = helper
+        .simulate_zap_out(user_lp_bal, &ask_asset_info)
+        .unwrap();
+    let err = helper
+        .zap_out(
+            &user,
+            user_lp_bal,
+            &ask_asset_info,
+            Some(sim_out + Uint128::one()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WithdrawSlippageViolation {
+            asset_name: ask_asset_info.to_string(),
+            received: sim_out,
+            expected: sim_out + Uint128::one(),
+        },
+        err.downcast().unwrap()
+    );
+    helper
+        .zap_out(&user, user_lp_bal, &ask_asset_info, Some(sim_out))
+        .unwrap();
+
+    assert_eq!(helper.token_balance(&helper.lp_token, &user), 0);
+    assert_eq!(helper.coin_balance(&test_coins[1], &user), sim_out.u128());
+    for test_coin in &test_coins {
+        assert_eq!(helper.coin_balance(test_coin, &helper.liquidity_manager), 0);
+    }
+
+    // Zap in with the cw20 token
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(500_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+    assert_eq!(
+        helper.token_balance(&helper.lp_token, &user),
+        sim_lp_amount.u128()
+    );
+
+    // Asset which is not in the pair
+    let err = helper
+        .zap_out(&user, 1000, &native_asset_info("random".to_string()), None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AssetNotInPair("random".to_string()),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn test_zap_xyk_dynamic_fee() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("TEST")];
+    // The pair charges 1% instead of the fee set in the factory
+    let mut helper = Helper::new(
+        &owner,
+        test_coins.clone(),
+        PoolParams::Constant(XYKPoolParams {
+            track_asset_balances: None,
+            dynamic_fee: Some(DynamicFeeParams {
+                min_fee_bps: 100,
+                max_fee_bps: 200,
+                max_volatility_bps: 500,
+                window: 3600,
+            }),
+        }),
+    )
+    .unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[
+                helper.assets[&test_coins[0]].with_balance(100_000_000000_u128),
+                helper.assets[&test_coins[1]].with_balance(50_000_000000_u128),
+            ],
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+    assert_eq!(
+        helper.token_balance(&helper.lp_token, &user),
+        sim_lp_amount.u128()
+    );
+    // The swapped part accounts for the fee charged by the pair thus only rounding leftovers are returned
+    assert!(helper.coin_balance(&test_coins[0], &user) < 1000);
+    assert!(helper.coin_balance(&test_coins[1], &user) < 1000);
+
+    let ask_asset_info = helper.assets[&test_coins[1]].clone();
+    let user_lp_bal = helper.token_balance(&helper.lp_token, &user);
+    let ask_balance_before = helper.coin_balance(&test_coins[1], &user);
+    let sim_out = helper
+        .simulate_zap_out(user_lp_bal, &ask_asset_info)
+        .unwrap();
+    helper
+        .zap_out(&user, user_lp_bal, &ask_asset_info, Some(sim_out))
+        .unwrap();
+    assert_eq!(
+        helper.coin_balance(&test_coins[1], &user),
+        ask_balance_before + sim_out.u128()
+    );
+}
+
+#[test]
+fn test_zap_ignores_contract_balance() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("TEST")];
+    let mut helper = Helper::new(
+        &owner,
+        test_coins.clone(),
+        PoolParams::Constant(XYKPoolParams {
+            track_asset_balances: None,
+            dynamic_fee: None,
+        }),
+    )
+    .unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[
+                helper.assets[&test_coins[0]].with_balance(100_000_000000_u128),
+                helper.assets[&test_coins[1]].with_balance(50_000_000000_u128),
+            ],
+            None,
+        )
+        .unwrap();
+
+    // Funds sent to the liquidity manager by mistake
+    let stray_amount = 5_000_000000u128;
+    let liquidity_manager = helper.liquidity_manager.clone();
+    helper.give_me_money(
+        &[
+            helper.assets[&test_coins[0]].with_balance(stray_amount),
+            helper.assets[&test_coins[1]].with_balance(stray_amount),
+        ],
+        &liquidity_manager,
+    );
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+    let user_lp_bal = helper.token_balance(&helper.lp_token, &user);
+    assert_eq!(user_lp_bal, sim_lp_amount.u128());
+    // Only rounding leftovers of the user's own offer are returned
+    assert!(helper.coin_balance(&test_coins[0], &user) < 1000);
+    assert!(helper.coin_balance(&test_coins[1], &user) < 1000);
+    for test_coin in &test_coins {
+        assert_eq!(
+            helper.coin_balance(test_coin, &liquidity_manager),
+            stray_amount
+        );
+    }
+
+    let ask_asset_info = helper.assets[&test_coins[1]].clone();
+    let ask_balance_before = helper.coin_balance(&test_coins[1], &user);
+    let sim_out = helper
+        .simulate_zap_out(user_lp_bal, &ask_asset_info)
+        .unwrap();
+    helper
+        .zap_out(&user, user_lp_bal, &ask_asset_info, Some(sim_out))
+        .unwrap();
+    assert_eq!(
+        helper.coin_balance(&test_coins[1], &user),
+        ask_balance_before + sim_out.u128()
+    );
+    for test_coin in &test_coins {
+        assert_eq!(
+            helper.coin_balance(test_coin, &liquidity_manager),
+            stray_amount
+        );
+    }
+}
+
+#[test]
+fn test_zap_stable() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::cw20("UST")];
+    let mut helper = Helper::new(
+        &owner,
+        test_coins.clone(),
+        PoolParams::Stable(StablePoolParams {
+            amp: 40,
+            owner: None,
+        }),
+    )
+    .unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[
+                helper.assets[&test_coins[0]].with_balance(100_000_000000_u128),
+                helper.assets[&test_coins[1]].with_balance(100_000_000000_u128),
+            ],
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(1000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    helper
+        .zap_in(&user, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+    let user_lp_bal = helper.token_balance(&helper.lp_token, &user);
+    assert_eq!(user_lp_bal, sim_lp_amount.u128());
+    assert_eq!(helper.coin_balance(&test_coins[1], &user), 0);
+
+    let ask_asset_info = helper.assets[&test_coins[0]].clone();
+    let sim_out = helper
+        .simulate_zap_out(user_lp_bal, &ask_asset_info)
+        .unwrap();
+    // The swap part is simulated against the pool before the withdrawal
+    let min_out = sim_out.multiply_ratio(99u8, 100u8);
+    helper
+        .zap_out(&user, user_lp_bal, &ask_asset_info, Some(min_out))
+        .unwrap();
+    assert!(helper.coin_balance(&test_coins[0], &user) >= min_out.u128());
+    for test_coin in &test_coins {
+        assert_eq!(helper.coin_balance(test_coin, &helper.liquidity_manager), 0);
+    }
+}
+
+#[test]
+fn test_pcl_simulations() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("TEST")];
+    let mut helper = Helper::new(
+        &owner,
+        test_coins.clone(),
+        PoolParams::Concentrated(ConcentratedPoolParams {
+            amp: f64_to_dec(40f64),
+            gamma: f64_to_dec(0.000145),
+            mid_fee: f64_to_dec(0.0026),
+            out_fee: f64_to_dec(0.0045),
+            fee_gamma: f64_to_dec(0.00023),
+            repeg_profit_threshold: f64_to_dec(0.000002),
+            min_price_scale_delta: f64_to_dec(0.000146),
+            price_scale: Decimal::one(),
+            ma_half_time: 600,
+            track_asset_balances: None,
+            fee_share: None,
+        }),
+    )
+    .unwrap();
+
+    let initial_assets = [
+        helper.assets[&test_coins[0]].with_balance(100_000_000000_u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000_u128),
+    ];
+    let sim_lp_amount = helper.simulate_provide(None, &initial_assets).unwrap();
+    helper
+        .provide_liquidity(&owner, &initial_assets, Some(sim_lp_amount))
+        .unwrap();
+    assert_eq!(
+        helper.token_balance(&helper.lp_token, &owner),
+        sim_lp_amount.u128()
+    );
+
+    // Imbalanced provide is charged with the provide fee
+    let user = Addr::unchecked("user");
+    let provide_assets = [
+        helper.assets[&test_coins[0]].with_balance(100_000000u128),
+        helper.assets[&test_coins[1]].with_balance(50_000000u128),
+    ];
+    helper.give_me_money(&provide_assets, &user);
+    let sim_lp_amount = helper
+        .simulate_provide(Some(f64_to_dec(0.5)), &provide_assets)
+        .unwrap();
+    let err = helper
+        .provide_liquidity(&user, &provide_assets, Some(sim_lp_amount + Uint128::one()))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ProvideSlippageViolation(sim_lp_amount, sim_lp_amount + Uint128::one()),
+        err.downcast().unwrap()
+    );
+    helper
+        .provide_liquidity(&user, &provide_assets, Some(sim_lp_amount))
+        .unwrap();
+    let user_lp_bal = helper.token_balance(&helper.lp_token, &user);
+    assert_eq!(user_lp_bal, sim_lp_amount.u128());
+
+    let sim_withdraw = helper.simulate_withdraw(user_lp_bal).unwrap();
+    helper
+        .withdraw_liquidity(&user, user_lp_bal, Some(sim_withdraw.clone()))
+        .unwrap();
+    for asset in sim_withdraw {
+        let test_coin = helper
+            .assets
+            .iter()
+            .find(|(_, info)| **info == asset.info)
+            .map(|(coin, _)| coin.clone())
+            .unwrap();
+        assert_eq!(helper.coin_balance(&test_coin, &user), asset.amount.u128());
+    }
+
+    // Single-sided zap in
+    let user2 = Addr::unchecked("user2");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(100_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user2);
+    let sim_lp_amount = helper.simulate_zap_in(&offer_asset).unwrap();
+    helper
+        .zap_in(&user2, &offer_asset, Some(sim_lp_amount))
+        .unwrap();
+    assert_eq!(
+        helper.token_balance(&helper.lp_token, &user2),
+        sim_lp_amount.u128()
+    );
+}
@@ -1,68 +1,58 @@
 use crate::error::ContractError;
-use crate::migration::PRICE_LAST_V100;
-use crate::querier::{query_cumulative_prices, query_prices};
+use crate::migration::{migrate_from_v2, migrate_prices_from_v100};
+use crate::querier::{query_cumulative_prices, query_observation, query_prices};
 use crate::state::{
-    get_precision, store_precisions, Config, PriceCumulativeLast, CONFIG, PRICE_LAST,
+    get_precision, store_precisions, Config, PairState, PriceCumulativeLast, CONFIG, PAIRS,
+};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
+use astroport::oracle::{
+    ConfigResponse, ConsultPathResponse, ConsultResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    OraclePairResponse, PriceSource, QueryMsg, DEFAULT_PERIOD,
 };
-use astroport::asset::{Asset, AssetInfo};
-use astroport::oracle::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use astroport::pair::TWAP_PRECISION;
 use astroport::querier::query_pair_info;
 
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128, Uint256,
+    attr, entry_point, to_binary, Addr, Binary, Decimal, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
 };
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-oracle";
 /// Contract version that is used for migration.
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-
-/// Time between two consecutive TWAP updates.
-pub const PERIOD: u64 = 86400;
+/// The maximum number of pairs examined during a single update.
+const MAX_UPDATE_LIMIT: u32 = 30;
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    mut deps: DepsMut,
+    deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let factory_contract = deps.api.addr_validate(&msg.factory_contract)?;
-
-    for asset_info in &msg.asset_infos {
-        asset_info.check(deps.api)?;
-        store_precisions(deps.branch(), asset_info, &factory_contract)?;
+    if let Some(quote_asset) = &msg.quote_asset {
+        quote_asset.check(deps.api)?;
     }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    let pair_info = query_pair_info(&deps.querier, &factory_contract, &msg.asset_infos)?;
 
     let config = Config {
         owner: info.sender,
         factory: factory_contract,
-        asset_infos: msg.asset_infos,
-        pair: pair_info.clone(),
+        quote_asset: msg.quote_asset,
+        max_staleness: msg.max_staleness.filter(|max_staleness| *max_staleness > 0),
+        max_deviation: msg
+            .max_deviation
+            .filter(|max_deviation| !max_deviation.is_zero()),
     };
     CONFIG.save(deps.storage, &config)?;
 
-    let prices = query_cumulative_prices(deps.querier, pair_info.contract_addr)?;
-    let average_prices = prices
-        .cumulative_prices
-        .iter()
-        .cloned()
-        .map(|(from, to, _)| (from, to, Decimal256::zero()))
-        .collect();
-
-    let price = PriceCumulativeLast {
-        cumulative_prices: prices.cumulative_prices,
-        average_prices,
-        block_timestamp_last: env.block.time.seconds(),
-    };
-    PRICE_LAST.save(deps.storage, &price)?;
+    track_pair(deps, &env, &config.factory, msg.asset_infos, msg.period)?;
 
     Ok(Response::default())
 }
@@ -70,32 +60,391 @@ pub fn instantiate(
 /// Exposes all the execute functions available in the contract.
 ///
 /// ## Variants
-/// * **ExecuteMsg::Update {}** Updates the local TWAP values for the assets in the Astroport pool.
+/// * **ExecuteMsg::Update { start_after, limit }** Updates the local TWAP values for a page of pairs
+/// whose period has elapsed.
+///
+/// * **ExecuteMsg::AddPair { asset_infos, period }** Starts tracking a new pair.
+///
+/// * **ExecuteMsg::RemovePair { asset_infos }** Stops tracking a pair.
+///
+/// * **ExecuteMsg::UpdatePairPeriod { asset_infos, period }** Changes the TWAP period of a pair.
+///
+/// * **ExecuteMsg::UnfreezePair { asset_infos }** Accepts the TWAP which exceeded the maximum deviation.
+///
+/// * **ExecuteMsg::UpdateConfig { quote_asset, max_staleness, max_deviation }** Updates the quote asset and the price guards.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Update {} => update(deps, env),
+        ExecuteMsg::Update { start_after, limit } => update(deps, env, start_after, limit),
+        ExecuteMsg::AddPair {
+            asset_infos,
+            period,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            if info.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let pair_addr = track_pair(deps, &env, &config.factory, asset_infos, period)?;
+
+            Ok(Response::new()
+                .add_attributes(vec![attr("action", "add_pair"), attr("pair", pair_addr)]))
+        }
+        ExecuteMsg::RemovePair { asset_infos } => remove_pair(deps, info, asset_infos),
+        ExecuteMsg::UpdatePairPeriod {
+            asset_infos,
+            period,
+        } => update_pair_period(deps, info, asset_infos, period),
+        ExecuteMsg::UnfreezePair { asset_infos } => unfreeze_pair(deps, env, info, asset_infos),
+        ExecuteMsg::UpdateConfig {
+            quote_asset,
+            max_staleness,
+            max_deviation,
+        } => update_config(deps, info, quote_asset, max_staleness, max_deviation),
     }
 }
 
-/// Updates the local TWAP values for the tokens in the target Astroport pool.
-pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+/// Starts tracking the pair with the given assets and returns its address.
+/// Pairs which don't support cumulative prices (PCL and stableswap pools) are tracked via price observations.
+fn track_pair(
+    mut deps: DepsMut,
+    env: &Env,
+    factory: &Addr,
+    asset_infos: Vec<AssetInfo>,
+    period: Option<u64>,
+) -> Result<Addr, ContractError> {
+    let period = period.unwrap_or(DEFAULT_PERIOD);
+    if period == 0 {
+        return Err(ContractError::InvalidPeriod {});
+    }
+
+    for asset_info in &asset_infos {
+        asset_info.check(deps.api)?;
+        store_precisions(deps.branch(), asset_info, factory)?;
+    }
+
+    let pair_info = query_pair_info(&deps.querier, factory, &asset_infos)?;
+    // The pool manager hosts all its pools at one address and exposes neither cumulative prices nor observations
+    if pair_info.pool_key.is_some() {
+        return Err(ContractError::ManagerPoolNotSupported {});
+    }
+    if PAIRS.has(deps.storage, &pair_info.contract_addr) {
+        return Err(ContractError::PairAlreadyTracked {});
+    }
+
+    let (price_source, price_last) =
+        match query_cumulative_prices(deps.querier, &pair_info.contract_addr) {
+            Ok(prices) => {
+                let average_prices = prices
+                    .cumulative_prices
+                    .iter()
+                    .cloned()
+                    .map(|(from, to, _)| (from, to, Decimal256::zero()))
+                    .collect();
+
+                (
+                    PriceSource::CumulativePrices,
+                    PriceCumulativeLast {
+                        cumulative_prices: prices.cumulative_prices,
+                        average_prices,
+                        block_timestamp_last: env.block.time.seconds(),
+                    },
+                )
+            }
+            Err(_) => {
+                let base = &pair_info.asset_infos[0];
+                let quote = &pair_info.asset_infos[1];
+
+                (
+                    PriceSource::Observations,
+                    PriceCumulativeLast {
+                        cumulative_prices: vec![],
+                        average_prices: vec![
+                            (base.clone(), quote.clone(), Decimal256::zero()),
+                            (quote.clone(), base.clone(), Decimal256::zero()),
+                        ],
+                        block_timestamp_last: env.block.time.seconds(),
+                    },
+                )
+            }
+        };
+
+    PAIRS.save(
+        deps.storage,
+        &pair_info.contract_addr,
+        &PairState {
+            pair_info: pair_info.clone(),
+            period,
+            price_source,
+            price_last,
+            frozen: false,
+        },
+    )?;
+
+    Ok(pair_info.contract_addr)
+}
+
+/// Stops tracking the pair with the given assets. Only the owner can execute this.
+fn remove_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let price_last = PRICE_LAST.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    let prices = query_cumulative_prices(deps.querier, config.pair.contract_addr)?;
-    let time_elapsed = env.block.time.seconds() - price_last.block_timestamp_last;
+    let pair_info = query_pair_info(&deps.querier, &config.factory, &asset_infos)?;
+    if !PAIRS.has(deps.storage, &pair_info.contract_addr) {
+        return Err(ContractError::PairNotTracked {});
+    }
+    PAIRS.remove(deps.storage, &pair_info.contract_addr);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_pair"),
+        attr("pair", pair_info.contract_addr),
+    ]))
+}
+
+/// Changes time between two consecutive TWAP updates for the pair. Only the owner can execute this.
+fn update_pair_period(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    period: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if period == 0 {
+        return Err(ContractError::InvalidPeriod {});
+    }
+
+    let pair_info = query_pair_info(&deps.querier, &config.factory, &asset_infos)?;
+    let mut pair_state = PAIRS
+        .may_load(deps.storage, &pair_info.contract_addr)?
+        .ok_or(ContractError::PairNotTracked {})?;
+    pair_state.period = period;
+    PAIRS.save(deps.storage, &pair_info.contract_addr, &pair_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pair_period"),
+        attr("pair", pair_info.contract_addr),
+        attr("period", period.to_string()),
+    ]))
+}
+
+/// Accepts the TWAP which exceeded the maximum deviation and unfreezes the pair.
+/// Only the owner can execute this.
+fn unfreeze_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_info = query_pair_info(&deps.querier, &config.factory, &asset_infos)?;
+    let mut pair_state = PAIRS
+        .may_load(deps.storage, &pair_info.contract_addr)?
+        .ok_or(ContractError::PairNotTracked {})?;
+    if !pair_state.frozen {
+        return Err(ContractError::PairNotFrozen {});
+    }
+
+    pair_state.price_last = fetch_prices(deps.querier, &env, &pair_state)?;
+    pair_state.frozen = false;
+    PAIRS.save(deps.storage, &pair_info.contract_addr, &pair_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unfreeze_pair"),
+        attr("pair", pair_info.contract_addr),
+    ]))
+}
+
+/// Updates the quote asset and the price guards. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    quote_asset: Option<AssetInfo>,
+    max_staleness: Option<u64>,
+    max_deviation: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(quote_asset) = quote_asset {
+        quote_asset.check(deps.api)?;
+        attributes.push(attr("quote_asset", quote_asset.to_string()));
+        config.quote_asset = Some(quote_asset);
+    }
+
+    if let Some(max_staleness) = max_staleness {
+        attributes.push(attr("max_staleness", max_staleness.to_string()));
+        config.max_staleness = Some(max_staleness).filter(|max_staleness| *max_staleness > 0);
+    }
+
+    if let Some(max_deviation) = max_deviation {
+        attributes.push(attr("max_deviation", max_deviation.to_string()));
+        config.max_deviation = Some(max_deviation).filter(|max_deviation| !max_deviation.is_zero());
+    }
 
-    // Ensure that at least one full period has passed since the last update
-    if time_elapsed < PERIOD {
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Updates the local TWAP values for the tracked pairs whose period has elapsed.
+/// If a new TWAP moves more than the maximum deviation, the pair keeps the last good prices and is frozen.
+/// Pairs whose prices can't be read (e.g. deregistered or broken pools) are frozen as well
+/// so they don't block updates of other pairs.
+///
+/// * **start_after** is an optional pair address to start reading after.
+///
+/// * **limit** is the maximum number of pairs examined.
+pub fn update(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let start_after = addr_opt_validate(deps.api, &start_after)?;
+    let limit = limit.unwrap_or(MAX_UPDATE_LIMIT).min(MAX_UPDATE_LIMIT) as usize;
+    let pairs = PAIRS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_pair = pairs
+        .last()
+        .map(|(pair_addr, _)| pair_addr.to_string())
+        .unwrap_or_default();
+
+    let mut updated_pairs = vec![];
+    let mut frozen_pairs = vec![];
+    let mut failed_pairs = vec![];
+    for (pair_addr, mut pair_state) in pairs {
+        let time_elapsed = env.block.time.seconds() - pair_state.price_last.block_timestamp_last;
+
+        // Skip pairs which were updated less than one period ago
+        if time_elapsed < pair_state.period {
+            continue;
+        }
+
+        let price_new = match fetch_prices(deps.querier, &env, &pair_state) {
+            Ok(price_new) => price_new,
+            Err(_) => {
+                // The pair keeps the last good prices till they can be read again
+                pair_state.frozen = true;
+                PAIRS.save(deps.storage, &pair_addr, &pair_state)?;
+                failed_pairs.push(pair_addr.to_string());
+                continue;
+            }
+        };
+        let deviation_exceeded = match config.max_deviation {
+            Some(max_deviation) => {
+                exceeds_deviation(max_deviation.into(), &pair_state.price_last, &price_new)
+            }
+            None => false,
+        };
+
+        if deviation_exceeded {
+            pair_state.frozen = true;
+            frozen_pairs.push(pair_addr.to_string());
+        } else {
+            pair_state.price_last = price_new;
+            pair_state.frozen = false;
+            updated_pairs.push(pair_addr.to_string());
+        }
+        PAIRS.save(deps.storage, &pair_addr, &pair_state)?;
+    }
+
+    // Ensure that at least one pair was processed
+    if updated_pairs.is_empty() && frozen_pairs.is_empty() && failed_pairs.is_empty() {
         return Err(ContractError::WrongPeriod {});
     }
 
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update"),
+        attr("pairs", updated_pairs.join(",")),
+        attr("frozen_pairs", frozen_pairs.join(",")),
+        attr("failed_pairs", failed_pairs.join(",")),
+        attr("last_pair", last_pair),
+    ]))
+}
+
+/// Reads new prices of the pair from its price source.
+fn fetch_prices(
+    querier: QuerierWrapper,
+    env: &Env,
+    pair_state: &PairState,
+) -> StdResult<PriceCumulativeLast> {
+    match pair_state.price_source {
+        PriceSource::CumulativePrices => accumulate_prices(
+            querier,
+            env,
+            &pair_state.pair_info.contract_addr,
+            &pair_state.price_last,
+        ),
+        PriceSource::Observations => observe_prices(querier, env, pair_state),
+    }
+}
+
+/// Returns true if any average price moved more than the maximum deviation since the last update.
+/// Zero prices mean that the TWAP was never calculated thus they are not checked.
+fn exceeds_deviation(
+    max_deviation: Decimal256,
+    price_last: &PriceCumulativeLast,
+    price_new: &PriceCumulativeLast,
+) -> bool {
+    price_new.average_prices.iter().any(|(from, to, new)| {
+        price_last
+            .average_prices
+            .iter()
+            .any(|(from_last, to_last, last)| {
+                let diff = if new > last {
+                    *new - *last
+                } else {
+                    *last - *new
+                };
+                from.equal(from_last)
+                    && to.equal(to_last)
+                    && !last.is_zero()
+                    && diff > *last * max_deviation
+            })
+    })
+}
+
+/// Calculates average prices from the difference between the current and the last cumulative prices.
+fn accumulate_prices(
+    querier: QuerierWrapper,
+    env: &Env,
+    pair_addr: &Addr,
+    price_last: &PriceCumulativeLast,
+) -> StdResult<PriceCumulativeLast> {
+    let prices = query_cumulative_prices(querier, pair_addr)?;
+    let time_elapsed = env.block.time.seconds() - price_last.block_timestamp_last;
+
     let mut average_prices = vec![];
     for (asset1_last, asset2_last, price_last) in price_last.cumulative_prices.iter() {
         for (asset1, asset2, price) in prices.cumulative_prices.iter() {
@@ -112,13 +461,45 @@ pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
         }
     }
 
-    let prices = PriceCumulativeLast {
+    Ok(PriceCumulativeLast {
         cumulative_prices: prices.cumulative_prices,
         average_prices,
         block_timestamp_last: env.block.time.seconds(),
-    };
-    PRICE_LAST.save(deps.storage, &prices)?;
-    Ok(Response::default())
+    })
+}
+
+/// Averages the price observations of a PCL or stableswap pair taken now and one pair period ago
+/// and converts the result into average prices with the same precision as cumulative prices have.
+/// Fails if the pair doesn't hold observations for the whole period yet.
+fn observe_prices(
+    querier: QuerierWrapper,
+    env: &Env,
+    pair_state: &PairState,
+) -> StdResult<PriceCumulativeLast> {
+    let pair_addr = &pair_state.pair_info.contract_addr;
+    let latest = query_observation(querier, pair_addr, 0)?;
+    let period_start = query_observation(querier, pair_addr, pair_state.period)?;
+
+    // Observed price is the amount of the first asset for one unit of the second asset
+    let price = (Decimal256::from(latest.price) + Decimal256::from(period_start.price))
+        / Uint256::from(2u8);
+    let price_precision = Decimal256::from_ratio(10_u128.pow(TWAP_PRECISION.into()), 1u8);
+
+    let base = &pair_state.pair_info.asset_infos[0];
+    let quote = &pair_state.pair_info.asset_infos[1];
+
+    Ok(PriceCumulativeLast {
+        cumulative_prices: vec![],
+        average_prices: vec![
+            (
+                base.clone(),
+                quote.clone(),
+                price.inv().unwrap_or_default() * price_precision,
+            ),
+            (quote.clone(), base.clone(), price * price_precision),
+        ],
+        block_timestamp_last: env.block.time.seconds(),
+    })
 }
 
 /// Exposes all the queries available in the contract.
@@ -126,67 +507,221 @@ pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
 /// ## Queries
 /// * **QueryMsg::Consult { token, amount }** Validates assets and calculates a new average
 /// amount with updated precision
+///
+/// * **QueryMsg::ConsultPath { token, amount, path }** Converts a token amount through a chain of pairs
+///
+/// * **QueryMsg::ConsultQuote { token, amount, path }** Converts a token amount into the quote asset
+///
+/// * **QueryMsg::Pairs {}** Returns the list of tracked pairs
+///
+/// * **QueryMsg::Config {}** Returns the oracle configuration
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Consult { token, amount } => to_binary(&consult(deps, token, amount)?),
+        QueryMsg::Consult { token, amount } => to_binary(&consult(deps, env, token, amount)?),
+        QueryMsg::ConsultPath {
+            token,
+            amount,
+            path,
+        } => to_binary(&consult_path(deps, env, token, amount, path)?),
+        QueryMsg::ConsultQuote {
+            token,
+            amount,
+            mut path,
+        } => {
+            let quote_asset = CONFIG
+                .load(deps.storage)?
+                .quote_asset
+                .ok_or_else(|| StdError::generic_err("Quote asset is not set"))?;
+            path.push(quote_asset);
+            to_binary(&consult_path(deps, env, token, amount, path)?)
+        }
+        QueryMsg::Pairs {} => to_binary(&query_pairs(deps)?),
+        QueryMsg::Config {} => {
+            let config = CONFIG.load(deps.storage)?;
+            to_binary(&ConfigResponse {
+                owner: config.owner,
+                factory: config.factory,
+                quote_asset: config.quote_asset,
+                max_staleness: config.max_staleness,
+                max_deviation: config.max_deviation,
+            })
+        }
     }
 }
 
-/// Multiplies a token amount by its latest TWAP value.
+/// Multiplies a token amount by its latest TWAP value in every tracked pair which contains the token.
+/// Fails if any of these pairs has a TWAP older than the staleness bound.
 /// * **token** token for which we multiply its TWAP value by an amount.
 ///
 /// * **amount** amount of tokens we multiply the TWAP by.
 fn consult(
     deps: Deps,
+    env: Env,
     token: AssetInfo,
     amount: Uint128,
-) -> Result<Vec<(AssetInfo, Uint256)>, StdError> {
+) -> Result<Vec<ConsultResponse>, StdError> {
     let config = CONFIG.load(deps.storage)?;
-    let price_last = PRICE_LAST.load(deps.storage)?;
+    let pairs = PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
 
-    let mut average_prices = vec![];
-    for (from, to, value) in price_last.average_prices {
-        if from.equal(&token) {
-            average_prices.push((to, value));
+    let mut response = vec![];
+    for (pair_addr, pair_state) in pairs {
+        for (from, to, price_average) in &pair_state.price_last.average_prices {
+            if from.equal(&token) {
+                assert_not_stale(&config, &env, &pair_state)?;
+
+                response.push(ConsultResponse {
+                    asset_info: to.clone(),
+                    amount: convert_amount(deps, &pair_addr, &token, to, *price_average, amount)?,
+                    last_update: pair_state.price_last.block_timestamp_last,
+                    frozen: pair_state.frozen,
+                });
+            }
         }
     }
 
-    if average_prices.is_empty() {
+    if response.is_empty() {
         return Err(StdError::generic_err("Invalid Token"));
     }
 
-    // Get the token's precision
-    let p = get_precision(deps.storage, &token)?;
-    let one = Uint128::new(10_u128.pow(p.into()));
-
-    average_prices
-        .iter()
-        .map(|(asset, price_average)| {
-            if price_average.is_zero() {
-                let price = query_prices(
-                    deps.querier,
-                    config.pair.contract_addr.clone(),
-                    Asset {
-                        info: token.clone(),
-                        amount: one,
-                    },
-                    Some(asset.clone()),
-                )?
-                .return_amount;
-                Ok((
-                    asset.clone(),
-                    Uint256::from(price).multiply_ratio(Uint256::from(amount), Uint256::from(one)),
-                ))
-            } else {
-                let price_precision = Uint256::from(10_u128.pow(TWAP_PRECISION.into()));
-                Ok((
-                    asset.clone(),
-                    Uint256::from(amount) * *price_average / price_precision,
-                ))
-            }
+    Ok(response)
+}
+
+/// Fails if the pair TWAP is older than the staleness bound.
+fn assert_not_stale(config: &Config, env: &Env, pair_state: &PairState) -> StdResult<()> {
+    if let Some(max_staleness) = config.max_staleness {
+        if pair_state
+            .price_last
+            .block_timestamp_last
+            .saturating_add(max_staleness)
+            < env.block.time.seconds()
+        {
+            return Err(StdError::generic_err(format!(
+                "Price of pair {} is stale",
+                pair_state.pair_info.contract_addr
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts a token amount into the ask asset using the pair TWAP.
+/// If the TWAP is not calculated yet, the pair spot price is used.
+fn convert_amount(
+    deps: Deps,
+    pair_addr: &Addr,
+    token: &AssetInfo,
+    ask_asset: &AssetInfo,
+    price_average: Decimal256,
+    amount: Uint128,
+) -> StdResult<Uint256> {
+    if price_average.is_zero() {
+        // Get the token's precision
+        let p = get_precision(deps.storage, token)?;
+        let one = Uint128::new(10_u128.pow(p.into()));
+
+        let price = query_prices(
+            deps.querier,
+            pair_addr,
+            Asset {
+                info: token.clone(),
+                amount: one,
+            },
+            Some(ask_asset.clone()),
+        )?
+        .return_amount;
+        Ok(Uint256::from(price).multiply_ratio(Uint256::from(amount), Uint256::from(one)))
+    } else {
+        let price_precision = Uint256::from(10_u128.pow(TWAP_PRECISION.into()));
+        Ok(Uint256::from(amount) * price_average / price_precision)
+    }
+}
+
+/// Converts a token amount through the chain of tracked pairs defined by the path.
+/// Fails if any pair of the chain is not tracked, frozen or its TWAP is older than the staleness bound.
+/// The response carries the oldest TWAP update time among the pairs of the chain.
+fn consult_path(
+    deps: Deps,
+    env: Env,
+    token: AssetInfo,
+    amount: Uint128,
+    path: Vec<AssetInfo>,
+) -> StdResult<ConsultPathResponse> {
+    if path.is_empty() {
+        return Err(StdError::generic_err("Path must not be empty"));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut offer_asset = token;
+    let mut offer_amount = Uint256::from(amount);
+    let mut last_update = env.block.time.seconds();
+    for ask_asset in path {
+        let pair_info = query_pair_info(
+            &deps.querier,
+            &config.factory,
+            &[offer_asset.clone(), ask_asset.clone()],
+        )?;
+        let pair_state = PAIRS
+            .may_load(deps.storage, &pair_info.contract_addr)?
+            .ok_or_else(|| {
+                StdError::generic_err(format!("Pair {} is not tracked", pair_info.contract_addr))
+            })?;
+
+        if pair_state.frozen {
+            return Err(StdError::generic_err(format!(
+                "Price of pair {} is frozen",
+                pair_info.contract_addr
+            )));
+        }
+        assert_not_stale(&config, &env, &pair_state)?;
+        last_update = last_update.min(pair_state.price_last.block_timestamp_last);
+
+        let price_average = pair_state
+            .price_last
+            .average_prices
+            .iter()
+            .find(|(from, to, _)| from.equal(&offer_asset) && to.equal(&ask_asset))
+            .map(|(_, _, price_average)| *price_average)
+            .ok_or_else(|| StdError::generic_err("Invalid Token"))?;
+
+        offer_amount = convert_amount(
+            deps,
+            &pair_info.contract_addr,
+            &offer_asset,
+            &ask_asset,
+            price_average,
+            offer_amount.try_into()?,
+        )?;
+        offer_asset = ask_asset;
+    }
+
+    Ok(ConsultPathResponse {
+        amount: offer_amount,
+        last_update,
+        staleness: env.block.time.seconds() - last_update,
+    })
+}
+
+/// Returns all tracked pairs.
+fn query_pairs(deps: Deps) -> StdResult<Vec<OraclePairResponse>> {
+    PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, pair_state) = item?;
+            Ok(OraclePairResponse {
+                pair_addr: pair_state.pair_info.contract_addr,
+                asset_infos: pair_state.pair_info.asset_infos,
+                period: pair_state.period,
+                price_source: pair_state.price_source,
+                block_timestamp_last: pair_state.price_last.block_timestamp_last,
+                frozen: pair_state.frozen,
+            })
         })
-        .collect::<Result<Vec<(AssetInfo, Uint256)>, StdError>>()
+        .collect()
 }
 
 /// Manages the contract migration.
@@ -197,46 +732,10 @@ pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Respons
     match contract_version.contract.as_ref() {
         "astroport-oracle" => match contract_version.version.as_ref() {
             "1.0.0" | "1.0.1" | "1.0.2" => {
-                let config = CONFIG.load(deps.storage)?;
-                let price_last_v100 = PRICE_LAST_V100.load(deps.storage)?;
-
-                let cumulative_prices = vec![
-                    (
-                        config.asset_infos[0].clone(),
-                        config.asset_infos[1].clone(),
-                        price_last_v100.price0_cumulative_last,
-                    ),
-                    (
-                        config.asset_infos[1].clone(),
-                        config.asset_infos[0].clone(),
-                        price_last_v100.price1_cumulative_last,
-                    ),
-                ];
-                let average_prices = vec![
-                    (
-                        config.asset_infos[0].clone(),
-                        config.asset_infos[1].clone(),
-                        price_last_v100.price_0_average,
-                    ),
-                    (
-                        config.asset_infos[1].clone(),
-                        config.asset_infos[0].clone(),
-                        price_last_v100.price_1_average,
-                    ),
-                ];
-
-                PRICE_LAST.save(
-                    deps.storage,
-                    &PriceCumulativeLast {
-                        cumulative_prices,
-                        average_prices,
-                        block_timestamp_last: price_last_v100.block_timestamp_last,
-                    },
-                )?;
-                for asset_info in &config.asset_infos {
-                    store_precisions(deps.branch(), asset_info, &config.factory)?;
-                }
+                migrate_prices_from_v100(deps.branch())?;
+                migrate_from_v2(deps.branch())?;
             }
+            "2.0.0" | "2.1.0" | "2.1.1" => migrate_from_v2(deps.branch())?,
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
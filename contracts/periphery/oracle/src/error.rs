@@ -13,6 +13,21 @@ pub enum ContractError {
     #[error("Period not elapsed")]
     WrongPeriod {},
 
+    #[error("Period must be greater than zero")]
+    InvalidPeriod {},
+
+    #[error("Pair is already tracked")]
+    PairAlreadyTracked {},
+
+    #[error("Pair is not tracked")]
+    PairNotTracked {},
+
+    #[error("Pair is not frozen")]
+    PairNotFrozen {},
+
+    #[error("Pools hosted by the pool manager can't be tracked")]
+    ManagerPoolNotSupported {},
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 }
@@ -1,7 +1,12 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal256, Uint128};
+use cosmwasm_std::{Addr, Decimal256, DepsMut, StdResult, Uint128};
 use cw_storage_plus::Item;
 
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::oracle::{PriceSource, DEFAULT_PERIOD};
+
+use crate::state::{store_precisions, Config, PairState, PriceCumulativeLast, CONFIG, PAIRS};
+
 /// This structure stores the latest cumulative and average token prices for the target pool
 #[cw_serde]
 pub struct PriceCumulativeLastV100 {
@@ -18,3 +23,95 @@ pub struct PriceCumulativeLastV100 {
 }
 
 pub const PRICE_LAST_V100: Item<PriceCumulativeLastV100> = Item::new("price_last");
+
+/// Global configuration of the single pair oracle
+#[cw_serde]
+pub struct ConfigV2 {
+    /// The address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The factory contract address
+    pub factory: Addr,
+    /// The assets in the pool
+    pub asset_infos: Vec<AssetInfo>,
+    /// Information about the pair (LP token address, pair type etc)
+    pub pair: PairInfo,
+}
+
+pub const CONFIG_V2: Item<ConfigV2> = Item::new("config");
+
+pub const PRICE_LAST_V2: Item<PriceCumulativeLast> = Item::new("price_last");
+
+/// Converts prices stored by v1.0.x into the v2 layout.
+pub(crate) fn migrate_prices_from_v100(mut deps: DepsMut) -> StdResult<()> {
+    let config = CONFIG_V2.load(deps.storage)?;
+    let price_last_v100 = PRICE_LAST_V100.load(deps.storage)?;
+
+    let cumulative_prices = vec![
+        (
+            config.asset_infos[0].clone(),
+            config.asset_infos[1].clone(),
+            price_last_v100.price0_cumulative_last,
+        ),
+        (
+            config.asset_infos[1].clone(),
+            config.asset_infos[0].clone(),
+            price_last_v100.price1_cumulative_last,
+        ),
+    ];
+    let average_prices = vec![
+        (
+            config.asset_infos[0].clone(),
+            config.asset_infos[1].clone(),
+            price_last_v100.price_0_average,
+        ),
+        (
+            config.asset_infos[1].clone(),
+            config.asset_infos[0].clone(),
+            price_last_v100.price_1_average,
+        ),
+    ];
+
+    PRICE_LAST_V2.save(
+        deps.storage,
+        &PriceCumulativeLast {
+            cumulative_prices,
+            average_prices,
+            block_timestamp_last: price_last_v100.block_timestamp_last,
+        },
+    )?;
+    for asset_info in &config.asset_infos {
+        store_precisions(deps.branch(), asset_info, &config.factory)?;
+    }
+
+    Ok(())
+}
+
+/// Moves the single pair of v2 oracle into the tracked pairs map.
+pub(crate) fn migrate_from_v2(deps: DepsMut) -> StdResult<()> {
+    let config = CONFIG_V2.load(deps.storage)?;
+    let price_last = PRICE_LAST_V2.load(deps.storage)?;
+
+    PAIRS.save(
+        deps.storage,
+        &config.pair.contract_addr,
+        &PairState {
+            pair_info: config.pair.clone(),
+            period: DEFAULT_PERIOD,
+            price_source: PriceSource::CumulativePrices,
+            price_last,
+            frozen: false,
+        },
+    )?;
+    PRICE_LAST_V2.remove(deps.storage);
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: config.owner,
+            factory: config.factory,
+            quote_asset: None,
+            max_staleness: None,
+            max_deviation: None,
+        },
+    )
+}
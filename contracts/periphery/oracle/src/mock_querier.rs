@@ -107,6 +107,7 @@ impl WasmMockQuerier {
                                 contract_addr: Addr::unchecked("pair"),
                                 liquidity_token: Addr::unchecked("lp_token"),
                                 pair_type: PairType::Xyk {},
+                                pool_key: None,
                             })
                             .into(),
                         ),
@@ -1,4 +1,5 @@
 use astroport::asset::{Asset, AssetInfo};
+use astroport::observation::OracleObservation;
 use astroport::pair::{CumulativePricesResponse, QueryMsg as PairQueryMsg, SimulationResponse};
 use cosmwasm_std::{QuerierWrapper, StdResult};
 
@@ -12,6 +13,19 @@ pub fn query_cumulative_prices(
     querier.query_wasm_smart(pair_contract, &PairQueryMsg::CumulativePrices {})
 }
 
+/// Returns the price observation of a PCL or stableswap pair.
+///
+/// * **pair_contract** address of the pair for which we return data.
+///
+/// * **seconds_ago** how far back in time the observation is taken.
+pub fn query_observation(
+    querier: QuerierWrapper,
+    pair_contract: impl Into<String>,
+    seconds_ago: u64,
+) -> StdResult<OracleObservation> {
+    querier.query_wasm_smart(pair_contract, &PairQueryMsg::Observe { seconds_ago })
+}
+
 /// Returns information about an asset's price from a specific pair.
 ///
 /// * **pair_contract** pair that holds the target asset.
@@ -1,13 +1,14 @@
 use cosmwasm_schema::cw_serde;
 
 use astroport::asset::{AssetInfo, PairInfo};
-use cosmwasm_std::{Addr, Decimal256, DepsMut, StdResult, Storage, Uint128};
+use astroport::oracle::PriceSource;
+use cosmwasm_std::{Addr, Decimal, Decimal256, DepsMut, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
-/// Stores the latest cumulative and average prices at the given key
-pub const PRICE_LAST: Item<PriceCumulativeLast> = Item::new("price_last");
+/// Stores tracked pairs with their latest prices. Key is the pair contract address
+pub const PAIRS: Map<&Addr, PairState> = Map::new("pairs");
 
 /// This structure stores the latest cumulative and average token prices for the target pool
 #[cw_serde]
@@ -27,10 +28,27 @@ pub struct Config {
     pub owner: Addr,
     /// The factory contract address
     pub factory: Addr,
-    /// The assets in the pool. Each asset is described using a [`AssetInfo`]
-    pub asset_infos: Vec<AssetInfo>,
+    /// The asset in which quote prices are returned
+    pub quote_asset: Option<AssetInfo>,
+    /// Maximum age of a TWAP in seconds which is allowed to be used in queries
+    pub max_staleness: Option<u64>,
+    /// Maximum relative change of a TWAP during one update
+    pub max_deviation: Option<Decimal>,
+}
+
+/// This structure stores the state of a tracked pair
+#[cw_serde]
+pub struct PairState {
     /// Information about the pair (LP token address, pair type etc)
-    pub pair: PairInfo,
+    pub pair_info: PairInfo,
+    /// Time between two consecutive TWAP updates
+    pub period: u64,
+    /// Where the pair prices are read from
+    pub price_source: PriceSource,
+    /// The latest cumulative and average prices
+    pub price_last: PriceCumulativeLast,
+    /// Set when the last update exceeded the maximum deviation. Prices stay as of the last good update
+    pub frozen: bool,
 }
 
 /// Stores map of AssetInfo (as String) -> precision
@@ -64,6 +64,10 @@ fn oracle_overflow() {
     let instantiate_msg = InstantiateMsg {
         factory_contract: factory.to_string(),
         asset_infos: vec![astro_asset_info, usdc_asset_info],
+        period: None,
+        quote_asset: None,
+        max_staleness: None,
+        max_deviation: None,
     };
 
     // Set cumulative price to 192738282u128
@@ -105,5 +109,14 @@ fn oracle_overflow() {
         ],
     );
     env.block.time = env.block.time.plus_seconds(86400);
-    execute(deps.as_mut(), env, info, ExecuteMsg::Update {}).unwrap();
+    execute(
+        deps.as_mut(),
+        env,
+        info,
+        ExecuteMsg::Update {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
 }
@@ -2,7 +2,8 @@
 
 use anyhow::Result;
 use cosmwasm_std::{
-    attr, to_binary, Addr, BlockInfo, Coin, Decimal, QueryRequest, StdResult, Uint128, WasmQuery,
+    attr, to_binary, Addr, BlockInfo, Coin, Decimal, QueryRequest, StdResult, Uint128, Uint256,
+    WasmQuery,
 };
 use cw20::{BalanceResponse, Cw20QueryMsg, MinterResponse};
 use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
@@ -12,8 +13,12 @@ use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 
 use astroport::factory::{PairConfig, PairType};
 
-use astroport::oracle::QueryMsg::Consult;
-use astroport::oracle::{ExecuteMsg, InstantiateMsg};
+use astroport::oracle::QueryMsg::{Consult, ConsultPath, ConsultQuote};
+use astroport::oracle::{
+    ConfigResponse, ConsultPathResponse, ConsultResponse, ExecuteMsg, InstantiateMsg,
+    OraclePairResponse, PriceSource, QueryMsg,
+};
+use astroport::pair::StablePoolParams;
 
 const OWNER: &str = "owner";
 
@@ -149,6 +154,8 @@ fn instantiate_contracts(mut router: &mut App, owner: Addr) -> (Addr, Addr, u64)
                 maker_fee_bps: 0,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
             PairConfig {
                 code_id: pair_stable_code_id,
@@ -157,6 +164,8 @@ fn instantiate_contracts(mut router: &mut App, owner: Addr) -> (Addr, Addr, u64)
                 maker_fee_bps: 0,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
         ],
         token_code_id: 1u64,
@@ -526,6 +535,10 @@ fn consult() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        period: None,
+        quote_asset: None,
+        max_staleness: None,
+        max_deviation: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -542,7 +555,10 @@ fn consult() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap_err();
@@ -565,7 +581,10 @@ fn consult() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap();
@@ -580,14 +599,14 @@ fn consult() {
             },
             amount,
         };
-        let res: Vec<(AssetInfo, Uint128)> = router
+        let res: Vec<ConsultResponse> = router
             .wrap()
             .query(&QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: oracle_instance.to_string(),
                 msg: to_binary(&msg).unwrap(),
             }))
             .unwrap();
-        assert_eq!(res[0].1, amount);
+        assert_eq!(res[0].amount, Uint256::from(amount));
     }
 }
 
@@ -714,6 +733,10 @@ fn consult2() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        period: None,
+        quote_asset: None,
+        max_staleness: None,
+        max_deviation: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -730,7 +753,10 @@ fn consult2() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap_err();
@@ -740,7 +766,10 @@ fn consult2() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap();
@@ -765,7 +794,10 @@ fn consult2() {
             .execute_contract(
                 owner.clone(),
                 oracle_instance.clone(),
-                &ExecuteMsg::Update {},
+                &ExecuteMsg::Update {
+                    start_after: None,
+                    limit: None,
+                },
                 &[],
             )
             .unwrap();
@@ -788,14 +820,14 @@ fn consult2() {
             },
             amount,
         };
-        let res: Vec<(AssetInfo, Uint128)> = router
+        let res: Vec<ConsultResponse> = router
             .wrap()
             .query(&QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: oracle_instance.to_string(),
                 msg: to_binary(&msg).unwrap(),
             }))
             .unwrap();
-        assert_eq!(res[0].1, amount_exp);
+        assert_eq!(res[0].amount, Uint256::from(amount_exp));
     }
 
     // Change pair liquidity
@@ -818,7 +850,10 @@ fn consult2() {
             .execute_contract(
                 owner.clone(),
                 oracle_instance.clone(),
-                &ExecuteMsg::Update {},
+                &ExecuteMsg::Update {
+                    start_after: None,
+                    limit: None,
+                },
                 &[],
             )
             .unwrap();
@@ -841,14 +876,14 @@ fn consult2() {
             },
             amount,
         };
-        let res: Vec<(AssetInfo, Uint128)> = router
+        let res: Vec<ConsultResponse> = router
             .wrap()
             .query(&QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: oracle_instance.to_string(),
                 msg: to_binary(&msg).unwrap(),
             }))
             .unwrap();
-        assert_eq!(res[0].1, amount_exp);
+        assert_eq!(res[0].amount, Uint256::from(amount_exp));
     }
 }
 
@@ -928,6 +963,10 @@ fn consult_zero_price() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        period: None,
+        quote_asset: None,
+        max_staleness: None,
+        max_deviation: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -944,7 +983,10 @@ fn consult_zero_price() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap_err();
@@ -954,7 +996,10 @@ fn consult_zero_price() {
         .execute_contract(
             owner.clone(),
             oracle_instance.clone(),
-            &ExecuteMsg::Update {},
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
             &[],
         )
         .unwrap();
@@ -977,14 +1022,14 @@ fn consult_zero_price() {
             },
             amount: amount_in,
         };
-        let res: Vec<(AssetInfo, Uint128)> = router
+        let res: Vec<ConsultResponse> = router
             .wrap()
             .query(&QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: oracle_instance.to_string(),
                 msg: to_binary(&msg).unwrap(),
             }))
             .unwrap();
-        assert_eq!(res[0].1, amount_out);
+        assert_eq!(res[0].amount, Uint256::from(amount_out));
     }
 
     let res: StdResult<Uint128> = router.wrap().query_wasm_smart(
@@ -1054,6 +1099,10 @@ fn consult_zero_price() {
             &InstantiateMsg {
                 factory_contract: factory_instance.to_string(),
                 asset_infos: asset_infos.clone(),
+                period: None,
+                quote_asset: None,
+                max_staleness: None,
+                max_deviation: None,
             },
             &[],
             String::from("ORACLE 2"),
@@ -1061,7 +1110,7 @@ fn consult_zero_price() {
         )
         .unwrap();
 
-    let res: Vec<(AssetInfo, Uint128)> = router
+    let res: Vec<ConsultResponse> = router
         .wrap()
         .query_wasm_smart(
             &oracle_instance,
@@ -1072,5 +1121,931 @@ fn consult_zero_price() {
         )
         .unwrap();
     // Price is too small thus we get zero
-    assert_eq!(res[0].1.u128(), 0u128);
+    assert_eq!(res[0].amount, Uint256::zero());
+}
+
+#[test]
+fn multiple_pairs_with_observations() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        Option::from(owner.clone()),
+        Some(vec![
+            Coin {
+                denom: "cny".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ]),
+    );
+    let user = Addr::unchecked("user0000");
+
+    let (astro_token_instance, factory_instance, oracle_code_id) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let xyk_asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: usdc_token_instance.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: astro_token_instance.clone(),
+        },
+    ];
+    let xyk_assets = vec![
+        Asset {
+            info: xyk_asset_infos[0].clone(),
+            amount: Uint128::from(100_000_u128),
+        },
+        Asset {
+            info: xyk_asset_infos[1].clone(),
+            amount: Uint128::from(100_000_u128),
+        },
+    ];
+    let xyk_pair_info = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        xyk_assets.clone(),
+    );
+    provide_liquidity(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &xyk_pair_info,
+        xyk_assets,
+    )
+    .unwrap();
+
+    // Create stableswap pair which doesn't support cumulative prices
+    let stable_asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "cny".to_string(),
+        },
+    ];
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type: PairType::Stable {},
+                asset_infos: stable_asset_infos.clone(),
+                init_params: Some(
+                    to_binary(&StablePoolParams {
+                        amp: 100,
+                        owner: None,
+                    })
+                    .unwrap(),
+                ),
+            },
+            &[],
+        )
+        .unwrap();
+    let stable_pair_info: PairInfo = router
+        .wrap()
+        .query_wasm_smart(
+            &factory_instance,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: stable_asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    provide_liquidity(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &stable_pair_info,
+        vec![
+            Asset {
+                info: stable_asset_infos[0].clone(),
+                amount: Uint128::from(10_000_000_000u128),
+            },
+            Asset {
+                info: stable_asset_infos[1].clone(),
+                amount: Uint128::from(10_000_000_000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    let oracle_instance = router
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                factory_contract: factory_instance.to_string(),
+                asset_infos: xyk_asset_infos.clone(),
+                period: None,
+                quote_asset: None,
+                max_staleness: None,
+                max_deviation: None,
+            },
+            &[],
+            String::from("ORACLE"),
+            None,
+        )
+        .unwrap();
+
+    let add_stable_pair = ExecuteMsg::AddPair {
+        asset_infos: stable_asset_infos.clone(),
+        period: Some(3600),
+    };
+    let err = router
+        .execute_contract(user.clone(), oracle_instance.clone(), &add_stable_pair, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &add_stable_pair,
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &add_stable_pair,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair is already tracked");
+
+    let pairs: Vec<OraclePairResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &QueryMsg::Pairs {})
+        .unwrap();
+    assert_eq!(pairs.len(), 2);
+    let stable_pair = pairs
+        .iter()
+        .find(|pair| pair.pair_addr == stable_pair_info.contract_addr)
+        .unwrap();
+    assert_eq!(stable_pair.price_source, PriceSource::Observations);
+    assert_eq!(stable_pair.period, 3600);
+    let xyk_pair = pairs
+        .iter()
+        .find(|pair| pair.pair_addr == xyk_pair_info.contract_addr)
+        .unwrap();
+    assert_eq!(xyk_pair.price_source, PriceSource::CumulativePrices);
+    assert_eq!(xyk_pair.period, 86400);
+
+    // Make a trade to record the stableswap pair observation
+    router
+        .send_tokens(
+            owner.clone(),
+            user.clone(),
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1_000_000),
+            }],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            user.clone(),
+            stable_pair_info.contract_addr.clone(),
+            &astroport::pair::ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: stable_asset_infos[0].clone(),
+                    amount: Uint128::new(1_000_000),
+                },
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1_000_000),
+            }],
+        )
+        .unwrap();
+
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(3600);
+        block.height += 720;
+    });
+
+    // Only the stableswap pair period has elapsed
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("pairs", stable_pair_info.contract_addr.to_string()))));
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Period not elapsed");
+
+    let res: Vec<ConsultResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &oracle_instance,
+            &Consult {
+                token: stable_asset_infos[0].clone(),
+                amount: Uint128::new(1_000_000),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].asset_info, stable_asset_infos[1]);
+    assert!(
+        res[0].amount > Uint256::from(999_000u128) && res[0].amount <= Uint256::from(1_000_000u128)
+    );
+    assert!(!res[0].frozen);
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::UpdatePairPeriod {
+                asset_infos: stable_asset_infos.clone(),
+                period: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Period must be greater than zero"
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::RemovePair {
+                asset_infos: stable_asset_infos.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+    let pairs: Vec<OraclePairResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &QueryMsg::Pairs {})
+        .unwrap();
+    assert_eq!(pairs.len(), 1);
+
+    let err = router
+        .wrap()
+        .query_wasm_smart::<Vec<ConsultResponse>>(
+            &oracle_instance,
+            &Consult {
+                token: stable_asset_infos[0].clone(),
+                amount: Uint128::new(1_000_000),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: Querier contract error: Generic error: Invalid Token"
+    );
+}
+
+#[test]
+fn consult_path() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        Option::from(owner.clone()),
+        Some(vec![Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(100_000_000_000u128),
+        }]),
+    );
+    let user = Addr::unchecked("user0000");
+
+    let (astro_token_instance, factory_instance, oracle_code_id) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let astro = AssetInfo::Token {
+        contract_addr: astro_token_instance.clone(),
+    };
+    let usdc = AssetInfo::Token {
+        contract_addr: usdc_token_instance.clone(),
+    };
+    let luna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    // 1 ASTRO = 2 USDC = 1 LUNA
+    for assets in [
+        vec![
+            Asset {
+                info: astro.clone(),
+                amount: Uint128::from(100_000_000_000u128),
+            },
+            Asset {
+                info: usdc.clone(),
+                amount: Uint128::from(200_000_000_000u128),
+            },
+        ],
+        vec![
+            Asset {
+                info: usdc.clone(),
+                amount: Uint128::from(200_000_000_000u128),
+            },
+            Asset {
+                info: luna.clone(),
+                amount: Uint128::from(100_000_000_000u128),
+            },
+        ],
+    ] {
+        let pair_info = create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            assets.clone(),
+        );
+        provide_liquidity(&mut router, owner.clone(), user.clone(), &pair_info, assets).unwrap();
+    }
+
+    let oracle_instance = router
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                factory_contract: factory_instance.to_string(),
+                asset_infos: vec![astro.clone(), usdc.clone()],
+                period: None,
+                quote_asset: None,
+                max_staleness: None,
+                max_deviation: None,
+            },
+            &[],
+            String::from("ORACLE"),
+            None,
+        )
+        .unwrap();
+
+    let consult_path = ConsultPath {
+        token: astro.clone(),
+        amount: Uint128::new(1_000_000),
+        path: vec![usdc.clone(), luna.clone()],
+    };
+    let err = router
+        .wrap()
+        .query_wasm_smart::<ConsultPathResponse>(&oracle_instance, &consult_path)
+        .unwrap_err();
+    assert!(err.to_string().contains("is not tracked"));
+
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::AddPair {
+                asset_infos: vec![usdc.clone(), luna.clone()],
+                period: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // TWAPs are not calculated yet thus spot prices are used
+    let res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult_path)
+        .unwrap();
+    assert!(res.amount > Uint256::from(999_900u128) && res.amount <= Uint256::from(1_000_000u128));
+
+    let consult_quote = ConsultQuote {
+        token: astro.clone(),
+        amount: Uint128::new(1_000_000),
+        path: vec![usdc.clone()],
+    };
+    let err = router
+        .wrap()
+        .query_wasm_smart::<ConsultPathResponse>(&oracle_instance, &consult_quote)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: Querier contract error: Generic error: Quote asset is not set"
+    );
+
+    let err = router
+        .execute_contract(
+            user.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                quote_asset: Some(luna.clone()),
+                max_staleness: Some(2 * 86400),
+                max_deviation: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                quote_asset: Some(luna.clone()),
+                max_staleness: Some(2 * 86400),
+                max_deviation: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.quote_asset, Some(luna.clone()));
+    assert_eq!(config.max_staleness, Some(2 * 86400));
+
+    router.update_block(next_day);
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let twap_res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult_path)
+        .unwrap();
+    assert_eq!(twap_res.amount, Uint256::from(1_000_000u128));
+    assert_eq!(twap_res.last_update, router.block_info().time.seconds());
+    assert_eq!(twap_res.staleness, 0);
+    let quote_res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult_quote)
+        .unwrap();
+    assert_eq!(quote_res, twap_res);
+
+    // Reverse path
+    let res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &oracle_instance,
+            &ConsultPath {
+                token: luna.clone(),
+                amount: Uint128::new(1_000_000),
+                path: vec![usdc.clone(), astro.clone()],
+            },
+        )
+        .unwrap();
+    assert_eq!(res.amount, Uint256::from(1_000_000u128));
+
+    // Staleness is reported till it exceeds the bound
+    router.update_block(next_day);
+    let res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult_quote)
+        .unwrap();
+    assert_eq!(res.last_update, twap_res.last_update);
+    assert_eq!(res.staleness, 86400);
+
+    // TWAPs become stale after two days without updates
+    router.update_block(next_day);
+    router.update_block(next_day);
+    let err = router
+        .wrap()
+        .query_wasm_smart::<ConsultPathResponse>(&oracle_instance, &consult_quote)
+        .unwrap_err();
+    assert!(err.to_string().contains("is stale"));
+}
+
+#[test]
+fn deviation_freezes_price() {
+    let mut router = mock_app(None, None);
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user0000");
+    let (astro_token_instance, factory_instance, oracle_code_id) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: astro_token_instance.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: usdc_token_instance.clone(),
+        },
+    ];
+    let assets = vec![
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(1_000_000_u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(1_000_000_u128),
+        },
+    ];
+    let pair_info = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        assets.clone(),
+    );
+    provide_liquidity(&mut router, owner.clone(), user.clone(), &pair_info, assets).unwrap();
+
+    let oracle_instance = router
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                factory_contract: factory_instance.to_string(),
+                asset_infos: asset_infos.clone(),
+                period: None,
+                quote_asset: None,
+                max_staleness: None,
+                max_deviation: Some(Decimal::percent(10)),
+            },
+            &[],
+            String::from("ORACLE"),
+            None,
+        )
+        .unwrap();
+
+    router.update_block(next_day);
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let good_update = router.block_info().time.seconds();
+
+    let consult = Consult {
+        token: asset_infos[0].clone(),
+        amount: Uint128::new(1000),
+    };
+    let res: Vec<ConsultResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult)
+        .unwrap();
+    assert_eq!(res[0].amount, Uint256::from(1000u128));
+    assert_eq!(res[0].last_update, good_update);
+    assert!(!res[0].frozen);
+
+    // Move the pool price far away
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        user.clone(),
+        Uint128::new(500_000),
+    );
+    router
+        .execute_contract(
+            user.clone(),
+            astro_token_instance.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: pair_info.contract_addr.to_string(),
+                amount: Uint128::new(500_000),
+                msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    router.update_block(next_day);
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("frozen_pairs", pair_info.contract_addr.to_string()))));
+
+    // The last good price is kept
+    let res: Vec<ConsultResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult)
+        .unwrap();
+    assert_eq!(res[0].amount, Uint256::from(1000u128));
+    assert_eq!(res[0].last_update, good_update);
+    assert!(res[0].frozen);
+
+    // Frozen pairs can't be used in conversion chains
+    let consult_path = ConsultPath {
+        token: asset_infos[0].clone(),
+        amount: Uint128::new(1000),
+        path: vec![asset_infos[1].clone()],
+    };
+    let err = router
+        .wrap()
+        .query_wasm_smart::<ConsultPathResponse>(&oracle_instance, &consult_path)
+        .unwrap_err();
+    assert!(err.to_string().contains("is frozen"));
+
+    let pairs: Vec<OraclePairResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &QueryMsg::Pairs {})
+        .unwrap();
+    assert!(pairs[0].frozen);
+
+    let unfreeze = ExecuteMsg::UnfreezePair {
+        asset_infos: asset_infos.clone(),
+    };
+    let err = router
+        .execute_contract(user.clone(), oracle_instance.clone(), &unfreeze, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(owner.clone(), oracle_instance.clone(), &unfreeze, &[])
+        .unwrap();
+    let err = router
+        .execute_contract(owner.clone(), oracle_instance.clone(), &unfreeze, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair is not frozen");
+
+    // The new price is accepted
+    let res: Vec<ConsultResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult)
+        .unwrap();
+    assert!(res[0].amount < Uint256::from(500u128));
+    assert_eq!(res[0].last_update, router.block_info().time.seconds());
+    assert!(!res[0].frozen);
+    let path_res: ConsultPathResponse = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &consult_path)
+        .unwrap();
+    assert_eq!(path_res.amount, res[0].amount);
+}
+
+#[test]
+fn broken_pair_does_not_block_update() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        Option::from(owner.clone()),
+        Some(vec![
+            Coin {
+                denom: "cny".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ]),
+    );
+    let user = Addr::unchecked("user0000");
+
+    let (astro_token_instance, factory_instance, oracle_code_id) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let xyk_asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: usdc_token_instance.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: astro_token_instance.clone(),
+        },
+    ];
+    let xyk_assets = vec![
+        Asset {
+            info: xyk_asset_infos[0].clone(),
+            amount: Uint128::from(100_000_u128),
+        },
+        Asset {
+            info: xyk_asset_infos[1].clone(),
+            amount: Uint128::from(100_000_u128),
+        },
+    ];
+    let xyk_pair_info = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        xyk_assets.clone(),
+    );
+    provide_liquidity(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &xyk_pair_info,
+        xyk_assets,
+    )
+    .unwrap();
+
+    // Stableswap pair without trades has an empty observation buffer
+    let stable_asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "cny".to_string(),
+        },
+    ];
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type: PairType::Stable {},
+                asset_infos: stable_asset_infos.clone(),
+                init_params: Some(
+                    to_binary(&StablePoolParams {
+                        amp: 100,
+                        owner: None,
+                    })
+                    .unwrap(),
+                ),
+            },
+            &[],
+        )
+        .unwrap();
+    let stable_pair_info: PairInfo = router
+        .wrap()
+        .query_wasm_smart(
+            &factory_instance,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: stable_asset_infos.clone(),
+            },
+        )
+        .unwrap();
+
+    let oracle_instance = router
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                factory_contract: factory_instance.to_string(),
+                asset_infos: xyk_asset_infos.clone(),
+                period: None,
+                quote_asset: None,
+                max_staleness: None,
+                max_deviation: None,
+            },
+            &[],
+            String::from("ORACLE"),
+            None,
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::AddPair {
+                asset_infos: stable_asset_infos.clone(),
+                period: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(86400);
+        block.height += 17280;
+    });
+
+    // The broken pair is frozen while the other one is updated
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            oracle_instance.clone(),
+            &ExecuteMsg::Update {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("pairs", xyk_pair_info.contract_addr.to_string()))));
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.attributes.contains(&attr(
+            "failed_pairs",
+            stable_pair_info.contract_addr.to_string()
+        ))));
+
+    let pairs: Vec<OraclePairResponse> = router
+        .wrap()
+        .query_wasm_smart(&oracle_instance, &QueryMsg::Pairs {})
+        .unwrap();
+    for pair in &pairs {
+        assert_eq!(
+            pair.frozen,
+            pair.pair_addr == stable_pair_info.contract_addr
+        );
+    }
+
+    let err = router
+        .wrap()
+        .query_wasm_smart::<ConsultPathResponse>(
+            &oracle_instance,
+            &ConsultPath {
+                token: stable_asset_infos[0].clone(),
+                amount: Uint128::new(1_000_000),
+                path: vec![stable_asset_infos[1].clone()],
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("is frozen"));
+
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(86400);
+        block.height += 17280;
+    });
+
+    // Pairs are updated in pages sorted by address
+    let mut pair_addrs = pairs
+        .into_iter()
+        .map(|pair| pair.pair_addr)
+        .collect::<Vec<_>>();
+    pair_addrs.sort();
+    let update_page = |router: &mut App, start_after: Option<String>| {
+        router
+            .execute_contract(
+                owner.clone(),
+                oracle_instance.clone(),
+                &ExecuteMsg::Update {
+                    start_after,
+                    limit: Some(1),
+                },
+                &[],
+            )
+            .unwrap()
+    };
+    let res = update_page(&mut router, None);
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("last_pair", pair_addrs[0].to_string()))));
+    let res = update_page(&mut router, Some(pair_addrs[0].to_string()));
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("last_pair", pair_addrs[1].to_string()))));
 }
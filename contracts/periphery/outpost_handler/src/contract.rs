@@ -0,0 +1,265 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, from_binary, from_slice, to_binary, wasm_execute, Addr, Binary, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Reply, Response, StdResult, SubMsg, SubMsgResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::state::{PendingRefund, CONFIG, PENDING_REFUND};
+use astroport::outpost_handler::{
+    Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MemoMsg, QueryMsg, SwapVenue,
+};
+use astroport::router::{Cw20HookMsg as RouterHookMsg, SwapOperation};
+use pool_manager::msg::{
+    Cw20HookMsg as PoolManagerHookMsg, SwapOperation as PoolManagerSwapOperation,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "astroport-outpost-handler";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A `reply` call code ID used for memo swap sub-messages.
+const SWAP_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            cw20_ics20: deps.api.addr_validate(&msg.cw20_ics20)?,
+            router: deps.api.addr_validate(&msg.router)?,
+            pool_manager: msg
+                .pool_manager
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
+/// it depending on the received template.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+    }
+}
+
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// Only the CW20-ICS20 contract is allowed to send tokens to this contract.
+///
+/// * **cw20_msg** CW20 message to process.
+pub(crate) fn receive_cw20(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if cw20_msg.sender != config.cw20_ics20 {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::OutpostMemo {
+            channel,
+            sender,
+            receiver,
+            memo,
+        } => {
+            let receiver = deps.api.addr_validate(&receiver)?;
+            let memo: MemoMsg =
+                from_slice(memo.as_bytes()).map_err(|e| ContractError::InvalidMemo {
+                    reason: e.to_string(),
+                })?;
+
+            match memo {
+                MemoMsg::Swap {
+                    operations,
+                    minimum_receive,
+                    to,
+                    max_spread,
+                    venue,
+                } => {
+                    let to = to.unwrap_or_else(|| receiver.to_string());
+                    let swap_msg = swap_msg(
+                        &config,
+                        &info.sender,
+                        cw20_msg.amount,
+                        operations,
+                        minimum_receive,
+                        to.clone(),
+                        max_spread,
+                        venue.unwrap_or(SwapVenue::Router),
+                    )?;
+
+                    PENDING_REFUND.save(
+                        deps.storage,
+                        &PendingRefund {
+                            token: info.sender.clone(),
+                            receiver: receiver.clone(),
+                            amount: cw20_msg.amount,
+                        },
+                    )?;
+
+                    Ok(Response::new()
+                        .add_submessage(SubMsg::reply_always(swap_msg, SWAP_REPLY_ID))
+                        .add_attributes(vec![
+                            attr("action", "outpost_memo_swap"),
+                            attr("channel", channel),
+                            attr("sender", sender),
+                            attr("receiver", receiver),
+                            attr("to", to),
+                            attr("offer_asset", info.sender),
+                            attr("amount", cw20_msg.amount),
+                        ]))
+                }
+            }
+        }
+        // The receiver is kept as is since the token contract validates it on transfer
+        Cw20HookMsg::TransferFailure { receiver } => Ok(Response::new()
+            .add_message(wasm_execute(
+                &info.sender,
+                &Cw20ExecuteMsg::Transfer {
+                    recipient: receiver.clone(),
+                    amount: cw20_msg.amount,
+                },
+                vec![],
+            )?)
+            .add_attributes(vec![
+                attr("action", "transfer_failure"),
+                attr("receiver", receiver),
+                attr("token", info.sender),
+                attr("amount", cw20_msg.amount),
+            ])),
+    }
+}
+
+/// Builds the CW20 send message that swaps the received tokens through the router or the pool manager.
+#[allow(clippy::too_many_arguments)]
+fn swap_msg(
+    config: &Config,
+    token: &Addr,
+    amount: Uint128,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: String,
+    max_spread: Option<Decimal>,
+    venue: SwapVenue,
+) -> Result<WasmMsg, ContractError> {
+    let (contract, msg) = match venue {
+        SwapVenue::Router => (
+            config.router.to_string(),
+            to_binary(&RouterHookMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive,
+                to: Some(to),
+                max_spread,
+            })?,
+        ),
+        SwapVenue::PoolManager => {
+            let pool_manager = config
+                .pool_manager
+                .as_ref()
+                .ok_or(ContractError::PoolManagerNotSet {})?;
+            let operations = operations
+                .into_iter()
+                .map(|operation| match operation {
+                    SwapOperation::AstroSwap {
+                        offer_asset_info,
+                        ask_asset_info,
+                    } => Ok(PoolManagerSwapOperation {
+                        offer_asset_info,
+                        ask_asset_info,
+                        pool_key: None,
+                    }),
+                    SwapOperation::NativeSwap { .. } => Err(ContractError::UnsupportedOperation {}),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            (
+                pool_manager.to_string(),
+                to_binary(&PoolManagerHookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to: Some(to),
+                    max_spread,
+                })?,
+            )
+        }
+    };
+
+    Ok(wasm_execute(
+        token,
+        &Cw20ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        },
+        vec![],
+    )?)
+}
+
+/// The entry point to the contract for processing replies from submessages.
+/// If the memo swap failed, the received tokens are sent to the receiver of the IBC transfer.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SWAP_REPLY_ID => {
+            let refund = PENDING_REFUND.load(deps.storage)?;
+            PENDING_REFUND.remove(deps.storage);
+
+            match msg.result {
+                SubMsgResult::Ok(_) => {
+                    Ok(Response::new().add_attribute("action", "outpost_memo_swap_success"))
+                }
+                SubMsgResult::Err(err) => Ok(Response::new()
+                    .add_message(wasm_execute(
+                        &refund.token,
+                        &Cw20ExecuteMsg::Transfer {
+                            recipient: refund.receiver.to_string(),
+                            amount: refund.amount,
+                        },
+                        vec![],
+                    )?)
+                    .add_attributes(vec![
+                        attr("action", "outpost_memo_swap_failure"),
+                        attr("receiver", refund.receiver),
+                        attr("token", refund.token),
+                        attr("amount", refund.amount),
+                        attr("error", err),
+                    ])),
+            }
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+    }
+}
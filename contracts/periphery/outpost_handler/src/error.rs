@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid memo: {reason}")]
+    InvalidMemo { reason: String },
+
+    #[error("Swaps through the pool manager are not enabled")]
+    PoolManagerNotSet {},
+
+    #[error("The pool manager only supports AstroSwap operations")]
+    UnsupportedOperation {},
+
+    #[error("Failed to parse or process reply message")]
+    FailedToParseReply {},
+}
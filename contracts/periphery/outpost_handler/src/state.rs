@@ -0,0 +1,21 @@
+use astroport::outpost_handler::Config;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::Item;
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Tokens to return to the receiver of an IBC transfer if the memo swap fails
+#[cw_serde]
+pub struct PendingRefund {
+    /// The CW20 token received over IBC
+    pub token: Addr,
+    /// The receiver of the IBC transfer on the Hub
+    pub receiver: Addr,
+    /// The amount received over IBC
+    pub amount: Uint128,
+}
+
+/// Stores the refund for the swap currently in flight
+pub const PENDING_REFUND: Item<PendingRefund> = Item::new("pending_refund");
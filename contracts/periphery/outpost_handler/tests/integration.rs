@@ -0,0 +1,381 @@
+#![cfg(not(tarpaulin_include))]
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{to_binary, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+
+use astroport::asset::{token_asset_info, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::outpost_handler::{
+    Config, Cw20HookMsg, InstantiateMsg, MemoMsg, QueryMsg, SwapVenue,
+};
+use astroport::router::SwapOperation;
+use astroport_outpost_handler::error::ContractError;
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        astroport_token::contract::execute,
+        astroport_token::contract::instantiate,
+        astroport_token::contract::query,
+    ))
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            astroport_pair::contract::query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply),
+    )
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    )
+}
+
+fn router_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_router::contract::execute,
+            astroport_router::contract::instantiate,
+            astroport_router::contract::query,
+        )
+        .with_reply_empty(astroport_router::contract::reply),
+    )
+}
+
+fn outpost_handler_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_outpost_handler::contract::execute,
+            astroport_outpost_handler::contract::instantiate,
+            astroport_outpost_handler::contract::query,
+        )
+        .with_reply_empty(astroport_outpost_handler::contract::reply),
+    )
+}
+
+struct Helper {
+    owner: Addr,
+    cw20_ics20: Addr,
+    token_x: Addr,
+    token_y: Addr,
+    handler: Addr,
+}
+
+impl Helper {
+    fn init(app: &mut App) -> Self {
+        let owner = Addr::unchecked("owner");
+        let cw20_ics20 = Addr::unchecked("cw20_ics20");
+
+        let token_code_id = app.store_code(token_contract());
+        let pair_code_id = app.store_code(pair_contract());
+        let factory_code_id = app.store_code(factory_contract());
+        let router_code_id = app.store_code(router_contract());
+        let handler_code_id = app.store_code(outpost_handler_contract());
+
+        let factory = app
+            .instantiate_contract(
+                factory_code_id,
+                owner.clone(),
+                &astroport::factory::InstantiateMsg {
+                    pair_configs: vec![PairConfig {
+                        code_id: pair_code_id,
+                        pair_type: PairType::Xyk {},
+                        total_fee_bps: 0,
+                        maker_fee_bps: 0,
+                        is_disabled: false,
+                        is_generator_disabled: false,
+                        pool_creation_fee: None,
+                        listing_policy: None,
+                    }],
+                    token_code_id,
+                    fee_address: None,
+                    generator_address: None,
+                    owner: owner.to_string(),
+                    whitelist_code_id: 0,
+                    coin_registry_address: "coin_registry".to_string(),
+                },
+                &[],
+                "factory",
+                None,
+            )
+            .unwrap();
+
+        let mut tokens = vec![];
+        for name in ["TOX", "TOY"] {
+            let token = app
+                .instantiate_contract(
+                    token_code_id,
+                    owner.clone(),
+                    &astroport::token::InstantiateMsg {
+                        name: name.to_string(),
+                        symbol: name.to_string(),
+                        decimals: 6,
+                        initial_balances: vec![],
+                        mint: Some(MinterResponse {
+                            minter: owner.to_string(),
+                            cap: None,
+                        }),
+                        marketing: None,
+                    },
+                    &[],
+                    name,
+                    None,
+                )
+                .unwrap();
+            tokens.push(token);
+        }
+        let (token_x, token_y) = (tokens[0].clone(), tokens[1].clone());
+
+        let asset_infos = vec![
+            token_asset_info(token_x.clone()),
+            token_asset_info(token_y.clone()),
+        ];
+        app.execute_contract(
+            owner.clone(),
+            factory.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type: PairType::Xyk {},
+                asset_infos: asset_infos.clone(),
+                init_params: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let pair: PairInfo = app
+            .wrap()
+            .query_wasm_smart(
+                &factory,
+                &astroport::factory::QueryMsg::Pair { asset_infos },
+            )
+            .unwrap();
+
+        let router = app
+            .instantiate_contract(
+                router_code_id,
+                owner.clone(),
+                &astroport::router::InstantiateMsg {
+                    astroport_factory: factory.to_string(),
+                },
+                &[],
+                "router",
+                None,
+            )
+            .unwrap();
+
+        let handler = app
+            .instantiate_contract(
+                handler_code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    cw20_ics20: cw20_ics20.to_string(),
+                    router: router.to_string(),
+                    pool_manager: None,
+                },
+                &[],
+                "outpost_handler",
+                None,
+            )
+            .unwrap();
+
+        let helper = Self {
+            owner,
+            cw20_ics20,
+            token_x,
+            token_y,
+            handler,
+        };
+
+        for token in [&helper.token_x, &helper.token_y] {
+            helper
+                .mint(app, token, 1_000_000_000000, &pair.contract_addr)
+                .unwrap();
+        }
+
+        helper
+    }
+
+    fn mint(
+        &self,
+        app: &mut App,
+        token: &Addr,
+        amount: u128,
+        receiver: &Addr,
+    ) -> AnyResult<AppResponse> {
+        app.execute_contract(
+            self.owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::Mint {
+                recipient: receiver.to_string(),
+                amount: amount.into(),
+            },
+            &[],
+        )
+    }
+
+    fn balance(&self, app: &App, token: &Addr, address: &Addr) -> u128 {
+        let res: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+
+    /// Mimics the CW20-ICS20 contract forwarding a received transfer to the hook contract
+    fn send_hook(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: u128,
+        msg: &Cw20HookMsg,
+    ) -> AnyResult<AppResponse> {
+        self.mint(app, &self.token_x, amount, sender).unwrap();
+        app.execute_contract(
+            sender.clone(),
+            self.token_x.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: self.handler.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(msg).unwrap(),
+            },
+            &[],
+        )
+    }
+
+    fn outpost_memo(&self, receiver: &Addr, memo: String) -> Cw20HookMsg {
+        Cw20HookMsg::OutpostMemo {
+            channel: "channel-0".to_string(),
+            sender: "remote_sender".to_string(),
+            receiver: receiver.to_string(),
+            memo,
+        }
+    }
+
+    fn swap_memo(&self, minimum_receive: Option<u128>, venue: Option<SwapVenue>) -> String {
+        let memo = MemoMsg::Swap {
+            operations: vec![SwapOperation::AstroSwap {
+                offer_asset_info: token_asset_info(self.token_x.clone()),
+                ask_asset_info: token_asset_info(self.token_y.clone()),
+            }],
+            minimum_receive: minimum_receive.map(Uint128::new),
+            to: None,
+            max_spread: None,
+            venue,
+        };
+        String::from_utf8(to_binary(&memo).unwrap().to_vec()).unwrap()
+    }
+}
+
+#[test]
+fn test_config() {
+    let mut app = App::default();
+    let helper = Helper::init(&mut app);
+
+    let config: Config = app
+        .wrap()
+        .query_wasm_smart(&helper.handler, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.cw20_ics20, helper.cw20_ics20);
+    assert_eq!(config.pool_manager, None);
+}
+
+#[test]
+fn test_memo_swap() {
+    let mut app = App::default();
+    let helper = Helper::init(&mut app);
+    let receiver = Addr::unchecked("receiver");
+    let cw20_ics20 = helper.cw20_ics20.clone();
+
+    let memo = helper.outpost_memo(&receiver, helper.swap_memo(Some(990_000), None));
+    helper
+        .send_hook(&mut app, &cw20_ics20, 1_000_000, &memo)
+        .unwrap();
+
+    assert_eq!(helper.balance(&app, &helper.token_x, &receiver), 0);
+    assert_eq!(helper.balance(&app, &helper.token_y, &receiver), 999_999);
+    assert_eq!(helper.balance(&app, &helper.token_x, &helper.handler), 0);
+}
+
+#[test]
+fn test_memo_swap_failure_refunds_receiver() {
+    let mut app = App::default();
+    let helper = Helper::init(&mut app);
+    let receiver = Addr::unchecked("receiver");
+    let cw20_ics20 = helper.cw20_ics20.clone();
+
+    // The minimum receive can't be satisfied so the swap fails
+    let memo = helper.outpost_memo(&receiver, helper.swap_memo(Some(1_000_001), None));
+    helper
+        .send_hook(&mut app, &cw20_ics20, 1_000_000, &memo)
+        .unwrap();
+
+    assert_eq!(helper.balance(&app, &helper.token_x, &receiver), 1_000_000);
+    assert_eq!(helper.balance(&app, &helper.token_y, &receiver), 0);
+    assert_eq!(helper.balance(&app, &helper.token_x, &helper.handler), 0);
+}
+
+#[test]
+fn test_memo_errors() {
+    let mut app = App::default();
+    let helper = Helper::init(&mut app);
+    let receiver = Addr::unchecked("receiver");
+    let cw20_ics20 = helper.cw20_ics20.clone();
+
+    let memo = helper.outpost_memo(&receiver, helper.swap_memo(None, None));
+    let err = helper
+        .send_hook(&mut app, &Addr::unchecked("random"), 1_000_000, &memo)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let memo = helper.outpost_memo(&receiver, "{\"unknown\": {}}".to_string());
+    let err = helper
+        .send_hook(&mut app, &cw20_ics20, 1_000_000, &memo)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidMemo { .. }
+    ));
+
+    let memo = helper.outpost_memo(
+        &receiver,
+        helper.swap_memo(None, Some(SwapVenue::PoolManager)),
+    );
+    let err = helper
+        .send_hook(&mut app, &cw20_ics20, 1_000_000, &memo)
+        .unwrap_err();
+    assert_eq!(ContractError::PoolManagerNotSet {}, err.downcast().unwrap());
+}
+
+#[test]
+fn test_transfer_failure() {
+    let mut app = App::default();
+    let helper = Helper::init(&mut app);
+    let receiver = Addr::unchecked("receiver");
+    let cw20_ics20 = helper.cw20_ics20.clone();
+
+    let msg = Cw20HookMsg::TransferFailure {
+        receiver: receiver.to_string(),
+    };
+    helper
+        .send_hook(&mut app, &cw20_ics20, 1_000_000, &msg)
+        .unwrap();
+
+    assert_eq!(helper.balance(&app, &helper.token_x, &receiver), 1_000_000);
+    assert_eq!(helper.balance(&app, &helper.token_x, &helper.handler), 0);
+}
@@ -5,7 +5,7 @@ use astroport::querier::query_supply;
 
 use astroport_pcl_common::utils::check_cw20_in_pool;
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Api, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsgResponse, SubMsgResult, Uint128
+    entry_point, from_binary, to_binary, wasm_execute, Addr, Api, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
@@ -13,16 +13,13 @@ use cw_utils::{must_pay, parse_instantiate_response_data};
 
 use crate::msg::SwapOperation;
 
-use astroport::router::{
-    InstantiateMsg, MigrateMsg,
-};
-
-use crate::msg::{ExecuteMsg,QueryMsg,Cw20HookMsg};
+use crate::msg::{ExecuteMsg,InstantiateMsg,MigrateMsg,QueryMsg,Cw20HookMsg};
 use crate::error::ContractError;
-use crate::handlers::{execute_create_pair, execute_provide_liquidity, execute_swap_operations, execute_withdraw_liquidity, generate_key_from_asset_info, generate_key_from_assets, DENOM};
+use crate::handlers::{execute_create_pair, execute_provide_liquidity, execute_swap_operations, execute_withdraw_liquidity, generate_key_from_asset_info, generate_key_from_assets, DENOM, INSTANTIATE_TOKEN_REPLY_ID};
 
 use crate::query::{query_compute_d, query_lp_price, simulate_swap_operations,query_config};
-use crate::state::{ PAIR_BALANCES, POOLS, QUEUED_MINT};
+use crate::state::{ load_pair_info, FACTORY, LP_TOKEN_POOLS, PAIR_BALANCES, POOLS, QUEUED_MINT, TRICRYPTO_POOLS};
+use crate::tricrypto;
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "pina-colada";
@@ -30,6 +27,8 @@ const CONTRACT_NAME: &str = "pina-colada";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const AFTER_SWAP_REPLY_ID: u64 = 1;
+/// A `reply` call code ID used to catch a failed registration of a new pool in the factory
+const REGISTER_POOL_REPLY_ID: u64 = 2;
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -37,11 +36,13 @@ pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-
+    if let Some(factory_addr) = msg.factory_addr {
+        FACTORY.save(deps.storage, &deps.api.addr_validate(&factory_addr)?)?;
+    }
 
     Ok(Response::default())
 }
@@ -119,11 +120,15 @@ pub fn receive_cw20(
         } => {
             //println!("{} is {}",info.sender.clone(),String::from("Test"));
             
-            let pool_key=generate_key_from_asset_info(&[operations[0].clone().offer_asset_info,operations[0].clone().ask_asset_info].to_vec());
-            let config=POOLS.may_load(deps.storage, pool_key.clone()).unwrap();
+            let pool_key = operations[0].pool_key.clone().unwrap_or_else(|| {
+                generate_key_from_asset_info(&[operations[0].clone().offer_asset_info,operations[0].clone().ask_asset_info].to_vec())
+            });
 
             // Only asset contract can execute this message
-            check_cw20_in_pool(&config.unwrap(), &info.sender)?;
+            match TRICRYPTO_POOLS.may_load(deps.storage, pool_key.clone())? {
+                Some(config) => check_cw20_in_pool(&config, &info.sender)?,
+                None => check_cw20_in_pool(&POOLS.load(deps.storage, pool_key)?, &info.sender)?,
+            }
 
             let to_addr = addr_opt_validate(deps.api, &to)?;
             execute_swap_operations(
@@ -138,7 +143,24 @@ pub fn receive_cw20(
             )
         },
         
-        Cw20HookMsg::WithdrawLiquidity { assets } => execute_withdraw_liquidity(deps,env,info.clone(),info.sender.clone(),cw20_msg.amount,assets)
+        Cw20HookMsg::WithdrawLiquidity { assets } => {
+            // Tricrypto pools are found by their LP token as the withdrawn assets are optional
+            let tricrypto_pool = LP_TOKEN_POOLS
+                .may_load(deps.storage, &info.sender)?
+                .filter(|pool_key| TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()));
+            match tricrypto_pool {
+                Some(pool_key) => tricrypto::withdraw_liquidity(
+                    deps,
+                    env,
+                    info,
+                    Addr::unchecked(cw20_msg.sender),
+                    pool_key,
+                    cw20_msg.amount,
+                    assets,
+                ),
+                None => execute_withdraw_liquidity(deps,env,info.clone(),info.sender.clone(),cw20_msg.amount,assets),
+            }
+        }
     }
 }
 
@@ -146,7 +168,7 @@ pub fn receive_cw20(
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
-            id: _INSTANTIATE_TOKEN_REPLY_ID,
+            id: INSTANTIATE_TOKEN_REPLY_ID,
             result:
                 SubMsgResult::Ok(SubMsgResponse {
                     data: Some(data), ..
@@ -156,19 +178,53 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             let config=POOLS.may_load(deps.storage, pool_key.clone()).unwrap();
             let init_response = parse_instantiate_response_data(data.as_slice())
             .map_err(|e| StdError::generic_err(format!("{e}")))?;
-            if let Some(mut config)=config{
-                config.pair_info.liquidity_token =
-                deps.api.addr_validate(&init_response.contract_address)?;
-                POOLS.save(deps.storage,pool_key ,&config)?;
+            let liquidity_token = deps.api.addr_validate(&init_response.contract_address)?;
+            let pair_info = if let Some(mut config)=config{
+                config.pair_info.liquidity_token = liquidity_token;
+                POOLS.save(deps.storage,pool_key.clone() ,&config)?;
+                Some(config.pair_info)
+            } else if let Some(mut config) = TRICRYPTO_POOLS.may_load(deps.storage, pool_key.clone())? {
+                config.pair_info.liquidity_token = liquidity_token;
+                TRICRYPTO_POOLS.save(deps.storage, pool_key.clone(), &config)?;
+                Some(config.pair_info)
+            } else {
+                None
+            };
+            if let Some(pair_info)=pair_info{
+                LP_TOKEN_POOLS.save(deps.storage, &pair_info.liquidity_token, &pool_key)?;
                 QUEUED_MINT.remove(deps.storage);
-                Ok(Response::new()
-                .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
+
+                let mut response = Response::new();
+                // Register the new pool in the factory so it is discoverable like any other pair.
+                // A failed registration (e.g. a standalone pair with the same assets) doesn't revert the pool creation
+                if let Some(factory) = FACTORY.may_load(deps.storage)? {
+                    response = response.add_submessage(SubMsg::reply_on_error(
+                        wasm_execute(
+                            factory,
+                            &astroport::factory::ExecuteMsg::RegisterManagerPool {
+                                asset_infos: pair_info.asset_infos.clone(),
+                                liquidity_token: pair_info.liquidity_token.to_string(),
+                                pair_type: pair_info.pair_type.clone(),
+                                pool_key: pool_key.clone(),
+                            },
+                            vec![],
+                        )?,
+                        REGISTER_POOL_REPLY_ID,
+                    ));
+                }
+
+                Ok(response
+                .add_attribute("liquidity_token_addr", pair_info.liquidity_token))
                //return  Err(ContractError::FailedToParseReply {})
             }else{
                 return  Err(ContractError::FailedToParseReply {})
             }
                   
         }
+        Reply {
+            id: REGISTER_POOL_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new().add_attribute("register_pool_error", err)),
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
@@ -195,16 +251,20 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             operations,
         )?)?),
         QueryMsg::Pool {pool_key} => Ok(to_binary(&query_pool(deps,pool_key)?)?),
-        QueryMsg::Pair {pool_key} => Ok(to_binary(&POOLS.load(deps.storage,pool_key)?.pair_info)?),
+        QueryMsg::Pair {pool_key} => Ok(to_binary(&load_pair_info(deps.storage,pool_key)?)?),
+        QueryMsg::PairByLpToken { lp_token } => {
+            let pool_key = LP_TOKEN_POOLS.load(deps.storage, &deps.api.addr_validate(&lp_token)?)?;
+            Ok(to_binary(&load_pair_info(deps.storage, pool_key)?)?)
+        }
         QueryMsg::ComputeD { pool_key }=>Ok(to_binary(&query_compute_d(deps,env,pool_key)?)?),
         QueryMsg::Config {pool_key  }=> Ok(to_binary(&query_config(deps,env,pool_key)?)?),
         QueryMsg::LpPrice {pool_key  }=>Ok(to_binary(&query_lp_price(deps,env,pool_key)?)?),
 }
 }
 fn query_pool(deps: Deps,pool_key:String)->StdResult<PoolResponse>{
-    let config= POOLS.load(deps.storage,pool_key.clone())?;
+    let pair_info= load_pair_info(deps.storage,pool_key.clone())?;
     let assets= PAIR_BALANCES.load(deps.storage,pool_key.clone())?;
-    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let total_share = query_supply(&deps.querier, &pair_info.liquidity_token)?;
     let resp = PoolResponse {
         assets,
         total_share,
@@ -218,7 +278,7 @@ fn query_pool(deps: Deps,pool_key:String)->StdResult<PoolResponse>{
 /// Manages contract migration.
 #[cfg(not(tarpaulin_include))]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
@@ -226,9 +286,24 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             "1.1.1" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
+        CONTRACT_NAME => {}
         _ => return Err(ContractError::MigrationError {}),
     };
 
+    if let Some(factory_addr) = msg.factory_addr {
+        FACTORY.save(deps.storage, &deps.api.addr_validate(&factory_addr)?)?;
+    }
+
+    // Index the LP tokens of the pools created before they were indexed
+    let pools = POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (pool_key, config) in pools {
+        if !config.pair_info.liquidity_token.as_str().is_empty() {
+            LP_TOKEN_POOLS.save(deps.storage, &config.pair_info.liquidity_token, &pool_key)?;
+        }
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
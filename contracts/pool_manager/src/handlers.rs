@@ -19,7 +19,7 @@ use astroport_pcl_common::utils::{
     check_asset_infos, check_assets, compute_swap, get_share_in_assets,
     mint_liquidity_token_message,
 };
-use astroport_pcl_common::{calc_d, get_xcp};
+use astroport_pcl_common::{assert_safe_pool_volumes, calc_d, get_xcp};
 use cosmwasm_schema::serde::de;
 use std::str;
 
@@ -27,8 +27,10 @@ use crate::error::ContractError;
 use crate::msg::SwapOperation;
 use crate::state::{
     decrease_asset_balance, decrease_pair_balances, find_asset_index, increment_asset_balance,
-    increment_pair_balances, pair_key, BALANCES, PAIR_BALANCES, POOLS, QUEUED_MINT,
+    increment_pair_balances, pair_key, BALANCES, FACTORY, PAIR_BALANCES, POOLS, QUEUED_MINT,
+    TRICRYPTO_POOLS,
 };
+use crate::tricrypto::{self, N_COINS};
 use crate::utils::query_pools;
 use cosmwasm_std::{
     attr, from_binary, to_binary, wasm_execute, wasm_instantiate, Addr, Api, BankMsg, Binary, Coin,
@@ -39,8 +41,8 @@ use cw20::{Cw20ExecuteMsg, MinterResponse};
 use itertools::Itertools;
 pub(crate) const LP_TOKEN_PRECISION: u8 = 6;
 const MAX_SWAP_OPERATIONS: usize = 10;
-const DUMMY_ADDRESS: &str = "PINA_COLADA";
-const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+pub(crate) const DUMMY_ADDRESS: &str = "PINA_COLADA";
+pub(crate) const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
 /// Returns the end result of a simulation for one or multiple swap
 /// operations using a [`SimulateSwapOperationsResponse`] object.
 ///
@@ -51,14 +53,11 @@ const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
 pub static DENOM: &str = "aarch";
 
 pub fn generate_key_from_assets(assets: &Vec<Asset>) -> String {
-    str::from_utf8(&pair_key(&[assets[0].clone().info, assets[1].clone().info]))
-        .unwrap()
-        .to_string()
+    generate_key_from_asset_info(&assets.iter().map(|asset| asset.info.clone()).collect())
 }
+/// Returns the key of the pool holding all the specified assets.
 pub fn generate_key_from_asset_info(assets: &Vec<AssetInfo>) -> String {
-    str::from_utf8(&pair_key(&[assets[0].clone(), assets[1].clone()]))
-        .unwrap()
-        .to_string()
+    str::from_utf8(&pair_key(assets)).unwrap().to_string()
 }
 pub fn send_native(to: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
     let msg = BankMsg::Send {
@@ -130,6 +129,19 @@ pub fn execute_provide_liquidity(
 ) -> Result<Response, ContractError> {
     let pool_key = generate_key_from_assets(&assets);
 
+    if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+        return tricrypto::provide_liquidity(
+            deps,
+            env,
+            info,
+            pool_key,
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        );
+    }
+
     let mut config = POOLS.load(deps.storage, pool_key.clone())?;
     //println!("{:?} {}", config, String::from("CONFIG HERE "));
     //println!("{:?}", assets.len());
@@ -233,8 +245,8 @@ pub fn execute_provide_liquidity(
         .collect_vec();
     //println!("{:?}", new_xp);
     new_xp[1] *= config.pool_state.price_state.price_scale;
-    //println!("{:?}", new_xp);
     let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    assert_safe_pool_volumes(&new_xp, &amp_gamma)?;
     let new_d = calc_d(&new_xp, &amp_gamma)?;
 
     let share = if total_share.is_zero() {
@@ -463,12 +475,18 @@ pub fn execute_withdraw_liquidity(
 pub fn execute_create_pair(
     deps: &mut DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     init_params: Option<Binary>,
     asset_infos: Vec<AssetInfo>,
 ) -> Result<Response, ContractError> {
-    if asset_infos.len() != 2 {
-        return Err(StdError::generic_err("asset_infos must contain exactly two elements").into());
+    match asset_infos.len() {
+        2 => {}
+        N_COINS => return tricrypto::create_pair(deps, env, info, init_params, asset_infos),
+        _ => {
+            return Err(
+                StdError::generic_err("asset_infos must contain two or three elements").into(),
+            )
+        }
     }
 
     check_asset_infos(deps.api, &asset_infos)?;
@@ -507,12 +525,15 @@ pub fn execute_create_pair(
         },
     };
 
+    let key = generate_key_from_asset_info(&asset_infos);
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
             liquidity_token: Addr::unchecked(""),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Custom("concentrated".to_string()),
+            pool_key: Some(key.clone()),
         },
         factory_addr: Addr::unchecked(DUMMY_ADDRESS),
         pool_params,
@@ -536,13 +557,34 @@ pub fn execute_create_pair(
         }
     }
 
-    let key = generate_key_from_asset_info(&asset_infos);
     //println!("{:?}", key);
     POOLS.save(deps.storage, key.clone(), &config)?;
     PAIR_BALANCES.save(deps.storage, key.clone(), &balances)?;
     //BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
 
-    let token_name = format_lp_token_name(&asset_infos, &deps.querier)?;
+    let response = instantiate_lp_token(deps, &env, info, &key, &config.pair_info)?;
+
+    Ok(response.add_attribute(
+        "asset_balances_tracking".to_owned(),
+        if config.track_asset_balances {
+            "enabled"
+        } else {
+            "disabled"
+        }
+        .to_owned(),
+    ))
+}
+
+/// Instantiates the LP token of the pool stored under `pool_key`. The token address is saved in the reply.
+/// If the factory is set, the pool is approved by the factory first.
+pub(crate) fn instantiate_lp_token(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: MessageInfo,
+    pool_key: &str,
+    pair_info: &PairInfo,
+) -> Result<Response, ContractError> {
+    let token_name = format_lp_token_name(&pair_info.asset_infos, &deps.querier)?;
 
     // Create LP token
     let sub_msg = SubMsg::reply_on_success(
@@ -564,16 +606,24 @@ pub fn execute_create_pair(
         )?,
         INSTANTIATE_TOKEN_REPLY_ID,
     );
-    QUEUED_MINT.save(deps.storage, &key)?;
-    Ok(Response::new().add_submessage(sub_msg).add_attribute(
-        "asset_balances_tracking".to_owned(),
-        if config.track_asset_balances {
-            "enabled"
-        } else {
-            "disabled"
-        }
-        .to_owned(),
-    ))
+    QUEUED_MINT.save(deps.storage, &pool_key.to_string())?;
+
+    let mut response = Response::new();
+    // The factory applies its listing policy and charges the pool creation fee before the pool is created.
+    // Attached funds are forwarded to pay the fee
+    if let Some(factory) = FACTORY.may_load(deps.storage)? {
+        response = response.add_message(wasm_execute(
+            factory,
+            &astroport::factory::ExecuteMsg::ApproveManagerPool {
+                asset_infos: pair_info.asset_infos.clone(),
+                pair_type: pair_info.pair_type.clone(),
+                payer: info.sender.to_string(),
+            },
+            info.funds,
+        )?);
+    }
+
+    Ok(response.add_submessage(sub_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -599,10 +649,12 @@ pub fn execute_swap_operations(
     for operation in operations.into_iter().enumerate() {
         let (offer_asset_info, ask_asset_info) =
             (operation.1.offer_asset_info, operation.1.ask_asset_info);
-        if operation.0 == operations_len - 1 {
-            let pool_key = generate_key_from_asset_info(
+        let pool_key = operation.1.pool_key.unwrap_or_else(|| {
+            generate_key_from_asset_info(
                 &[offer_asset_info.clone(), ask_asset_info.clone()].to_vec(),
-            );
+            )
+        });
+        if operation.0 == operations_len - 1 {
             let offer_asset = Asset {
                 info: offer_asset_info.clone(),
                 amount: return_amount,
@@ -613,6 +665,7 @@ pub fn execute_swap_operations(
                 &env,
                 pool_key,
                 offer_asset,
+                ask_asset_info.clone(),
                 Some(Decimal::MAX),
                 max_spread,
             )
@@ -635,9 +688,6 @@ pub fn execute_swap_operations(
                 }
             }
         } else {
-            let pool_key = generate_key_from_asset_info(
-                &[offer_asset_info.clone(), ask_asset_info.clone()].to_vec(),
-            );
             let offer_asset = Asset {
                 info: offer_asset_info.clone(),
                 amount: return_amount,
@@ -648,6 +698,7 @@ pub fn execute_swap_operations(
                 &env,
                 pool_key,
                 offer_asset,
+                ask_asset_info.clone(),
                 Some(Decimal::MAX),
                 max_spread,
             );
@@ -668,6 +719,8 @@ pub fn execute_swap_operations(
 ///
 /// * **offer_asset** proposed asset for swapping.
 ///
+/// * **ask_asset_info** the asset to swap to.
+///
 /// * **belief_price** is used to calculate the maximum swap spread.
 ///
 /// * **max_spread** sets the maximum spread of the swap operation.
@@ -678,15 +731,35 @@ fn swap_internal(
     env: &Env,
     pool_key: String,
     offer_asset: Asset,
+    ask_asset_info: AssetInfo,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
 ) -> Result<Uint128, ContractError> {
+    if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+        return tricrypto::swap(
+            deps,
+            env,
+            pool_key,
+            offer_asset,
+            ask_asset_info,
+            belief_price,
+            max_spread,
+        );
+    }
+
+    let mut config = POOLS.load(deps.storage, pool_key.clone())?;
+    // The pool key may be specified explicitly thus both assets must be checked
+    for asset_info in [&offer_asset.info, &ask_asset_info] {
+        if !config.pair_info.asset_infos.contains(asset_info) {
+            return Err(ContractError::InvalidAsset(asset_info.to_string()));
+        }
+    }
+
     let precisions = Precisions::new(deps.storage)?;
     let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
     let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
     let offer_ind = find_asset_index(deps, pool_key.clone(), offer_asset.clone());
     let ask_ind = 1 ^ offer_ind;
-    let mut config = POOLS.load(deps.storage, pool_key.clone())?;
     increment_asset_balance(deps, pool_key.clone(), offer_ind, offer_asset.amount);
 
     let mut pools = query_pools(&deps, &config, &precisions)?;
@@ -7,6 +7,7 @@ use std::str::FromStr;
 
 use crate::error::ContractError;
 use crate::factory_helper::{instantiate_token, mint, mint_native, FactoryHelper};
+use crate::handlers::generate_key_from_asset_info;
 use crate::msg::Cw20HookMsg;
 use crate::msg::ExecuteMsg;
 use crate::msg::SwapOperation;
@@ -18,8 +19,10 @@ use astroport::pair::PoolResponse;
 use astroport::pair_concentrated::{
     ConcentratedPoolConfig, ConcentratedPoolParams, ConcentratedPoolUpdateParams,
 };
+use astroport::pair_tricrypto::TricryptoPoolParams;
+use astroport::router::SimulateSwapOperationsResponse;
 use crate::msg::QueryMsg;
-use astroport::router::{InstantiateMsg, MigrateMsg};
+use crate::msg::InstantiateMsg;
 use astroport::token;
 use cosmwasm_std::{coins, from_binary, to_binary, Addr, Coin, Decimal, Empty, StdError, Uint128};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -52,6 +55,19 @@ pub fn dec_to_f64(val: impl Display) -> f64 {
     f64::from_str(&val.to_string()).unwrap()
 }
 
+fn query_token_balance(app: &App, token: &Addr, address: &Addr) -> Uint128 {
+    let resp: cw20::BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &cw20::Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    resp.balance
+}
+
 fn router_contract() -> Box<dyn Contract<Empty>> {
     Box::new(
         ContractWrapper::new_with_empty(
@@ -76,7 +92,7 @@ fn pool_manager_works() {
             router_code,
             owner.clone(),
             &InstantiateMsg {
-                astroport_factory: String::from("Pina_Colada"),
+                factory_addr: None,
             },
             &[],
             "router",
@@ -169,6 +185,7 @@ fn pool_manager_works() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: token_y.clone(),
                     },
+                    pool_key: None,
                 },
                 SwapOperation {
                     offer_asset_info: AssetInfo::Token {
@@ -177,6 +194,7 @@ fn pool_manager_works() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: token_z,
                     },
+                    pool_key: None,
                 },
             ],
             minimum_receive: None,
@@ -237,7 +255,7 @@ fn test_native_to_token_swap() {
             router_code,
             owner.clone(),
             &InstantiateMsg {
-                astroport_factory: String::from("Pina_Colada"),
+                factory_addr: None,
             },
             &[],
             "router",
@@ -329,6 +347,7 @@ fn test_native_to_token_swap() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: token_x.clone(),
             },
+            pool_key: None,
         }],
         minimum_receive: None,
         to: None,
@@ -358,7 +377,7 @@ fn test_token_to_native_swap() {
             router_code,
             owner.clone(),
             &InstantiateMsg {
-                astroport_factory: String::from("Pina_Colada"),
+                factory_addr: None,
             },
             &[],
             "router",
@@ -453,6 +472,7 @@ fn test_token_to_native_swap() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: String::from(DENOM),
                 },
+                pool_key: None,
             }],
             minimum_receive: None,
             to: None,
@@ -463,3 +483,382 @@ fn test_token_to_native_swap() {
     app.execute_contract(owner.clone(), token_x.clone(), &swap_msg, &[])
         .unwrap();
 }
+
+#[test]
+fn tricrypto_pool_works() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+
+    let router_code = app.store_code(router_contract());
+    let pool_manager = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg { factory_addr: None },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let helper = FactoryHelper::init(&mut app, &owner, &pool_manager);
+    let tokens = ["TOX", "TOY", "TOZ"]
+        .iter()
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None))
+        .collect::<Vec<_>>();
+    let asset_infos = tokens
+        .iter()
+        .map(|token| token_asset_info(token.clone()))
+        .collect::<Vec<_>>();
+    let pool_key = generate_key_from_asset_info(&asset_infos);
+
+    let n = 100_000_000000u128;
+    for token in &tokens {
+        mint(&mut app, &owner, token, 2 * n, &owner).unwrap();
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pool_manager.to_string(),
+                expires: None,
+                amount: n.into(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let params = TricryptoPoolParams {
+        amp: f64_to_dec(40f64),
+        gamma: f64_to_dec(0.000145),
+        mid_fee: f64_to_dec(0.0026),
+        out_fee: f64_to_dec(0.0045),
+        fee_gamma: f64_to_dec(0.00023),
+        repeg_profit_threshold: f64_to_dec(0.000002),
+        min_price_scale_delta: f64_to_dec(0.000146),
+        price_scales: vec![Decimal::one(), Decimal::one()],
+        ma_half_time: 600,
+    };
+    app.execute_contract(
+        owner.clone(),
+        pool_manager.clone(),
+        &ExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            token_code_id: helper.cw20_token_code_id,
+            init_params: Some(to_binary(&params).unwrap()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &pool_manager,
+            &QueryMsg::Pair {
+                pool_key: pool_key.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pair_info.pair_type,
+        PairType::Custom("tricrypto".to_string())
+    );
+    assert_eq!(pair_info.asset_infos, asset_infos);
+
+    app.execute_contract(
+        owner.clone(),
+        pool_manager.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: tokens
+                .iter()
+                .map(|token| token_asset(token.clone(), n.into()))
+                .collect(),
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let lp_balance = query_token_balance(&app, &pair_info.liquidity_token, &owner);
+    assert!(!lp_balance.is_zero());
+
+    // The pool key is required as the pool holds more than two assets
+    let operations = vec![SwapOperation {
+        offer_asset_info: asset_infos[0].clone(),
+        ask_asset_info: asset_infos[2].clone(),
+        pool_key: Some(pool_key.clone()),
+    }];
+    let offer_amount = Uint128::from(1_000_000000u128);
+    let simulation: SimulateSwapOperationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pool_manager,
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        tokens[0].clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: pool_manager.to_string(),
+            amount: offer_amount,
+            msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+                max_spread: Some(f64_to_dec(0.02)),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_token_balance(&app, &tokens[2], &owner).u128(),
+        n + simulation.amount.u128()
+    );
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pool_manager,
+            &QueryMsg::Pool {
+                pool_key: pool_key.clone(),
+            },
+        )
+        .unwrap();
+    let pool_amounts = pool
+        .assets
+        .iter()
+        .map(|asset| asset.amount.u128())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pool_amounts,
+        vec![n + offer_amount.u128(), n, n - simulation.amount.u128()]
+    );
+
+    // The pool is found by its LP token, the withdrawn assets are sent to the LP token sender
+    app.execute_contract(
+        owner.clone(),
+        pair_info.liquidity_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: pool_manager.to_string(),
+            amount: lp_balance,
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert!(query_token_balance(&app, &pair_info.liquidity_token, &owner).is_zero());
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(&pool_manager, &QueryMsg::Pool { pool_key })
+        .unwrap();
+    for (token, pool_asset) in tokens.iter().zip(&pool.assets) {
+        assert_eq!(
+            query_token_balance(&app, token, &owner) + pool_asset.amount,
+            Uint128::from(2 * n)
+        );
+    }
+}
+
+#[test]
+fn swap_through_manager_pool_via_router() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+
+    // The pool manager instantiates LP tokens with code id 2
+    let pool_manager_code = app.store_code(router_contract());
+    let mut helper = FactoryHelper::init(&mut app, &owner, &Addr::unchecked(""));
+    let factory_code = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    ));
+    let astroport_router_code = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_router::contract::execute,
+            astroport_router::contract::instantiate,
+            astroport_router::contract::query,
+        )
+        .with_reply_empty(astroport_router::contract::reply),
+    ));
+
+    let factory = app
+        .instantiate_contract(
+            factory_code,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                pair_configs: vec![],
+                token_code_id: helper.cw20_token_code_id,
+                fee_address: None,
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: "coin_registry".to_string(),
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+    let pool_manager = app
+        .instantiate_contract(
+            pool_manager_code,
+            owner.clone(),
+            &InstantiateMsg {
+                factory_addr: Some(factory.to_string()),
+            },
+            &[],
+            "pool_manager",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            pool_manager: Some(pool_manager.to_string()),
+            pool_creation_fee: None,
+            listing_policy: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let astroport_router = app
+        .instantiate_contract(
+            astroport_router_code,
+            owner.clone(),
+            &astroport::router::InstantiateMsg {
+                astroport_factory: factory.to_string(),
+            },
+            &[],
+            "astroport_router",
+            None,
+        )
+        .unwrap();
+
+    helper.pool_manager = pool_manager.clone();
+    let token_x = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOX", None);
+    let token_y = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOY", None);
+    let asset_infos = vec![
+        token_asset_info(token_x.clone()),
+        token_asset_info(token_y.clone()),
+    ];
+    helper
+        .create_pair(
+            &mut app,
+            &owner,
+            [asset_infos[0].clone(), asset_infos[1].clone()],
+            Some(to_binary(&common_pcl_params()).unwrap()),
+        )
+        .unwrap();
+
+    let n = 1_000_000_000000u128;
+    for token in [&token_x, &token_y] {
+        mint(&mut app, &owner, token, n, &owner).unwrap();
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pool_manager.to_string(),
+                expires: None,
+                amount: n.into(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        owner.clone(),
+        pool_manager.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                token_asset(token_x.clone(), n.into()),
+                token_asset(token_y.clone(), n.into()),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The factory lists the pool under the pool manager address along with its key
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &factory,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(pair_info.contract_addr, pool_manager);
+    assert_eq!(
+        pair_info.pool_key,
+        Some(generate_key_from_asset_info(&asset_infos))
+    );
+
+    let operations = vec![astroport::router::SwapOperation::AstroSwap {
+        offer_asset_info: asset_infos[0].clone(),
+        ask_asset_info: asset_infos[1].clone(),
+    }];
+    let offer_amount = Uint128::from(1_000_000000u128);
+    let simulation: SimulateSwapOperationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &astroport_router,
+            &astroport::router::QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    assert!(!simulation.amount.is_zero());
+
+    mint(&mut app, &owner, &token_x, offer_amount.u128(), &user).unwrap();
+    app.execute_contract(
+        user.clone(),
+        token_x.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: astroport_router.to_string(),
+            amount: offer_amount,
+            msg: to_binary(&astroport::router::Cw20HookMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(simulation.amount),
+                to: None,
+                max_spread: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert!(query_token_balance(&app, &token_x, &user).is_zero());
+    assert_eq!(
+        query_token_balance(&app, &token_y, &user),
+        simulation.amount
+    );
+}
@@ -9,3 +9,4 @@ pub mod state;
 pub mod utils;
 pub mod handlers;
 pub mod query;
+pub mod tricrypto;
@@ -16,7 +16,8 @@ use astroport::pair_concentrated::ConcentratedPoolConfig;
 use crate::error::ContractError;
 use crate::handlers::{generate_key_from_asset_info, LP_TOKEN_PRECISION};
 use crate::msg::SwapOperation;
-use crate::state::{ POOLS};
+use crate::state::{ POOLS, TRICRYPTO_POOLS};
+use crate::tricrypto;
 use crate::utils::{query_pools, query_pools_sim};
 pub fn simulate_swap_operations(
     deps: Deps,
@@ -31,12 +32,18 @@ pub fn simulate_swap_operations(
 
     for operation in operations.into_iter() {
         let (offer_asset_info,ask_asset_info)= (operation.offer_asset_info,operation.ask_asset_info);
-        let pool_key=generate_key_from_asset_info(&[offer_asset_info.clone(),ask_asset_info.clone()].to_vec());
+        let pool_key = operation.pool_key.unwrap_or_else(|| {
+            generate_key_from_asset_info(&[offer_asset_info.clone(),ask_asset_info.clone()].to_vec())
+        });
         let offer_asset=  Asset {
             info: offer_asset_info.clone(),
             amount:return_amount,
         };
-        let subresult=query_simulation(deps,env.clone(),offer_asset,pool_key).unwrap();
+        let subresult = if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+            tricrypto::query_simulation(deps, env.clone(), offer_asset, ask_asset_info, pool_key)?
+        } else {
+            query_simulation(deps,env.clone(),offer_asset,pool_key).unwrap()
+        };
         return_amount=subresult.return_amount;
     }
 
@@ -93,6 +100,9 @@ pub fn query_simulation(
 }
 /// Compute the current LP token virtual price.
 pub fn query_lp_price(deps: Deps, env: Env, pool_key:String) -> Result<Decimal256,ContractError> {
+    if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+        return tricrypto::query_lp_price(deps, env, pool_key);
+    }
     let mut config = POOLS.load(deps.storage,pool_key.clone())?;
     let total_lp = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
         .to_decimal256(LP_TOKEN_PRECISION)?;
@@ -116,6 +126,9 @@ pub fn query_lp_price(deps: Deps, env: Env, pool_key:String) -> Result<Decimal25
 
 /// Returns the pair contract configuration.
 pub fn query_config(deps: Deps, env: Env,pool_key:String) -> Result<ConfigResponse,ContractError> {
+    if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+        return tricrypto::query_config(deps, env, pool_key);
+    }
     let config = POOLS.load(deps.storage,pool_key)?;
     let amp_gamma = config.pool_state.get_amp_gamma(&env);
     let dec256_price_scale = config.pool_state.price_state.price_scale;
@@ -148,6 +161,9 @@ pub fn query_config(deps: Deps, env: Env,pool_key:String) -> Result<ConfigRespon
 
 /// Compute the current pool D value.
 pub fn query_compute_d(deps: Deps, env: Env,pool_key:String) -> Result<Decimal256,ContractError> {
+    if TRICRYPTO_POOLS.has(deps.storage, pool_key.clone()) {
+        return tricrypto::query_compute_d(deps, env, pool_key);
+    }
     let config = POOLS.load(deps.storage,pool_key)?;
     let precisions = Precisions::new(deps.storage)?;
 
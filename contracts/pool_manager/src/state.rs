@@ -1,9 +1,12 @@
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{Asset, AssetInfo, PairInfo};
 use cosmwasm_std::{Addr, CustomQuery, Order, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map, SnapshotMap};
 use itertools::Itertools;
 
-use astroport_pcl_common::{error::PclError, state::Config};
+use astroport_pcl_common::{
+    error::PclError,
+    state::{Config, MultiPriceState},
+};
 use cosmwasm_std::DepsMut;
 /// Stores pool parameters and state.
 
@@ -49,7 +52,20 @@ impl<'a> Precisions {
 }
 
 pub const QUEUED_MINT: Item<String> = Item::new("pool_key");
+/// Stores the factory contract the pools are registered in
+pub const FACTORY: Item<Addr> = Item::new("factory");
 pub const POOLS: Map<String, Config> = Map::new("pools");
+/// Stores the configs of the tricrypto pools keyed the same way as [`POOLS`]
+pub const TRICRYPTO_POOLS: Map<String, Config<MultiPriceState>> = Map::new("tricrypto_pools");
+/// Returns the pair info of the pool stored under `pool_key` whatever its type is
+pub fn load_pair_info(storage: &dyn Storage, pool_key: String) -> StdResult<PairInfo> {
+    match TRICRYPTO_POOLS.may_load(storage, pool_key.clone())? {
+        Some(config) => Ok(config.pair_info),
+        None => Ok(POOLS.load(storage, pool_key)?.pair_info),
+    }
+}
+/// Maps LP tokens to the keys of the pools minting them
+pub const LP_TOKEN_POOLS: Map<&Addr, String> = Map::new("lp_token_pools");
 pub const PAIR_BALANCES: Map<String, Vec<Asset>> = Map::new("pair_balances");
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
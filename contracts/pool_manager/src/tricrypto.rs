@@ -0,0 +1,611 @@
+use std::convert::TryFrom;
+
+use astroport::asset::{
+    addr_opt_validate, Asset, AssetInfo, CoinsExt, Decimal256Ext, PairInfo,
+    MINIMUM_LIQUIDITY_AMOUNT,
+};
+use astroport::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
+use astroport::factory::PairType;
+use astroport::pair::{ConfigResponse, SimulationResponse, MIN_TRADE_SIZE};
+use astroport::pair_concentrated::UpdatePoolParams;
+use astroport::pair_tricrypto::{TricryptoPoolConfig, TricryptoPoolParams};
+use astroport::querier::query_supply;
+use astroport_pcl_common::state::{
+    AmpGamma, Config, MultiPriceState, PoolParams, PoolState, Precisions,
+};
+use astroport_pcl_common::utils::{
+    assert_max_spread, before_swap_check, check_asset_infos, check_assets, get_share_in_assets,
+    mint_liquidity_token_message,
+};
+use astroport_pcl_common::utils_n_coins::{
+    assert_slippage_tolerance, calc_last_prices, calc_provide_fee, compute_swap, select_pools,
+    to_internal_repr,
+};
+use astroport_pcl_common::{assert_safe_pool_volumes, calc_d, get_xcp_n};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps,
+    DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw20::Cw20ExecuteMsg;
+use itertools::Itertools;
+
+use crate::error::ContractError;
+use crate::handlers::{
+    generate_key_from_asset_info, instantiate_lp_token, DUMMY_ADDRESS, LP_TOKEN_PRECISION,
+};
+use crate::state::{
+    decrease_asset_balance, decrease_pair_balances, increment_asset_balance,
+    increment_pair_balances, PAIR_BALANCES, POOLS, TRICRYPTO_POOLS,
+};
+use crate::utils::{query_pools, query_pools_sim};
+
+/// The number of assets in a tricrypto pool.
+pub(crate) const N_COINS: usize = 3;
+
+/// Creates a tricrypto pool with the [`TricryptoPoolParams`] specified in `init_params`.
+pub fn create_pair(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    init_params: Option<Binary>,
+    asset_infos: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    check_asset_infos(deps.api, &asset_infos)?;
+
+    let params: TricryptoPoolParams =
+        from_binary(&init_params.ok_or(ContractError::InitParamsNotFound {})?)?;
+
+    if params.price_scales.len() != N_COINS - 1 {
+        return Err(StdError::generic_err(format!(
+            "price_scales must contain exactly {} elements",
+            N_COINS - 1
+        ))
+        .into());
+    }
+
+    if params.price_scales.iter().any(Decimal::is_zero) {
+        return Err(StdError::generic_err("Initial price scale can not be zero").into());
+    }
+
+    let key = generate_key_from_asset_info(&asset_infos);
+    if POOLS.has(deps.storage, key.clone()) || TRICRYPTO_POOLS.has(deps.storage, key.clone()) {
+        return Err(StdError::generic_err("Pool already exists").into());
+    }
+
+    Precisions::store_precisions_pina_colada(deps.branch(), &asset_infos)?;
+
+    let mut pool_params = PoolParams::default();
+    pool_params.update_params(UpdatePoolParams {
+        mid_fee: Some(params.mid_fee),
+        out_fee: Some(params.out_fee),
+        fee_gamma: Some(params.fee_gamma),
+        repeg_profit_threshold: Some(params.repeg_profit_threshold),
+        min_price_scale_delta: Some(params.min_price_scale_delta),
+        ma_half_time: Some(params.ma_half_time),
+    })?;
+
+    let price_scales = params
+        .price_scales
+        .into_iter()
+        .map(Decimal256::from)
+        .collect_vec();
+
+    let pool_state = PoolState {
+        initial: AmpGamma::default(),
+        future: AmpGamma::new(params.amp, params.gamma)?,
+        future_time: env.block.time.seconds(),
+        initial_time: 0,
+        price_state: MultiPriceState {
+            oracle_prices: price_scales.clone(),
+            last_prices: price_scales.clone(),
+            price_scales,
+            last_price_update: env.block.time.seconds(),
+            xcp_profit: Decimal256::zero(),
+            xcp_profit_real: Decimal256::zero(),
+        },
+    };
+
+    let config = Config {
+        pair_info: PairInfo {
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: Addr::unchecked(""),
+            asset_infos: asset_infos.clone(),
+            pair_type: PairType::Custom("tricrypto".to_string()),
+            pool_key: Some(key.clone()),
+        },
+        factory_addr: Addr::unchecked(DUMMY_ADDRESS),
+        pool_params,
+        pool_state,
+        owner: None,
+        track_asset_balances: false,
+        fee_share: None,
+    };
+
+    let balances = asset_infos
+        .iter()
+        .map(|info| Asset {
+            info: info.clone(),
+            amount: Uint128::zero(),
+        })
+        .collect_vec();
+
+    TRICRYPTO_POOLS.save(deps.storage, key.clone(), &config)?;
+    PAIR_BALANCES.save(deps.storage, key.clone(), &balances)?;
+
+    instantiate_lp_token(deps, &env, info, &key, &config.pair_info)
+}
+
+/// Provides liquidity to the tricrypto pool stored under `pool_key`.
+/// All pool assets must be specified as the pool key is derived from them, zero amounts are allowed.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_key: String,
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    auto_stake: Option<bool>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = TRICRYPTO_POOLS.load(deps.storage, pool_key.clone())?;
+
+    check_assets(deps.api, &assets)?;
+    // Also checks that all assets belong to the pool and are not duplicated
+    info.funds
+        .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
+
+    let precisions = Precisions::new(deps.storage)?;
+    // Pool balances are tracked internally thus they don't include the deposits yet
+    let pools = query_pools(deps, &config, &precisions)?;
+
+    // Amounts are sorted in the same order as pools
+    let amounts = pools
+        .iter()
+        .map(|pool| {
+            assets
+                .iter()
+                .find(|asset| asset.info == pool.info)
+                .map(|asset| asset.amount)
+                .unwrap_or_default()
+        })
+        .collect_vec();
+    let deposits = pools
+        .iter()
+        .zip(&amounts)
+        .map(|(pool, amount)| {
+            Decimal256::with_precision(*amount, precisions.get_precision(&pool.info)?)
+                .map_err(Into::into)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+
+    // Initial provide can not be one-sided
+    if total_share.is_zero() && deposits.iter().any(Decimal256::is_zero) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    increment_pair_balances(deps, pool_key.clone(), amounts.clone());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (pool, amount) in pools.iter().zip(&amounts) {
+        // If the asset is a token contract, then we need to execute a TransferFrom msg to receive assets
+        if let AssetInfo::Token { contract_addr } = &pool.info {
+            if !amount.is_zero() {
+                messages.push(
+                    wasm_execute(
+                        contract_addr,
+                        &Cw20ExecuteMsg::TransferFrom {
+                            owner: info.sender.to_string(),
+                            recipient: env.contract.address.to_string(),
+                            amount: *amount,
+                        },
+                        vec![],
+                    )?
+                    .into(),
+                )
+            }
+        }
+    }
+
+    let new_xs = pools
+        .iter()
+        .zip(&deposits)
+        .map(|(pool, deposit)| pool.amount + *deposit)
+        .collect_vec();
+    let new_xp = to_internal_repr(&new_xs, &config.pool_state.price_state.price_scales);
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    assert_safe_pool_volumes(&new_xp, &amp_gamma)?;
+
+    let new_d = calc_d(&new_xp, &amp_gamma)?;
+
+    let share = if total_share.is_zero() {
+        let xcp = get_xcp_n(new_d, &config.pool_state.price_state.price_scales)?;
+        let mint_amount = xcp
+            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT.to_decimal256(LP_TOKEN_PRECISION)?)
+            .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+
+        messages.extend(mint_liquidity_token_message(
+            deps.querier,
+            &config,
+            &env.contract.address,
+            &env.contract.address,
+            MINIMUM_LIQUIDITY_AMOUNT,
+            false,
+        )?);
+
+        // share cannot become zero after minimum liquidity subtraction
+        if mint_amount.is_zero() {
+            return Err(ContractError::MinimumLiquidityAmountError {});
+        }
+
+        config.pool_state.price_state.xcp_profit_real = Decimal256::one();
+        config.pool_state.price_state.xcp_profit = Decimal256::one();
+
+        mint_amount
+    } else {
+        let old_xp = to_internal_repr(
+            &pools.iter().map(|a| a.amount).collect_vec(),
+            &config.pool_state.price_state.price_scales,
+        );
+        let old_d = calc_d(&old_xp, &amp_gamma)?;
+        let share = (total_share * new_d / old_d).saturating_sub(total_share);
+
+        let ideposits = to_internal_repr(&deposits, &config.pool_state.price_state.price_scales);
+
+        share * (Decimal256::one() - calc_provide_fee(&ideposits, &new_xp, &config.pool_params))
+    };
+
+    // calculate accrued share
+    let share_ratio = share / (total_share + share);
+    let imbalanced = new_xs
+        .iter()
+        .zip(&deposits)
+        .any(|(x, deposit)| deposit.diff(*x * share_ratio) >= MIN_TRADE_SIZE);
+
+    let mut slippage = Decimal256::zero();
+
+    // If deposit doesn't diverge too much from the balanced share, we don't update the price
+    if imbalanced {
+        slippage = assert_slippage_tolerance(
+            &deposits,
+            share,
+            &config.pool_state.price_state,
+            slippage_tolerance,
+        )?;
+
+        let last_prices = calc_last_prices(&new_xs, &config, &env)?;
+        config.pool_state.update_price(
+            &config.pool_params,
+            &env,
+            total_share + share,
+            &new_xp,
+            &last_prices,
+        )?;
+    }
+
+    let share_uint128 = share.to_uint(LP_TOKEN_PRECISION)?;
+
+    // Mint LP tokens for the sender or for the receiver (if set)
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let auto_stake = auto_stake.unwrap_or(false);
+    messages.extend(mint_liquidity_token_message(
+        deps.querier,
+        &config,
+        &env.contract.address,
+        &receiver,
+        share_uint128,
+        auto_stake,
+    )?);
+
+    TRICRYPTO_POOLS.save(deps.storage, pool_key, &config)?;
+
+    let attrs = vec![
+        attr("action", "provide_liquidity"),
+        attr("sender", info.sender),
+        attr("receiver", receiver),
+        attr("assets", assets.iter().join(", ")),
+        attr("share", share_uint128),
+        attr("slippage", slippage.to_string()),
+    ];
+
+    Ok(Response::new().add_messages(messages).add_attributes(attrs))
+}
+
+/// Withdraws liquidity from the tricrypto pool stored under `pool_key`.
+///
+/// * **sender** address that will receive assets back from the pool
+///
+/// * **amount** amount of provided LP tokens
+///
+/// * **assets** must be empty as imbalanced withdraw is disabled.
+pub fn withdraw_liquidity(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    pool_key: String,
+    amount: Uint128,
+    assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let mut config = TRICRYPTO_POOLS.load(deps.storage, pool_key.clone())?;
+
+    if info.sender != config.pair_info.liquidity_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(deps, &config, &precisions)?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+
+    let refund_assets = if assets.is_empty() {
+        // Usual withdraw (balanced)
+        get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share)
+    } else {
+        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+    };
+
+    // decrease XCP
+    let xs = pools
+        .iter()
+        .zip(&refund_assets)
+        .map(|(pool, refund)| pool.amount - refund.amount)
+        .collect_vec();
+    let xp = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    let d = calc_d(&xp, &amp_gamma)?;
+    config.pool_state.price_state.xcp_profit_real =
+        get_xcp_n(d, &config.pool_state.price_state.price_scales)?
+            / (total_share - amount).to_decimal256(LP_TOKEN_PRECISION)?;
+
+    let refund_assets = refund_assets
+        .into_iter()
+        .map(|asset| {
+            let prec = precisions.get_precision(&asset.info).unwrap();
+
+            Ok(Asset {
+                info: asset.info,
+                amount: asset.amount.to_uint(prec)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    decrease_pair_balances(
+        deps,
+        pool_key.clone(),
+        refund_assets.iter().map(|asset| asset.amount).collect(),
+    );
+
+    let mut messages = refund_assets
+        .iter()
+        .cloned()
+        .map(|asset| asset.into_msg(&sender))
+        .collect::<StdResult<Vec<_>>>()?;
+    messages.push(
+        wasm_execute(
+            &config.pair_info.liquidity_token,
+            &Cw20ExecuteMsg::Burn { amount },
+            vec![],
+        )?
+        .into(),
+    );
+
+    TRICRYPTO_POOLS.save(deps.storage, pool_key, &config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "withdraw_liquidity"),
+        attr("sender", sender),
+        attr("withdrawn_share", amount),
+        attr("refund_assets", refund_assets.iter().join(", ")),
+    ]))
+}
+
+/// Updates internal pools of the tricrypto pool stored under `pool_key` and returns the swap output.
+///
+/// * **offer_asset** proposed asset for swapping.
+///
+/// * **ask_asset_info** the asset to swap to.
+///
+/// * **belief_price** is used to calculate the maximum swap spread.
+///
+/// * **max_spread** sets the maximum spread of the swap operation.
+pub fn swap(
+    deps: &mut DepsMut,
+    env: &Env,
+    pool_key: String,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+) -> Result<Uint128, ContractError> {
+    let precisions = Precisions::new(deps.storage)?;
+    let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
+    let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
+    let mut config = TRICRYPTO_POOLS.load(deps.storage, pool_key.clone())?;
+
+    // Pool balances are tracked internally thus they don't include the offer amount yet
+    let pools = query_pools(deps, &config, &precisions)?;
+
+    let (offer_ind, ask_ind) =
+        select_pools(Some(&offer_asset.info), Some(&ask_asset_info), &pools)?;
+    let ask_asset_prec = precisions.get_precision(&pools[ask_ind].info)?;
+
+    before_swap_check(&pools, offer_asset_dec.amount)?;
+
+    let mut xs = pools.iter().map(|asset| asset.amount).collect_vec();
+
+    let swap_result = compute_swap(
+        &xs,
+        offer_asset_dec.amount,
+        offer_ind,
+        ask_ind,
+        &config,
+        env,
+        Decimal256::zero(),
+    )?;
+    xs[offer_ind] += offer_asset_dec.amount;
+    xs[ask_ind] -= swap_result.dy + swap_result.maker_fee;
+
+    let return_amount = swap_result.dy.to_uint(ask_asset_prec)?;
+    let spread_amount = swap_result.spread_fee.to_uint(ask_asset_prec)?;
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_asset.amount,
+        return_amount,
+        spread_amount,
+    )?;
+
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+
+    increment_asset_balance(deps, pool_key.clone(), offer_ind, offer_asset.amount);
+    decrease_asset_balance(deps, pool_key.clone(), ask_ind, return_amount);
+
+    // Skip very small trade sizes which could significantly mess up the price due to rounding errors,
+    // especially if token precisions are 18.
+    if (swap_result.dy + swap_result.maker_fee) >= MIN_TRADE_SIZE
+        && offer_asset_dec.amount >= MIN_TRADE_SIZE
+    {
+        let last_prices = calc_last_prices(&xs, &config, env)?;
+
+        // update_price() works only with internal representation
+        let xp = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+        config
+            .pool_state
+            .update_price(&config.pool_params, env, total_share, &xp, &last_prices)?;
+    }
+
+    TRICRYPTO_POOLS.save(deps.storage, pool_key, &config)?;
+
+    Ok(return_amount)
+}
+
+/// Returns information about a swap simulation in the tricrypto pool stored under `pool_key`.
+pub fn query_simulation(
+    deps: Deps,
+    env: Env,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    pool_key: String,
+) -> Result<SimulationResponse, ContractError> {
+    let config = TRICRYPTO_POOLS.load(deps.storage, pool_key)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
+    let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
+
+    let pools = query_pools_sim(deps, &config, &precisions)?;
+
+    let (offer_ind, ask_ind) =
+        select_pools(Some(&offer_asset.info), Some(&ask_asset_info), &pools)?;
+    let ask_asset_prec = precisions.get_precision(&pools[ask_ind].info)?;
+
+    before_swap_check(&pools, offer_asset_dec.amount)?;
+
+    let xs = pools.iter().map(|asset| asset.amount).collect_vec();
+
+    let swap_result = compute_swap(
+        &xs,
+        offer_asset_dec.amount,
+        offer_ind,
+        ask_ind,
+        &config,
+        &env,
+        Decimal256::zero(),
+    )?;
+
+    Ok(SimulationResponse {
+        return_amount: swap_result.dy.to_uint(ask_asset_prec)?,
+        spread_amount: swap_result.spread_fee.to_uint(ask_asset_prec)?,
+        commission_amount: swap_result.total_fee.to_uint(ask_asset_prec)?,
+    })
+}
+
+/// Compute the current LP token virtual price of the tricrypto pool stored under `pool_key`.
+pub fn query_lp_price(deps: Deps, env: Env, pool_key: String) -> Result<Decimal256, ContractError> {
+    let config = TRICRYPTO_POOLS.load(deps.storage, pool_key)?;
+    let total_lp = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+    if !total_lp.is_zero() {
+        let precisions = Precisions::new(deps.storage)?;
+        let xs = query_pools_sim(deps, &config, &precisions)?
+            .into_iter()
+            .map(|asset| asset.amount)
+            .collect_vec();
+        let ixs = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+        let amp_gamma = config.pool_state.get_amp_gamma(&env);
+        let d = calc_d(&ixs, &amp_gamma)?;
+        let xcp = get_xcp_n(d, &config.pool_state.price_state.price_scales)?;
+
+        Ok(xcp / total_lp)
+    } else {
+        Ok(Decimal256::zero())
+    }
+}
+
+/// Converts [`Decimal256`] prices into [`Decimal`] ones.
+fn to_decimals(prices: &[Decimal256]) -> StdResult<Vec<Decimal>> {
+    prices
+        .iter()
+        .map(|price| {
+            Decimal::from_atomics(Uint128::try_from(price.atomics())?, price.decimal_places())
+                .map_err(|e| StdError::generic_err(format!("{e}")))
+        })
+        .collect()
+}
+
+/// Returns the configuration of the tricrypto pool stored under `pool_key`.
+pub fn query_config(
+    deps: Deps,
+    env: Env,
+    pool_key: String,
+) -> Result<ConfigResponse, ContractError> {
+    let config = TRICRYPTO_POOLS.load(deps.storage, pool_key)?;
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    let price_state = &config.pool_state.price_state;
+
+    Ok(ConfigResponse {
+        block_time_last: 0, // keeping this field for backwards compatibility
+        params: Some(to_binary(&TricryptoPoolConfig {
+            amp: amp_gamma.amp,
+            gamma: amp_gamma.gamma,
+            mid_fee: config.pool_params.mid_fee,
+            out_fee: config.pool_params.out_fee,
+            fee_gamma: config.pool_params.fee_gamma,
+            repeg_profit_threshold: config.pool_params.repeg_profit_threshold,
+            min_price_scale_delta: config.pool_params.min_price_scale_delta,
+            price_scales: to_decimals(&price_state.price_scales)?,
+            oracle_prices: to_decimals(&price_state.oracle_prices)?,
+            ma_half_time: config.pool_params.ma_half_time,
+        })?),
+        owner: config.owner.unwrap_or(Addr::unchecked("Pina Colada")),
+        factory_addr: Addr::unchecked("Pina Colada"),
+    })
+}
+
+/// Compute the current D value of the tricrypto pool stored under `pool_key`.
+pub fn query_compute_d(
+    deps: Deps,
+    env: Env,
+    pool_key: String,
+) -> Result<Decimal256, ContractError> {
+    let config = TRICRYPTO_POOLS.load(deps.storage, pool_key)?;
+    let precisions = Precisions::new(deps.storage)?;
+
+    let xs = query_pools_sim(deps, &config, &precisions)?
+        .into_iter()
+        .map(|a| a.amount)
+        .collect_vec();
+
+    if xs.iter().any(Decimal256::is_zero) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let ixs = to_internal_repr(&xs, &config.pool_state.price_state.price_scales);
+
+    let amp_gamma = config.pool_state.get_amp_gamma(&env);
+    Ok(calc_d(&ixs, &amp_gamma)?)
+}
@@ -4,13 +4,13 @@ use astroport::asset::DecimalAsset;
 use crate::state::PAIR_BALANCES;
 use crate::error::ContractError;
 use crate::handlers::generate_key_from_asset_info;
-pub(crate) fn query_pools(
+pub(crate) fn query_pools<P>(
     deps:&DepsMut,      
-    config: &Config,
+    config: &Config<P>,
     precisions: &Precisions,
 ) -> Result<Vec<DecimalAsset>, ContractError> {
     //
-    let key=generate_key_from_asset_info(&([config.pair_info.asset_infos[0].clone(),config.pair_info.asset_infos[1].clone()].to_vec()));
+    let key=generate_key_from_asset_info(&config.pair_info.asset_infos);
     println!("{}",key);
     let pairs=PAIR_BALANCES.load(deps.storage,key).unwrap();
     println!("{:?}",pairs);
@@ -24,13 +24,13 @@ pub(crate) fn query_pools(
     
 }
 
-pub(crate) fn query_pools_sim(
+pub(crate) fn query_pools_sim<P>(
     deps:Deps,      
-    config: &Config,
+    config: &Config<P>,
     precisions: &Precisions,
 ) -> Result<Vec<DecimalAsset>, ContractError> {
     //
-    let key=generate_key_from_asset_info(&([config.pair_info.asset_infos[0].clone(),config.pair_info.asset_infos[1].clone()].to_vec()));
+    let key=generate_key_from_asset_info(&config.pair_info.asset_infos);
     println!("{}",key);
     let pairs=PAIR_BALANCES.load(deps.storage,key).unwrap();
     println!("{:?}",pairs);
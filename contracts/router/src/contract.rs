@@ -7,6 +7,7 @@ use cw20::Cw20ReceiveMsg;
 
 use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
 use astroport::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
+use astroport::pool_manager::{QueryMsg as ManagerQueryMsg, SwapOperation as ManagerSwapOperation};
 use astroport::querier::query_pair_info;
 use astroport::router::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
@@ -311,18 +312,35 @@ fn simulate_swap_operations(
                     &[offer_asset_info.clone(), ask_asset_info.clone()],
                 )?;
 
-                let res: SimulationResponse = deps.querier.query_wasm_smart(
-                    pair_info.contract_addr,
-                    &PairQueryMsg::Simulation {
-                        offer_asset: Asset {
-                            info: offer_asset_info.clone(),
-                            amount: return_amount,
-                        },
-                        ask_asset_info: Some(ask_asset_info.clone()),
-                    },
-                )?;
-
-                return_amount = res.return_amount;
+                return_amount = match pair_info.pool_key {
+                    Some(pool_key) => {
+                        let res: SimulateSwapOperationsResponse = deps.querier.query_wasm_smart(
+                            pair_info.contract_addr,
+                            &ManagerQueryMsg::SimulateSwapOperations {
+                                offer_amount: return_amount,
+                                operations: vec![ManagerSwapOperation {
+                                    offer_asset_info,
+                                    ask_asset_info,
+                                    pool_key: Some(pool_key),
+                                }],
+                            },
+                        )?;
+                        res.amount
+                    }
+                    None => {
+                        let res: SimulationResponse = deps.querier.query_wasm_smart(
+                            pair_info.contract_addr,
+                            &PairQueryMsg::Simulation {
+                                offer_asset: Asset {
+                                    info: offer_asset_info.clone(),
+                                    amount: return_amount,
+                                },
+                                ask_asset_info: Some(ask_asset_info.clone()),
+                            },
+                        )?;
+                        res.return_amount
+                    }
+                };
             }
             SwapOperation::NativeSwap { .. } => {
                 return Err(ContractError::NativeSwapNotSupported {})
@@ -1,5 +1,9 @@
 use astroport::asset::{Asset, AssetInfo};
 use astroport::pair::ExecuteMsg as PairExecuteMsg;
+use astroport::pool_manager::{
+    Cw20HookMsg as ManagerCw20HookMsg, ExecuteMsg as ManagerExecuteMsg,
+    SwapOperation as ManagerSwapOperation,
+};
 use astroport::querier::{query_balance, query_pair_info, query_token_balance};
 use astroport::router::SwapOperation;
 use cosmwasm_std::{
@@ -55,14 +59,24 @@ pub fn execute_swap_operation(
                 amount,
             };
 
-            asset_into_swap_msg(
-                pair_info.contract_addr.to_string(),
-                offer_asset,
-                ask_asset_info,
-                max_spread,
-                to,
-                single,
-            )?
+            match pair_info.pool_key {
+                Some(pool_key) => asset_into_manager_swap_msg(
+                    pair_info.contract_addr.to_string(),
+                    pool_key,
+                    offer_asset,
+                    ask_asset_info,
+                    max_spread,
+                    to,
+                )?,
+                None => asset_into_swap_msg(
+                    pair_info.contract_addr.to_string(),
+                    offer_asset,
+                    ask_asset_info,
+                    max_spread,
+                    to,
+                    single,
+                )?,
+            }
         }
         SwapOperation::NativeSwap { .. } => return Err(ContractError::NativeSwapNotSupported {}),
     };
@@ -128,3 +142,61 @@ pub fn asset_into_swap_msg(
         })),
     }
 }
+
+/// Creates a message of type [`CosmosMsg`] representing a swap in a pool hosted by the pool manager.
+///
+/// * **pool_manager** pool manager contract hosting the pool.
+///
+/// * **pool_key** key of the pool in the pool manager.
+///
+/// * **offer_asset** asset that is swapped. It also mentions the amount to swap.
+///
+/// * **ask_asset_info** asset that is swapped to.
+///
+/// * **max_spread** max spread enforced for the swap.
+///
+/// * **to** address that receives the ask assets.
+pub fn asset_into_manager_swap_msg(
+    pool_manager: String,
+    pool_key: String,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> StdResult<CosmosMsg> {
+    let operations = vec![ManagerSwapOperation {
+        offer_asset_info: offer_asset.info.clone(),
+        ask_asset_info,
+        pool_key: Some(pool_key),
+    }];
+
+    match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pool_manager,
+            funds: vec![Coin {
+                denom: denom.to_string(),
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&ManagerExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to,
+                max_spread,
+            })?,
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pool_manager,
+                amount: offer_asset.amount,
+                msg: to_binary(&ManagerCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to,
+                    max_spread,
+                })?,
+            })?,
+        })),
+    }
+}
@@ -148,6 +148,7 @@ impl WasmMockQuerier {
                             },
                         ],
                         pair_type: PairType::Xyk {},
+                        pool_key: None,
                     }))),
                     None => SystemResult::Err(SystemError::InvalidRequest {
                         error: "No pair info exists".to_string(),
@@ -79,6 +79,8 @@ impl FactoryHelper {
                     maker_fee_bps: 0,
                     is_disabled: false,
                     is_generator_disabled: false,
+                    pool_creation_fee: None,
+                    listing_policy: None,
                 },
                 PairConfig {
                     code_id: pair_code_id,
@@ -87,6 +89,8 @@ impl FactoryHelper {
                     maker_fee_bps: 0,
                     is_disabled: false,
                     is_generator_disabled: false,
+                    pool_creation_fee: None,
+                    listing_policy: None,
                 },
             ],
             token_code_id: cw20_token_code_id,
@@ -1011,6 +1011,7 @@ pub fn send_pending_rewards(
             msg: to_binary(&VestingExecuteMsg::Claim {
                 recipient: Some(to.to_string()),
                 amount: Some(pending_rewards),
+                asset_info: None,
             })?,
             funds: vec![],
         });
@@ -22,7 +22,7 @@ use astroport::{
     token::InstantiateMsg as TokenInstantiateMsg,
     vesting::{
         Cw20HookMsg as VestingHookMsg, InstantiateMsg as VestingInstantiateMsg, VestingAccount,
-        VestingSchedule, VestingSchedulePoint,
+        VestingCurve, VestingSchedule, VestingSchedulePoint,
     },
 };
 
@@ -1140,6 +1140,8 @@ fn disabling_pool() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: true,
+            pool_creation_fee: None,
+            listing_policy: None,
         },
     };
 
@@ -1169,6 +1171,8 @@ fn disabling_pool() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         },
     };
 
@@ -3067,6 +3071,9 @@ fn setup_pools() {
         generator_address: Some(generator_instance.to_string()),
         whitelist_code_id: None,
         coin_registry_address: None,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
@@ -3324,6 +3331,9 @@ fn deactivate_pools_by_pair_types() {
         generator_address: Some(generator_instance.to_string()),
         whitelist_code_id: None,
         coin_registry_address: None,
+        pool_manager: None,
+        pool_creation_fee: None,
+        listing_policy: None,
     };
 
     app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
@@ -3430,6 +3440,8 @@ fn deactivate_pools_by_pair_types() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: true,
+            pool_creation_fee: None,
+            listing_policy: None,
         },
     };
 
@@ -3533,6 +3545,8 @@ fn deactivate_pools_by_pair_types() {
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         },
     };
 
@@ -3938,6 +3952,8 @@ fn instantiate_factory(
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id,
         fee_address: None,
@@ -3955,6 +3971,8 @@ fn instantiate_factory(
             maker_fee_bps: 10,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         });
     }
 
@@ -4062,6 +4080,8 @@ fn instantiate_generator(
                         amount,
                     },
                     end_point: None,
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -7,7 +7,7 @@ use anyhow::Result as AnyResult;
 use astroport::asset::{token_asset_info, AssetInfo, PairInfo};
 use astroport::factory::{PairConfig, PairType};
 use astroport::vesting::{Cw20HookMsg as VestingHookMsg, VestingAccount};
-use astroport::vesting::{InstantiateMsg, VestingSchedule, VestingSchedulePoint};
+use astroport::vesting::{InstantiateMsg, VestingCurve, VestingSchedule, VestingSchedulePoint};
 use astroport_governance::generator_controller::{ExecuteMsg, QueryMsg};
 use astroport_governance::generator_controller::{UserInfoResponse, VotedPoolInfoResponse};
 use astroport_mocks::cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
@@ -60,6 +60,8 @@ impl ControllerHelper {
                 maker_fee_bps: 10,
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
             }],
             token_code_id: escrow_helper.astro_token_code_id,
             fee_address: None,
@@ -180,6 +182,8 @@ impl ControllerHelper {
                             amount: Uint128::new(100000_000000),
                         },
                         end_point: None,
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     }],
                 }],
             })
@@ -1,6 +1,7 @@
 #![cfg(not(tarpaulin_include))]
 
 use anyhow::Result;
+use astroport::asset::AssetInfo;
 use astroport::{staking as xastro, token as astro};
 use astroport_governance::voting_escrow::{
     Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockInfoResponse, QueryMsg, VotingPowerResponse,
@@ -67,8 +68,10 @@ impl EscrowHelper {
         let msg = xastro::InstantiateMsg {
             owner: owner.to_string(),
             token_code_id: astro_token_code_id,
-            deposit_token_addr: astro_token.to_string(),
+            deposit_token: AssetInfo::cw20(astro_token.clone()),
             marketing: None,
+            unbonding_period: None,
+            tokenfactory_subdenom: None,
         };
         let staking_instance = router
             .instantiate_contract(
@@ -100,7 +103,7 @@ impl EscrowHelper {
         let msg = InstantiateMsg {
             owner: owner.to_string(),
             guardian_addr: Some("guardian".to_string()),
-            deposit_token_addr: res.share_token_addr.to_string(),
+            deposit_token_addr: res.share_token.to_string(),
             marketing: None,
             logo_urls_whitelist: vec![],
         };
@@ -117,7 +120,7 @@ impl EscrowHelper {
 
         Self {
             owner,
-            xastro_token: res.share_token_addr,
+            xastro_token: Addr::unchecked(res.share_token.to_string()),
             astro_token,
             staking_instance,
             escrow_instance: voting_instance,
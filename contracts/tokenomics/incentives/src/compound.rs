@@ -0,0 +1,635 @@
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    attr, coins, ensure, to_binary, wasm_execute, Addr, Decimal, DepsMut, Env, MessageInfo,
+    QuerierWrapper, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Item;
+use itertools::Itertools;
+
+use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::PairType;
+use astroport::pair::MAX_ALLOWED_SLIPPAGE;
+use astroport::querier::query_fee_info;
+use astroport::router::SwapOperation;
+use astroport::{factory, pair, router, vesting, U256};
+use pool_manager::handlers::generate_key_from_asset_info;
+
+use crate::error::ContractError;
+use crate::reply::{COMPOUND_BALANCE_REPLY_ID, COMPOUND_PROVIDE_REPLY_ID, COMPOUND_SWAPS_REPLY_ID};
+use crate::state::{record_claims, Op, PoolInfo, UserInfo, CONFIG};
+use crate::utils::{collect_rewards, is_pool_registered, query_pair_info};
+
+/// Temporary context which lives only within one ClaimAndCompound transaction.
+#[cw_serde]
+pub struct CompoundContext {
+    /// The user whose rewards are compounded
+    pub user: Addr,
+    /// The LP token position which is compounded
+    pub lp_token: AssetInfo,
+    /// The pair which receives liquidity
+    pub pair_info: PairInfo,
+    /// Contract balances of pool assets before compounding started
+    pub balances_before: Vec<Uint128>,
+    /// Rewards which are pool assets and were already held by the contract
+    pub direct_amounts: Vec<Uint128>,
+    /// The minimum amount of LP tokens to be minted
+    pub min_lp_out: Uint128,
+    /// Contract LP token balance before providing liquidity
+    pub lp_balance_before: Uint128,
+    /// The maximum spread of every swap
+    pub max_spread: Option<Decimal>,
+}
+
+/// Stores context between ClaimAndCompound submessages
+pub const COMPOUND_CONTEXT: Item<CompoundContext> = Item::new("compound_context");
+
+/// The contract which swaps rewards into pool assets
+enum SwapVenue {
+    /// Swaps through the pairs registered in the factory
+    Router(Addr),
+    /// Swaps through the pools hosted by the pool manager
+    PoolManager(Addr),
+}
+
+/// Claims rewards from the user position and converts them into pool assets.
+/// External rewards are kept on the contract balance and ASTRO rewards are claimed from the vesting contract
+/// to this contract. Rewards which are not pool assets are swapped through the router or through the pool manager
+/// if it hosts the pool. The flow continues in [`after_swaps`].
+pub fn claim_and_compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    min_lp_out: Uint128,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+    let config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(deps.as_ref(), &lp_asset)?;
+    is_pool_registered(deps.querier, &config, &pair_info, &lp_token)?;
+
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
+    let mut user_info = UserInfo::load_position(deps.storage, &info.sender, &lp_asset)?;
+
+    let (attrs, rewards) = collect_rewards(
+        deps.storage,
+        &env,
+        &info.sender,
+        vec![(&lp_asset, &mut pool_info, &mut user_info)],
+    )?;
+
+    pool_info.save(deps.storage, &lp_asset)?;
+    user_info.save(deps.storage, &info.sender, &lp_asset)?;
+
+    let balances_before = pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| asset_info.query_pool(&deps.querier, &env.contract.address))
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut direct_amounts = vec![Uint128::zero(); pair_info.asset_infos.len()];
+    let mut to_swap = vec![];
+
+    let protocol_reward_amount: Uint128 = rewards
+        .iter()
+        .filter(|claimed| !claimed.is_external)
+        .map(|claimed| claimed.reward.amount)
+        .sum();
+    for (reward_info, reward_assets) in &rewards
+        .iter()
+        .filter(|claimed| claimed.is_external)
+        .map(|claimed| &claimed.reward)
+        .group_by(|asset| asset.info.clone())
+    {
+        let amount: Uint128 = reward_assets.map(|asset| asset.amount).sum();
+        match pair_info.asset_infos.iter().position(|a| a == &reward_info) {
+            Some(ind) => direct_amounts[ind] += amount,
+            None => to_swap.push(reward_info.with_balance(amount)),
+        }
+    }
+
+    // Rewards stay on this contract and any failure reverts the whole transaction
+    // thus the claim is recorded right away.
+    record_claims(deps.storage, &env, &info.sender, rewards)?;
+
+    let mut messages = vec![];
+
+    // Claim ASTRO rewards to this contract.
+    // If ASTRO is a pool asset it is accounted by balance difference.
+    if !protocol_reward_amount.is_zero() {
+        messages.push(SubMsg::new(wasm_execute(
+            &config.vesting_contract,
+            &vesting::ExecuteMsg::Claim {
+                recipient: Some(env.contract.address.to_string()),
+                amount: Some(protocol_reward_amount),
+                asset_info: None,
+            },
+            vec![],
+        )?));
+
+        if !pair_info.asset_infos.contains(&config.astro_token) {
+            to_swap.push(config.astro_token.with_balance(protocol_reward_amount));
+        }
+    }
+
+    if !to_swap.is_empty() {
+        // Pools hosted by the pool manager share its address
+        let swap_venue = match config
+            .pool_manager
+            .filter(|pool_manager| *pool_manager == pair_info.contract_addr)
+        {
+            Some(pool_manager) => SwapVenue::PoolManager(pool_manager),
+            None => SwapVenue::Router(config.router.ok_or(ContractError::RouterNotSet {})?),
+        };
+
+        for reward in to_swap {
+            let ask_asset_info = find_swap_target(
+                deps.querier,
+                &config.factory,
+                &swap_venue,
+                &reward.info,
+                &pair_info,
+            )?
+            .ok_or_else(|| ContractError::NoSwapRoute {
+                reward: reward.info.to_string(),
+                lp_token: lp_token.clone(),
+            })?;
+            messages.push(swap_msg(&swap_venue, reward, ask_asset_info, max_spread)?);
+        }
+    }
+
+    COMPOUND_CONTEXT.save(
+        deps.storage,
+        &CompoundContext {
+            user: info.sender,
+            lp_token: lp_asset,
+            pair_info,
+            balances_before,
+            direct_amounts,
+            min_lp_out,
+            lp_balance_before: Uint128::zero(),
+            max_spread,
+        },
+    )?;
+
+    let response = Response::new().add_attributes(attrs);
+
+    // Continue compounding after the last message is processed
+    if let Some(last_msg) = messages.last_mut() {
+        last_msg.id = COMPOUND_SWAPS_REPLY_ID;
+        last_msg.reply_on = ReplyOn::Success;
+        Ok(response.add_submessages(messages))
+    } else {
+        let after_swaps_response = after_swaps(deps, env)?;
+        Ok(response
+            .add_submessages(after_swaps_response.messages)
+            .add_attributes(after_swaps_response.attributes))
+    }
+}
+
+/// Called once all rewards are converted into pool assets.
+/// XYK pools require balanced deposits thus the excess asset is partially swapped through the pool itself.
+/// Other pool types accept imbalanced deposits, and liquidity is provided right away.
+pub fn after_swaps(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let ctx = COMPOUND_CONTEXT.load(deps.storage)?;
+    let amounts = compound_amounts(deps.querier, &env, &ctx)?;
+
+    if ctx.pair_info.pair_type == PairType::Xyk {
+        // The pair may charge a dynamic fee instead of the one set in the factory.
+        // Pairs which don't support the query charge the factory fee
+        let total_fee_rate = match deps.querier.query_wasm_smart::<pair::CurrentFeeResponse>(
+            &ctx.pair_info.contract_addr,
+            &pair::QueryMsg::CurrentFee {},
+        ) {
+            Ok(current_fee) => current_fee.total_fee_rate,
+            Err(_) => {
+                let config = CONFIG.load(deps.storage)?;
+                query_fee_info(
+                    &deps.querier,
+                    config.factory,
+                    ctx.pair_info.pair_type.clone(),
+                )?
+                .total_fee_rate
+            }
+        };
+        let fee_bps = (Uint128::new(10000) * total_fee_rate).u128() as u16;
+        let reserves = ctx
+            .pair_info
+            .query_pools(&deps.querier, &ctx.pair_info.contract_addr)?
+            .into_iter()
+            .map(|asset| asset.amount)
+            .collect_vec();
+
+        if let Some((offer_ind, offer_amount)) = xyk_balancing_swap(&amounts, &reserves, fee_bps)? {
+            let offer_asset = ctx.pair_info.asset_infos[offer_ind].with_balance(offer_amount);
+            let swap_msg =
+                pair_swap_msg(&ctx.pair_info.contract_addr, offer_asset, ctx.max_spread)?;
+
+            return Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+                swap_msg,
+                COMPOUND_BALANCE_REPLY_ID,
+            )));
+        }
+    }
+
+    provide(deps, env, ctx, amounts)
+}
+
+/// Called once the balancing swap through the XYK pool is done.
+pub fn after_balancing_swap(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let ctx = COMPOUND_CONTEXT.load(deps.storage)?;
+    let amounts = compound_amounts(deps.querier, &env, &ctx)?;
+
+    provide(deps, env, ctx, amounts)
+}
+
+/// Called once liquidity is provided. Checks minted LP tokens amount and deposits them on behalf of the user.
+pub fn after_provide(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let ctx = COMPOUND_CONTEXT.load(deps.storage)?;
+    COMPOUND_CONTEXT.remove(deps.storage);
+
+    let minted = ctx
+        .lp_token
+        .query_pool(&deps.querier, &env.contract.address)?
+        .checked_sub(ctx.lp_balance_before)?;
+
+    ensure!(
+        minted >= ctx.min_lp_out,
+        ContractError::CompoundSlippage {
+            minted,
+            min_lp_out: ctx.min_lp_out,
+        }
+    );
+
+    let mut pool_info = PoolInfo::load(deps.storage, &ctx.lp_token)?;
+    let mut user_info = UserInfo::load_position(deps.storage, &ctx.user, &ctx.lp_token)?;
+
+    // Rewards were claimed within the same block thus there is nothing to claim here
+    pool_info.update_rewards(deps.storage, &env, &ctx.lp_token)?;
+    user_info.update_and_sync_position(Op::Add(minted), &mut pool_info);
+    pool_info.save(deps.storage, &ctx.lp_token)?;
+    user_info.save(deps.storage, &ctx.user, &ctx.lp_token)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "compound"),
+        attr("user", ctx.user),
+        attr("lp_token", ctx.lp_token.to_string()),
+        attr("amount", minted),
+    ]))
+}
+
+/// Provides liquidity with the given amounts and saves LP token balance to calculate minted LP tokens later.
+fn provide(
+    deps: DepsMut,
+    env: Env,
+    mut ctx: CompoundContext,
+    amounts: Vec<Uint128>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        amounts.iter().any(|amount| !amount.is_zero()),
+        ContractError::NothingToCompound {}
+    );
+
+    let assets = ctx
+        .pair_info
+        .asset_infos
+        .iter()
+        .zip(amounts)
+        .map(|(asset_info, amount)| asset_info.with_balance(amount))
+        .collect_vec();
+
+    let mut messages = vec![];
+    let mut funds = vec![];
+    for asset in assets.iter().filter(|asset| !asset.amount.is_zero()) {
+        match &asset.info {
+            AssetInfo::Token { contract_addr } => messages.push(SubMsg::new(wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: ctx.pair_info.contract_addr.to_string(),
+                    amount: asset.amount,
+                    expires: None,
+                },
+                vec![],
+            )?)),
+            AssetInfo::NativeToken { .. } => funds.push(asset.as_coin()?),
+        }
+    }
+
+    messages.push(SubMsg::reply_on_success(
+        wasm_execute(
+            &ctx.pair_info.contract_addr,
+            &pair::ExecuteMsg::ProvideLiquidity {
+                assets,
+                // Minted LP tokens amount is checked against min_lp_out
+                slippage_tolerance: Some(Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?),
+                auto_stake: Some(false),
+                receiver: None,
+            },
+            funds,
+        )?,
+        COMPOUND_PROVIDE_REPLY_ID,
+    ));
+
+    ctx.lp_balance_before = ctx
+        .lp_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    COMPOUND_CONTEXT.save(deps.storage, &ctx)?;
+
+    Ok(Response::new().add_submessages(messages))
+}
+
+/// Returns pool assets amounts available for compounding.
+fn compound_amounts(
+    querier: QuerierWrapper,
+    env: &Env,
+    ctx: &CompoundContext,
+) -> StdResult<Vec<Uint128>> {
+    ctx.pair_info
+        .asset_infos
+        .iter()
+        .zip(ctx.balances_before.iter().zip(ctx.direct_amounts.iter()))
+        .map(|(asset_info, (balance_before, direct_amount))| {
+            let balance = asset_info.query_pool(&querier, &env.contract.address)?;
+            Ok(balance.checked_sub(*balance_before)? + direct_amount)
+        })
+        .collect()
+}
+
+/// Finds a pool asset which has a direct pair with the reward asset in the factory
+/// or in the pool manager if the rewards are swapped through it.
+fn find_swap_target(
+    querier: QuerierWrapper,
+    factory_addr: &Addr,
+    swap_venue: &SwapVenue,
+    reward_info: &AssetInfo,
+    pair_info: &PairInfo,
+) -> StdResult<Option<AssetInfo>> {
+    for asset_info in &pair_info.asset_infos {
+        let asset_infos = vec![reward_info.clone(), asset_info.clone()];
+        let maybe_pair = match swap_venue {
+            SwapVenue::Router(_) => querier.query_wasm_smart::<PairInfo>(
+                factory_addr,
+                &factory::QueryMsg::Pair { asset_infos },
+            ),
+            SwapVenue::PoolManager(pool_manager) => querier.query_wasm_smart::<PairInfo>(
+                pool_manager,
+                &pool_manager::msg::QueryMsg::Pair {
+                    pool_key: generate_key_from_asset_info(&asset_infos),
+                },
+            ),
+        };
+        if maybe_pair.is_ok() {
+            return Ok(Some(asset_info.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Composes a single hop swap message through the swap venue.
+fn swap_msg(
+    swap_venue: &SwapVenue,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_spread: Option<Decimal>,
+) -> StdResult<SubMsg> {
+    match swap_venue {
+        SwapVenue::Router(router_addr) => {
+            router_swap_msg(router_addr, offer_asset, ask_asset_info, max_spread)
+        }
+        SwapVenue::PoolManager(pool_manager) => {
+            pool_manager_swap_msg(pool_manager, offer_asset, ask_asset_info, max_spread)
+        }
+    }
+}
+
+/// Composes a single hop swap message through the router.
+/// Router sends swapped assets back to this contract.
+fn router_swap_msg(
+    router_addr: &Addr,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_spread: Option<Decimal>,
+) -> StdResult<SubMsg> {
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: offer_asset.info.clone(),
+        ask_asset_info,
+        pool_key: None,
+    }];
+
+    let msg = match &offer_asset.info {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: router_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&router::Cw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                    max_spread,
+                })?,
+            },
+            vec![],
+        )?,
+        AssetInfo::NativeToken { denom } => wasm_execute(
+            router_addr,
+            &router::ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+                max_spread,
+            },
+            coins(offer_asset.amount.u128(), denom),
+        )?,
+    };
+
+    Ok(SubMsg::new(msg))
+}
+
+/// Composes a single hop swap message through the pool manager.
+/// The pool manager sends swapped assets back to this contract.
+fn pool_manager_swap_msg(
+    pool_manager: &Addr,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_spread: Option<Decimal>,
+) -> StdResult<SubMsg> {
+    let operations = vec![pool_manager::msg::SwapOperation {
+        offer_asset_info: offer_asset.info.clone(),
+        ask_asset_info,
+        pool_key: None,
+    }];
+
+    let msg = match &offer_asset.info {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: pool_manager.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&pool_manager::msg::Cw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                    max_spread,
+                })?,
+            },
+            vec![],
+        )?,
+        AssetInfo::NativeToken { denom } => wasm_execute(
+            pool_manager,
+            &pool_manager::msg::ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+                max_spread,
+            },
+            coins(offer_asset.amount.u128(), denom),
+        )?,
+    };
+
+    Ok(SubMsg::new(msg))
+}
+
+/// Composes a swap message directly to the pair.
+fn pair_swap_msg(
+    pair_addr: &Addr,
+    offer_asset: Asset,
+    max_spread: Option<Decimal>,
+) -> StdResult<WasmMsg> {
+    match &offer_asset.info {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: pair_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&pair::Cw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread,
+                    to: None,
+                })?,
+            },
+            vec![],
+        ),
+        AssetInfo::NativeToken { .. } => {
+            let funds = vec![offer_asset.as_coin()?];
+            wasm_execute(
+                pair_addr,
+                &pair::ExecuteMsg::Swap {
+                    offer_asset,
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread,
+                    to: None,
+                },
+                funds,
+            )
+        }
+    }
+}
+
+/// Calculates how much of the excess asset should be swapped through the XYK pool
+/// so that the remaining amounts match the pool ratio after the swap.
+/// Returns the index of the asset to offer and the offer amount or None if no swap is needed.
+///
+/// The excess is the part of one asset which can't be matched with the other asset at the current pool ratio.
+/// Swap amount s for excess x, offer asset reserve r and fee f is the solution of
+/// (x - s) / r' = out / (other reserve - out) which is
+/// s = (sqrt(((2 - f) * r)^2 + 4 * (1 - f) * r * x) - (2 - f) * r) / (2 * (1 - f))
+pub fn xyk_balancing_swap(
+    amounts: &[Uint128],
+    reserves: &[Uint128],
+    fee_bps: u16,
+) -> StdResult<Option<(usize, Uint128)>> {
+    if reserves.iter().any(|reserve| reserve.is_zero()) {
+        return Err(StdError::generic_err("Pool is empty"));
+    }
+
+    let (offer_ind, excess) = if amounts[0].full_mul(reserves[1]) > amounts[1].full_mul(reserves[0])
+    {
+        (
+            0,
+            amounts[0] - amounts[1].multiply_ratio(reserves[0], reserves[1]),
+        )
+    } else {
+        (
+            1,
+            amounts[1] - amounts[0].multiply_ratio(reserves[1], reserves[0]),
+        )
+    };
+
+    if excess.is_zero() {
+        return Ok(None);
+    }
+
+    const BPS: u128 = 10000;
+    let fee = U256::from(fee_bps);
+    let reserve = U256::from(reserves[offer_ind].u128());
+    let excess = U256::from(excess.u128());
+    let one_minus_fee = U256::from(BPS) - fee;
+    let two_minus_fee = U256::from(2 * BPS) - fee;
+
+    let b = reserve * two_minus_fee;
+    let d = b * b + U256::from(4u8) * one_minus_fee * U256::from(BPS) * reserve * excess;
+    let swap_amount = (d.integer_sqrt() - b) / (U256::from(2u8) * one_minus_fee);
+
+    if swap_amount.is_zero() {
+        Ok(None)
+    } else {
+        Ok(Some((offer_ind, Uint128::new(swap_amount.as_u128()))))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_xyk_balancing_swap() {
+        // Without fees half of the pool size deposit requires to swap sqrt(1.5) - 1 of the reserve
+        let swap = xyk_balancing_swap(
+            &[Uint128::new(500_000), Uint128::zero()],
+            &[Uint128::new(1_000_000), Uint128::new(1_000_000)],
+            0,
+        )
+        .unwrap();
+        assert_eq!(swap, Some((0, Uint128::new(224_744))));
+
+        let swap = xyk_balancing_swap(
+            &[Uint128::zero(), Uint128::new(1000)],
+            &[Uint128::new(1_000_000), Uint128::new(1_000_000)],
+            30,
+        )
+        .unwrap();
+        assert_eq!(swap, Some((1, Uint128::new(500))));
+
+        // Amounts already match the pool ratio
+        let swap = xyk_balancing_swap(
+            &[Uint128::new(1000), Uint128::new(2000)],
+            &[Uint128::new(1_000_000), Uint128::new(2_000_000)],
+            30,
+        )
+        .unwrap();
+        assert_eq!(swap, None);
+
+        // Only the excess is considered
+        let swap = xyk_balancing_swap(
+            &[Uint128::new(1000), Uint128::new(1000)],
+            &[Uint128::new(1_000_000), Uint128::new(2_000_000)],
+            0,
+        )
+        .unwrap();
+        assert_eq!(swap, Some((0, Uint128::new(249))));
+
+        let err = xyk_balancing_swap(
+            &[Uint128::new(1000), Uint128::new(1000)],
+            &[Uint128::zero(), Uint128::new(2_000_000)],
+            0,
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Pool is empty"));
+    }
+}
@@ -3,7 +3,7 @@ use cosmwasm_std::{CheckedFromRatioError, OverflowError, StdError, Uint128};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
-use astroport::incentives::MAX_REWARD_TOKENS;
+use astroport::incentives::{MAX_CLAIM_LOG_LIMIT, MAX_REWARD_TOKENS, MAX_VOTE_BPS};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -61,4 +61,40 @@ pub enum ContractError {
 
     #[error("Failed to set 0 alloc point for pool {lp_token}")]
     ZeroAllocPoint { lp_token: String },
+
+    #[error("Gauge voting is disabled")]
+    GaugeDisabled {},
+
+    #[error("Vote weights must be more than 0 and their sum must not exceed {MAX_VOTE_BPS} bps")]
+    InvalidVoteWeights {},
+
+    #[error("User {user} doesn't have voting power")]
+    NoVotingPower { user: String },
+
+    #[error("No finished epochs to apply votes from")]
+    NoFinishedEpoch {},
+
+    #[error("Votes from epoch {epoch} were already applied")]
+    EpochAlreadyTuned { epoch: u64 },
+
+    #[error("There are no eligible pools voted in epoch {epoch}")]
+    NoVotedPools { epoch: u64 },
+
+    #[error("Router contract is not set")]
+    RouterNotSet {},
+
+    #[error("There is no pair to swap reward {reward} into assets of pool {lp_token}")]
+    NoSwapRoute { reward: String, lp_token: String },
+
+    #[error("There are no rewards to compound")]
+    NothingToCompound {},
+
+    #[error("Claim log limit must be within 1..={MAX_CLAIM_LOG_LIMIT}")]
+    InvalidClaimLogLimit {},
+
+    #[error("Minted LP tokens amount {minted} is less than minimum {min_lp_out}")]
+    CompoundSlippage {
+        minted: Uint128,
+        min_lp_out: Uint128,
+    },
 }
@@ -15,15 +15,18 @@ use astroport::asset::{
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::factory;
 use astroport::factory::PairType;
-use astroport::incentives::{Cw20Msg, ExecuteMsg, IncentivizationFeeInfo};
+use astroport::incentives::{Cw20Msg, ExecuteMsg, IncentivizationFeeInfo, MAX_CLAIM_LOG_LIMIT};
 
+use crate::compound::claim_and_compound;
 use crate::error::ContractError;
+use crate::gauge::{tune_gauges, update_gauge_config, vote};
 use crate::state::{
     Op, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CONFIG, OWNERSHIP_PROPOSAL,
 };
 use crate::utils::{
-    asset_info_key, claim_orphaned_rewards, claim_rewards, deactivate_blocked_pools,
-    deactivate_pool, incentivize, is_pool_registered, query_pair_info, remove_reward_from_pool,
+    apply_active_pools, asset_info_key, claim_orphaned_rewards, claim_rewards,
+    deactivate_blocked_pools, deactivate_pool, incentivize, is_pool_registered, query_pair_info,
+    remove_reward_from_pool, validate_pool,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -70,6 +73,11 @@ pub fn execute(
 
             Ok(response)
         }
+        ExecuteMsg::ClaimAndCompound {
+            lp_token,
+            min_lp_out,
+            max_spread,
+        } => claim_and_compound(deps, env, info, lp_token, min_lp_out, max_spread),
         ExecuteMsg::Receive(cw20msg) => {
             let maybe_lp = Asset::cw20(info.sender, cw20msg.amount);
             let recipient = match from_binary(&cw20msg.msg)? {
@@ -118,6 +126,9 @@ pub fn execute(
             generator_controller,
             guardian,
             incentivization_fee_info,
+            router,
+            pool_manager,
+            claim_log_limit,
         } => update_config(
             deps,
             info,
@@ -125,6 +136,9 @@ pub fn execute(
             generator_controller,
             guardian,
             incentivization_fee_info,
+            router,
+            pool_manager,
+            claim_log_limit,
         ),
         ExecuteMsg::UpdateBlockedTokenslist { add, remove } => {
             update_blocked_pool_tokens(deps, env, info, add, remove)
@@ -162,6 +176,11 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::UpdateGaugeConfig { gauge_config } => {
+            update_gauge_config(deps, info, gauge_config)
+        }
+        ExecuteMsg::Vote { votes } => vote(deps, env, info, votes),
+        ExecuteMsg::TuneGauges {} => tune_gauges(deps, env),
     }
 }
 
@@ -284,51 +303,13 @@ pub fn setup_pools(
     let setup_pools = pools
         .into_iter()
         .map(|(lp_token, alloc_point)| {
-            let maybe_lp = determine_asset_info(&lp_token, deps.api)?;
-            let pair_info = query_pair_info(deps.as_ref(), &maybe_lp)?;
-
-            is_pool_registered(deps.querier, &config, &pair_info, &lp_token)?;
-
-            // check if assets in the blocked list
-            for asset in &pair_info.asset_infos {
-                if BLOCKED_TOKENS.has(deps.storage, &asset_info_key(asset)) {
-                    return Err(ContractError::BlockedToken {
-                        token: asset.to_string(),
-                    });
-                }
-            }
-
-            // check if pair type is blacklisted
-            if blacklisted_pair_types.contains(&pair_info.pair_type) {
-                return Err(ContractError::BlockedPairType {
-                    pair_type: pair_info.pair_type,
-                });
-            }
-
+            let maybe_lp =
+                validate_pool(deps.as_ref(), &config, &blacklisted_pair_types, &lp_token)?;
             Ok((maybe_lp, alloc_point))
         })
         .collect::<Result<Vec<_>, ContractError>>()?;
 
-    // Update all reward indexes and remove astro rewards from old active pools
-    for (lp_token_asset, _) in ACTIVE_POOLS.load(deps.storage)? {
-        let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
-        pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
-        pool_info.disable_astro_rewards();
-        pool_info.save(deps.storage, &lp_token_asset)?;
-    }
-
-    config.total_alloc_points = setup_pools.iter().map(|(_, alloc)| alloc).sum();
-
-    // Set astro rewards for new active pools
-    for (active_pool, alloc_points) in &setup_pools {
-        let mut pool_info = PoolInfo::may_load(deps.storage, active_pool)?.unwrap_or_default();
-        pool_info.update_rewards(deps.storage, &env, active_pool)?;
-        pool_info.set_astro_rewards(&config, *alloc_points);
-        pool_info.save(deps.storage, active_pool)?;
-    }
-
-    ACTIVE_POOLS.save(deps.storage, &setup_pools)?;
-    CONFIG.save(deps.storage, &config)?;
+    apply_active_pools(deps.storage, &env, &mut config, setup_pools)?;
 
     Ok(Response::new().add_attribute("action", "setup_pools"))
 }
@@ -368,6 +349,7 @@ fn set_tokens_per_second(
     Ok(Response::new().add_attribute("action", "set_tokens_per_second"))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_config(
     deps: DepsMut,
     info: MessageInfo,
@@ -375,6 +357,9 @@ fn update_config(
     generator_controller: Option<String>,
     guardian: Option<String>,
     incentivization_fee_info: Option<IncentivizationFeeInfo>,
+    router: Option<String>,
+    pool_manager: Option<String>,
+    claim_log_limit: Option<u32>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -412,6 +397,24 @@ fn update_config(
         config.incentivization_fee_info = Some(new_info);
     }
 
+    if let Some(router) = router {
+        config.router = Some(deps.api.addr_validate(&router)?);
+        attrs.push(attr("new_router", router));
+    }
+
+    if let Some(pool_manager) = pool_manager {
+        config.pool_manager = Some(deps.api.addr_validate(&pool_manager)?);
+        attrs.push(attr("new_pool_manager", pool_manager));
+    }
+
+    if let Some(claim_log_limit) = claim_log_limit {
+        if !(1..=MAX_CLAIM_LOG_LIMIT).contains(&claim_log_limit) {
+            return Err(ContractError::InvalidClaimLogLimit {});
+        }
+        config.claim_log_limit = claim_log_limit;
+        attrs.push(attr("new_claim_log_limit", claim_log_limit.to_string()));
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(attrs))
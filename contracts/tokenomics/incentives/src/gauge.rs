@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{
+    attr, ensure, Addr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Uint128,
+};
+use cw_storage_plus::Bound;
+use itertools::Itertools;
+
+use astroport::factory;
+use astroport::factory::PairType;
+use astroport::incentives::{
+    epoch_start, GaugeConfig, TuneInfo, UserVoteInfo, EPOCHS_START, EPOCH_LENGTH, MAX_PAGE_LIMIT,
+    MAX_VOTE_BPS,
+};
+use astroport::xastro_token;
+
+use crate::error::ContractError;
+use crate::state::{
+    CONFIG, EPOCH_SNAPSHOT_BLOCK, GAUGE_CONFIG, GAUGE_TALLY, TUNE_HISTORY, USER_VOTES,
+};
+use crate::utils::{apply_active_pools, validate_pool};
+
+/// Enables, updates or disables gauge voting.
+pub fn update_gauge_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    gauge_config: Option<GaugeConfig>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![attr("action", "update_gauge_config")];
+
+    if let Some(gauge_config) = gauge_config {
+        deps.api.addr_validate(gauge_config.voting_token.as_str())?;
+        ensure!(
+            gauge_config.max_active_pools > 0,
+            StdError::generic_err("max_active_pools must be more than 0")
+        );
+
+        attrs.push(attr("voting_token", &gauge_config.voting_token));
+        attrs.push(attr(
+            "max_active_pools",
+            gauge_config.max_active_pools.to_string(),
+        ));
+
+        GAUGE_CONFIG.save(deps.storage, &gauge_config)?;
+    } else {
+        attrs.push(attr("gauge", "disabled"));
+        GAUGE_CONFIG.remove(deps.storage);
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Records user vote in the current epoch. If user has already voted in this epoch,
+/// their previous vote is removed from the tally and replaced with the new one.
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    votes: Vec<(String, u16)>,
+) -> Result<Response, ContractError> {
+    let gauge_config = GAUGE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::GaugeDisabled {})?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let total_bps = votes.iter().map(|(_, bps)| *bps as u32).sum::<u32>();
+    ensure!(
+        !votes.is_empty()
+            && votes.iter().all(|(_, bps)| *bps > 0)
+            && total_bps <= MAX_VOTE_BPS as u32,
+        ContractError::InvalidVoteWeights {}
+    );
+
+    let mut pools_set: HashSet<_> = Default::default();
+
+    let blocked_pair_types: Vec<PairType> = deps
+        .querier
+        .query_wasm_smart(&config.factory, &factory::QueryMsg::BlacklistedPairTypes {})?;
+
+    let votes = votes
+        .into_iter()
+        .map(|(lp_token, bps)| {
+            let lp_asset = validate_pool(deps.as_ref(), &config, &blocked_pair_types, &lp_token)?;
+            let lp_token = lp_asset.to_string();
+            if !pools_set.insert(lp_token.clone()) {
+                return Err(ContractError::DuplicatedPoolFound {});
+            }
+
+            Ok((lp_token, bps))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let epoch = epoch_start(env.block.time.seconds());
+
+    // Voting power is snapshotted at the first vote in the epoch.
+    // This prevents reusing the same tokens from different addresses within one epoch.
+    let snapshot_block = match EPOCH_SNAPSHOT_BLOCK.may_load(deps.storage, epoch)? {
+        Some(block) => block,
+        None => {
+            EPOCH_SNAPSHOT_BLOCK.save(deps.storage, epoch, &env.block.height)?;
+            env.block.height
+        }
+    };
+
+    let voting_power = deps
+        .querier
+        .query_wasm_smart::<cw20::BalanceResponse>(
+            &gauge_config.voting_token,
+            &xastro_token::QueryMsg::BalanceAt {
+                address: info.sender.to_string(),
+                block: snapshot_block,
+            },
+        )?
+        .balance;
+
+    ensure!(
+        !voting_power.is_zero(),
+        ContractError::NoVotingPower {
+            user: info.sender.to_string(),
+        }
+    );
+
+    // Remove the previous vote from the tally
+    if let Some(prev_vote) = USER_VOTES.may_load(deps.storage, (&info.sender, epoch))? {
+        for (lp_token, bps) in &prev_vote.votes {
+            let key = (epoch, lp_token.as_str());
+            let votes_amount = GAUGE_TALLY
+                .load(deps.storage, key)?
+                .checked_sub(prev_vote.voting_power.multiply_ratio(*bps, MAX_VOTE_BPS))?;
+            if votes_amount.is_zero() {
+                GAUGE_TALLY.remove(deps.storage, key);
+            } else {
+                GAUGE_TALLY.save(deps.storage, key, &votes_amount)?;
+            }
+        }
+    }
+
+    for (lp_token, bps) in &votes {
+        GAUGE_TALLY.update::<_, ContractError>(
+            deps.storage,
+            (epoch, lp_token.as_str()),
+            |votes_amount| {
+                Ok(votes_amount
+                    .unwrap_or_default()
+                    .checked_add(voting_power.multiply_ratio(*bps, MAX_VOTE_BPS))?)
+            },
+        )?;
+    }
+
+    USER_VOTES.save(
+        deps.storage,
+        (&info.sender, epoch),
+        &UserVoteInfo {
+            epoch,
+            voting_power,
+            votes,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "vote"),
+        attr("user", &info.sender),
+        attr("epoch", epoch.to_string()),
+        attr("voting_power", voting_power),
+    ]))
+}
+
+/// Applies votes from the last finished epoch to pool allocation points.
+/// Pools which became ineligible since voting (deregistered, blocked token or pair type) are skipped.
+/// Only the top [`GaugeConfig::max_active_pools`] pools receive ASTRO emissions.
+pub fn tune_gauges(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let gauge_config = GAUGE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::GaugeDisabled {})?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let current_epoch = epoch_start(env.block.time.seconds());
+    ensure!(
+        env.block.time.seconds() >= EPOCHS_START + EPOCH_LENGTH,
+        ContractError::NoFinishedEpoch {}
+    );
+    let epoch = current_epoch - EPOCH_LENGTH;
+
+    ensure!(
+        !TUNE_HISTORY.has(deps.storage, epoch),
+        ContractError::EpochAlreadyTuned { epoch }
+    );
+
+    let blocked_pair_types: Vec<PairType> = deps
+        .querier
+        .query_wasm_smart(&config.factory, &factory::QueryMsg::BlacklistedPairTypes {})?;
+
+    let mut pools = vec![];
+    for (lp_token, votes) in query_tally(deps.as_ref(), epoch)? {
+        if pools.len() == gauge_config.max_active_pools as usize {
+            break;
+        }
+
+        if let Ok(lp_asset) = validate_pool(deps.as_ref(), &config, &blocked_pair_types, &lp_token)
+        {
+            pools.push((lp_asset, votes));
+        }
+    }
+
+    ensure!(!pools.is_empty(), ContractError::NoVotedPools { epoch });
+
+    let tune_info = TuneInfo {
+        epoch,
+        tune_ts: env.block.time.seconds(),
+        pools: pools
+            .iter()
+            .map(|(lp_asset, alloc_points)| (lp_asset.to_string(), *alloc_points))
+            .collect(),
+    };
+
+    apply_active_pools(deps.storage, &env, &mut config, pools)?;
+    TUNE_HISTORY.save(deps.storage, epoch, &tune_info)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "tune_gauges"),
+        attr("epoch", epoch.to_string()),
+        attr("pools_number", tune_info.pools.len().to_string()),
+    ]))
+}
+
+/// Returns all pools voted in the specified epoch sorted by votes in descending order.
+pub fn query_tally(deps: Deps, epoch: u64) -> StdResult<Vec<(String, Uint128)>> {
+    let tally = GAUGE_TALLY
+        .prefix(epoch)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .sorted_by(|(lp_a, votes_a), (lp_b, votes_b)| {
+            votes_b.cmp(votes_a).then_with(|| lp_a.cmp(lp_b))
+        })
+        .collect();
+
+    Ok(tally)
+}
+
+/// Returns user vote in the specified epoch.
+pub fn query_user_vote(deps: Deps, user: &Addr, epoch: u64) -> StdResult<Option<UserVoteInfo>> {
+    USER_VOTES.may_load(deps.storage, (user, epoch))
+}
+
+/// Returns paginated history of applied gauge tunings.
+pub fn query_gauge_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<TuneInfo>> {
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    TUNE_HISTORY
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, tune_info)| tune_info))
+        .collect()
+}
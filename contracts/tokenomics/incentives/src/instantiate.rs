@@ -3,10 +3,11 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
 
 use astroport::asset::{addr_opt_validate, validate_native_denom};
-use astroport::incentives::{Config, InstantiateMsg};
+use astroport::incentives::{Config, InstantiateMsg, DEFAULT_CLAIM_LOG_LIMIT};
 
 use crate::error::ContractError;
 use crate::state::{ACTIVE_POOLS, CONFIG};
+use crate::utils::query_pool_manager;
 
 /// Contract name that is used for migration.
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -29,11 +30,14 @@ pub fn instantiate(
         validate_native_denom(&fee_info.fee.denom)?;
     }
 
+    let factory = deps.api.addr_validate(&msg.factory)?;
+    let pool_manager = query_pool_manager(deps.querier, &factory)?;
+
     CONFIG.save(
         deps.storage,
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
-            factory: deps.api.addr_validate(&msg.factory)?,
+            factory,
             generator_controller: None,
             astro_token: msg.astro_token,
             astro_per_second: Uint128::zero(),
@@ -41,6 +45,9 @@ pub fn instantiate(
             vesting_contract: deps.api.addr_validate(&msg.vesting_contract)?,
             guardian: addr_opt_validate(deps.api, &msg.guardian)?,
             incentivization_fee_info: msg.incentivization_fee_info,
+            router: None,
+            pool_manager,
+            claim_log_limit: DEFAULT_CLAIM_LOG_LIMIT,
         },
     )?;
     ACTIVE_POOLS.save(deps.storage, &vec![])?;
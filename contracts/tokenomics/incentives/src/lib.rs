@@ -1,5 +1,7 @@
+pub mod compound;
 pub mod error;
 pub mod execute;
+pub mod gauge;
 pub mod instantiate;
 pub mod query;
 pub mod reply;
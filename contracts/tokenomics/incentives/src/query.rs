@@ -5,11 +5,13 @@ use cw_storage_plus::Bound;
 use itertools::Itertools;
 
 use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt};
-use astroport::incentives::{QueryMsg, RewardType, ScheduleResponse, MAX_PAGE_LIMIT};
+use astroport::incentives::{epoch_start, QueryMsg, RewardType, ScheduleResponse, MAX_PAGE_LIMIT};
 
 use crate::error::ContractError;
+use crate::gauge::{query_gauge_history, query_tally, query_user_vote};
 use crate::state::{
-    list_pool_stakers, PoolInfo, UserInfo, BLOCKED_TOKENS, CONFIG, EXTERNAL_REWARD_SCHEDULES,
+    list_claim_log, list_pool_distributed, list_pool_stakers, list_user_claimed, PoolInfo,
+    UserInfo, BLOCKED_TOKENS, CONFIG, EXTERNAL_REWARD_SCHEDULES, GAUGE_CONFIG,
 };
 use crate::utils::{asset_info_key, from_key_to_asset_info};
 
@@ -89,6 +91,40 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             start_after,
             limit,
         )?)?),
+        QueryMsg::GaugeConfig {} => Ok(to_binary(&GAUGE_CONFIG.may_load(deps.storage)?)?),
+        QueryMsg::UserVote { user, epoch } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            let epoch = epoch.unwrap_or_else(|| epoch_start(env.block.time.seconds()));
+            Ok(to_binary(&query_user_vote(deps, &user_addr, epoch)?)?)
+        }
+        QueryMsg::GaugeTally { epoch } => {
+            let epoch = epoch.unwrap_or_else(|| epoch_start(env.block.time.seconds()));
+            Ok(to_binary(&query_tally(deps, epoch)?)?)
+        }
+        QueryMsg::GaugeHistory { start_after, limit } => {
+            Ok(to_binary(&query_gauge_history(deps, start_after, limit)?)?)
+        }
+        QueryMsg::UserClaimedRewards { user } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            Ok(to_binary(&list_user_claimed(deps.storage, &user_addr)?)?)
+        }
+        QueryMsg::ClaimHistory {
+            user,
+            start_after,
+            limit,
+        } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            Ok(to_binary(&list_claim_log(
+                deps.storage,
+                &user_addr,
+                start_after,
+                limit,
+            )?)?)
+        }
+        QueryMsg::PoolDistributedRewards { lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            Ok(to_binary(&list_pool_distributed(deps.storage, &lp_asset)?)?)
+        }
     }
 }
 
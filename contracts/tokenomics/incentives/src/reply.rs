@@ -2,21 +2,60 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{DepsMut, Env, Reply, Response, SubMsgResult};
 
+use crate::compound::{after_balancing_swap, after_provide, after_swaps};
 use crate::error::ContractError;
+use crate::utils::process_claim_transfer;
 
 pub const POST_TRANSFER_REPLY_ID: u64 = 1;
+pub const COMPOUND_SWAPS_REPLY_ID: u64 = 2;
+pub const COMPOUND_BALANCE_REPLY_ID: u64 = 3;
+pub const COMPOUND_PROVIDE_REPLY_ID: u64 = 4;
+pub const CLAIM_TRANSFER_REPLY_ID: u64 = 5;
 
 /// The entry point to the contract for processing replies from submessages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
-        // Caller context: either utils:claim_rewards() or utils:remove_reward_from_pool().
+        // Caller context: utils:remove_reward_from_pool() or utils:claim_orphaned_rewards().
         // If cw20 token reverts the transfer, we bypass it silently.
         // This can happen in abnormal situations when cw20 contract was tweaked and broken.
         Reply {
             id: POST_TRANSFER_REPLY_ID,
             result: SubMsgResult::Err(err_msg),
         } => Ok(Response::new().add_attribute("transfer_error", err_msg)),
+        // Caller context: utils:claim_rewards(). Claim history is recorded once all transfers are replied.
+        Reply {
+            id: CLAIM_TRANSFER_REPLY_ID,
+            result: SubMsgResult::Ok(_),
+        } => {
+            process_claim_transfer(deps.storage, &env, true)?;
+            Ok(Response::new())
+        }
+        // Caller context: utils:claim_rewards().
+        // Failed reward transfer is bypassed silently the same way as POST_TRANSFER_REPLY_ID.
+        // Its rewards are not recorded in claim history.
+        Reply {
+            id: CLAIM_TRANSFER_REPLY_ID,
+            result: SubMsgResult::Err(err_msg),
+        } => {
+            process_claim_transfer(deps.storage, &env, false)?;
+            Ok(Response::new().add_attribute("transfer_error", err_msg))
+        }
+        // Caller context: compound:claim_and_compound(). All rewards are converted into pool assets.
+        Reply {
+            id: COMPOUND_SWAPS_REPLY_ID,
+            result: SubMsgResult::Ok(_),
+        } => after_swaps(deps, env),
+        // Caller context: compound:after_swaps(). Assets were balanced according to XYK pool ratio.
+        Reply {
+            id: COMPOUND_BALANCE_REPLY_ID,
+            result: SubMsgResult::Ok(_),
+        } => after_balancing_swap(deps, env),
+        // Caller context: compound:provide(). LP tokens were minted to this contract.
+        Reply {
+            id: COMPOUND_PROVIDE_REPLY_ID,
+            result: SubMsgResult::Ok(_),
+        } => after_provide(deps, env),
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
@@ -7,7 +7,9 @@ use itertools::Itertools;
 
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use astroport::common::OwnershipProposal;
-use astroport::incentives::{Config, IncentivesSchedule};
+use astroport::incentives::{
+    ClaimLogEntry, Config, GaugeConfig, IncentivesSchedule, TuneInfo, UserVoteInfo,
+};
 use astroport::incentives::{PoolInfoResponse, RewardInfo, RewardType};
 use astroport::incentives::{MAX_PAGE_LIMIT, MAX_REWARD_TOKENS};
 
@@ -39,6 +41,36 @@ pub const USER_INFO: Map<(&AssetInfo, &Addr), UserInfo> = Map::new("user_info");
 pub const EXTERNAL_REWARD_SCHEDULES: Map<(&AssetInfo, &AssetInfo, u64), Decimal> =
     Map::new("reward_schedules");
 
+/// Gauge voting settings. Gauge voting is disabled if this item is not set.
+pub const GAUGE_CONFIG: Item<GaugeConfig> = Item::new("gauge_config");
+/// Block height at which voting power is snapshotted for a specific epoch.
+/// key: epoch start timestamp, value: block height of the first vote in this epoch
+pub const EPOCH_SNAPSHOT_BLOCK: Map<u64, u64> = Map::new("epoch_snapshot_block");
+/// key: (user, epoch start timestamp), value: user vote
+pub const USER_VOTES: Map<(&Addr, u64), UserVoteInfo> = Map::new("user_votes");
+/// key: (epoch start timestamp, LP token), value: total votes for this pool
+pub const GAUGE_TALLY: Map<(u64, &str), Uint128> = Map::new("gauge_tally");
+/// key: epoch start timestamp, value: allocation points applied from this epoch votes
+pub const TUNE_HISTORY: Map<u64, TuneInfo> = Map::new("tune_history");
+
+/// Total rewards claimed by a user.
+/// key: (user, reward asset), value: reward asset with claimed amount
+pub const USER_CLAIMED: Map<(&Addr, &AssetInfo), Asset> = Map::new("user_claimed");
+/// Total rewards distributed to stakers of a pool.
+/// key: (LP token asset, reward asset), value: reward asset with distributed amount
+pub const POOL_DISTRIBUTED: Map<(&AssetInfo, &AssetInfo), Asset> = Map::new("pool_distributed");
+/// key: (user, entry id), value: claim log entry
+pub const CLAIM_LOG: Map<(&Addr, u64), ClaimLogEntry> = Map::new("claim_log");
+/// key: user, value: (the oldest kept entry id, next entry id)
+pub const CLAIM_LOG_BOUNDS: Map<&Addr, (u64, u64)> = Map::new("claim_log_bounds");
+/// Claims waiting for replies from reward transfer messages.
+/// Claim history is recorded only for rewards which were transferred successfully.
+/// Works as a stack since a reward transfer may trigger another claim which is fully processed first.
+pub const PENDING_CLAIMS: Item<Vec<PendingClaim>> = Item::new("pending_claims");
+/// The maximum number of outdated claim log entries removed at once.
+/// Limits gas usage in case the claim log limit was lowered.
+const MAX_CLAIM_LOG_PRUNE: u64 = 10;
+
 /// Accumulates all orphaned rewards i.e. those which were added to a pool
 /// but this pool never received any LP tokens deposits.
 /// key: Key: binary representing [`AssetInfo`] converted with [`asset_info_key`],
@@ -495,6 +527,147 @@ pub fn list_pool_stakers(
         .collect()
 }
 
+/// Reward claimed from a specific pool.
+#[cw_serde]
+pub struct ClaimedReward {
+    pub lp_token: AssetInfo,
+    /// Whether the reward comes from external schedules or from ASTRO emissions
+    pub is_external: bool,
+    pub reward: Asset,
+}
+
+/// Claimed rewards which are recorded in claim history once all transfer messages are replied.
+#[cw_serde]
+pub struct PendingClaim {
+    pub user: Addr,
+    pub rewards: Vec<ClaimedReward>,
+    /// Transfers which are not replied yet in the order of messages: (reward asset, is external)
+    pub transfers: Vec<(AssetInfo, bool)>,
+}
+
+/// Groups claimed rewards by pool and records them in claim history.
+/// Rewards of the same asset are merged thus each pool gets a single claim log entry.
+pub fn record_claims(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    rewards: Vec<ClaimedReward>,
+) -> StdResult<()> {
+    let claim_log_limit = CONFIG.load(storage)?.claim_log_limit;
+    let mut pools: Vec<(AssetInfo, Vec<Asset>)> = vec![];
+    for ClaimedReward {
+        lp_token, reward, ..
+    } in rewards
+    {
+        let claimed = match pools.iter_mut().find(|(lp, _)| *lp == lp_token) {
+            Some((_, claimed)) => claimed,
+            None => {
+                pools.push((lp_token, vec![]));
+                &mut pools.last_mut().unwrap().1
+            }
+        };
+        match claimed.iter_mut().find(|a| a.info == reward.info) {
+            Some(a) => a.amount += reward.amount,
+            None => claimed.push(reward),
+        }
+    }
+
+    pools.into_iter().try_for_each(|(lp_token, claimed)| {
+        record_claim(storage, env, user, &lp_token, claimed, claim_log_limit)
+    })
+}
+
+/// Adds claimed rewards to user and pool totals and appends a new entry to the user claim log.
+/// The oldest log entries above claim_log_limit are pruned.
+pub fn record_claim(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    lp_token: &AssetInfo,
+    rewards: Vec<Asset>,
+    claim_log_limit: u32,
+) -> StdResult<()> {
+    for reward in &rewards {
+        for map in [
+            USER_CLAIMED.key((user, &reward.info)),
+            POOL_DISTRIBUTED.key((lp_token, &reward.info)),
+        ] {
+            let total = map
+                .may_load(storage)?
+                .map(|total| total.amount)
+                .unwrap_or_default()
+                .checked_add(reward.amount)?;
+            map.save(storage, &reward.info.with_balance(total))?;
+        }
+    }
+
+    let (mut first_id, next_id) = CLAIM_LOG_BOUNDS
+        .may_load(storage, user)?
+        .unwrap_or_default();
+    CLAIM_LOG.save(
+        storage,
+        (user, next_id),
+        &ClaimLogEntry {
+            id: next_id,
+            lp_token: lp_token.to_string(),
+            rewards,
+            block_height: env.block.height,
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+    let next_id = next_id + 1;
+
+    let prune_to = next_id
+        .saturating_sub(claim_log_limit as u64)
+        .min(first_id + MAX_CLAIM_LOG_PRUNE);
+    while first_id < prune_to {
+        CLAIM_LOG.remove(storage, (user, first_id));
+        first_id += 1;
+    }
+
+    CLAIM_LOG_BOUNDS.save(storage, user, &(first_id, next_id))
+}
+
+/// Lists total rewards claimed by the user.
+pub fn list_user_claimed(storage: &dyn Storage, user: &Addr) -> StdResult<Vec<Asset>> {
+    // AssetInfo keys can't be deserialized thus values are stored along with asset info
+    USER_CLAIMED
+        .prefix(user)
+        .range_raw(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, asset)| asset))
+        .collect()
+}
+
+/// Lists total rewards distributed to stakers of the pool.
+pub fn list_pool_distributed(storage: &dyn Storage, lp_token: &AssetInfo) -> StdResult<Vec<Asset>> {
+    POOL_DISTRIBUTED
+        .prefix(lp_token)
+        .range_raw(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, asset)| asset))
+        .collect()
+}
+
+/// Lists user claim log entries from the oldest to the newest.
+pub fn list_claim_log(
+    storage: &dyn Storage,
+    user: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<ClaimLogEntry>> {
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    CLAIM_LOG
+        .prefix(user)
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect()
+}
+
 /// This structure is for internal use only.
 /// Used to add/subtract LP tokens from user position and pool.
 pub enum Op<T> {
@@ -1,46 +1,157 @@
 use cosmwasm_std::{
-    attr, ensure, wasm_execute, Addr, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper,
-    ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    attr, ensure, wasm_execute, Addr, Attribute, Deps, DepsMut, Env, MessageInfo, Order,
+    QuerierWrapper, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
 };
+use cw20::{Cw20QueryMsg, MinterResponse};
 use itertools::Itertools;
 
-use astroport::asset::{
-    determine_asset_info, pair_info_by_pool, AssetInfo, AssetInfoExt, CoinsExt, PairInfo,
-};
+use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt, CoinsExt, PairInfo};
 use astroport::factory::PairType;
 use astroport::incentives::{Config, IncentivesSchedule, InputSchedule, MAX_ORPHANED_REWARD_LIMIT};
 use astroport::{factory, pair, vesting};
 
 use crate::error::ContractError;
-use crate::reply::POST_TRANSFER_REPLY_ID;
+use crate::reply::{CLAIM_TRANSFER_REPLY_ID, POST_TRANSFER_REPLY_ID};
 use crate::state::{
-    Op, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CONFIG, ORPHANED_REWARDS,
+    record_claims, ClaimedReward, Op, PendingClaim, PoolInfo, UserInfo, ACTIVE_POOLS,
+    BLOCKED_TOKENS, CONFIG, ORPHANED_REWARDS, PENDING_CLAIMS,
 };
 
 /// Claim all rewards and compose [`Response`] object containing all attributes and messages.
-/// This function doesn't mutate the state but mutates in-memory objects.
-/// Function caller is responsible for updating the state.
+/// This function mutates in-memory objects and queues the claim which is recorded in claim history
+/// once reward transfers are replied. Function caller is responsible for updating the state.
 /// If vesting_contract is None this function reads config from state and gets vesting address.
 pub fn claim_rewards(
-    storage: &dyn Storage,
+    storage: &mut dyn Storage,
     vesting_contract: Option<Addr>,
     env: Env,
     user: &Addr,
     pool_tuples: Vec<(&AssetInfo, &mut PoolInfo, &mut UserInfo)>,
 ) -> Result<Response, ContractError> {
+    let (attrs, rewards) = collect_rewards(storage, &env, user, pool_tuples)?;
+
+    // Aggregating rewards by asset info.
+    // This allows to reduce number of output messages thus reducing total gas cost.
+    // Each reward asset is sent in a single message thus a failed transfer can be matched with claimed rewards.
+    let external_rewards = rewards.iter().filter(|claimed| claimed.is_external).fold(
+        vec![],
+        |mut acc: Vec<Asset>, claimed| {
+            match acc.iter_mut().find(|a| a.info == claimed.reward.info) {
+                Some(a) => a.amount += claimed.reward.amount,
+                None => acc.push(claimed.reward.clone()),
+            }
+            acc
+        },
+    );
+
+    let mut transfers = external_rewards
+        .iter()
+        .map(|asset| (asset.info.clone(), true))
+        .collect_vec();
+    let mut messages = external_rewards
+        .into_iter()
+        .map(|asset| asset.into_submsg(user, Some((ReplyOn::Always, CLAIM_TRANSFER_REPLY_ID))))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Claim Astroport rewards
+    let protocol_reward_amount: Uint128 = rewards
+        .iter()
+        .filter(|claimed| !claimed.is_external)
+        .map(|claimed| claimed.reward.amount)
+        .sum();
+    if !protocol_reward_amount.is_zero() {
+        let config = CONFIG.load(storage)?;
+        let vesting_contract = vesting_contract.unwrap_or(config.vesting_contract);
+        // Failed vesting claim reverts the whole transaction thus only successful reply is needed
+        messages.push(SubMsg::reply_on_success(
+            wasm_execute(
+                vesting_contract,
+                &vesting::ExecuteMsg::Claim {
+                    recipient: Some(user.to_string()),
+                    amount: Some(protocol_reward_amount),
+                    asset_info: None,
+                },
+                vec![],
+            )?,
+            CLAIM_TRANSFER_REPLY_ID,
+        ));
+        transfers.push((config.astro_token, false));
+    }
+
+    if !transfers.is_empty() {
+        let mut pending_claims = PENDING_CLAIMS.may_load(storage)?.unwrap_or_default();
+        pending_claims.push(PendingClaim {
+            user: user.clone(),
+            rewards,
+            transfers,
+        });
+        PENDING_CLAIMS.save(storage, &pending_claims)?;
+    }
+
+    Ok(Response::new()
+        .add_attributes(attrs)
+        .add_submessages(messages))
+}
+
+/// Processes reply from a reward transfer message sent in [`claim_rewards`].
+/// Rewards of a failed transfer are excluded from the claim.
+/// Once all transfers are replied the claim is recorded in claim history.
+pub fn process_claim_transfer(
+    storage: &mut dyn Storage,
+    env: &Env,
+    succeeded: bool,
+) -> StdResult<()> {
+    let mut pending_claims = PENDING_CLAIMS.load(storage)?;
+    let mut pending = pending_claims
+        .pop()
+        .ok_or_else(|| StdError::generic_err("No pending claims"))?;
+
+    let (info, is_external) = pending.transfers.remove(0);
+    if !succeeded {
+        pending
+            .rewards
+            .retain(|claimed| claimed.is_external != is_external || claimed.reward.info != info);
+    }
+
+    if pending.transfers.is_empty() {
+        record_claims(storage, env, &pending.user, pending.rewards)?;
+    } else {
+        pending_claims.push(pending);
+    }
+
+    if pending_claims.is_empty() {
+        PENDING_CLAIMS.remove(storage);
+        Ok(())
+    } else {
+        PENDING_CLAIMS.save(storage, &pending_claims)
+    }
+}
+
+/// Calculates all rewards for the given user positions and syncs user indexes with pool indexes.
+/// Returns response attributes and non-zero rewards claimed from each pool.
+/// This function mutates in-memory objects. Function caller is responsible for updating the state.
+pub fn collect_rewards(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    pool_tuples: Vec<(&AssetInfo, &mut PoolInfo, &mut UserInfo)>,
+) -> Result<(Vec<Attribute>, Vec<ClaimedReward>), ContractError> {
     let mut attrs = vec![attr("action", "claim_rewards"), attr("user", user)];
-    let mut external_rewards = vec![];
-    let mut protocol_reward_amount = Uint128::zero();
+    let mut rewards = vec![];
     for (lp_token_asset, pool_info, pos) in pool_tuples {
         attrs.push(attr("claimed_position", lp_token_asset.to_string()));
 
-        pool_info.update_rewards(storage, &env, lp_token_asset)?;
+        pool_info.update_rewards(storage, env, lp_token_asset)?;
 
         // Claim outstanding rewards from finished schedules
         for finished_reward in pos.claim_finished_rewards(storage, lp_token_asset, pool_info)? {
             if !finished_reward.amount.is_zero() {
                 attrs.push(attr("claimed_finished_reward", finished_reward.to_string()));
-                external_rewards.push(finished_reward);
+                rewards.push(ClaimedReward {
+                    lp_token: lp_token_asset.clone(),
+                    is_external: true,
+                    reward: finished_reward,
+                });
             }
         }
 
@@ -51,11 +162,11 @@ pub fn claim_rewards(
             attrs.push(attr("claimed_reward", reward_asset.to_string()));
 
             if !reward_asset.amount.is_zero() {
-                if is_external {
-                    external_rewards.push(reward_asset);
-                } else {
-                    protocol_reward_amount += reward_asset.amount;
-                }
+                rewards.push(ClaimedReward {
+                    lp_token: lp_token_asset.clone(),
+                    is_external,
+                    reward: reward_asset,
+                });
             }
         }
 
@@ -63,39 +174,7 @@ pub fn claim_rewards(
         pos.update_and_sync_position(Op::Noop, pool_info);
     }
 
-    // Aggregating rewards by asset info.
-    // This allows to reduce number of output messages thus reducing total gas cost.
-    let mut messages = external_rewards
-        .into_iter()
-        .group_by(|asset| asset.info.clone())
-        .into_iter()
-        .map(|(info, assets)| {
-            let amount: Uint128 = assets.into_iter().map(|asset| asset.amount).sum();
-            info.with_balance(amount)
-                .into_submsg(user, Some((ReplyOn::Error, POST_TRANSFER_REPLY_ID)))
-        })
-        .collect::<StdResult<Vec<_>>>()?;
-
-    // Claim Astroport rewards
-    if !protocol_reward_amount.is_zero() {
-        let vesting_contract = if let Some(vesting_contract) = vesting_contract {
-            vesting_contract
-        } else {
-            CONFIG.load(storage)?.vesting_contract
-        };
-        messages.push(SubMsg::new(wasm_execute(
-            vesting_contract,
-            &vesting::ExecuteMsg::Claim {
-                recipient: Some(user.to_string()),
-                amount: Some(protocol_reward_amount),
-            },
-            vec![],
-        )?));
-    }
-
-    Ok(Response::new()
-        .add_attributes(attrs)
-        .add_submessages(messages))
+    Ok((attrs, rewards))
 }
 
 /// Only factory can set the allocation points to zero for the specified pool.
@@ -340,12 +419,96 @@ pub fn remove_reward_from_pool(
     ]))
 }
 
+/// Checks that the pool is registered in the factory, doesn't contain blocked tokens
+/// and its pair type is not blocked. Returns LP token asset info.
+pub fn validate_pool(
+    deps: Deps,
+    config: &Config,
+    blocked_pair_types: &[PairType],
+    lp_token: &str,
+) -> Result<AssetInfo, ContractError> {
+    let maybe_lp = determine_asset_info(lp_token, deps.api)?;
+    let pair_info = query_pair_info(deps, &maybe_lp)?;
+
+    is_pool_registered(deps.querier, config, &pair_info, lp_token)?;
+
+    // check if assets in the blocked list
+    for asset in &pair_info.asset_infos {
+        if BLOCKED_TOKENS.has(deps.storage, &asset_info_key(asset)) {
+            return Err(ContractError::BlockedToken {
+                token: asset.to_string(),
+            });
+        }
+    }
+
+    // check if pair type is blacklisted
+    if blocked_pair_types.contains(&pair_info.pair_type) {
+        return Err(ContractError::BlockedPairType {
+            pair_type: pair_info.pair_type,
+        });
+    }
+
+    Ok(maybe_lp)
+}
+
+/// Replaces the set of pools receiving ASTRO emissions with the new one.
+/// Updates reward indexes, disables ASTRO rewards in the old active pools
+/// and sets them according to allocation points in the new ones.
+pub fn apply_active_pools(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &mut Config,
+    pools: Vec<(AssetInfo, Uint128)>,
+) -> StdResult<()> {
+    // Update all reward indexes and remove astro rewards from old active pools
+    for (lp_token_asset, _) in ACTIVE_POOLS.load(storage)? {
+        let mut pool_info = PoolInfo::load(storage, &lp_token_asset)?;
+        pool_info.update_rewards(storage, env, &lp_token_asset)?;
+        pool_info.disable_astro_rewards();
+        pool_info.save(storage, &lp_token_asset)?;
+    }
+
+    config.total_alloc_points = pools.iter().map(|(_, alloc)| alloc).sum();
+
+    // Set astro rewards for new active pools
+    for (active_pool, alloc_points) in &pools {
+        let mut pool_info = PoolInfo::may_load(storage, active_pool)?.unwrap_or_default();
+        pool_info.update_rewards(storage, env, active_pool)?;
+        pool_info.set_astro_rewards(config, *alloc_points);
+        pool_info.save(storage, active_pool)?;
+    }
+
+    ACTIVE_POOLS.save(storage, &pools)?;
+    CONFIG.save(storage, config)
+}
+
 /// Queries pair info corresponding to given LP token.
 /// Handles both native and cw20 tokens. If the token is native it must follow the following format:
 /// factory/{lp_minter}/{token_name} where lp_minter is a valid bech32 address on the current chain.
 pub fn query_pair_info(deps: Deps, lp_asset: &AssetInfo) -> StdResult<PairInfo> {
     match lp_asset {
-        AssetInfo::Token { contract_addr } => pair_info_by_pool(&deps.querier, contract_addr),
+        AssetInfo::Token { contract_addr } => {
+            let minter = deps
+                .querier
+                .query_wasm_smart::<MinterResponse>(contract_addr, &Cw20QueryMsg::Minter {})?
+                .minter;
+            let config = CONFIG.load(deps.storage)?;
+
+            match config.pool_manager {
+                // Pools hosted by the pool manager share its address thus they are looked up by their LP token
+                Some(pool_manager) if pool_manager.as_str() == minter => {
+                    deps.querier.query_wasm_smart(
+                        pool_manager,
+                        &pool_manager::msg::QueryMsg::PairByLpToken {
+                            lp_token: contract_addr.to_string(),
+                        },
+                    )
+                }
+                _ => deps
+                    .querier
+                    .query_wasm_smart(minter, &pair::QueryMsg::Pair {}),
+            }
+        }
         AssetInfo::NativeToken { denom } => {
             let parts = denom.split('/').collect_vec();
             if denom.starts_with("factory") && parts.len() >= 3 {
@@ -362,6 +525,14 @@ pub fn query_pair_info(deps: Deps, lp_asset: &AssetInfo) -> StdResult<PairInfo>
     }
 }
 
+/// Returns the pool manager set in the factory config. The result is cached in the contract config.
+pub fn query_pool_manager(querier: QuerierWrapper, factory: &Addr) -> StdResult<Option<Addr>> {
+    let factory_config: factory::ConfigResponse =
+        querier.query_wasm_smart(factory, &factory::QueryMsg::Config {})?;
+
+    Ok(factory_config.pool_manager)
+}
+
 /// Checks if the pool with the following asset infos is registered in the factory contract and
 /// LP tokens address/denom matches the one registered in the factory.
 pub fn is_pool_registered(
@@ -6,10 +6,11 @@ use std::collections::HashMap;
 use anyhow::Result as AnyResult;
 use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
 use cosmwasm_std::{
-    to_binary, Addr, Api, BlockInfo, CanonicalAddr, Coin, Empty, Env, IbcMsg, IbcQuery,
-    RecoverPubkeyError, StdError, StdResult, Storage, Timestamp, Uint128, VerificationError,
+    to_binary, Addr, Api, Binary, BlockInfo, CanonicalAddr, Coin, Deps, Empty, Env, IbcMsg,
+    IbcQuery, RecoverPubkeyError, StdError, StdResult, Storage, Timestamp, Uint128,
+    VerificationError,
 };
-use cw20::MinterResponse;
+use cw20::{Cw20Coin, MinterResponse};
 use cw_multi_test::{
     AddressGenerator, App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper,
     DistributionKeeper, Executor, FailingModule, StakeKeeper, WasmKeeper,
@@ -20,12 +21,13 @@ use crate::helper::broken_cw20;
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::factory::{PairConfig, PairType};
 use astroport::incentives::{
-    Config, ExecuteMsg, IncentivesSchedule, IncentivizationFeeInfo, InputSchedule,
-    PoolInfoResponse, QueryMsg, RewardInfo, ScheduleResponse,
+    ClaimLogEntry, Config, ExecuteMsg, GaugeConfig, IncentivesSchedule, IncentivizationFeeInfo,
+    InputSchedule, PoolInfoResponse, QueryMsg, RewardInfo, ScheduleResponse, TuneInfo,
+    UserVoteInfo,
 };
 use astroport::pair::StablePoolParams;
-use astroport::vesting::{VestingAccount, VestingSchedule, VestingSchedulePoint};
-use astroport::{factory, native_coin_registry, pair, vesting};
+use astroport::vesting::{VestingAccount, VestingCurve, VestingSchedule, VestingSchedulePoint};
+use astroport::{factory, native_coin_registry, pair, vesting, xastro_token};
 
 fn factory_contract() -> Box<dyn Contract<Empty>> {
     Box::new(
@@ -49,6 +51,25 @@ fn pair_contract() -> Box<dyn Contract<Empty>> {
     )
 }
 
+/// Mimics XYK pairs which were deployed before the CurrentFee query was introduced
+fn legacy_pair_query(deps: Deps, env: Env, msg: pair::QueryMsg) -> StdResult<Binary> {
+    match msg {
+        pair::QueryMsg::CurrentFee {} => Err(StdError::generic_err("Unsupported query")),
+        msg => astroport_pair::contract::query(deps, env, msg),
+    }
+}
+
+fn legacy_pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            legacy_pair_query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply),
+    )
+}
+
 fn pair_stable_contract() -> Box<dyn Contract<Empty>> {
     Box::new(
         ContractWrapper::new_with_empty(
@@ -84,6 +105,14 @@ fn token_contract() -> Box<dyn Contract<Empty>> {
     ))
 }
 
+fn xastro_token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        astroport_xastro_token::contract::execute,
+        astroport_xastro_token::contract::instantiate,
+        astroport_xastro_token::contract::query,
+    ))
+}
+
 fn broken_token_contract() -> Box<dyn Contract<Empty>> {
     Box::new(ContractWrapper::new_with_empty(
         broken_cw20::execute,
@@ -286,6 +315,8 @@ impl Helper {
                             maker_fee_bps: 0,
                             is_disabled: false,
                             is_generator_disabled: false,
+                            pool_creation_fee: None,
+                            listing_policy: None,
                         },
                         PairConfig {
                             code_id: pair_stable_code,
@@ -294,6 +325,8 @@ impl Helper {
                             maker_fee_bps: 0,
                             is_disabled: false,
                             is_generator_disabled: false,
+                            pool_creation_fee: None,
+                            listing_policy: None,
                         },
                     ],
                     token_code_id,
@@ -345,6 +378,9 @@ impl Helper {
                 generator_address: Some(generator.to_string()),
                 whitelist_code_id: None,
                 coin_registry_address: None,
+                pool_manager: None,
+                pool_creation_fee: None,
+                listing_policy: None,
             },
             &[],
         )
@@ -369,6 +405,8 @@ impl Helper {
                             amount: astro_for_vesting.amount,
                         },
                         end_point: None,
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     }],
                 }],
             },
@@ -526,6 +564,37 @@ impl Helper {
             &factory::ExecuteMsg::UpdatePairConfig {
                 config: PairConfig {
                     is_generator_disabled: true,
+                    pool_creation_fee: None,
+                    listing_policy: None,
+                    ..pair_config
+                },
+            },
+            &[],
+        )
+    }
+
+    /// New XYK pairs are created from a code which doesn't support the CurrentFee query
+    pub fn use_legacy_xyk_pair(&mut self) -> AnyResult<AppResponse> {
+        let code_id = self.app.store_code(legacy_pair_contract());
+        let pair_config = self
+            .app
+            .wrap()
+            .query_wasm_smart::<factory::ConfigResponse>(
+                &self.factory,
+                &factory::QueryMsg::Config {},
+            )
+            .unwrap()
+            .pair_configs
+            .into_iter()
+            .find(|c| c.pair_type == PairType::Xyk {})
+            .unwrap();
+
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.factory.clone(),
+            &factory::ExecuteMsg::UpdatePairConfig {
+                config: PairConfig {
+                    code_id,
                     ..pair_config
                 },
             },
@@ -716,6 +785,176 @@ impl Helper {
         )
     }
 
+    pub fn claim_and_compound(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        min_lp_out: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ClaimAndCompound {
+                lp_token: lp_token.to_string(),
+                min_lp_out: min_lp_out.into(),
+                max_spread: None,
+            },
+            &[],
+        )
+    }
+
+    /// Instantiates snapshot-enabled xASTRO token with initial balances
+    pub fn init_voting_token(&mut self, balances: &[(&Addr, u128)]) -> Addr {
+        let xastro_code = self.app.store_code(xastro_token_contract());
+        self.app
+            .instantiate_contract(
+                xastro_code,
+                self.owner.clone(),
+                &xastro_token::InstantiateMsg {
+                    name: "xASTRO".to_string(),
+                    symbol: "XASTRO".to_string(),
+                    decimals: 6,
+                    initial_balances: balances
+                        .iter()
+                        .map(|(address, amount)| Cw20Coin {
+                            address: address.to_string(),
+                            amount: (*amount).into(),
+                        })
+                        .collect(),
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "xASTRO",
+                None,
+            )
+            .unwrap()
+    }
+
+    pub fn update_gauge_config(
+        &mut self,
+        from: &Addr,
+        gauge_config: Option<GaugeConfig>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateGaugeConfig { gauge_config },
+            &[],
+        )
+    }
+
+    pub fn vote(&mut self, from: &Addr, votes: Vec<(String, u16)>) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::Vote { votes },
+            &[],
+        )
+    }
+
+    pub fn tune_gauges(&mut self) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked("permissionless"),
+            self.generator.clone(),
+            &ExecuteMsg::TuneGauges {},
+            &[],
+        )
+    }
+
+    pub fn query_user_vote(&self, user: &Addr, epoch: Option<u64>) -> Option<UserVoteInfo> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::UserVote {
+                    user: user.to_string(),
+                    epoch,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_gauge_tally(&self, epoch: Option<u64>) -> Vec<(String, Uint128)> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.generator, &QueryMsg::GaugeTally { epoch })
+            .unwrap()
+    }
+
+    pub fn query_gauge_history(&self, start_after: Option<u64>) -> Vec<TuneInfo> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::GaugeHistory {
+                    start_after,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn set_claim_log_limit(&mut self, limit: u32) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateConfig {
+                vesting_contract: None,
+                generator_controller: None,
+                guardian: None,
+                incentivization_fee_info: None,
+                router: None,
+                pool_manager: None,
+                claim_log_limit: Some(limit),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_user_claimed(&self, user: &Addr) -> Vec<Asset> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::UserClaimedRewards {
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_claim_history(
+        &self,
+        user: &Addr,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    ) -> Vec<ClaimLogEntry> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::ClaimHistory {
+                    user: user.to_string(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_pool_distributed(&self, lp_token: &str) -> Vec<Asset> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::PoolDistributedRewards {
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn next_block(&mut self, plus_seconds: u64) {
         self.app.update_block(|block| {
             block.time = block.time.plus_seconds(plus_seconds);
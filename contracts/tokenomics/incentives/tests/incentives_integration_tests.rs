@@ -5,8 +5,8 @@ use cw_multi_test::Executor;
 
 use astroport::asset::{native_asset_info, AssetInfo, AssetInfoExt};
 use astroport::incentives::{
-    ExecuteMsg, IncentivizationFeeInfo, ScheduleResponse, EPOCHS_START, EPOCH_LENGTH,
-    MAX_REWARD_TOKENS,
+    ExecuteMsg, GaugeConfig, IncentivizationFeeInfo, ScheduleResponse, DEFAULT_CLAIM_LOG_LIMIT,
+    EPOCHS_START, EPOCH_LENGTH, MAX_CLAIM_LOG_LIMIT, MAX_REWARD_TOKENS,
 };
 use astroport_incentives::error::ContractError;
 
@@ -1588,6 +1588,11 @@ fn test_update_config() {
     let astro = native_asset_info("astro".to_string());
     let mut helper = Helper::new("owner", &astro).unwrap();
 
+    // The claim log is bounded by default and the pool manager is cached from the factory config
+    let config = helper.query_config();
+    assert_eq!(config.claim_log_limit, DEFAULT_CLAIM_LOG_LIMIT);
+    assert_eq!(config.pool_manager, None);
+
     let new_vesting = TestAddr::new("new_vesting");
     let new_generator_controller = TestAddr::new("new_generator_controller");
     let new_guardian = TestAddr::new("new_guardian");
@@ -1601,6 +1606,9 @@ fn test_update_config() {
         generator_controller: Some(new_generator_controller.to_string()),
         guardian: Some(new_guardian.to_string()),
         incentivization_fee_info: Some(new_incentivization_fee_info.clone()),
+        router: Some(TestAddr::new("new_router").to_string()),
+        pool_manager: Some(TestAddr::new("new_pool_manager").to_string()),
+        claim_log_limit: Some(200),
     };
 
     let err = helper
@@ -1628,6 +1636,23 @@ fn test_update_config() {
         config.incentivization_fee_info.unwrap(),
         new_incentivization_fee_info
     );
+    assert_eq!(config.router.unwrap(), TestAddr::new("new_router"));
+    assert_eq!(
+        config.pool_manager.unwrap(),
+        TestAddr::new("new_pool_manager")
+    );
+    assert_eq!(config.claim_log_limit, 200);
+
+    // The claim log can't be unbounded
+    for limit in [0, MAX_CLAIM_LOG_LIMIT + 1] {
+        let err = helper.set_claim_log_limit(limit).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidClaimLogLimit {}
+        );
+    }
+    helper.set_claim_log_limit(MAX_CLAIM_LOG_LIMIT).unwrap();
+    assert_eq!(helper.query_config().claim_log_limit, MAX_CLAIM_LOG_LIMIT);
 }
 
 #[test]
@@ -2060,6 +2085,17 @@ fn test_broken_cw20_incentives() {
         .query_pool(&helper.app.wrap(), &user)
         .unwrap();
     assert_eq!(broken_reward_balance.u128(), 0);
+
+    // Claim history contains only successfully transferred rewards
+    let valid_reward = schedules[0].1.reward_info.with_balance(999_999994u128);
+    assert_eq!(helper.query_user_claimed(&user), [valid_reward.clone()]);
+    assert_eq!(helper.query_pool_distributed(&lp_token), [valid_reward]);
+    let history = helper.query_claim_history(&user, None, None);
+    assert!(!history.is_empty());
+    assert!(history.iter().all(|entry| entry
+        .rewards
+        .iter()
+        .all(|asset| asset.info == schedules[0].1.reward_info)));
 }
 
 #[test]
@@ -2201,3 +2237,464 @@ fn test_orphaned_rewards() {
         ContractError::NoOrphanedRewards {}
     );
 }
+
+#[test]
+fn test_gauge_voting() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro).unwrap();
+    let owner = helper.owner.clone();
+    let user1 = TestAddr::new("user1");
+    let user2 = TestAddr::new("user2");
+    let user3 = TestAddr::new("user3");
+
+    let tokens = [
+        AssetInfo::native("usd"),
+        AssetInfo::native("foo"),
+        AssetInfo::native("bar"),
+    ];
+    let lp1 = helper
+        .create_pair(&[tokens[0].clone(), tokens[1].clone()])
+        .unwrap()
+        .liquidity_token
+        .to_string();
+    let lp2 = helper
+        .create_pair(&[tokens[0].clone(), tokens[2].clone()])
+        .unwrap()
+        .liquidity_token
+        .to_string();
+    let lp3 = helper
+        .create_pair(&[tokens[1].clone(), tokens[2].clone()])
+        .unwrap()
+        .liquidity_token
+        .to_string();
+
+    let voting_token = helper.init_voting_token(&[(&user1, 600), (&user2, 400)]);
+    // Voting power is snapshotted at the beginning of the block
+    helper.next_block(10);
+
+    let err = helper.vote(&user1, vec![(lp1.clone(), 10000)]).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::GaugeDisabled {}
+    );
+
+    let gauge_config = GaugeConfig {
+        voting_token: voting_token.clone(),
+        max_active_pools: 2,
+    };
+    let err = helper
+        .update_gauge_config(&user1, Some(gauge_config.clone()))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper
+        .update_gauge_config(&owner, Some(gauge_config))
+        .unwrap();
+
+    let err = helper
+        .vote(&user1, vec![(lp1.clone(), 5001), (lp2.clone(), 5000)])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidVoteWeights {}
+    );
+    let err = helper
+        .vote(&user1, vec![(lp1.clone(), 0), (lp2.clone(), 5000)])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidVoteWeights {}
+    );
+    let err = helper
+        .vote(&user1, vec![(lp1.clone(), 5000), (lp1.clone(), 5000)])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DuplicatedPoolFound {}
+    );
+
+    helper
+        .vote(&user1, vec![(lp1.clone(), 7000), (lp2.clone(), 3000)])
+        .unwrap();
+    helper.vote(&user2, vec![(lp3.clone(), 10000)]).unwrap();
+
+    assert_eq!(
+        helper.query_gauge_tally(None),
+        vec![
+            (lp1.clone(), Uint128::new(420)),
+            (lp3.clone(), Uint128::new(400)),
+            (lp2.clone(), Uint128::new(180)),
+        ]
+    );
+
+    helper.next_block(10);
+
+    // Tokens transferred after the epoch snapshot can't be used for voting
+    helper
+        .app
+        .execute_contract(
+            user1.clone(),
+            voting_token.clone(),
+            &cw20::Cw20ExecuteMsg::Transfer {
+                recipient: user3.to_string(),
+                amount: 600u128.into(),
+            },
+            &[],
+        )
+        .unwrap();
+    helper.next_block(10);
+    let err = helper.vote(&user3, vec![(lp2.clone(), 10000)]).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoVotingPower {
+            user: user3.to_string()
+        }
+    );
+
+    // Voting again overrides the previous vote
+    helper.vote(&user1, vec![(lp1.clone(), 10000)]).unwrap();
+    assert_eq!(
+        helper.query_gauge_tally(None),
+        vec![
+            (lp1.clone(), Uint128::new(600)),
+            (lp3.clone(), Uint128::new(400)),
+        ]
+    );
+    let user_vote = helper.query_user_vote(&user1, None).unwrap();
+    assert_eq!(user_vote.epoch, EPOCHS_START);
+    assert_eq!(user_vote.voting_power.u128(), 600);
+    assert_eq!(user_vote.votes, vec![(lp1.clone(), 10000)]);
+
+    let err = helper.tune_gauges().unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoFinishedEpoch {}
+    );
+
+    helper.next_block(EPOCH_LENGTH);
+
+    helper.tune_gauges().unwrap();
+    let err = helper.tune_gauges().unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::EpochAlreadyTuned {
+            epoch: EPOCHS_START
+        }
+    );
+
+    assert_eq!(helper.query_config().total_alloc_points.u128(), 1000);
+    let history = helper.query_gauge_history(None);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].epoch, EPOCHS_START);
+    assert_eq!(
+        history[0].pools,
+        vec![
+            (lp1.clone(), Uint128::new(600)),
+            (lp3.clone(), Uint128::new(400)),
+        ]
+    );
+    assert_eq!(helper.query_gauge_history(Some(EPOCHS_START)), vec![]);
+
+    helper.set_tokens_per_second(100).unwrap();
+    let reward_info = helper.query_reward_info(&lp1);
+    assert_eq!(reward_info[0].rps.to_uint_floor().u128(), 60);
+    let reward_info = helper.query_reward_info(&lp3);
+    assert_eq!(reward_info[0].rps.to_uint_floor().u128(), 40);
+    assert!(helper.pool_info(&lp2).is_err());
+
+    // Nobody voted in the current epoch
+    helper.next_block(EPOCH_LENGTH);
+    let err = helper.tune_gauges().unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoVotedPools {
+            epoch: EPOCHS_START + EPOCH_LENGTH
+        }
+    );
+}
+
+#[test]
+fn test_claim_and_compound() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(1_000_000_000u64),
+        asset_infos[1].with_balance(1_000_000_000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    // Incentivize with one of the pool assets
+    let bank = TestAddr::new("bank");
+    let reward = asset_infos[0].with_balance(7 * 86400 * 100u128); // 100 ufoo per second
+    let (schedule, _) = helper.create_schedule(&reward, 1).unwrap();
+    helper.mint_assets(&bank, &[reward]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(&bank, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+
+    let user = TestAddr::new("user");
+    let err = helper.claim_and_compound(&user, &lp_token, 0).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PositionDoesntExist {
+            user: user.to_string(),
+            lp_token: lp_token.clone(),
+        }
+    );
+
+    let user_assets = [
+        asset_infos[0].with_balance(100_000u64),
+        asset_infos[1].with_balance(100_000u64),
+    ];
+    helper
+        .provide_liquidity(&user, &user_assets, &pair_info.contract_addr, true)
+        .unwrap();
+    let deposit_before = helper.query_deposit(&lp_token, &user).unwrap();
+
+    helper.next_block(86400);
+
+    // User is the only staker thus they receive all 8_640_000 ufoo.
+    // Roughly a half is swapped to bar and the rest is provided to the pool.
+    let err = helper
+        .claim_and_compound(&user, &lp_token, 5_000_000)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::CompoundSlippage { .. }
+    ));
+
+    helper
+        .claim_and_compound(&user, &lp_token, 4_300_000)
+        .unwrap();
+
+    let deposit_after = helper.query_deposit(&lp_token, &user).unwrap();
+    let minted = deposit_after - deposit_before;
+    assert!(
+        (4_300_000..4_320_000).contains(&minted),
+        "unexpected LP amount minted: {minted}"
+    );
+    assert!(helper
+        .query_pending_rewards(&user, &lp_token)
+        .iter()
+        .all(|asset| asset.amount.is_zero()));
+
+    // Enable ASTRO emissions. ASTRO is not a pool asset thus it must be swapped through the router
+    helper.setup_pools(vec![(lp_token.clone(), 100)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+    helper.next_block(86400);
+
+    let err = helper.claim_and_compound(&user, &lp_token, 0).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RouterNotSet {}
+    );
+
+    // Regular claim still works
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+}
+
+#[test]
+fn test_claim_and_compound_legacy_xyk_pair() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    // The pair doesn't support the CurrentFee query thus the factory fee is used
+    helper.use_legacy_xyk_pair().unwrap();
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(1_000_000_000u64),
+        asset_infos[1].with_balance(1_000_000_000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward = asset_infos[0].with_balance(7 * 86400 * 100u128);
+    let (schedule, _) = helper.create_schedule(&reward, 1).unwrap();
+    helper.mint_assets(&bank, &[reward]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(&bank, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+
+    let user = TestAddr::new("user");
+    let user_assets = [
+        asset_infos[0].with_balance(100_000u64),
+        asset_infos[1].with_balance(100_000u64),
+    ];
+    helper
+        .provide_liquidity(&user, &user_assets, &pair_info.contract_addr, true)
+        .unwrap();
+    let deposit_before = helper.query_deposit(&lp_token, &user).unwrap();
+
+    helper.next_block(86400);
+
+    helper
+        .claim_and_compound(&user, &lp_token, 4_300_000)
+        .unwrap();
+
+    let deposit_after = helper.query_deposit(&lp_token, &user).unwrap();
+    let minted = deposit_after - deposit_before;
+    assert!(
+        (4_300_000..4_320_000).contains(&minted),
+        "unexpected LP amount minted: {minted}"
+    );
+}
+
+#[test]
+fn test_claim_history() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    helper.setup_pools(vec![(lp_token.clone(), 100)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward_info = AssetInfo::native("reward");
+    let reward = reward_info.with_balance(7 * 86400 * 50u128);
+    let (schedule, _) = helper.create_schedule(&reward, 2).unwrap();
+    helper.mint_assets(&bank, &[reward]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(&bank, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    // Deposit doesn't produce empty claim log entries
+    assert!(helper.query_claim_history(&user, None, None).is_empty());
+    assert!(helper.query_user_claimed(&user).is_empty());
+
+    helper.next_block(86400);
+
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+
+    let history = helper.query_claim_history(&user, None, None);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, 0);
+    assert_eq!(history[0].lp_token, lp_token);
+    assert_eq!(history[0].timestamp, helper.app.block_info().time.seconds());
+    assert_eq!(history[0].block_height, helper.app.block_info().height);
+    assert_eq!(history[0].rewards, pending);
+    assert_eq!(helper.query_user_claimed(&user).len(), 2);
+
+    // Keep only 2 last entries
+    helper.set_claim_log_limit(2).unwrap();
+
+    let mut total_claimed = pending.clone();
+    for _ in 0..3 {
+        helper.next_block(86400);
+        let pending = helper.query_pending_rewards(&user, &lp_token);
+        helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+        for (total, asset) in total_claimed.iter_mut().zip(pending) {
+            assert_eq!(total.info, asset.info);
+            total.amount += asset.amount;
+        }
+    }
+
+    let history = helper.query_claim_history(&user, None, None);
+    assert_eq!(
+        history.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+        [2, 3]
+    );
+    let history = helper.query_claim_history(&user, Some(2), None);
+    assert_eq!(
+        history.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+        [3]
+    );
+
+    // Totals are not affected by the log limit
+    let mut user_claimed = helper.query_user_claimed(&user);
+    user_claimed.sort_by_key(|asset| asset.info.to_string());
+    total_claimed.sort_by_key(|asset| asset.info.to_string());
+    assert_eq!(user_claimed, total_claimed);
+    assert_eq!(
+        user_claimed
+            .iter()
+            .find(|asset| asset.info == astro)
+            .unwrap()
+            .amount
+            .u128(),
+        4 * 86400 * 100
+    );
+
+    // The user is the only staker thus all rewards were distributed to them
+    let mut pool_distributed = helper.query_pool_distributed(&lp_token);
+    pool_distributed.sort_by_key(|asset| asset.info.to_string());
+    assert_eq!(pool_distributed, total_claimed);
+
+    // Another staker
+    let user2 = TestAddr::new("user2");
+    helper
+        .provide_liquidity(&user2, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+    helper.next_block(86400);
+    helper
+        .claim_rewards(&user2, vec![lp_token.clone()])
+        .unwrap();
+
+    assert_eq!(helper.query_claim_history(&user2, None, None).len(), 1);
+    let user2_claimed = helper.query_user_claimed(&user2);
+    let pool_distributed = helper.query_pool_distributed(&lp_token);
+    for asset in &pool_distributed {
+        let user1_amount = total_claimed
+            .iter()
+            .find(|a| a.info == asset.info)
+            .unwrap()
+            .amount;
+        let user2_amount = user2_claimed
+            .iter()
+            .find(|a| a.info == asset.info)
+            .unwrap()
+            .amount;
+        assert_eq!(asset.amount, user1_amount + user2_amount);
+    }
+}
@@ -5,6 +5,9 @@ use astroport::maker::{
     Config, ExecuteMsg, SecondReceiverConfig, SecondReceiverParams, MAX_SECOND_RECEIVER_CUT,
 };
 use astroport::pair::Cw20HookMsg;
+use astroport::pool_manager::{
+    Cw20HookMsg as ManagerCw20HookMsg, ExecuteMsg as ManagerExecuteMsg, SwapOperation,
+};
 use astroport::querier::query_pair_info;
 
 use cosmwasm_std::{
@@ -57,6 +60,10 @@ pub fn build_swap_msg(
     to: Option<&AssetInfo>,
     amount_in: Uint128,
 ) -> Result<SubMsg, ContractError> {
+    if let Some(pool_key) = &pool.pool_key {
+        return build_manager_swap_msg(max_spread, pool, pool_key, from, to, amount_in);
+    }
+
     if from.is_native_token() {
         let offer_asset = Asset {
             info: from.clone(),
@@ -92,6 +99,75 @@ pub fn build_swap_msg(
     }
 }
 
+/// This function creates a swap message for a pool hosted by the pool manager.
+///
+/// * **max_spread** max allowed spread.
+///
+/// * **pool** pool's information.
+///
+/// * **pool_key** key of the pool in the pool manager.
+///
+/// * **from**  asset we want to swap.
+///
+/// * **to** asset we want to swap to. The other pool asset is used if not set.
+///
+/// * **amount_in** amount of tokens to swap.
+fn build_manager_swap_msg(
+    max_spread: Decimal,
+    pool: &PairInfo,
+    pool_key: &str,
+    from: &AssetInfo,
+    to: Option<&AssetInfo>,
+    amount_in: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let ask_asset_info = to
+        .or_else(|| {
+            pool.asset_infos
+                .iter()
+                .find(|asset_info| *asset_info != from)
+        })
+        .cloned()
+        .ok_or_else(|| StdError::generic_err(format!("No asset to swap {from} to")))?;
+    let operations = vec![SwapOperation {
+        offer_asset_info: from.clone(),
+        ask_asset_info,
+        pool_key: Some(pool_key.to_string()),
+    }];
+
+    if from.is_native_token() {
+        let offer_asset = Asset {
+            info: from.clone(),
+            amount: amount_in,
+        };
+
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: pool.contract_addr.to_string(),
+            msg: to_binary(&ManagerExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+                max_spread: Some(max_spread),
+            })?,
+            funds: vec![offer_asset.as_coin()?],
+        }))
+    } else {
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: from.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: pool.contract_addr.to_string(),
+                amount: amount_in,
+                msg: to_binary(&ManagerCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                    max_spread: Some(max_spread),
+                })?,
+            })?,
+            funds: vec![],
+        }))
+    }
+}
+
 /// This function builds distribute messages. It swap all assets through bridges if needed.
 ///
 /// * **bridge_assets** array with assets we want to swap and then to distribute.
@@ -173,6 +173,8 @@ fn instantiate_contracts(
             maker_fee_bps: 0,
             is_disabled: false,
             is_generator_disabled: false,
+            pool_creation_fee: None,
+            listing_policy: None,
         }],
         token_code_id: 1u64,
         fee_address: None,
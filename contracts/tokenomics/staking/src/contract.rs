@@ -1,19 +1,23 @@
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Deps,
-    DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, coin, entry_point, from_binary, to_binary, wasm_execute, Addr, BankMsg, Binary,
+    CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, Reply, ReplyOn, Response,
+    StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
-use cw_utils::parse_instantiate_response_data;
+use cw_storage_plus::Bound;
+use cw_utils::{must_pay, parse_instantiate_response_data};
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::migration::{convert_deposit_token, migrate_from_v1};
+use crate::state::{Config, CONFIG, TOTAL_UNBONDING, UNBONDINGS};
+use astroport::asset::{AssetInfo, AssetInfoExt};
 use astroport::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, UnbondingInfo,
 };
+use astroport::token_factory::{tf_burn_msg, tf_create_denom_msg, tf_denom, tf_mint_msg};
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 
-use astroport::querier::{query_supply, query_token_balance};
+use astroport::querier::query_supply;
 use astroport::xastro_token::InstantiateMsg as TokenInstantiateMsg;
 
 /// Contract name that is used for migration.
@@ -41,12 +45,32 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    msg.deposit_token.check(deps.api)?;
+
+    // xASTRO tokenfactory denom is known in advance thus no reply is needed
+    if let Some(subdenom) = msg.tokenfactory_subdenom {
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                deposit_token: msg.deposit_token,
+                share_token: AssetInfo::native(tf_denom(&env.contract.address, &subdenom)),
+                unbonding_period: msg.unbonding_period.unwrap_or_default(),
+            },
+        )?;
+
+        return Ok(Response::new().add_message(tf_create_denom_msg(
+            env.contract.address.to_string(),
+            subdenom,
+        )));
+    }
+
     // Store config
     CONFIG.save(
         deps.storage,
         &Config {
-            astro_token_addr: deps.api.addr_validate(&msg.deposit_token_addr)?,
-            xastro_token_addr: Addr::unchecked(""),
+            deposit_token: msg.deposit_token,
+            share_token: AssetInfo::cw20(Addr::unchecked("")),
+            unbonding_period: msg.unbonding_period.unwrap_or_default(),
         },
     )?;
 
@@ -83,6 +107,12 @@ pub fn instantiate(
 /// ## Variants
 /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
 /// it depending on the received template.
+///
+/// * **ExecuteMsg::Enter {}** Deposits native ASTRO in exchange for xASTRO.
+///
+/// * **ExecuteMsg::Leave {}** Burns tokenfactory xASTRO in exchange for ASTRO.
+///
+/// * **ExecuteMsg::Claim {}** Claims ASTRO from all unbondings which passed the unbonding period.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -92,6 +122,25 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Enter {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let amount = match &config.deposit_token {
+                AssetInfo::NativeToken { denom } => must_pay(&info, denom)?,
+                AssetInfo::Token { .. } => return Err(ContractError::Unauthorized {}),
+            };
+
+            enter(deps, env, config, info.sender.to_string(), amount)
+        }
+        ExecuteMsg::Leave {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let amount = match &config.share_token {
+                AssetInfo::NativeToken { denom } => must_pay(&info, denom)?,
+                AssetInfo::Token { .. } => return Err(ContractError::Unauthorized {}),
+            };
+
+            leave(deps, env, config, info.sender.to_string(), amount)
+        }
+        ExecuteMsg::Claim {} => claim(deps, env, info),
     }
 }
 
@@ -108,14 +157,15 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
         } => {
             let mut config = CONFIG.load(deps.storage)?;
 
-            if config.xastro_token_addr != Addr::unchecked("") {
+            if config.share_token != AssetInfo::cw20(Addr::unchecked("")) {
                 return Err(ContractError::Unauthorized {});
             }
 
             let init_response = parse_instantiate_response_data(data.as_slice())
                 .map_err(|e| StdError::generic_err(format!("{e}")))?;
 
-            config.xastro_token_addr = deps.api.addr_validate(&init_response.contract_address)?;
+            config.share_token =
+                AssetInfo::cw20(deps.api.addr_validate(&init_response.contract_address)?);
 
             CONFIG.save(deps.storage, &config)?;
 
@@ -135,110 +185,258 @@ fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-
-    let recipient = cw20_msg.sender;
-    let mut amount = cw20_msg.amount;
-
-    let mut total_deposit = query_token_balance(
-        &deps.querier,
-        &config.astro_token_addr,
-        env.contract.address.clone(),
-    )?;
-    let total_shares = query_supply(&deps.querier, &config.xastro_token_addr)?;
+    let sent_token = AssetInfo::cw20(info.sender);
 
     match from_binary(&cw20_msg.msg)? {
         Cw20HookMsg::Enter {} => {
-            let mut messages = vec![];
-            if info.sender != config.astro_token_addr {
+            if sent_token != config.deposit_token {
                 return Err(ContractError::Unauthorized {});
             }
 
-            // In a CW20 `send`, the total balance of the recipient is already increased.
-            // To properly calculate the total amount of ASTRO deposited in staking, we should subtract the user deposit from the pool
-            total_deposit -= amount;
-            let mint_amount: Uint128 = if total_shares.is_zero() || total_deposit.is_zero() {
-                amount = amount
-                    .checked_sub(MINIMUM_STAKE_AMOUNT)
-                    .map_err(|_| ContractError::MinimumStakeAmountError {})?;
-
-                // amount cannot become zero after minimum stake subtraction
-                if amount.is_zero() {
-                    return Err(ContractError::MinimumStakeAmountError {});
-                }
-
-                messages.push(wasm_execute(
-                    config.xastro_token_addr.clone(),
-                    &Cw20ExecuteMsg::Mint {
-                        recipient: env.contract.address.to_string(),
-                        amount: MINIMUM_STAKE_AMOUNT,
-                    },
-                    vec![],
-                )?);
-
-                amount
-            } else {
-                amount = amount
-                    .checked_mul(total_shares)?
-                    .checked_div(total_deposit)?;
-
-                if amount.is_zero() {
-                    return Err(ContractError::StakeAmountTooSmall {});
-                }
-
-                amount
-            };
-
-            messages.push(wasm_execute(
-                config.xastro_token_addr,
-                &Cw20ExecuteMsg::Mint {
-                    recipient: recipient.clone(),
-                    amount: mint_amount,
-                },
-                vec![],
-            )?);
-
-            Ok(Response::new().add_messages(messages).add_attributes(vec![
-                attr("action", "enter"),
-                attr("recipient", recipient),
-                attr("astro_amount", cw20_msg.amount),
-                attr("xastro_amount", mint_amount),
-            ]))
+            enter(deps, env, config, cw20_msg.sender, cw20_msg.amount)
         }
         Cw20HookMsg::Leave {} => {
-            if info.sender != config.xastro_token_addr {
+            if sent_token != config.share_token {
                 return Err(ContractError::Unauthorized {});
             }
 
-            let what = amount
-                .checked_mul(total_deposit)?
-                .checked_div(total_shares)?;
-
-            // Burn share
-            let res = Response::new()
-                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr: config.xastro_token_addr.to_string(),
-                    msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
-                    funds: vec![],
-                }))
-                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr: config.astro_token_addr.to_string(),
-                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                        recipient: recipient.clone(),
-                        amount: what,
-                    })?,
-                    funds: vec![],
-                }));
-
-            Ok(res.add_attributes(vec![
-                attr("action", "leave"),
-                attr("recipient", recipient),
-                attr("xastro_amount", cw20_msg.amount),
-                attr("astro_amount", what),
-            ]))
+            leave(deps, env, config, cw20_msg.sender, cw20_msg.amount)
+        }
+    }
+}
+
+/// Mints xASTRO in exchange for deposited ASTRO.
+/// Deposited ASTRO must be already on the contract balance.
+fn enter(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    recipient: String,
+    deposit_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut amount = deposit_amount;
+
+    // The contract balance is already increased by the user deposit.
+    // To properly calculate the total amount of ASTRO deposited in staking, we should subtract the user deposit from the pool
+    let total_deposit =
+        total_deposit(deps.querier, deps.storage, &env, &config)?.checked_sub(deposit_amount)?;
+    let total_shares = total_shares(deps.querier, &config)?;
+
+    let mut messages = vec![];
+
+    let mint_amount: Uint128 = if total_shares.is_zero() || total_deposit.is_zero() {
+        amount = amount
+            .checked_sub(MINIMUM_STAKE_AMOUNT)
+            .map_err(|_| ContractError::MinimumStakeAmountError {})?;
+
+        // amount cannot become zero after minimum stake subtraction
+        if amount.is_zero() {
+            return Err(ContractError::MinimumStakeAmountError {});
+        }
+
+        messages.extend(mint_shares(
+            &env,
+            &config,
+            env.contract.address.as_str(),
+            MINIMUM_STAKE_AMOUNT,
+        )?);
+
+        amount
+    } else {
+        amount = amount
+            .checked_mul(total_shares)?
+            .checked_div(total_deposit)?;
+
+        if amount.is_zero() {
+            return Err(ContractError::StakeAmountTooSmall {});
+        }
+
+        amount
+    };
+
+    messages.extend(mint_shares(&env, &config, &recipient, mint_amount)?);
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "enter"),
+        attr("recipient", recipient),
+        attr("astro_amount", deposit_amount),
+        attr("xastro_amount", mint_amount),
+    ]))
+}
+
+/// Burns xASTRO and either returns ASTRO or creates a pending withdrawal if the unbonding period is set.
+/// xASTRO must be already on the contract balance.
+fn leave(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let total_deposit = total_deposit(deps.querier, deps.storage, &env, &config)?;
+    let total_shares = total_shares(deps.querier, &config)?;
+
+    let what = amount
+        .checked_mul(total_deposit)?
+        .checked_div(total_shares)?;
+
+    // Burn share
+    let burn_msg = burn_shares(&env, &config, amount)?;
+
+    let mut attrs = vec![
+        attr("action", "leave"),
+        attr("recipient", &recipient),
+        attr("xastro_amount", amount),
+        attr("astro_amount", what),
+    ];
+
+    if config.unbonding_period == 0 {
+        let res = Response::new().add_message(burn_msg).add_message(
+            config
+                .deposit_token
+                .with_balance(what)
+                .into_msg(recipient)?,
+        );
+
+        return Ok(res.add_attributes(attrs));
+    }
+
+    // ASTRO stays on the contract balance till the end of the unbonding period.
+    // It is excluded from total deposit thus further rewards accrue only to remaining stakers.
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let release_ts = env.block.time.seconds() + config.unbonding_period;
+    UNBONDINGS.update::<_, ContractError>(deps.storage, (&recipient, release_ts), |unbonding| {
+        Ok(unbonding.unwrap_or_default().checked_add(what)?)
+    })?;
+    TOTAL_UNBONDING.save(
+        deps.storage,
+        &total_unbonding(deps.storage)?.checked_add(what)?,
+    )?;
+
+    attrs.push(attr("release_ts", release_ts.to_string()));
+
+    Ok(Response::new().add_message(burn_msg).add_attributes(attrs))
+}
+
+/// Composes messages to mint xASTRO to the recipient.
+/// Tokenfactory mints coins to the contract itself thus they are sent to the recipient afterwards.
+fn mint_shares(
+    env: &Env,
+    config: &Config,
+    recipient: &str,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    match &config.share_token {
+        AssetInfo::Token { contract_addr } => Ok(vec![wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount,
+            },
+            vec![],
+        )?
+        .into()]),
+        AssetInfo::NativeToken { denom } => {
+            let coin = coin(amount.u128(), denom);
+            let mut messages = vec![tf_mint_msg(env.contract.address.to_string(), coin.clone())];
+            if recipient != env.contract.address.as_str() {
+                messages.push(
+                    BankMsg::Send {
+                        to_address: recipient.to_string(),
+                        amount: vec![coin],
+                    }
+                    .into(),
+                );
+            }
+
+            Ok(messages)
+        }
+    }
+}
+
+/// Composes a message to burn xASTRO from the contract balance.
+fn burn_shares(env: &Env, config: &Config, amount: Uint128) -> StdResult<CosmosMsg> {
+    match &config.share_token {
+        AssetInfo::Token { contract_addr } => {
+            Ok(wasm_execute(contract_addr, &Cw20ExecuteMsg::Burn { amount }, vec![])?.into())
         }
+        AssetInfo::NativeToken { denom } => Ok(tf_burn_msg(
+            env.contract.address.to_string(),
+            coin(amount.u128(), denom),
+        )),
     }
 }
 
+/// Returns the total xASTRO supply.
+fn total_shares(querier: QuerierWrapper, config: &Config) -> StdResult<Uint128> {
+    match &config.share_token {
+        AssetInfo::Token { contract_addr } => query_supply(&querier, contract_addr),
+        AssetInfo::NativeToken { denom } => Ok(querier.query_supply(denom)?.amount),
+    }
+}
+
+/// Returns the amount of ASTRO which belongs to xASTRO holders. Pending unbondings are excluded.
+fn total_deposit(
+    querier: QuerierWrapper,
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+) -> StdResult<Uint128> {
+    let balance = config
+        .deposit_token
+        .query_pool(&querier, &env.contract.address)?;
+
+    Ok(balance.checked_sub(total_unbonding(storage)?)?)
+}
+
+/// Claims ASTRO from all user unbondings which passed the unbonding period.
+fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let released = UNBONDINGS
+        .prefix(&info.sender)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(env.block.time.seconds())),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut amount = Uint128::zero();
+    for (release_ts, unbonding_amount) in released {
+        UNBONDINGS.remove(deps.storage, (&info.sender, release_ts));
+        amount += unbonding_amount;
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    TOTAL_UNBONDING.save(
+        deps.storage,
+        &total_unbonding(deps.storage)?.checked_sub(amount)?,
+    )?;
+
+    Ok(Response::new()
+        .add_message(
+            config
+                .deposit_token
+                .with_balance(amount)
+                .into_msg(&info.sender)?,
+        )
+        .add_attributes([
+            attr("action", "claim"),
+            attr("recipient", info.sender),
+            attr("astro_amount", amount),
+        ]))
+}
+
+/// Returns the total ASTRO amount in pending withdrawals.
+fn total_unbonding(storage: &dyn Storage) -> StdResult<Uint128> {
+    Ok(TOTAL_UNBONDING.may_load(storage)?.unwrap_or_default())
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -246,49 +444,84 @@ fn receive_cw20(
 ///
 /// * **QueryMsg::TotalShares {}** Returns the total xASTRO supply using a [`Uint128`] object.
 ///
-/// * **QueryMsg::Config {}** Returns the amount of ASTRO that's currently in the staking pool using a [`Uint128`] object.
+/// * **QueryMsg::TotalDeposit {}** Returns the amount of ASTRO that's currently in the staking pool using a [`Uint128`] object.
+/// Pending unbondings are excluded.
+///
+/// * **QueryMsg::PendingUnbondings { user }** Returns the list of user unbondings using a vector of [`UnbondingInfo`] objects.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let config = CONFIG.load(deps.storage)?;
     match msg {
         QueryMsg::Config {} => Ok(to_binary(&ConfigResponse {
-            deposit_token_addr: config.astro_token_addr,
-            share_token_addr: config.xastro_token_addr,
+            deposit_token_addr: token_addr(&config.deposit_token),
+            share_token_addr: token_addr(&config.share_token),
+            deposit_token: config.deposit_token,
+            share_token: config.share_token,
+            unbonding_period: config.unbonding_period,
         })?),
-        QueryMsg::TotalShares {} => {
-            to_binary(&query_supply(&deps.querier, &config.xastro_token_addr)?)
+        QueryMsg::TotalShares {} => to_binary(&total_shares(deps.querier, &config)?),
+        QueryMsg::TotalDeposit {} => {
+            to_binary(&total_deposit(deps.querier, deps.storage, &env, &config)?)
+        }
+        QueryMsg::PendingUnbondings { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            let unbondings = UNBONDINGS
+                .prefix(&user)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(release_ts, amount)| UnbondingInfo { amount, release_ts }))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_binary(&unbondings)
         }
-        QueryMsg::TotalDeposit {} => to_binary(&query_token_balance(
-            &deps.querier,
-            &config.astro_token_addr,
-            env.contract.address,
-        )?),
+    }
+}
+
+/// Returns the cw20 contract address of the token or `None` if the token is a native denom.
+fn token_addr(token: &AssetInfo) -> Option<Addr> {
+    match token {
+        AssetInfo::Token { contract_addr } => Some(contract_addr.clone()),
+        AssetInfo::NativeToken { .. } => None,
     }
 }
 
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
+/// Used for migration of contract. Optionally replaces cw20 ASTRO with native ASTRO.
 /// ## Params
 /// * **_deps** is the object of type [`DepsMut`].
 ///
-/// * **_env** is the object of type [`Env`].
+/// * **env** is the object of type [`Env`].
 ///
-/// * **_msg** is the object of type [`MigrateMsg`].
+/// * **msg** is the object of type [`MigrateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "astroport-staking" => match contract_version.version.as_ref() {
-            "1.0.0" | "1.0.1" | "1.0.2" => {}
+            "1.0.0" | "1.0.1" | "1.0.2" | "1.1.0" | "1.2.0" => migrate_from_v1(deps.branch())?,
+            "2.0.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    // Pending unbondings (if any) are not affected by the new unbonding period
+    if let Some(unbonding_period) = msg.unbonding_period {
+        CONFIG.update::<_, StdError>(deps.storage, |mut config| {
+            config.unbonding_period = unbonding_period;
+            Ok(config)
+        })?;
+    }
+
+    let mut response = Response::new();
+    if let Some(conversion) = msg.convert_deposit_token {
+        if let Some(transfer_msg) = convert_deposit_token(deps.branch(), &env, conversion)? {
+            response = response.add_message(transfer_msg);
+        }
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    Ok(Response::new()
+    Ok(response
         .add_attribute("previous_contract_name", &contract_version.contract)
         .add_attribute("previous_contract_version", &contract_version.version)
         .add_attribute("new_contract_name", CONTRACT_NAME)
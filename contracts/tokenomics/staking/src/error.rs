@@ -1,5 +1,6 @@
 use crate::contract::MINIMUM_STAKE_AMOUNT;
-use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
+use cosmwasm_std::{DivideByZeroError, OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 /// This enum describes staking contract errors
@@ -8,6 +9,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -22,6 +26,15 @@ pub enum ContractError {
 
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("There are no unbondings to claim")]
+    NothingToClaim {},
+
+    #[error("Deposit token can be converted only from cw20 ASTRO to a native denom")]
+    InvalidDepositTokenConversion {},
+
+    #[error("Contract must hold at least {amount} {denom} to convert the deposit token")]
+    InsufficientDepositTokenBalance { amount: Uint128, denom: String },
 }
 
 impl From<OverflowError> for ContractError {
@@ -0,0 +1,79 @@
+use astroport::asset::{AssetInfo, AssetInfoExt};
+use astroport::staking::DepositTokenConversion;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CosmosMsg, DepsMut, Env, StdResult};
+use cw_storage_plus::Item;
+
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG};
+
+/// This structure stores the staking contract config before v2.0.0.
+#[cw_serde]
+struct OldConfig {
+    /// The ASTRO token contract address
+    astro_token_addr: Addr,
+    /// The xASTRO token contract address
+    xastro_token_addr: Addr,
+    /// The unbonding period introduced in v1.2.0
+    #[serde(default)]
+    unbonding_period: u64,
+}
+
+/// Converts cw20 token addresses from the old config into [`AssetInfo`] objects.
+pub(crate) fn migrate_from_v1(deps: DepsMut) -> StdResult<()> {
+    let old_config: OldConfig = Item::new("config").load(deps.storage)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            deposit_token: AssetInfo::cw20(old_config.astro_token_addr),
+            share_token: AssetInfo::cw20(old_config.xastro_token_addr),
+            unbonding_period: old_config.unbonding_period,
+        },
+    )
+}
+
+/// Replaces cw20 ASTRO with native ASTRO and returns a message which sends all cw20 ASTRO held by the contract
+/// to the conversion recipient. Native ASTRO must be sent to the contract beforehand so neither the xASTRO
+/// to ASTRO ratio nor pending unbondings change.
+pub(crate) fn convert_deposit_token(
+    deps: DepsMut,
+    env: &Env,
+    conversion: DepositTokenConversion,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let native_token = AssetInfo::native(&conversion.denom);
+    if config.deposit_token.is_native_token() || native_token == config.share_token {
+        return Err(ContractError::InvalidDepositTokenConversion {});
+    }
+    native_token.check(deps.api)?;
+    let cw20_recipient = deps.api.addr_validate(&conversion.cw20_recipient)?;
+
+    let cw20_balance = config
+        .deposit_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    let native_balance = native_token.query_pool(&deps.querier, &env.contract.address)?;
+    if native_balance < cw20_balance {
+        return Err(ContractError::InsufficientDepositTokenBalance {
+            amount: cw20_balance,
+            denom: conversion.denom,
+        });
+    }
+
+    let transfer_msg = if cw20_balance.is_zero() {
+        None
+    } else {
+        Some(
+            config
+                .deposit_token
+                .with_balance(cw20_balance)
+                .into_msg(cw20_recipient)?,
+        )
+    };
+
+    config.deposit_token = native_token;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(transfer_msg)
+}
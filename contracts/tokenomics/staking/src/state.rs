@@ -1,15 +1,24 @@
+use astroport::asset::AssetInfo;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
 
 /// This structure stores the main parameters for the staking contract.
 #[cw_serde]
 pub struct Config {
-    /// The ASTRO token contract address
-    pub astro_token_addr: Addr,
-    /// The xASTRO token contract address
-    pub xastro_token_addr: Addr,
+    /// The ASTRO token: either cw20 contract or native denom
+    pub deposit_token: AssetInfo,
+    /// The xASTRO token: either cw20 contract or tokenfactory denom
+    pub share_token: AssetInfo,
+    /// The number of seconds ASTRO stays locked after leaving. 0 means ASTRO is returned at once
+    pub unbonding_period: u64,
 }
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Pending withdrawals. key: (user, release timestamp), value: ASTRO amount
+pub const UNBONDINGS: Map<(&Addr, u64), Uint128> = Map::new("unbondings");
+
+/// Total ASTRO amount in pending withdrawals. This ASTRO doesn't belong to xASTRO holders anymore
+pub const TOTAL_UNBONDING: Item<Uint128> = Item::new("total_unbonding");
@@ -1,8 +1,12 @@
 #![cfg(not(tarpaulin_include))]
 
-use astroport::staking::{ConfigResponse, Cw20HookMsg, InstantiateMsg as xInstatiateMsg, QueryMsg};
+use astroport::asset::AssetInfo;
+use astroport::staking::{
+    ConfigResponse, Cw20HookMsg, DepositTokenConversion, ExecuteMsg,
+    InstantiateMsg as xInstatiateMsg, MigrateMsg, QueryMsg, UnbondingInfo,
+};
 use astroport::token::InstantiateMsg;
-use cosmwasm_std::{attr, to_binary, Addr, QueryRequest, Uint128, WasmQuery};
+use cosmwasm_std::{attr, coin, to_binary, Addr, QueryRequest, Uint128, WasmQuery};
 use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 use cw_multi_test::{App, ContractWrapper, Executor};
 
@@ -124,6 +128,14 @@ fn mock_app() -> App {
 }
 
 fn instantiate_contracts(router: &mut App, owner: Addr) -> (Addr, Addr, Addr) {
+    instantiate_contracts_with_unbonding(router, owner, None)
+}
+
+fn instantiate_contracts_with_unbonding(
+    router: &mut App,
+    owner: Addr,
+    unbonding_period: Option<u64>,
+) -> (Addr, Addr, Addr) {
     let astro_token_contract = Box::new(ContractWrapper::new_with_empty(
         astroport_token::contract::execute,
         astroport_token::contract::instantiate,
@@ -177,8 +189,10 @@ fn instantiate_contracts(router: &mut App, owner: Addr) -> (Addr, Addr, Addr) {
     let msg = xInstatiateMsg {
         owner: owner.to_string(),
         token_code_id: x_astro_token_code_id,
-        deposit_token_addr: astro_token_instance.to_string(),
+        deposit_token: AssetInfo::cw20(astro_token_instance.clone()),
         marketing: None,
+        unbonding_period,
+        tokenfactory_subdenom: None,
     };
     let staking_instance = router
         .instantiate_contract(
@@ -203,9 +217,13 @@ fn instantiate_contracts(router: &mut App, owner: Addr) -> (Addr, Addr, Addr) {
     // In multitest, contract names are named in the order in which contracts are created.
     assert_eq!("contract0", astro_token_instance);
     assert_eq!("contract1", staking_instance);
-    assert_eq!("contract2", res.share_token_addr);
+    assert_eq!(
+        res.deposit_token,
+        AssetInfo::cw20(astro_token_instance.clone())
+    );
+    assert_eq!(res.share_token, AssetInfo::cw20_unchecked("contract2"));
 
-    let x_astro_token_instance = res.share_token_addr;
+    let x_astro_token_instance = Addr::unchecked(res.share_token.to_string());
 
     (
         astro_token_instance,
@@ -870,3 +888,511 @@ fn should_not_allow_directly_burn_from_xastro() {
         .unwrap_err();
     assert_eq!(res.root_cause().to_string(), "Unauthorized");
 }
+
+#[test]
+fn leave_with_unbonding_period() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+
+    let (astro_token_instance, staking_instance, x_astro_token_instance) =
+        instantiate_contracts_with_unbonding(&mut router, owner.clone(), Some(86400));
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.unbonding_period, 86400);
+
+    mint_some_astro(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        ALICE,
+    );
+    mint_some_astro(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        BOB,
+    );
+    let alice_address = Addr::unchecked(ALICE);
+    let bob_address = Addr::unchecked(BOB);
+
+    // Alice and Bob enter 2000 ASTRO each. Alice receives 1000 xASTRO due to the minimum stake amount
+    let msg = Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::Enter {}).unwrap(),
+        amount: Uint128::from(2000u128),
+    };
+    for user in [&alice_address, &bob_address] {
+        router
+            .execute_contract(user.clone(), astro_token_instance.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    // Alice leaves with 1000 xASTRO
+    let msg = Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::Leave {}).unwrap(),
+        amount: Uint128::from(1000u128),
+    };
+    router
+        .execute_contract(
+            alice_address.clone(),
+            x_astro_token_instance.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    let release_ts = router.block_info().time.seconds() + 86400;
+
+    // ASTRO is not returned right away
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: alice_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::from(8000u128));
+
+    let res: Vec<UnbondingInfo> = router
+        .wrap()
+        .query_wasm_smart(
+            &staking_instance,
+            &QueryMsg::PendingUnbondings {
+                user: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![UnbondingInfo {
+            amount: Uint128::from(1000u128),
+            release_ts,
+        }]
+    );
+
+    // Pending ASTRO is excluded from total deposit
+    let res: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(res, Uint128::from(3000u128));
+
+    // Staking receives 10000 ASTRO rewards which accrue only to remaining stakers
+    mint_some_astro(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        staking_instance.as_str(),
+    );
+    let res: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(res, Uint128::from(13000u128));
+
+    // Unbonding period hasn't passed yet
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::Claim {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "There are no unbondings to claim"
+    );
+
+    router.update_block(|block| block.time = block.time.plus_seconds(86400));
+
+    router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::Claim {},
+            &[],
+        )
+        .unwrap();
+
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: alice_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::from(9000u128));
+
+    let res: Vec<UnbondingInfo> = router
+        .wrap()
+        .query_wasm_smart(
+            &staking_instance,
+            &QueryMsg::PendingUnbondings {
+                user: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(res.is_empty());
+
+    // Claimed ASTRO doesn't affect total deposit
+    let res: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(res, Uint128::from(13000u128));
+
+    // Bob leaves with 2000 xASTRO of 3000 total shares
+    let msg = Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::Leave {}).unwrap(),
+        amount: Uint128::from(2000u128),
+    };
+    router
+        .execute_contract(bob_address.clone(), x_astro_token_instance, &msg, &[])
+        .unwrap();
+
+    let res: Vec<UnbondingInfo> = router
+        .wrap()
+        .query_wasm_smart(
+            &staking_instance,
+            &QueryMsg::PendingUnbondings {
+                user: BOB.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res[0].amount, Uint128::from(8666u128));
+}
+
+#[test]
+fn native_deposit_token() {
+    let owner = Addr::unchecked("owner");
+    let alice_address = Addr::unchecked(ALICE);
+
+    let mut router = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &alice_address,
+                vec![coin(10_000, "uastro"), coin(10_000, "uluna")],
+            )
+            .unwrap()
+    });
+
+    let x_astro_token_code_id = router.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_xastro_token::contract::execute,
+        astroport_xastro_token::contract::instantiate,
+        astroport_xastro_token::contract::query,
+    )));
+    let staking_code_id = router.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_staking::contract::execute,
+            astroport_staking::contract::instantiate,
+            astroport_staking::contract::query,
+        )
+        .with_reply_empty(astroport_staking::contract::reply),
+    ));
+
+    let staking_instance = router
+        .instantiate_contract(
+            staking_code_id,
+            owner.clone(),
+            &xInstatiateMsg {
+                owner: owner.to_string(),
+                token_code_id: x_astro_token_code_id,
+                deposit_token: AssetInfo::native("uastro"),
+                marketing: None,
+                unbonding_period: None,
+                tokenfactory_subdenom: None,
+            },
+            &[],
+            String::from("xASTRO"),
+            None,
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.deposit_token, AssetInfo::native("uastro"));
+    let x_astro_token_instance = Addr::unchecked(res.share_token.to_string());
+
+    // Other denoms are rejected
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::Enter {},
+            &[coin(2000, "uluna")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Must send reserve token 'uastro'"
+    );
+
+    // xASTRO is not a tokenfactory denom thus native leave is not allowed
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::Leave {},
+            &[coin(2000, "uastro")],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    // Alice enters with 2000 ASTRO and receives 1000 xASTRO due to the minimum stake amount
+    router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::Enter {},
+            &[coin(2000, "uastro")],
+        )
+        .unwrap();
+
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &x_astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: alice_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::from(1000u128));
+
+    let res: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(res, Uint128::from(2000u128));
+
+    // Alice leaves and receives native ASTRO back
+    router
+        .execute_contract(
+            alice_address.clone(),
+            x_astro_token_instance,
+            &Cw20ExecuteMsg::Send {
+                contract: staking_instance.to_string(),
+                msg: to_binary(&Cw20HookMsg::Leave {}).unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let balance = router
+        .wrap()
+        .query_balance(&alice_address, "uastro")
+        .unwrap();
+    assert_eq!(balance.amount, Uint128::from(9000u128));
+}
+
+#[test]
+fn migrate_to_native_deposit_token() {
+    let owner = Addr::unchecked("owner");
+    let alice_address = Addr::unchecked(ALICE);
+
+    let mut router = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, vec![coin(10_000, "uastro")])
+            .unwrap()
+    });
+
+    let astro_token_code_id = router.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_token::contract::execute,
+        astroport_token::contract::instantiate,
+        astroport_token::contract::query,
+    )));
+    let x_astro_token_code_id = router.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_xastro_token::contract::execute,
+        astroport_xastro_token::contract::instantiate,
+        astroport_xastro_token::contract::query,
+    )));
+    let staking_code_id = router.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_staking::contract::execute,
+            astroport_staking::contract::instantiate,
+            astroport_staking::contract::query,
+        )
+        .with_reply_empty(astroport_staking::contract::reply)
+        .with_migrate_empty(astroport_staking::contract::migrate),
+    ));
+
+    let astro_token_instance = router
+        .instantiate_contract(
+            astro_token_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                name: String::from("Astro token"),
+                symbol: String::from("ASTRO"),
+                decimals: 6,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: owner.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            },
+            &[],
+            String::from("ASTRO"),
+            None,
+        )
+        .unwrap();
+    let staking_instance = router
+        .instantiate_contract(
+            staking_code_id,
+            owner.clone(),
+            &xInstatiateMsg {
+                owner: owner.to_string(),
+                token_code_id: x_astro_token_code_id,
+                deposit_token: AssetInfo::cw20(astro_token_instance.clone()),
+                marketing: None,
+                unbonding_period: None,
+                tokenfactory_subdenom: None,
+            },
+            &[],
+            String::from("xASTRO"),
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.deposit_token_addr, Some(astro_token_instance.clone()));
+    let x_astro_token_instance = res.share_token_addr.unwrap();
+
+    // Alice enters with 2000 cw20 ASTRO and receives 1000 xASTRO due to the minimum stake amount
+    mint_some_astro(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        ALICE,
+    );
+    router
+        .execute_contract(
+            alice_address.clone(),
+            astro_token_instance.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: staking_instance.to_string(),
+                msg: to_binary(&Cw20HookMsg::Enter {}).unwrap(),
+                amount: Uint128::from(2000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let migrate_msg = MigrateMsg {
+        unbonding_period: None,
+        convert_deposit_token: Some(DepositTokenConversion {
+            denom: "uastro".to_string(),
+            cw20_recipient: owner.to_string(),
+        }),
+    };
+
+    // Native ASTRO must be sent to the contract before the conversion
+    let err = router
+        .migrate_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &migrate_msg,
+            staking_code_id,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Contract must hold at least 2000 uastro to convert the deposit token"
+    );
+
+    router
+        .send_tokens(
+            owner.clone(),
+            staking_instance.clone(),
+            &[coin(2000, "uastro")],
+        )
+        .unwrap();
+    router
+        .migrate_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &migrate_msg,
+            staking_code_id,
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.deposit_token, AssetInfo::native("uastro"));
+    assert_eq!(res.deposit_token_addr, None);
+    assert_eq!(res.share_token_addr, Some(x_astro_token_instance.clone()));
+
+    // cw20 ASTRO is sent to the conversion recipient
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: owner.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::from(2000u128));
+
+    let res: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(res, Uint128::from(2000u128));
+
+    // The deposit token can't be converted twice
+    let err = router
+        .migrate_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &migrate_msg,
+            staking_code_id,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Deposit token can be converted only from cw20 ASTRO to a native denom"
+    );
+
+    // Alice leaves with cw20 xASTRO and receives native ASTRO
+    router
+        .execute_contract(
+            alice_address.clone(),
+            x_astro_token_instance,
+            &Cw20ExecuteMsg::Send {
+                contract: staking_instance.to_string(),
+                msg: to_binary(&Cw20HookMsg::Leave {}).unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let balance = router
+        .wrap()
+        .query_balance(&alice_address, "uastro")
+        .unwrap();
+    assert_eq!(balance.amount, Uint128::from(1000u128));
+}
@@ -3,15 +3,18 @@ use cosmwasm_std::{
     Response, StdError, StdResult, SubMsg, Uint128,
 };
 
-use crate::state::{read_vesting_infos, Config, CONFIG, OWNERSHIP_PROPOSAL, VESTING_INFO};
+use crate::state::{
+    load_vesting_info, read_vesting_infos, remove_vesting_info, save_vesting_info, Config, CONFIG,
+    OWNERSHIP_PROPOSAL, PENDING_TRANSFERS,
+};
 
 use crate::error::ContractError;
 use astroport::asset::{addr_opt_validate, token_asset_info, AssetInfo, AssetInfoExt};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::vesting::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy, QueryMsg,
-    VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo, VestingSchedule,
-    VestingSchedulePoint,
+    AssetVestingInfo, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy,
+    QueryMsg, VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingCurve,
+    VestingInfo, VestingSchedule, VestingSchedulePoint,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
@@ -21,7 +24,8 @@ use cw_utils::must_pay;
 const CONTRACT_NAME: &str = "astroport-vesting";
 /// Contract version that is used for migration.
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-/// Maximum limit of schedules per user
+/// Maximum limit of schedules per user. Finished and fully claimed schedules are not counted
+/// as they are pruned when new schedules are registered
 const SCHEDULES_LIMIT: usize = 8;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
@@ -41,6 +45,8 @@ pub fn instantiate(
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
             vesting_token: msg.vesting_token,
+            transfer_approval_required: false,
+            extra_vesting_assets: vec![],
         },
     )?;
 
@@ -49,7 +55,7 @@ pub fn instantiate(
 
 /// Exposes execute functions available in the contract.
 ///
-/// * **ExecuteMsg::Claim { recipient, amount }** Claims vested tokens and transfers them to the vesting recipient.
+/// * **ExecuteMsg::Claim { recipient, amount, asset_info }** Claims vested tokens and transfers them to the vesting recipient.
 ///
 /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
 /// depending on the received template.
@@ -57,9 +63,21 @@ pub fn instantiate(
 /// * **ExecuteMsg::RegisterVestingAccounts { vesting_accounts }** Registers vesting accounts
 /// using the provided vector of [`VestingAccount`] structures.
 ///
-/// * **ExecuteMsg::WithdrawFromActiveSchedule { account, recipient, withdraw_amount }**
+/// * **ExecuteMsg::WithdrawFromActiveSchedule { account, recipient, withdraw_amount, asset_info }**
 /// Withdraws tokens from the only one active vesting schedule of the specified account.
 ///
+/// * **ExecuteMsg::RevokeVestingSchedules { account, recipient }** Revokes all revocable schedules
+/// of the specified account and sends unvested tokens to the recipient.
+///
+/// * **ExecuteMsg::TransferVestingAccount { new_address }** Moves the sender vesting account to a new address
+/// or creates a pending transfer if owner approval is required.
+///
+/// * **ExecuteMsg::CancelVestingAccountTransfer {}** Cancels the pending transfer of the sender vesting account.
+///
+/// * **ExecuteMsg::ApproveVestingAccountTransfer { account }** Approves a pending vesting account transfer.
+///
+/// * **ExecuteMsg::UpdateConfig { transfer_approval_required, add_vesting_assets }** Updates contract parameters.
+///
 /// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
 ///
 /// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
@@ -74,24 +92,67 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Claim { recipient, amount } => claim(deps, env, info, recipient, amount),
+        ExecuteMsg::Claim {
+            recipient,
+            amount,
+            asset_info,
+        } => claim(deps, env, info, recipient, amount, asset_info),
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::RegisterVestingAccounts { vesting_accounts } => {
             let config = CONFIG.load(deps.storage)?;
 
-            match &config.vesting_token {
-                AssetInfo::NativeToken { denom } if info.sender == config.owner => {
-                    let amount = must_pay(&info, denom)?;
-                    register_vesting_accounts(deps, env, vesting_accounts, amount)
-                }
-                _ => Err(ContractError::Unauthorized {}),
+            if info.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
             }
+
+            // Extra native assets are recognized by attached funds
+            let extra_asset = info
+                .funds
+                .first()
+                .map(|coin| AssetInfo::native(&coin.denom))
+                .filter(|asset_info| config.extra_vesting_assets.contains(asset_info));
+
+            let (asset_info, amount) = match (extra_asset, &config.vesting_token) {
+                (Some(asset_info), _) => {
+                    let amount = must_pay(&info, &asset_info.to_string())?;
+                    (asset_info, amount)
+                }
+                (None, AssetInfo::NativeToken { denom }) => {
+                    (config.vesting_token.clone(), must_pay(&info, denom)?)
+                }
+                _ => return Err(ContractError::Unauthorized {}),
+            };
+
+            register_vesting_accounts(deps, env, &config, &asset_info, vesting_accounts, amount)
         }
         ExecuteMsg::WithdrawFromActiveSchedule {
             account,
             recipient,
             withdraw_amount,
-        } => withdraw_from_active_schedule(deps, env, info, account, recipient, withdraw_amount),
+            asset_info,
+        } => withdraw_from_active_schedule(
+            deps,
+            env,
+            info,
+            account,
+            recipient,
+            withdraw_amount,
+            asset_info,
+        ),
+        ExecuteMsg::RevokeVestingSchedules { account, recipient } => {
+            revoke_vesting_schedules(deps, env, info, account, recipient)
+        }
+        ExecuteMsg::TransferVestingAccount { new_address } => {
+            transfer_vesting_account(deps, info, new_address)
+        }
+        ExecuteMsg::CancelVestingAccountTransfer {} => cancel_vesting_account_transfer(deps, info),
+        ExecuteMsg::ApproveVestingAccountTransfer { account } => {
+            approve_vesting_account_transfer(deps, info, account)
+        }
+        ExecuteMsg::UpdateConfig {
+            transfer_approval_required,
+            add_vesting_assets,
+        } => update_config(deps, info, transfer_approval_required, add_vesting_assets),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -136,27 +197,37 @@ fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    let asset_info = token_asset_info(info.sender);
 
     // Permission check
-    if cw20_msg.sender != config.owner || token_asset_info(info.sender) != config.vesting_token {
+    if cw20_msg.sender != config.owner || !config.is_vesting_asset(&asset_info) {
         return Err(ContractError::Unauthorized {});
     }
 
     match from_binary(&cw20_msg.msg)? {
-        Cw20HookMsg::RegisterVestingAccounts { vesting_accounts } => {
-            register_vesting_accounts(deps, env, vesting_accounts, cw20_msg.amount)
-        }
+        Cw20HookMsg::RegisterVestingAccounts { vesting_accounts } => register_vesting_accounts(
+            deps,
+            env,
+            &config,
+            &asset_info,
+            vesting_accounts,
+            cw20_msg.amount,
+        ),
     }
 }
 
 /// Create new vesting schedules.
 ///
+/// * **asset_info** the asset which is vested.
+///
 /// * **vesting_accounts** list of accounts and associated vesting schedules to create.
 ///
 /// * **cw20_amount** sets the amount that confirms the total amount of all accounts to register.
 pub fn register_vesting_accounts(
     deps: DepsMut,
     env: Env,
+    config: &Config,
+    asset_info: &AssetInfo,
     vesting_accounts: Vec<VestingAccount>,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
@@ -179,19 +250,25 @@ pub fn register_vesting_accounts(
             to_deposit = to_deposit.checked_add(amount)?;
         }
 
-        if let Some(mut old_info) = VESTING_INFO.may_load(deps.storage, &account_address)? {
-            if old_info.schedules.len() + 1 > SCHEDULES_LIMIT {
-                return Err(ContractError::ExceedSchedulesMaximumLimit(
-                    vesting_account.address,
-                ));
-            };
+        if let Some(mut old_info) =
+            load_vesting_info(deps.storage, config, &account_address, asset_info)?
+        {
+            prune_claimed_schedules(&mut old_info, env.block.time.seconds());
             released_amount = old_info.released_amount;
             vesting_account.schedules.append(&mut old_info.schedules);
         }
 
-        VESTING_INFO.save(
+        if vesting_account.schedules.len() > SCHEDULES_LIMIT {
+            return Err(ContractError::ExceedSchedulesMaximumLimit(
+                vesting_account.address,
+            ));
+        }
+
+        save_vesting_info(
             deps.storage,
+            config,
             &account_address,
+            asset_info,
             &VestingInfo {
                 schedules: vesting_account.schedules,
                 released_amount,
@@ -206,6 +283,7 @@ pub fn register_vesting_accounts(
     Ok(response.add_attributes({
         vec![
             attr("action", "register_vesting_accounts"),
+            attr("asset", asset_info.to_string()),
             attr("deposited", to_deposit),
         ]
     }))
@@ -230,6 +308,16 @@ fn assert_vesting_schedules(
                 return Err(ContractError::VestingScheduleError(addr.to_string()));
             }
         }
+
+        match (&sch.curve, &sch.end_point) {
+            (VestingCurve::Linear {}, _) => {}
+            (VestingCurve::Cliff { cliff_time }, Some(end_point))
+                if sch.start_point.time < *cliff_time && *cliff_time < end_point.time => {}
+            (VestingCurve::Step { step_duration }, Some(end_point))
+                if *step_duration > 0
+                    && *step_duration <= end_point.time - sch.start_point.time => {}
+            _ => return Err(ContractError::VestingCurveError(addr.to_string())),
+        }
     }
 
     Ok(())
@@ -240,15 +328,20 @@ fn assert_vesting_schedules(
 /// * **recipient** vesting recipient for which to claim tokens.
 ///
 /// * **amount** amount of vested tokens to claim.
+///
+/// * **asset_info** vested asset to claim. The main vesting token is used if not specified.
 pub fn claim(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: Option<String>,
     amount: Option<Uint128>,
+    asset_info: Option<AssetInfo>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let mut vesting_info = VESTING_INFO.load(deps.storage, &info.sender)?;
+    let asset_info = resolve_vesting_asset(&config, asset_info)?;
+    let mut vesting_info = load_vesting_info(deps.storage, &config, &info.sender, &asset_info)?
+        .ok_or_else(|| ContractError::NoVestingAccount(info.sender.to_string()))?;
 
     let available_amount = compute_available_amount(env.block.time.seconds(), &vesting_info)?;
 
@@ -264,23 +357,42 @@ pub fn claim(
     let mut response = Response::new();
 
     if !claim_amount.is_zero() {
-        let transfer_msg = config.vesting_token.with_balance(claim_amount).into_msg(
+        let transfer_msg = asset_info.with_balance(claim_amount).into_msg(
             addr_opt_validate(deps.api, &recipient)?.unwrap_or_else(|| info.sender.clone()),
         )?;
         response = response.add_submessage(SubMsg::new(transfer_msg));
 
         vesting_info.released_amount = vesting_info.released_amount.checked_add(claim_amount)?;
-        VESTING_INFO.save(deps.storage, &info.sender, &vesting_info)?;
+        save_vesting_info(
+            deps.storage,
+            &config,
+            &info.sender,
+            &asset_info,
+            &vesting_info,
+        )?;
     };
 
     Ok(response.add_attributes(vec![
         attr("action", "claim"),
         attr("address", &info.sender),
+        attr("asset", asset_info.to_string()),
         attr("available_amount", available_amount),
         attr("claimed_amount", claim_amount),
     ]))
 }
 
+/// Returns the main vesting token if the asset is not specified, otherwise checks that the asset can be vested.
+fn resolve_vesting_asset(
+    config: &Config,
+    asset_info: Option<AssetInfo>,
+) -> Result<AssetInfo, ContractError> {
+    match asset_info {
+        None => Ok(config.vesting_token.clone()),
+        Some(asset_info) if config.is_vesting_asset(&asset_info) => Ok(asset_info),
+        Some(asset_info) => Err(ContractError::UnknownVestingAsset(asset_info.to_string())),
+    }
+}
+
 /// Computes the amount of vested and yet unclaimed tokens for a specific vesting recipient.
 /// Returns the computed amount if the operation is successful.
 ///
@@ -314,7 +426,21 @@ fn calc_schedule_unlocked_amount(
     let mut available_amount = schedule.start_point.amount;
 
     if let Some(end_point) = &schedule.end_point {
-        let passed_time = current_time.min(end_point.time) - schedule.start_point.time;
+        let mut passed_time = current_time.min(end_point.time) - schedule.start_point.time;
+        match schedule.curve {
+            VestingCurve::Linear {} => {}
+            VestingCurve::Cliff { cliff_time } => {
+                if current_time < cliff_time {
+                    return Ok(Uint128::zero());
+                }
+            }
+            VestingCurve::Step { step_duration } => {
+                if current_time < end_point.time {
+                    passed_time -= passed_time % step_duration;
+                }
+            }
+        }
+
         let time_period = end_point.time - schedule.start_point.time;
         if passed_time != 0 {
             let release_amount = Uint128::from(passed_time).multiply_ratio(
@@ -328,6 +454,21 @@ fn calc_schedule_unlocked_amount(
     Ok(available_amount)
 }
 
+/// Removes schedules which have unlocked all their tokens (including revoked ones) while
+/// the released amount covers them. Their amounts are subtracted from the released amount
+/// thus the available amount stays the same.
+fn prune_claimed_schedules(vesting_info: &mut VestingInfo, current_time: u64) {
+    vesting_info.schedules.retain(|schedule| {
+        let finish_point = schedule.end_point.unwrap_or(schedule.start_point);
+        if finish_point.time > current_time || finish_point.amount > vesting_info.released_amount {
+            return true;
+        }
+
+        vesting_info.released_amount -= finish_point.amount;
+        false
+    });
+}
+
 /// Withdraw tokens from active vesting schedule.
 ///
 /// Withdraw is possible if there is only one active vesting schedule.
@@ -344,6 +485,8 @@ fn calc_schedule_unlocked_amount(
 ///
 /// * **amount** amount to withdraw from the only one active schedule.
 ///
+/// * **asset_info** vested asset to withdraw. The main vesting token is used if not specified.
+///
 fn withdraw_from_active_schedule(
     deps: DepsMut,
     env: Env,
@@ -351,6 +494,7 @@ fn withdraw_from_active_schedule(
     account: String,
     receiver: Option<String>,
     amount: Uint128,
+    asset_info: Option<AssetInfo>,
 ) -> Result<Response, ContractError> {
     if amount.is_zero() {
         return Err(ContractError::ZeroAmountWithdrawal {});
@@ -361,11 +505,18 @@ fn withdraw_from_active_schedule(
         return Err(ContractError::Unauthorized {});
     }
 
+    let asset_info = resolve_vesting_asset(&config, asset_info)?;
     let acc = deps.api.addr_validate(&account)?;
-    let mut vesting_info = VESTING_INFO.load(deps.storage, &acc)?;
+    let mut vesting_info = load_vesting_info(deps.storage, &config, &acc, &asset_info)?
+        .ok_or_else(|| ContractError::NoVestingAccount(account.clone()))?;
     let block_time = env.block.time.seconds();
 
+    // Withdrawal moves the start point which breaks cliff and step curves, thus only linear schedules are considered
     let mut active_schedules = vesting_info.schedules.iter_mut().filter(|schedule| {
+        if schedule.curve != (VestingCurve::Linear {}) {
+            return false;
+        }
+
         if let Some(end_point) = schedule.end_point {
             block_time >= schedule.start_point.time && block_time < end_point.time
         } else {
@@ -400,57 +551,327 @@ fn withdraw_from_active_schedule(
         return Err(ContractError::NoActiveVestingSchedule(account));
     };
 
-    VESTING_INFO.save(deps.storage, &acc, &vesting_info)?;
+    save_vesting_info(deps.storage, &config, &acc, &asset_info, &vesting_info)?;
 
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender);
-    let transfer_msg = config
-        .vesting_token
-        .with_balance(amount)
-        .into_msg(receiver.clone())?;
+    let transfer_msg = asset_info.with_balance(amount).into_msg(receiver.clone())?;
 
     Ok(Response::new().add_message(transfer_msg).add_attributes([
         attr("action", "withdraw_from_active_schedule"),
         attr("account", account),
+        attr("asset", asset_info.to_string()),
         attr("amount", amount),
         attr("receiver", receiver),
     ]))
 }
 
+/// Revokes all revocable vesting schedules of the account in all vested assets.
+///
+/// Each revoked schedule is replaced with the schedule which has already unlocked amount as its start point
+/// and no end point. Thus vested tokens stay claimable by the account.
+/// Schedules which haven't started yet are removed completely.
+///
+/// * **account** whose schedules to revoke.
+///
+/// * **recipient** who will receive unvested tokens. Owner is used if it is not specified.
+fn revoke_vesting_schedules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account: String,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let acc = deps.api.addr_validate(&account)?;
+    let recipient = addr_opt_validate(deps.api, &recipient)?.unwrap_or(config.owner.clone());
+    let block_time = env.block.time.seconds();
+
+    let mut revoked = 0;
+    let mut response = Response::new();
+    for asset_info in config.vesting_assets() {
+        let mut vesting_info = if let Some(vesting_info) =
+            load_vesting_info(deps.storage, &config, &acc, asset_info)?
+        {
+            vesting_info
+        } else {
+            continue;
+        };
+
+        let mut unvested_amount = Uint128::zero();
+        let mut schedules = vec![];
+        for schedule in vesting_info.schedules {
+            if !schedule.revocable {
+                schedules.push(schedule);
+                continue;
+            }
+
+            revoked += 1;
+
+            let total_amount = schedule
+                .end_point
+                .map(|end_point| end_point.amount)
+                .unwrap_or(schedule.start_point.amount);
+            let unlocked_amount = if schedule.start_point.time > block_time {
+                Uint128::zero()
+            } else {
+                calc_schedule_unlocked_amount(&schedule, block_time)?
+            };
+            unvested_amount += total_amount - unlocked_amount;
+
+            if !unlocked_amount.is_zero() {
+                schedules.push(VestingSchedule {
+                    start_point: VestingSchedulePoint {
+                        time: block_time,
+                        amount: unlocked_amount,
+                    },
+                    end_point: None,
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
+                });
+            }
+        }
+
+        vesting_info.schedules = schedules;
+        save_vesting_info(deps.storage, &config, &acc, asset_info, &vesting_info)?;
+
+        if !unvested_amount.is_zero() {
+            let unvested = asset_info.with_balance(unvested_amount);
+            response = response
+                .add_attribute("unvested", unvested.to_string())
+                .add_message(unvested.into_msg(recipient.clone())?);
+        }
+    }
+
+    if revoked == 0 {
+        return Err(ContractError::NothingToRevoke(account));
+    }
+
+    Ok(response.add_attributes([
+        attr("action", "revoke_vesting_schedules"),
+        attr("account", account),
+        attr("revoked_schedules", revoked.to_string()),
+        attr("recipient", recipient),
+    ]))
+}
+
+/// Moves the sender vesting account to a new address.
+/// If owner approval is required, the transfer is saved as pending. A new request replaces the previous one.
+///
+/// * **new_address** address which will receive vested tokens.
+fn transfer_vesting_account(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if !has_vesting_info(deps.as_ref(), &config, &info.sender)? {
+        return Err(ContractError::NoVestingAccount(info.sender.to_string()));
+    }
+
+    let new_address = deps.api.addr_validate(&new_address)?;
+    if has_vesting_info(deps.as_ref(), &config, &new_address)? {
+        return Err(ContractError::AccountAlreadyExists(new_address.to_string()));
+    }
+
+    if config.transfer_approval_required {
+        PENDING_TRANSFERS.save(deps.storage, &info.sender, &new_address)?;
+
+        return Ok(Response::new().add_attributes([
+            attr("action", "request_vesting_account_transfer"),
+            attr("from", info.sender),
+            attr("to", new_address),
+        ]));
+    }
+
+    move_vesting_account(deps, &config, &info.sender, &new_address)
+}
+
+/// Cancels the pending transfer of the sender vesting account.
+fn cancel_vesting_account_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let new_address = PENDING_TRANSFERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::NoPendingTransfer(info.sender.to_string()))?;
+    PENDING_TRANSFERS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_vesting_account_transfer"),
+        attr("from", info.sender),
+        attr("to", new_address),
+    ]))
+}
+
+/// Approves a pending vesting account transfer.
+///
+/// * **account** address whose pending transfer is approved.
+fn approve_vesting_account_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    account: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let account = deps.api.addr_validate(&account)?;
+    let new_address = PENDING_TRANSFERS
+        .may_load(deps.storage, &account)?
+        .ok_or_else(|| ContractError::NoPendingTransfer(account.to_string()))?;
+
+    // The new address could have received vesting schedules since the request
+    if has_vesting_info(deps.as_ref(), &config, &new_address)? {
+        return Err(ContractError::AccountAlreadyExists(new_address.to_string()));
+    }
+
+    move_vesting_account(deps, &config, &account, &new_address)
+}
+
+/// Returns true if the account vests any asset.
+fn has_vesting_info(deps: Deps, config: &Config, account: &Addr) -> StdResult<bool> {
+    for asset_info in config.vesting_assets() {
+        if load_vesting_info(deps.storage, config, account, asset_info)?.is_some() {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Moves schedules and released amounts of all vested assets from one address to another.
+fn move_vesting_account(
+    deps: DepsMut,
+    config: &Config,
+    from: &Addr,
+    to: &Addr,
+) -> Result<Response, ContractError> {
+    let mut attrs = vec![
+        attr("action", "transfer_vesting_account"),
+        attr("from", from),
+        attr("to", to),
+    ];
+
+    for asset_info in config.vesting_assets() {
+        if let Some(vesting_info) = load_vesting_info(deps.storage, config, from, asset_info)? {
+            remove_vesting_info(deps.storage, config, from, asset_info);
+            save_vesting_info(deps.storage, config, to, asset_info, &vesting_info)?;
+            attrs.push(attr(
+                "released",
+                asset_info
+                    .with_balance(vesting_info.released_amount)
+                    .to_string(),
+            ));
+        }
+    }
+    PENDING_TRANSFERS.remove(deps.storage, from);
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Updates contract parameters.
+///
+/// * **transfer_approval_required** whether vesting account transfers must be approved by the owner.
+///
+/// * **add_vesting_assets** new assets which can be vested besides the main vesting token.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    transfer_approval_required: Option<bool>,
+    add_vesting_assets: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![attr("action", "update_config")];
+
+    if let Some(transfer_approval_required) = transfer_approval_required {
+        config.transfer_approval_required = transfer_approval_required;
+        attrs.push(attr(
+            "transfer_approval_required",
+            transfer_approval_required.to_string(),
+        ));
+    }
+
+    for asset_info in add_vesting_assets.unwrap_or_default() {
+        asset_info.check(deps.api)?;
+        if config.is_vesting_asset(&asset_info) {
+            return Err(ContractError::DuplicatedVestingAsset(
+                asset_info.to_string(),
+            ));
+        }
+        attrs.push(attr("new_vesting_asset", asset_info.to_string()));
+        config.extra_vesting_assets.push(asset_info);
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
 /// * **QueryMsg::Config {}** Returns the contract configuration in an object of type [`Config`].
 ///
-/// * **QueryMsg::VestingAccount { address }** Returns information about the vesting schedules that have a specific vesting recipient.
+/// * **QueryMsg::VestingAccount { address, asset_info }** Returns information about the vesting schedules that have a specific vesting recipient.
 ///
 /// * **QueryMsg::VestingAccounts {
 ///             start_after,
 ///             limit,
 ///             order_by,
+///             asset_info,
 ///         }** Returns a list of vesting schedules together with their vesting recipients.
 ///
-/// * **QueryMsg::AvailableAmount { address }** Returns the available amount of tokens that can be claimed by a specific vesting recipient.
+/// * **QueryMsg::AvailableAmount { address, asset_info }** Returns the available amount of tokens that can be claimed by a specific vesting recipient.
+///
+/// * **QueryMsg::VestingAccountAssets { address }** Returns vesting information of a specific vesting recipient in all vested assets.
+///
+/// * **QueryMsg::PendingTransfer { address }** Returns the new address of a pending vesting account transfer.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
-        QueryMsg::VestingAccount { address } => {
-            Ok(to_binary(&query_vesting_account(deps, address)?)?)
-        }
+        QueryMsg::VestingAccount {
+            address,
+            asset_info,
+        } => Ok(to_binary(&query_vesting_account(
+            deps, address, asset_info,
+        )?)?),
         QueryMsg::VestingAccounts {
             start_after,
             limit,
             order_by,
+            asset_info,
         } => Ok(to_binary(&query_vesting_accounts(
             deps,
             start_after,
             limit,
             order_by,
+            asset_info,
         )?)?),
-        QueryMsg::AvailableAmount { address } => Ok(to_binary(&query_vesting_available_amount(
-            deps, env, address,
+        QueryMsg::AvailableAmount {
+            address,
+            asset_info,
+        } => Ok(to_binary(&query_vesting_available_amount(
+            deps, env, address, asset_info,
         )?)?),
         QueryMsg::Timestamp {} => Ok(to_binary(&query_timestamp(env)?)?),
+        QueryMsg::VestingAccountAssets { address } => {
+            to_binary(&query_vesting_account_assets(deps, env, address)?)
+        }
+        QueryMsg::PendingTransfer { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&PENDING_TRANSFERS.may_load(deps.storage, &address)?)
+        }
     }
 }
 
@@ -461,6 +882,8 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(ConfigResponse {
         owner: config.owner,
         vesting_token: config.vesting_token,
+        transfer_approval_required: config.transfer_approval_required,
+        extra_vesting_assets: config.extra_vesting_assets,
     })
 }
 
@@ -473,13 +896,58 @@ pub fn query_timestamp(env: Env) -> StdResult<u64> {
 /// Returns the vesting data for a specific vesting recipient using a [`VestingAccountResponse`] object.
 ///
 /// * **address** vesting recipient for which to return vesting data.
-pub fn query_vesting_account(deps: Deps, address: String) -> StdResult<VestingAccountResponse> {
+///
+/// * **asset_info** the vested asset. The main vesting token is used if not specified.
+pub fn query_vesting_account(
+    deps: Deps,
+    address: String,
+    asset_info: Option<AssetInfo>,
+) -> StdResult<VestingAccountResponse> {
     let address = deps.api.addr_validate(&address)?;
-    let info = VESTING_INFO.load(deps.storage, &address)?;
+    let info = load_queried_vesting_info(deps, &address, asset_info)?;
 
     Ok(VestingAccountResponse { address, info })
 }
 
+/// Loads vesting info of the account for the queried asset or returns a not found error.
+fn load_queried_vesting_info(
+    deps: Deps,
+    address: &Addr,
+    asset_info: Option<AssetInfo>,
+) -> StdResult<VestingInfo> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_info = resolve_vesting_asset(&config, asset_info)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    load_vesting_info(deps.storage, &config, address, &asset_info)?
+        .ok_or_else(|| StdError::not_found("astroport::vesting::VestingInfo"))
+}
+
+/// Returns the vesting data of a specific vesting recipient in all vested assets.
+///
+/// * **address** vesting recipient for which to return vesting data.
+pub fn query_vesting_account_assets(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<Vec<AssetVestingInfo>> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut assets = vec![];
+    for asset_info in config.vesting_assets() {
+        if let Some(info) = load_vesting_info(deps.storage, &config, &address, asset_info)? {
+            assets.push(AssetVestingInfo {
+                asset_info: asset_info.clone(),
+                available_amount: compute_available_amount(env.block.time.seconds(), &info)?,
+                info,
+            });
+        }
+    }
+
+    Ok(assets)
+}
+
 /// Returns a list of vesting schedules using a [`VestingAccountsResponse`] object.
 ///
 /// * **start_after** index from which to start reading vesting schedules.
@@ -487,15 +955,22 @@ pub fn query_vesting_account(deps: Deps, address: String) -> StdResult<VestingAc
 /// * **limit** amount of vesting schedules to return.
 ///
 /// * **order_by** whether results should be returned in an ascending or descending order.
+///
+/// * **asset_info** the vested asset. The main vesting token is used if not specified.
 pub fn query_vesting_accounts(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
+    asset_info: Option<AssetInfo>,
 ) -> StdResult<VestingAccountsResponse> {
     let start_after = addr_opt_validate(deps.api, &start_after)?;
+    let config = CONFIG.load(deps.storage)?;
+    let asset_info = resolve_vesting_asset(&config, asset_info)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
 
-    let vesting_infos = read_vesting_infos(deps, start_after, limit, order_by)?;
+    let vesting_infos =
+        read_vesting_infos(deps, &config, &asset_info, start_after, limit, order_by)?;
 
     let vesting_accounts: Vec<_> = vesting_infos
         .into_iter()
@@ -508,10 +983,17 @@ pub fn query_vesting_accounts(
 /// Returns the available amount of vested and yet to be claimed tokens for a specific vesting recipient.
 ///
 /// * **address** vesting recipient for which to return the available amount of tokens to claim.
-pub fn query_vesting_available_amount(deps: Deps, env: Env, address: String) -> StdResult<Uint128> {
+///
+/// * **asset_info** the vested asset. The main vesting token is used if not specified.
+pub fn query_vesting_available_amount(
+    deps: Deps,
+    env: Env,
+    address: String,
+    asset_info: Option<AssetInfo>,
+) -> StdResult<Uint128> {
     let address = deps.api.addr_validate(&address)?;
 
-    let info = VESTING_INFO.load(deps.storage, &address)?;
+    let info = load_queried_vesting_info(deps, &address, asset_info)?;
     let available_amount = compute_available_amount(env.block.time.seconds(), &info)?;
     Ok(available_amount)
 }
@@ -523,7 +1005,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     match contract_version.contract.as_ref() {
         "astroport-vesting" => match contract_version.version.as_ref() {
-            "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" => {}
+            "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" | "1.3.2" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -23,6 +23,9 @@ pub enum ContractError {
     #[error("Vesting schedule error on addr: {0}. Should satisfy: (start < end, end > current_time and start_amount < end_amount)")]
     VestingScheduleError(String),
 
+    #[error("Vesting curve error on addr: {0}. Cliff and step curves require end point, cliff must be between start and end, step duration must be positive and not exceed schedule duration")]
+    VestingCurveError(String),
+
     #[error(
         "Vesting schedule amount error. The total amount should be equal to the received amount."
     )]
@@ -40,6 +43,24 @@ pub enum ContractError {
     #[error("For account {0} number of schedules exceeds maximum limit")]
     ExceedSchedulesMaximumLimit(String),
 
+    #[error("Account {0} has no revocable vesting schedules")]
+    NothingToRevoke(String),
+
+    #[error("Asset {0} can't be vested")]
+    UnknownVestingAsset(String),
+
+    #[error("Asset {0} is already vested")]
+    DuplicatedVestingAsset(String),
+
+    #[error("Account {0} has no vesting schedules")]
+    NoVestingAccount(String),
+
+    #[error("Account {0} already has vesting schedules")]
+    AccountAlreadyExists(String),
+
+    #[error("Account {0} has no pending transfer")]
+    NoPendingTransfer(String),
+
     #[error("Failed to withdraw from active schedule: amount left {0}")]
     NotEnoughTokens(Uint128),
 }
@@ -3,7 +3,7 @@ use cosmwasm_schema::cw_serde;
 use astroport::asset::AssetInfo;
 use astroport::common::OwnershipProposal;
 use astroport::vesting::{OrderBy, VestingInfo};
-use cosmwasm_std::{Addr, Deps, StdResult};
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 
 /// This structure stores the main parameters for the generator vesting contract.
@@ -13,6 +13,24 @@ pub struct Config {
     pub owner: Addr,
     /// [`AssetInfo`] of the ASTRO token
     pub vesting_token: AssetInfo,
+    /// Whether vesting account transfers must be approved by the owner
+    #[serde(default)]
+    pub transfer_approval_required: bool,
+    /// Other assets which can be vested besides the main vesting token
+    #[serde(default)]
+    pub extra_vesting_assets: Vec<AssetInfo>,
+}
+
+impl Config {
+    /// Returns all assets which can be vested starting with the main vesting token.
+    pub fn vesting_assets(&self) -> impl Iterator<Item = &AssetInfo> {
+        std::iter::once(&self.vesting_token).chain(self.extra_vesting_assets.iter())
+    }
+
+    /// Returns true if the asset can be vested in the contract.
+    pub fn is_vesting_asset(&self, asset_info: &AssetInfo) -> bool {
+        self.vesting_assets().any(|asset| asset == asset_info)
+    }
 }
 
 /// Stores the contract config at the given key.
@@ -21,16 +39,69 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// The first key is the address of an account that's vesting, the second key is an object of type [`VestingInfo`].
 pub const VESTING_INFO: Map<&Addr, VestingInfo> = Map::new("vesting_info");
 
+/// Vesting accounts of the extra vesting assets.
+/// The key consists of an asset denom or contract address and an account address, the value is an object of type [`VestingInfo`].
+pub const ASSET_VESTING_INFO: Map<(&str, &Addr), VestingInfo> = Map::new("asset_vesting_info");
+
+/// Pending vesting account transfers waiting for the owner approval. key: current address, value: new address
+pub const PENDING_TRANSFERS: Map<&Addr, Addr> = Map::new("pending_transfers");
+
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Loads vesting info of the account for the specified asset.
+/// The main vesting token is kept in [`VESTING_INFO`] while extra assets are kept in [`ASSET_VESTING_INFO`].
+pub fn load_vesting_info(
+    storage: &dyn Storage,
+    config: &Config,
+    account: &Addr,
+    asset_info: &AssetInfo,
+) -> StdResult<Option<VestingInfo>> {
+    if *asset_info == config.vesting_token {
+        VESTING_INFO.may_load(storage, account)
+    } else {
+        ASSET_VESTING_INFO.may_load(storage, (&asset_info.to_string(), account))
+    }
+}
+
+/// Saves vesting info of the account for the specified asset.
+pub fn save_vesting_info(
+    storage: &mut dyn Storage,
+    config: &Config,
+    account: &Addr,
+    asset_info: &AssetInfo,
+    vesting_info: &VestingInfo,
+) -> StdResult<()> {
+    if *asset_info == config.vesting_token {
+        VESTING_INFO.save(storage, account, vesting_info)
+    } else {
+        ASSET_VESTING_INFO.save(storage, (&asset_info.to_string(), account), vesting_info)
+    }
+}
+
+/// Removes vesting info of the account for the specified asset.
+pub fn remove_vesting_info(
+    storage: &mut dyn Storage,
+    config: &Config,
+    account: &Addr,
+    asset_info: &AssetInfo,
+) {
+    if *asset_info == config.vesting_token {
+        VESTING_INFO.remove(storage, account)
+    } else {
+        ASSET_VESTING_INFO.remove(storage, (&asset_info.to_string(), account))
+    }
+}
+
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
 
 /// Returns an empty vector if it does not find data, otherwise returns a vector that
-/// contains objects of type [`VESTING_INFO`].
+/// contains objects of type [`VESTING_INFO`] or [`ASSET_VESTING_INFO`] for extra vesting assets.
 /// ## Params
 ///
+/// * **asset_info** the vested asset.
+///
 /// * **start_after** index from which to start reading vesting schedules.
 ///
 /// * **limit** amount of vesting schedules to read.
@@ -38,6 +109,8 @@ const DEFAULT_LIMIT: u32 = 10;
 /// * **order_by** whether results should be returned in an ascending or descending order.
 pub fn read_vesting_infos(
     deps: Deps,
+    config: &Config,
+    asset_info: &AssetInfo,
     start_after: Option<Addr>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
@@ -49,16 +122,21 @@ pub fn read_vesting_infos(
         _ => (None, start_after),
     };
 
-    let info: Vec<(Addr, VestingInfo)> = VESTING_INFO
-        .range(
-            deps.storage,
-            start,
-            end,
-            order_by.unwrap_or(OrderBy::Desc).into(),
-        )
-        .take(limit)
-        .filter_map(|v| v.ok())
-        .collect();
+    let order: Order = order_by.unwrap_or(OrderBy::Desc).into();
+    let info: Vec<(Addr, VestingInfo)> = if *asset_info == config.vesting_token {
+        VESTING_INFO
+            .range(deps.storage, start, end, order)
+            .take(limit)
+            .filter_map(|v| v.ok())
+            .collect()
+    } else {
+        ASSET_VESTING_INFO
+            .prefix(&asset_info.to_string())
+            .range(deps.storage, start, end, order)
+            .take(limit)
+            .filter_map(|v| v.ok())
+            .collect()
+    };
 
     Ok(info)
 }
@@ -72,6 +150,12 @@ mod testing {
         use cosmwasm_std::{testing::mock_dependencies, Uint128};
 
         let mut deps = mock_dependencies();
+        let config = Config {
+            owner: Addr::unchecked("owner"),
+            vesting_token: AssetInfo::native("astro"),
+            transfer_approval_required: false,
+            extra_vesting_assets: vec![AssetInfo::native("partner")],
+        };
 
         let vi_mock = VestingInfo {
             released_amount: Uint128::zero(),
@@ -88,6 +172,8 @@ mod testing {
 
         let res = read_vesting_infos(
             deps.as_ref(),
+            &config,
+            &config.vesting_token,
             Some(Addr::unchecked("address2")),
             None,
             Some(OrderBy::Asc),
@@ -103,6 +189,8 @@ mod testing {
 
         let res = read_vesting_infos(
             deps.as_ref(),
+            &config,
+            &config.vesting_token,
             Some(Addr::unchecked("address2")),
             Some(1),
             Some(OrderBy::Asc),
@@ -112,6 +200,8 @@ mod testing {
 
         let res = read_vesting_infos(
             deps.as_ref(),
+            &config,
+            &config.vesting_token,
             Some(Addr::unchecked("address3")),
             None,
             Some(OrderBy::Desc),
@@ -127,11 +217,38 @@ mod testing {
 
         let res = read_vesting_infos(
             deps.as_ref(),
+            &config,
+            &config.vesting_token,
             Some(Addr::unchecked("address3")),
             Some(1),
             Some(OrderBy::Desc),
         )
         .unwrap();
         assert_eq!(res, vec![(Addr::unchecked("address2"), vi_mock.clone())]);
+
+        // Extra vesting assets are read separately
+        let partner = AssetInfo::native("partner");
+        save_vesting_info(
+            &mut deps.storage,
+            &config,
+            &Addr::unchecked("address5"),
+            &partner,
+            &vi_mock,
+        )
+        .unwrap();
+
+        let res = read_vesting_infos(deps.as_ref(), &config, &partner, None, None, None).unwrap();
+        assert_eq!(res, vec![(Addr::unchecked("address5"), vi_mock.clone())]);
+
+        let res = read_vesting_infos(
+            deps.as_ref(),
+            &config,
+            &config.vesting_token,
+            Some(Addr::unchecked("address4")),
+            None,
+            Some(OrderBy::Asc),
+        )
+        .unwrap();
+        assert!(res.is_empty());
     }
 }
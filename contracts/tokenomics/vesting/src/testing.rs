@@ -24,6 +24,8 @@ fn proper_initialization() {
         ConfigResponse {
             owner: Addr::unchecked("owner"),
             vesting_token: token_asset_info(Addr::unchecked("astro_token")),
+            transfer_approval_required: false,
+            extra_vesting_assets: vec![],
         }
     );
 }
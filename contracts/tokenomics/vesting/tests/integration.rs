@@ -2,11 +2,13 @@
 
 use astroport::asset::{native_asset_info, token_asset_info};
 use astroport::querier::query_balance;
-use astroport::vesting::{QueryMsg, VestingAccountResponse, VestingAccountsResponse, VestingInfo};
+use astroport::vesting::{
+    AssetVestingInfo, QueryMsg, VestingAccountResponse, VestingAccountsResponse, VestingInfo,
+};
 use astroport::{
     token::InstantiateMsg as TokenInstantiateMsg,
     vesting::{
-        Cw20HookMsg, ExecuteMsg, InstantiateMsg, VestingAccount, VestingSchedule,
+        Cw20HookMsg, ExecuteMsg, InstantiateMsg, VestingAccount, VestingCurve, VestingSchedule,
         VestingSchedulePoint,
     },
 };
@@ -54,6 +56,8 @@ fn claim() {
                             time: current_time + 101,
                             amount: Uint128::new(200),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -64,6 +68,8 @@ fn claim() {
                             time: current_time + 110,
                             amount: Uint128::new(100),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -74,6 +80,8 @@ fn claim() {
                             time: current_time + 200,
                             amount: Uint128::new(100),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                 ],
             }],
@@ -105,6 +113,8 @@ fn claim() {
                             time: current_time + 101,
                             amount: Uint128::new(100),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -115,6 +125,8 @@ fn claim() {
                             time: current_time + 110,
                             amount: Uint128::new(100),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -125,6 +137,8 @@ fn claim() {
                             time: current_time + 200,
                             amount: Uint128::new(100),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                 ],
             }],
@@ -143,6 +157,7 @@ fn claim() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let user1_vesting_amount: Uint128 = app
@@ -170,6 +185,7 @@ fn claim() {
     let msg = ExecuteMsg::Claim {
         recipient: None,
         amount: None,
+        asset_info: None,
     };
     let _res = app
         .execute_contract(user1.clone(), vesting_instance.clone(), &msg, &[])
@@ -177,6 +193,7 @@ fn claim() {
 
     let msg = QueryMsg::VestingAccount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let vesting_res: VestingAccountResponse = app
@@ -206,6 +223,7 @@ fn claim() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     // Check user balance after claim
@@ -249,6 +267,8 @@ fn claim_native() {
                         time: current_time + 101,
                         amount: Uint128::new(200),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -274,6 +294,8 @@ fn claim_native() {
                         time: current_time + 101,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 },
                 VestingSchedule {
                     start_point: VestingSchedulePoint {
@@ -284,6 +306,8 @@ fn claim_native() {
                         time: current_time + 110,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 },
                 VestingSchedule {
                     start_point: VestingSchedulePoint {
@@ -294,6 +318,8 @@ fn claim_native() {
                         time: current_time + 200,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 },
             ],
         }],
@@ -314,6 +340,7 @@ fn claim_native() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let user1_vesting_amount: Uint128 = app
@@ -337,6 +364,7 @@ fn claim_native() {
     let msg = ExecuteMsg::Claim {
         recipient: None,
         amount: None,
+        asset_info: None,
     };
     app.execute_contract(user1.clone(), vesting_instance.clone(), &msg, &[])
         .unwrap();
@@ -347,6 +375,7 @@ fn claim_native() {
             vesting_instance.clone(),
             &QueryMsg::VestingAccount {
                 address: user1.to_string(),
+                asset_info: None,
             },
         )
         .unwrap();
@@ -372,6 +401,7 @@ fn claim_native() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     // Check user balance after claim
@@ -428,6 +458,8 @@ fn register_vesting_accounts() {
                         time: current_time + 100,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -454,6 +486,8 @@ fn register_vesting_accounts() {
                         time: current_time + 150,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -489,6 +523,8 @@ fn register_vesting_accounts() {
                     time: current_time + 150,
                     amount: Uint128::new(100),
                 }),
+                curve: VestingCurve::Linear {},
+                revocable: false,
             }],
         }],
     };
@@ -514,6 +550,7 @@ fn register_vesting_accounts() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let user1_vesting_amount: Uint128 = app
@@ -552,6 +589,8 @@ fn register_vesting_accounts() {
                         time: current_time + 150,
                         amount: Uint128::new(200),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -570,6 +609,7 @@ fn register_vesting_accounts() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user2.to_string(),
+        asset_info: None,
     };
 
     let user2_vesting_amount: Uint128 = app
@@ -611,6 +651,8 @@ fn register_vesting_accounts() {
                         time: current_time + 200,
                         amount: Uint128::new(10),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -629,6 +671,7 @@ fn register_vesting_accounts() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let vesting_res: Uint128 = app
@@ -653,6 +696,7 @@ fn register_vesting_accounts() {
     let msg = ExecuteMsg::Claim {
         recipient: None,
         amount: None,
+        asset_info: None,
     };
     let _res = app
         .execute_contract(user1.clone(), vesting_instance.clone(), &msg, &[])
@@ -660,6 +704,7 @@ fn register_vesting_accounts() {
 
     let msg = QueryMsg::VestingAccount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let vesting_res: VestingAccountResponse = app
@@ -690,7 +735,8 @@ fn register_vesting_accounts() {
                 &QueryMsg::VestingAccounts {
                     start_after: None,
                     limit: None,
-                    order_by: None
+                    order_by: None,
+                    asset_info: None,
                 }
             )
             .unwrap(),
@@ -708,6 +754,8 @@ fn register_vesting_accounts() {
                                 time: 1571797719,
                                 amount: Uint128::new(200),
                             }),
+                            curve: VestingCurve::Linear {},
+                            revocable: false,
                         }],
                         released_amount: Uint128::zero(),
                     }
@@ -725,6 +773,8 @@ fn register_vesting_accounts() {
                                     time: 1571797919,
                                     amount: Uint128::new(10),
                                 }),
+                                curve: VestingCurve::Linear {},
+                                revocable: false,
                             },
                             VestingSchedule {
                                 start_point: VestingSchedulePoint {
@@ -734,7 +784,9 @@ fn register_vesting_accounts() {
                                 end_point: Some(VestingSchedulePoint {
                                     time: 1571797569,
                                     amount: Uint128::new(100),
-                                })
+                                }),
+                                curve: VestingCurve::Linear {},
+                                revocable: false,
                             }
                         ],
                         released_amount: Uint128::new(110),
@@ -783,6 +835,8 @@ fn register_vesting_accounts_native() {
                         time: current_time + 150,
                         amount: Uint128::new(100),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -808,6 +862,8 @@ fn register_vesting_accounts_native() {
                     time: current_time + 150,
                     amount: Uint128::new(100),
                 }),
+                curve: VestingCurve::Linear {},
+                revocable: false,
             }],
         }],
     };
@@ -840,6 +896,7 @@ fn register_vesting_accounts_native() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let user1_vesting_amount: Uint128 = app
@@ -873,6 +930,8 @@ fn register_vesting_accounts_native() {
                     time: current_time + 150,
                     amount: Uint128::new(200),
                 }),
+                curve: VestingCurve::Linear {},
+                revocable: false,
             }],
         }],
     };
@@ -892,6 +951,7 @@ fn register_vesting_accounts_native() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user2.to_string(),
+        asset_info: None,
     };
 
     let user2_vesting_amount: Uint128 = app
@@ -928,6 +988,8 @@ fn register_vesting_accounts_native() {
                     time: current_time + 200,
                     amount: Uint128::new(10),
                 }),
+                curve: VestingCurve::Linear {},
+                revocable: false,
             }],
         }],
     };
@@ -947,6 +1009,7 @@ fn register_vesting_accounts_native() {
 
     let msg = QueryMsg::AvailableAmount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let vesting_res: Uint128 = app
@@ -967,6 +1030,7 @@ fn register_vesting_accounts_native() {
     let msg = ExecuteMsg::Claim {
         recipient: None,
         amount: None,
+        asset_info: None,
     };
     let _res = app
         .execute_contract(user1.clone(), vesting_instance.clone(), &msg, &[])
@@ -974,6 +1038,7 @@ fn register_vesting_accounts_native() {
 
     let msg = QueryMsg::VestingAccount {
         address: user1.to_string(),
+        asset_info: None,
     };
 
     let vesting_res: VestingAccountResponse = app
@@ -1027,6 +1092,8 @@ fn withdraw_from_active_schedule() {
                         time: end_time,
                         amount: Uint128::new(100_000_000_000000),
                     }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
                 }],
             }],
         })
@@ -1053,6 +1120,7 @@ fn withdraw_from_active_schedule() {
         account: user1.to_string(),
         recipient: Some(recipient.to_string()),
         withdraw_amount,
+        asset_info: None,
     };
     app.execute_contract(owner.clone(), vesting_instance.clone(), &withdraw_msg, &[])
         .unwrap();
@@ -1124,6 +1192,8 @@ fn withdraw_overlapping_schedules() {
                             time: end_time,
                             amount: Uint128::new(50_000_000_000000),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -1131,6 +1201,8 @@ fn withdraw_overlapping_schedules() {
                             amount: Uint128::new(50_000_000_000000),
                         },
                         end_point: None,
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                 ],
             }],
@@ -1157,6 +1229,7 @@ fn withdraw_overlapping_schedules() {
         account: user1.to_string(),
         recipient: Some(recipient.to_string()),
         withdraw_amount,
+        asset_info: None,
     };
 
     // Since we do not consider schedule without end point as active it is possible to withdraw from
@@ -1221,6 +1294,8 @@ fn withdraw_overlapping_schedules2() {
                             time: end_time,
                             amount: Uint128::new(50_000_000_000000),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                     VestingSchedule {
                         start_point: VestingSchedulePoint {
@@ -1231,6 +1306,8 @@ fn withdraw_overlapping_schedules2() {
                             time: end_time + 86400 * 7,
                             amount: Uint128::new(50_000_000_000000),
                         }),
+                        curve: VestingCurve::Linear {},
+                        revocable: false,
                     },
                 ],
             }],
@@ -1256,6 +1333,7 @@ fn withdraw_overlapping_schedules2() {
         account: user1.to_string(),
         recipient: Some(recipient.to_string()),
         withdraw_amount: Uint128::new(10_000_000_000000),
+        asset_info: None,
     };
     let err = app
         .execute_contract(owner.clone(), vesting_instance.clone(), &withdraw_msg, &[])
@@ -1292,6 +1370,7 @@ fn withdraw_overlapping_schedules2() {
         account: user1.to_string(),
         recipient: Some(recipient.to_string()),
         withdraw_amount,
+        asset_info: None,
     };
     app.execute_contract(owner.clone(), vesting_instance.clone(), &withdraw_msg, &[])
         .unwrap();
@@ -1322,6 +1401,837 @@ fn withdraw_overlapping_schedules2() {
     );
 }
 
+#[test]
+fn cliff_and_step_schedules() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let start_time = 1_700_000_000;
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time));
+
+    let cliff_schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: start_time,
+            amount: Uint128::zero(),
+        },
+        end_point: Some(VestingSchedulePoint {
+            time: start_time + 1000,
+            amount: Uint128::new(1000),
+        }),
+        curve: VestingCurve::Cliff {
+            cliff_time: start_time + 250,
+        },
+        revocable: false,
+    };
+    let step_schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: start_time,
+            amount: Uint128::new(100),
+        },
+        end_point: Some(VestingSchedulePoint {
+            time: start_time + 1000,
+            amount: Uint128::new(1100),
+        }),
+        curve: VestingCurve::Step { step_duration: 300 },
+        revocable: false,
+    };
+
+    let register_msg = |schedules: Vec<VestingSchedule>, amount: u128| Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: user1.to_string(),
+                schedules,
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(amount),
+    };
+
+    // Cliff and step curves require the end point
+    let mut invalid_schedule = cliff_schedule.clone();
+    invalid_schedule.end_point = None;
+    invalid_schedule.start_point.amount = Uint128::new(1000);
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            astro_token.clone(),
+            &register_msg(vec![invalid_schedule], 1000),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VestingCurveError(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // Cliff must be after the start time
+    let mut invalid_schedule = cliff_schedule.clone();
+    invalid_schedule.curve = VestingCurve::Cliff {
+        cliff_time: start_time,
+    };
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            astro_token.clone(),
+            &register_msg(vec![invalid_schedule], 1000),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VestingCurveError(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // Step duration must be positive
+    let mut invalid_schedule = step_schedule.clone();
+    invalid_schedule.curve = VestingCurve::Step { step_duration: 0 };
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            astro_token.clone(),
+            &register_msg(vec![invalid_schedule], 1100),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VestingCurveError(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &register_msg(vec![cliff_schedule, step_schedule], 2100),
+        &[],
+    )
+    .unwrap();
+
+    for (passed_time, expected) in [
+        (100, 100),
+        (250, 350),
+        (299, 399),
+        (300, 700),
+        (650, 1350),
+        (999, 1999),
+        (1000, 2100),
+    ] {
+        app.update_block(|b| b.time = Timestamp::from_seconds(start_time + passed_time));
+        let available: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                &vesting_instance,
+                &QueryMsg::AvailableAmount {
+                    address: user1.to_string(),
+                    asset_info: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(available.u128(), expected, "passed time {passed_time}");
+    }
+
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 2100);
+}
+
+#[test]
+fn revoke_vesting_schedules() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let treasury = Addr::unchecked("treasury");
+    let start_time = 1_700_000_000;
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time));
+
+    let fixed_schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: start_time,
+            amount: Uint128::new(500),
+        },
+        end_point: None,
+        curve: VestingCurve::Linear {},
+        revocable: false,
+    };
+    let msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: user1.to_string(),
+                schedules: vec![
+                    VestingSchedule {
+                        start_point: VestingSchedulePoint {
+                            time: start_time,
+                            amount: Uint128::zero(),
+                        },
+                        end_point: Some(VestingSchedulePoint {
+                            time: start_time + 1000,
+                            amount: Uint128::new(1000),
+                        }),
+                        curve: VestingCurve::Linear {},
+                        revocable: true,
+                    },
+                    fixed_schedule.clone(),
+                ],
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(1500),
+    };
+    app.execute_contract(owner.clone(), astro_token.clone(), &msg, &[])
+        .unwrap();
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 400));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 900);
+
+    let revoke_msg = ExecuteMsg::RevokeVestingSchedules {
+        account: user1.to_string(),
+        recipient: Some(treasury.to_string()),
+    };
+
+    let err = app
+        .execute_contract(user1.clone(), vesting_instance.clone(), &revoke_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(owner.clone(), vesting_instance.clone(), &revoke_msg, &[])
+        .unwrap();
+
+    // Unvested tokens are sent to the treasury
+    check_token_balance(&mut app, &astro_token, &treasury, 600);
+
+    // Vested tokens stay with the user
+    let res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: user1.to_string(),
+                asset_info: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.info,
+        VestingInfo {
+            schedules: vec![
+                VestingSchedule {
+                    start_point: VestingSchedulePoint {
+                        time: start_time + 400,
+                        amount: Uint128::new(400),
+                    },
+                    end_point: None,
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
+                },
+                fixed_schedule,
+            ],
+            released_amount: Uint128::new(900),
+        }
+    );
+
+    let err = app
+        .execute_contract(owner.clone(), vesting_instance.clone(), &revoke_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NothingToRevoke(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // Nothing is unlocked after revocation
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 1000));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 900);
+}
+
+#[test]
+fn schedules_limit() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: app.block_info().time.seconds(),
+            amount: Uint128::new(100),
+        },
+        end_point: None,
+        curve: VestingCurve::Linear {},
+        revocable: false,
+    };
+    let register_msg = |schedules_num: usize| Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: USER1.to_string(),
+                schedules: vec![schedule.clone(); schedules_num],
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(100 * schedules_num as u128),
+    };
+
+    // The limit is applied to the schedules registered at once
+    let err = app
+        .execute_contract(owner.clone(), astro_token.clone(), &register_msg(9), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExceedSchedulesMaximumLimit(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // As well as to the total number of account schedules
+    app.execute_contract(owner.clone(), astro_token.clone(), &register_msg(6), &[])
+        .unwrap();
+    let err = app
+        .execute_contract(owner.clone(), astro_token.clone(), &register_msg(3), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExceedSchedulesMaximumLimit(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+    app.execute_contract(owner.clone(), astro_token.clone(), &register_msg(2), &[])
+        .unwrap();
+    let err = app
+        .execute_contract(owner.clone(), astro_token.clone(), &register_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExceedSchedulesMaximumLimit(USER1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // Fully claimed schedules are pruned on registration and don't count towards the limit
+    let user1 = Addr::unchecked(USER1);
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 800);
+    app.execute_contract(owner.clone(), astro_token.clone(), &register_msg(8), &[])
+        .unwrap();
+
+    let vesting_res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: USER1.to_string(),
+                asset_info: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(vesting_res.info.schedules.len(), 8);
+    assert_eq!(vesting_res.info.released_amount, Uint128::zero());
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 1600);
+}
+
+#[test]
+fn transfer_vesting_account() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let multisig = Addr::unchecked("multisig");
+    let start_time = 1_700_000_000;
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time));
+
+    let register_msg = |address: &Addr| Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: address.to_string(),
+                schedules: vec![VestingSchedule {
+                    start_point: VestingSchedulePoint {
+                        time: start_time,
+                        amount: Uint128::zero(),
+                    },
+                    end_point: Some(VestingSchedulePoint {
+                        time: start_time + 1000,
+                        amount: Uint128::new(1000),
+                    }),
+                    curve: VestingCurve::Linear {},
+                    revocable: false,
+                }],
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(1000),
+    };
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &register_msg(&user1),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &register_msg(&user2),
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 300));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 300);
+
+    // Only accounts with schedules can be transferred
+    let err = app
+        .execute_contract(
+            multisig.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::TransferVestingAccount {
+                new_address: USER1.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoVestingAccount(multisig.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // Can't merge into an existing vesting account
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::TransferVestingAccount {
+                new_address: USER2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AccountAlreadyExists(USER2.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::TransferVestingAccount {
+            new_address: multisig.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Schedules and released amount were moved
+    let res: VestingAccountsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccounts {
+                start_after: None,
+                limit: None,
+                order_by: None,
+                asset_info: None,
+            },
+        )
+        .unwrap();
+    let addresses: Vec<_> = res
+        .vesting_accounts
+        .iter()
+        .map(|acc| acc.address.to_string())
+        .collect();
+    assert_eq!(addresses, vec![USER2.to_string(), multisig.to_string()]);
+    assert_eq!(res.vesting_accounts[1].info.released_amount.u128(), 300);
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 500));
+    claim_and_check(&mut app, &multisig, &vesting_instance, &astro_token, 200);
+
+    // Enable owner approval
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                transfer_approval_required: Some(true),
+                add_vesting_assets: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::UpdateConfig {
+            transfer_approval_required: Some(true),
+            add_vesting_assets: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        user2.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::TransferVestingAccount {
+            new_address: user1.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Transfer is pending till owner approval
+    let pending: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::PendingTransfer {
+                address: user2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending, Some(user1.clone()));
+    claim_and_check(&mut app, &user2, &vesting_instance, &astro_token, 500);
+
+    // The sender can withdraw the request while it is pending
+    app.execute_contract(
+        user2.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::CancelVestingAccountTransfer {},
+        &[],
+    )
+    .unwrap();
+    let pending: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::PendingTransfer {
+                address: user2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending, None);
+
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::CancelVestingAccountTransfer {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoPendingTransfer(user2.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user2.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::TransferVestingAccount {
+            new_address: user1.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::ApproveVestingAccountTransfer {
+                account: user2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::ApproveVestingAccountTransfer {
+            account: user2.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let pending: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::PendingTransfer {
+                address: user2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending, None);
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::ApproveVestingAccountTransfer {
+                account: user2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoPendingTransfer(user2.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // User1 keeps 300 tokens claimed before the first transfer and receives the rest of user2 schedule
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 1000));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 800);
+}
+
+#[test]
+fn multi_asset_vesting() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let partner_token = instantiate_token(&mut app, token_code_id, "Partner", None);
+    mint_tokens(&mut app, &partner_token, &owner, 1000);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let start_time = 1_700_000_000;
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time));
+
+    let vesting_accounts = |start_amount: u128, end_amount: Option<u128>| {
+        vec![VestingAccount {
+            address: user1.to_string(),
+            schedules: vec![VestingSchedule {
+                start_point: VestingSchedulePoint {
+                    time: start_time,
+                    amount: Uint128::new(start_amount),
+                },
+                end_point: end_amount.map(|amount| VestingSchedulePoint {
+                    time: start_time + 1000,
+                    amount: Uint128::new(amount),
+                }),
+                curve: VestingCurve::Linear {},
+                revocable: false,
+            }],
+        }]
+    };
+    let native_msg = ExecuteMsg::RegisterVestingAccounts {
+        vesting_accounts: vesting_accounts(0, Some(1000)),
+    };
+    let partner_msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vesting_accounts(500, None),
+        })
+        .unwrap(),
+        amount: Uint128::new(500),
+    };
+
+    // Assets must be added by owner before vesting
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &native_msg,
+            &coins(1000, IBC_ASTRO),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let err = app
+        .execute_contract(owner.clone(), partner_token.clone(), &partner_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                transfer_approval_required: None,
+                add_vesting_assets: Some(vec![token_asset_info(astro_token.clone())]),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DuplicatedVestingAsset(astro_token.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::UpdateConfig {
+            transfer_approval_required: None,
+            add_vesting_assets: Some(vec![
+                native_asset_info(IBC_ASTRO.to_string()),
+                token_asset_info(partner_token.clone()),
+            ]),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &native_msg,
+        &coins(1000, IBC_ASTRO),
+    )
+    .unwrap();
+    app.execute_contract(owner.clone(), partner_token.clone(), &partner_msg, &[])
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: vesting_instance.to_string(),
+            msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+                vesting_accounts: vesting_accounts(100, None),
+            })
+            .unwrap(),
+            amount: Uint128::new(100),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 500));
+
+    let res: Vec<AssetVestingInfo> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccountAssets {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    let available: Vec<_> = res
+        .iter()
+        .map(|asset| (asset.asset_info.clone(), asset.available_amount.u128()))
+        .collect();
+    assert_eq!(
+        available,
+        vec![
+            (token_asset_info(astro_token.clone()), 100),
+            (native_asset_info(IBC_ASTRO.to_string()), 500),
+            (token_asset_info(partner_token.clone()), 500),
+        ]
+    );
+
+    // Main vesting token is claimed by default
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 100);
+
+    let claim_msg = |asset_info| ExecuteMsg::Claim {
+        recipient: None,
+        amount: None,
+        asset_info: Some(asset_info),
+    };
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &claim_msg(native_asset_info("random-coin".to_string())),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnknownVestingAsset("random-coin".to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &claim_msg(native_asset_info(IBC_ASTRO.to_string())),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &claim_msg(token_asset_info(partner_token.clone())),
+        &[],
+    )
+    .unwrap();
+    let balance = query_balance(&app.wrap(), &user1, IBC_ASTRO).unwrap();
+    assert_eq!(balance.u128(), 500);
+    check_token_balance(&mut app, &partner_token, &user1, 500);
+
+    // All assets are transferred at once
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::TransferVestingAccount {
+            new_address: user2.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    let res: Vec<AssetVestingInfo> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccountAssets {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(res.is_empty());
+
+    // Extra assets are queryable per account and in the accounts list
+    let res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: user2.to_string(),
+                asset_info: Some(token_asset_info(partner_token.clone())),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.address, user2);
+    assert_eq!(res.info.released_amount.u128(), 500);
+
+    let res: VestingAccountsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccounts {
+                start_after: None,
+                limit: None,
+                order_by: None,
+                asset_info: Some(native_asset_info(IBC_ASTRO.to_string())),
+            },
+        )
+        .unwrap();
+    let accounts: Vec<_> = res
+        .vesting_accounts
+        .into_iter()
+        .map(|account| account.address)
+        .collect();
+    assert_eq!(accounts, vec![user2.clone()]);
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(start_time + 1000));
+    app.execute_contract(
+        user2.clone(),
+        vesting_instance.clone(),
+        &claim_msg(native_asset_info(IBC_ASTRO.to_string())),
+        &[],
+    )
+    .unwrap();
+    let balance = query_balance(&app.wrap(), &user2, IBC_ASTRO).unwrap();
+    assert_eq!(balance.u128(), 500);
+}
+
 fn mock_app(owner: &Addr) -> App {
     App::new(|app, _, storage| {
         app.bank
@@ -1489,6 +2399,7 @@ fn claim_and_check(
         &ExecuteMsg::Claim {
             recipient: None,
             amount: None,
+            asset_info: None,
         },
         &[],
     )
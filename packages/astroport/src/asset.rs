@@ -568,6 +568,10 @@ pub struct PairInfo {
     pub liquidity_token: Addr,
     /// The pool type (xyk, stableswap etc) available in [`PairType`]
     pub pair_type: PairType,
+    /// The key of the pool in the pool manager. Only set for pools hosted by the pool manager,
+    /// `contract_addr` is the pool manager address for them
+    #[serde(default)]
+    pub pool_key: Option<String>,
 }
 
 impl PairInfo {
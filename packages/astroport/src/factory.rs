@@ -1,4 +1,4 @@
-use crate::asset::{AssetInfo, PairInfo};
+use crate::asset::{Asset, AssetInfo, PairInfo};
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Binary};
@@ -22,6 +22,12 @@ pub struct Config {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins with their precision
     pub coin_registry_address: Addr,
+    /// Pool manager contract allowed to register the pools it hosts
+    pub pool_manager: Option<Addr>,
+    /// Fee charged for creating a pair. It is sent to the fee address
+    pub pool_creation_fee: Option<Asset>,
+    /// Listing policy contract queried before a pair is created
+    pub listing_policy: Option<Addr>,
 }
 
 /// This enum describes available pair types.
@@ -71,6 +77,23 @@ pub struct PairConfig {
     /// Setting this to true means that pairs of this type will not be able
     /// to get an ASTRO generator
     pub is_generator_disabled: bool,
+    /// Overrides the factory pool creation fee for this pair type.
+    /// A zero amount makes the creation free
+    #[serde(default)]
+    pub pool_creation_fee: Option<Asset>,
+    /// Overrides the factory listing policy for this pair type.
+    /// [`ListingPolicy::Disabled`] lets pairs of this type skip the factory listing policy
+    #[serde(default)]
+    pub listing_policy: Option<ListingPolicy>,
+}
+
+/// This enum describes the listing policy applied before a pair is created.
+#[cw_serde]
+pub enum ListingPolicy {
+    /// Pairs are created without any listing checks
+    Disabled {},
+    /// The contract is queried with [`ListingPolicyQueryMsg::CanList`] before a pair is created
+    Contract { address: Addr },
 }
 
 impl PairConfig {
@@ -114,6 +137,12 @@ pub enum ExecuteMsg {
         whitelist_code_id: Option<u64>,
         /// The address of the contract that contains the coins and their accuracy
         coin_registry_address: Option<String>,
+        /// Pool manager contract allowed to register the pools it hosts
+        pool_manager: Option<String>,
+        /// Fee charged for creating a pair. A zero amount disables the fee
+        pool_creation_fee: Option<Asset>,
+        /// Listing policy queried before a pair is created. [`ListingPolicy::Disabled`] removes it
+        listing_policy: Option<ListingPolicy>,
     },
     /// UpdatePairConfig updates the config for a pair type.
     UpdatePairConfig {
@@ -129,6 +158,37 @@ pub enum ExecuteMsg {
         /// Optional binary serialised parameters for custom pool types
         init_params: Option<Binary>,
     },
+    /// RegisterManagerPool registers a pool hosted by the pool manager.
+    /// Can only be called by the pool manager set in the config
+    RegisterManagerPool {
+        /// The assets traded in the pool
+        asset_infos: Vec<AssetInfo>,
+        /// The LP token of the pool
+        liquidity_token: String,
+        /// The pool type
+        pair_type: PairType,
+        /// The key of the pool in the pool manager
+        pool_key: String,
+    },
+    /// ApproveManagerPool checks the listing policy and charges the pool creation fee for a pool
+    /// the pool manager is about to create. Native fees must be attached, cw20 fees are transferred from the payer.
+    /// Can only be called by the pool manager set in the config
+    ApproveManagerPool {
+        /// The assets traded in the pool
+        asset_infos: Vec<AssetInfo>,
+        /// The pool type
+        pair_type: PairType,
+        /// The account creating the pool
+        payer: String,
+    },
+    /// IndexPairs adds a page of the pairs created before the pairs by asset and by pair type indexes
+    /// were introduced to these indexes. Only the owner can execute this
+    IndexPairs {
+        /// The pair item to start indexing after
+        start_after: Option<Vec<AssetInfo>>,
+        /// The number of pairs to index
+        limit: Option<u32>,
+    },
     /// Deregister removes a previously created pair.
     Deregister {
         /// The assets for which we deregister a pool
@@ -169,6 +229,30 @@ pub enum QueryMsg {
         /// The number of pairs to read and return. It is an [`Option`] type.
         limit: Option<u32>,
     },
+    /// PairsByAsset returns the pairs containing a specific asset
+    #[returns(PairsResponse)]
+    PairsByAsset {
+        /// The asset the pairs contain
+        asset_info: AssetInfo,
+        /// The pair item to start reading from
+        start_after: Option<Vec<AssetInfo>>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+        /// Whether to skip the pairs whose pair type is disabled
+        exclude_disabled: Option<bool>,
+    },
+    /// PairsByType returns the pairs of a specific pair type
+    #[returns(PairsResponse)]
+    PairsByType {
+        /// The pair type
+        pair_type: PairType,
+        /// The pair item to start reading from
+        start_after: Option<Vec<AssetInfo>>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+        /// Whether to return no pairs if the pair type is disabled
+        exclude_disabled: Option<bool>,
+    },
     /// FeeInfo returns fee parameters for a specific pair. The response is returned using a [`FeeInfoResponse`] structure
     #[returns(FeeInfoResponse)]
     FeeInfo {
@@ -197,6 +281,12 @@ pub struct ConfigResponse {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins and their accuracy
     pub coin_registry_address: Addr,
+    /// Pool manager contract allowed to register the pools it hosts
+    pub pool_manager: Option<Addr>,
+    /// Fee charged for creating a pair
+    pub pool_creation_fee: Option<Asset>,
+    /// Listing policy contract queried before a pair is created
+    pub listing_policy: Option<Addr>,
 }
 
 /// This structure stores the parameters used in a migration message.
@@ -205,6 +295,20 @@ pub struct MigrateMsg {
     pub params: Binary,
 }
 
+/// This structure describes the query messages a listing policy contract must implement.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum ListingPolicyQueryMsg {
+    /// Returns whether a pair with the specified assets and type can be created
+    #[returns(bool)]
+    CanList {
+        /// The assets traded in the pair
+        asset_infos: Vec<AssetInfo>,
+        /// The pair type
+        pair_type: PairType,
+    },
+}
+
 /// A custom struct for each query response that returns an array of objects of type [`PairInfo`].
 #[cw_serde]
 pub struct PairsResponse {
@@ -21,6 +21,20 @@ pub const MAX_PAGE_LIMIT: u8 = 50;
 /// Max number of orphaned rewards to claim at a time
 pub const MAX_ORPHANED_REWARD_LIMIT: u8 = 10;
 
+/// Default number of claim log entries kept per user
+pub const DEFAULT_CLAIM_LOG_LIMIT: u32 = 100;
+/// Maximum number of claim log entries kept per user
+pub const MAX_CLAIM_LOG_LIMIT: u32 = 1000;
+
+/// Gauge vote weights are specified in basis points. Sum of all user weights must not exceed this value.
+pub const MAX_VOTE_BPS: u16 = 10000;
+
+/// Returns the start of the epoch which contains the given timestamp.
+/// Timestamps before [`EPOCHS_START`] belong to the very first epoch.
+pub fn epoch_start(ts: u64) -> u64 {
+    EPOCHS_START + ts.saturating_sub(EPOCHS_START) / EPOCH_LENGTH * EPOCH_LENGTH
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
@@ -101,6 +115,18 @@ pub enum ExecuteMsg {
         /// The LP token cw20 address or token factory denom
         lp_tokens: Vec<String>,
     },
+    /// Claim rewards from the specified position, swap them into pool assets through the router
+    /// (or through the pool manager for the pools it hosts), provide liquidity and deposit minted LP tokens
+    /// back on behalf of the user.
+    /// Rewards which are not pool assets must have a direct pair with one of the pool assets.
+    ClaimAndCompound {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+        /// The minimum amount of LP tokens to be minted and deposited
+        min_lp_out: Uint128,
+        /// The maximum spread of every swap. The pool default is used if not set
+        max_spread: Option<Decimal>,
+    },
     /// Receives a message of type [`Cw20ReceiveMsg`]. Handles cw20 LP token deposits.
     Receive(Cw20ReceiveMsg),
     /// Stake LP tokens in the Generator. LP tokens staked on behalf of recipient if recipient is set.
@@ -166,6 +192,13 @@ pub enum ExecuteMsg {
         guardian: Option<String>,
         /// New incentivization fee info
         incentivization_fee_info: Option<IncentivizationFeeInfo>,
+        /// The new router contract address which is used to compound rewards
+        router: Option<String>,
+        /// The new pool manager address. Must match the pool manager set in the factory
+        pool_manager: Option<String>,
+        /// The new maximum number of claim log entries kept per user.
+        /// Must be within 1..=[`MAX_CLAIM_LOG_LIMIT`]
+        claim_log_limit: Option<u32>,
     },
     /// Add or remove token to the block list.
     /// Only owner or guardian can execute this.
@@ -199,6 +232,20 @@ pub enum ExecuteMsg {
     /// Claims contract ownership
     /// Only the newly proposed owner can execute this
     ClaimOwnership {},
+    /// Enable, update or disable (if None) gauge voting.
+    /// Only the owner can execute this.
+    UpdateGaugeConfig { gauge_config: Option<GaugeConfig> },
+    /// Vote for pools which should receive ASTRO emissions after the current epoch ends.
+    /// Weights are specified in basis points and their sum must not exceed [`MAX_VOTE_BPS`].
+    /// Voting power is the voting token balance snapshotted at the first vote in the current epoch.
+    /// Voting again within the same epoch overrides the previous vote.
+    Vote {
+        /// The list of tuples (LP token cw20 address or token factory denom, vote weight in bps)
+        votes: Vec<(String, u16)>,
+    },
+    /// Apply votes casted during the last finished epoch to pool allocation points.
+    /// Anyone can execute this once per epoch.
+    TuneGauges {},
 }
 
 #[cw_serde]
@@ -256,6 +303,37 @@ pub enum QueryMsg {
         /// Limit number of returned schedules.
         limit: Option<u8>,
     },
+    /// Returns gauge voting config if gauge voting is enabled
+    #[returns(Option<GaugeConfig>)]
+    GaugeConfig {},
+    /// Returns user vote in the specified epoch. Defaults to the current epoch.
+    #[returns(Option<UserVoteInfo>)]
+    UserVote { user: String, epoch: Option<u64> },
+    /// Returns the list of tuples (LP token, votes) for the specified epoch sorted by votes.
+    /// Defaults to the current epoch.
+    #[returns(Vec<(String, Uint128)>)]
+    GaugeTally { epoch: Option<u64> },
+    /// Returns paginated history of applied gauge tunings
+    #[returns(Vec<TuneInfo>)]
+    GaugeHistory {
+        /// Start after specified epoch start timestamp
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Returns total rewards claimed by the user over all positions
+    #[returns(Vec<Asset>)]
+    UserClaimedRewards { user: String },
+    /// Returns paginated user claim log sorted from the oldest to the newest entry
+    #[returns(Vec<ClaimLogEntry>)]
+    ClaimHistory {
+        user: String,
+        /// Start after specified claim log entry id
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Returns total rewards distributed to stakers of the specified pool
+    #[returns(Vec<Asset>)]
+    PoolDistributedRewards { lp_token: String },
 }
 
 #[cw_serde]
@@ -266,6 +344,48 @@ pub struct IncentivizationFeeInfo {
     pub fee: Coin,
 }
 
+#[cw_serde]
+pub struct GaugeConfig {
+    /// Snapshot-enabled cw20 token (xASTRO) which balances define voting power
+    pub voting_token: Addr,
+    /// Maximum number of pools which receive ASTRO emissions after gauge tuning
+    pub max_active_pools: u8,
+}
+
+#[cw_serde]
+pub struct UserVoteInfo {
+    /// Epoch start timestamp
+    pub epoch: u64,
+    /// Voting power used in this epoch
+    pub voting_power: Uint128,
+    /// The list of tuples (LP token, vote weight in bps)
+    pub votes: Vec<(String, u16)>,
+}
+
+#[cw_serde]
+pub struct TuneInfo {
+    /// Start timestamp of the epoch which votes were applied
+    pub epoch: u64,
+    /// Time when votes were applied
+    pub tune_ts: u64,
+    /// The list of pools with their new allocation points
+    pub pools: Vec<(String, Uint128)>,
+}
+
+#[cw_serde]
+pub struct ClaimLogEntry {
+    /// Sequential entry id within the user claim log
+    pub id: u64,
+    /// The LP token cw20 address or token factory denom
+    pub lp_token: String,
+    /// Claimed rewards. ASTRO emissions are included as well
+    pub rewards: Vec<Asset>,
+    /// Block height when rewards were claimed
+    pub block_height: u64,
+    /// Block time when rewards were claimed
+    pub timestamp: u64,
+}
+
 #[cw_serde]
 pub struct Config {
     /// Address allowed to change contract parameters
@@ -287,6 +407,14 @@ pub struct Config {
     /// Defines native fee along with fee receiver.
     /// Fee is paid on adding NEW external reward to a specific pool
     pub incentivization_fee_info: Option<IncentivizationFeeInfo>,
+    /// The router contract which is used to swap rewards in ClaimAndCompound
+    pub router: Option<Addr>,
+    /// The pool manager address cached from the factory config.
+    /// Pools hosted by the pool manager are looked up through it
+    pub pool_manager: Option<Addr>,
+    /// The maximum number of claim log entries kept per user. Older entries are pruned.
+    /// Claimed and distributed totals are not affected by this limit.
+    pub claim_log_limit: u32,
 }
 
 #[cw_serde]
@@ -472,4 +600,19 @@ mod tests {
         );
         assert_eq!(schedule.rps, Decimal::one());
     }
+
+    #[test]
+    fn test_epoch_start() {
+        assert_eq!(epoch_start(0), EPOCHS_START);
+        assert_eq!(epoch_start(EPOCHS_START), EPOCHS_START);
+        assert_eq!(epoch_start(EPOCHS_START + EPOCH_LENGTH - 1), EPOCHS_START);
+        assert_eq!(
+            epoch_start(EPOCHS_START + EPOCH_LENGTH),
+            EPOCHS_START + EPOCH_LENGTH
+        );
+        assert_eq!(
+            epoch_start(EPOCHS_START + 10 * EPOCH_LENGTH + 3 * 86400),
+            EPOCHS_START + 10 * EPOCH_LENGTH
+        );
+    }
 }
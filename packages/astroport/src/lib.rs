@@ -18,12 +18,15 @@ pub mod pair;
 pub mod pair_bonded;
 pub mod pair_concentrated;
 pub mod pair_concentrated_inj;
+pub mod pair_tricrypto;
+pub mod pool_manager;
 pub mod querier;
 pub mod restricted_vector;
 pub mod router;
 pub mod shared_multisig;
 pub mod staking;
 pub mod token;
+pub mod token_factory;
 pub mod vesting;
 pub mod xastro_outpost_token;
 pub mod xastro_token;
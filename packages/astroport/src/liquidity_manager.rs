@@ -17,6 +17,15 @@ pub enum ExecuteMsg {
         pair_msg: PairExecuteMsg,
         min_lp_to_receive: Option<Uint128>,
     },
+    /// Provides liquidity with a single asset. For XYK pools the optimal part of the offer asset
+    /// is swapped into the other pool asset first; other pools accept a single-sided provide directly.
+    ZapIn {
+        pair_addr: String,
+        offer_asset: Asset,
+        min_lp_to_receive: Option<Uint128>,
+        auto_stake: Option<bool>,
+        receiver: Option<String>,
+    },
     Receive(Cw20ReceiveMsg),
 }
 
@@ -28,6 +37,11 @@ pub enum Cw20HookMsg {
         #[serde(default)]
         min_assets_to_receive: Vec<Asset>,
     },
+    /// Withdraws liquidity and swaps all withdrawn assets into `ask_asset_info`
+    ZapOut {
+        ask_asset_info: AssetInfo,
+        min_out: Option<Uint128>,
+    },
 }
 
 #[cw_serde]
@@ -42,6 +56,19 @@ pub enum QueryMsg {
     SimulateWithdraw {
         pair_addr: String,
         lp_tokens: Uint128,
+        /// Assets of the pool. Required for pools hosted by the pool manager
+        asset_infos: Option<Vec<AssetInfo>>,
+    },
+    #[returns(Uint128)]
+    SimulateZapIn {
+        pair_addr: String,
+        offer_asset: Asset,
+    },
+    #[returns(Uint128)]
+    SimulateZapOut {
+        pair_addr: String,
+        lp_tokens: Uint128,
+        ask_asset_info: AssetInfo,
     },
 }
 
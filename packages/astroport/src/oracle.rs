@@ -1,6 +1,9 @@
 use crate::asset::AssetInfo;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Uint128, Uint256};
+use cosmwasm_std::{Addr, Decimal, Uint128, Uint256};
+
+/// Default time between two consecutive TWAP updates.
+pub const DEFAULT_PERIOD: u64 = 86400;
 
 /// This structure stores general parameters for the contract.
 #[cw_serde]
@@ -9,13 +12,61 @@ pub struct InstantiateMsg {
     pub factory_contract: String,
     /// The assets that have a pool for which this contract provides price feeds
     pub asset_infos: Vec<AssetInfo>,
+    /// Time between two consecutive TWAP updates for this pool. Defaults to one day
+    pub period: Option<u64>,
+    /// The asset in which [`QueryMsg::ConsultQuote`] returns prices (e.g. USD stablecoin)
+    pub quote_asset: Option<AssetInfo>,
+    /// Maximum age of a TWAP in seconds which is allowed to be used in queries
+    pub max_staleness: Option<u64>,
+    /// Maximum relative change of a TWAP during one update. Pairs exceeding it are frozen
+    pub max_deviation: Option<Decimal>,
 }
 
 /// This structure describes the execute functions available in the contract.
 #[cw_serde]
 pub enum ExecuteMsg {
-    /// Update/accumulate prices
-    Update {},
+    /// Update/accumulate prices for the tracked pairs whose period has elapsed.
+    /// Pairs are processed in pages sorted by the pair address
+    Update {
+        /// Start after the specified pair address
+        start_after: Option<String>,
+        /// The maximum number of pairs examined. Defaults to the maximum
+        limit: Option<u32>,
+    },
+    /// Starts tracking a new pair. Only the owner can execute this
+    AddPair {
+        /// The assets of the pool
+        asset_infos: Vec<AssetInfo>,
+        /// Time between two consecutive TWAP updates for this pool. Defaults to one day
+        period: Option<u64>,
+    },
+    /// Stops tracking a pair. Only the owner can execute this
+    RemovePair {
+        /// The assets of the pool
+        asset_infos: Vec<AssetInfo>,
+    },
+    /// Changes time between two consecutive TWAP updates for a pair. Only the owner can execute this
+    UpdatePairPeriod {
+        /// The assets of the pool
+        asset_infos: Vec<AssetInfo>,
+        /// New period in seconds
+        period: u64,
+    },
+    /// Accepts the TWAP which exceeded the maximum deviation and unfreezes the pair.
+    /// Only the owner can execute this
+    UnfreezePair {
+        /// The assets of the pool
+        asset_infos: Vec<AssetInfo>,
+    },
+    /// Updates the quote asset and the price guards. Only the owner can execute this
+    UpdateConfig {
+        /// New quote asset
+        quote_asset: Option<AssetInfo>,
+        /// New maximum age of a TWAP in seconds. Zero disables staleness checks
+        max_staleness: Option<u64>,
+        /// New maximum relative change of a TWAP during one update. Zero disables deviation checks
+        max_deviation: Option<Decimal>,
+    },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -23,13 +74,106 @@ pub enum ExecuteMsg {
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     /// Calculates a new TWAP with updated precision
-    #[returns(Vec<(AssetInfo, Uint256)>)]
+    #[returns(Vec<ConsultResponse>)]
     Consult {
         /// The asset for which to compute a new TWAP value
         token: AssetInfo,
         /// The amount of tokens for which to compute the token price
         amount: Uint128,
     },
+    /// Converts a token amount through the given chain of tracked pairs using their TWAPs.
+    /// Fails if any pair of the chain is frozen or stale
+    #[returns(ConsultPathResponse)]
+    ConsultPath {
+        /// The asset to convert
+        token: AssetInfo,
+        /// The amount of tokens to convert
+        amount: Uint128,
+        /// The assets to convert through. The last one is the asset of the returned amount
+        path: Vec<AssetInfo>,
+    },
+    /// Converts a token amount into the quote asset through the given intermediate assets.
+    /// Fails if any pair of the chain is frozen or stale
+    #[returns(ConsultPathResponse)]
+    ConsultQuote {
+        /// The asset to convert
+        token: AssetInfo,
+        /// The amount of tokens to convert
+        amount: Uint128,
+        /// Intermediate assets between the token and the quote asset
+        path: Vec<AssetInfo>,
+    },
+    /// Returns the list of tracked pairs
+    #[returns(Vec<OraclePairResponse>)]
+    Pairs {},
+    /// Returns the oracle configuration
+    #[returns(ConfigResponse)]
+    Config {},
+}
+
+/// This structure describes a token amount converted using a pair TWAP.
+#[cw_serde]
+pub struct ConsultResponse {
+    /// The asset of the converted amount
+    pub asset_info: AssetInfo,
+    /// The converted amount
+    pub amount: Uint256,
+    /// The last time the TWAP was updated
+    pub last_update: u64,
+    /// Whether the pair is frozen because its price moved more than the maximum deviation
+    pub frozen: bool,
+}
+
+/// This structure describes a token amount converted through a chain of pair TWAPs.
+#[cw_serde]
+pub struct ConsultPathResponse {
+    /// The converted amount
+    pub amount: Uint256,
+    /// The oldest TWAP update time among the pairs of the chain
+    pub last_update: u64,
+    /// Seconds elapsed since the oldest TWAP update
+    pub staleness: u64,
+}
+
+/// This structure describes the oracle configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The factory contract address
+    pub factory: Addr,
+    /// The asset in which [`QueryMsg::ConsultQuote`] returns prices
+    pub quote_asset: Option<AssetInfo>,
+    /// Maximum age of a TWAP in seconds which is allowed to be used in queries
+    pub max_staleness: Option<u64>,
+    /// Maximum relative change of a TWAP during one update
+    pub max_deviation: Option<Decimal>,
+}
+
+/// Describes where the oracle reads prices of a pair from.
+#[cw_serde]
+pub enum PriceSource {
+    /// Cumulative prices of XYK pools
+    CumulativePrices,
+    /// Price observations of PCL and stableswap pools
+    Observations,
+}
+
+/// This structure describes a pair tracked by the oracle.
+#[cw_serde]
+pub struct OraclePairResponse {
+    /// The pair contract address
+    pub pair_addr: Addr,
+    /// The assets in the pool
+    pub asset_infos: Vec<AssetInfo>,
+    /// Time between two consecutive TWAP updates
+    pub period: u64,
+    /// Where the pair prices are read from
+    pub price_source: PriceSource,
+    /// The last time the TWAP was updated
+    pub block_timestamp_last: u64,
+    /// Whether the pair is frozen because its price moved more than the maximum deviation
+    pub frozen: bool,
 }
 
 /// This structure describes a migration message.
@@ -1,4 +1,46 @@
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::router::SwapOperation;
+
+/// This structure stores the main parameters for the outpost handler contract.
+#[cw_serde]
+pub struct Config {
+    /// The CW20-ICS20 contract allowed to forward memos and failed transfers
+    pub cw20_ics20: Addr,
+    /// The Astroport router contract
+    pub router: Addr,
+    /// The pool manager contract, if swaps through it are enabled
+    pub pool_manager: Option<Addr>,
+}
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The CW20-ICS20 contract allowed to forward memos and failed transfers
+    pub cw20_ics20: String,
+    /// The Astroport router contract
+    pub router: String,
+    /// The pool manager contract, if swaps through it are enabled
+    pub pool_manager: Option<String>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
+    Receive(Cw20ReceiveMsg),
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the configuration for the contract.
+    #[returns(Config)]
+    Config {},
+}
 
 /// Messages handled via CW20 transfers
 #[cw_serde]
@@ -21,3 +63,32 @@ pub enum Cw20HookMsg {
         receiver: String,
     },
 }
+
+/// The contract a memo swap is executed through.
+#[cw_serde]
+pub enum SwapVenue {
+    /// The Astroport router
+    Router,
+    /// The pool manager. Only [`SwapOperation::AstroSwap`] operations are supported
+    PoolManager,
+}
+
+/// Instructions accepted in the JSON memo of an incoming IBC transfer,
+/// e.g. `{"swap": {"operations": [...], "minimum_receive": "100", "to": "..."}}`
+#[cw_serde]
+pub enum MemoMsg {
+    /// Swaps the received tokens using the given operations.
+    /// If the swap fails, the tokens are sent to the receiver of the transfer instead
+    Swap {
+        /// The swap operations to execute, starting with the received token
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of tokens to get from the swap
+        minimum_receive: Option<Uint128>,
+        /// The recipient of the swapped tokens. Defaults to the receiver of the transfer
+        to: Option<String>,
+        /// Max spread
+        max_spread: Option<Decimal>,
+        /// The contract to swap through. Defaults to the router
+        venue: Option<SwapVenue>,
+    },
+}
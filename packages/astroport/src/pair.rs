@@ -12,6 +12,8 @@ pub const DEFAULT_SLIPPAGE: &str = "0.005";
 pub const MAX_ALLOWED_SLIPPAGE: &str = "0.5";
 /// The maximum fee share allowed, 10%
 pub const MAX_FEE_SHARE_BPS: u16 = 1000;
+/// The maximum dynamic swap fee allowed, 10%
+pub const MAX_DYNAMIC_FEE_BPS: u16 = 1000;
 
 /// Decimal precision for TWAP results
 pub const TWAP_PRECISION: u8 = 6;
@@ -71,6 +73,26 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Used to claim contract ownership.
     ClaimOwnership {},
+    /// Places a limit order which sells the offer asset for the other pool asset
+    /// at an average price not lower than `min_price`
+    PlaceLimitOrder {
+        offer_asset: Asset,
+        /// The minimum amount of the ask asset received per unit of the offer asset
+        min_price: Decimal,
+        /// The timestamp in seconds after which the order can't be filled
+        expiry: Option<u64>,
+    },
+    /// Cancels a limit order and returns its unfilled and filled amounts to the owner
+    CancelLimitOrder { order_id: u64 },
+    /// Sends the filled amount of a limit order to its owner
+    ClaimLimitOrder { order_id: u64 },
+    /// Removes expired limit orders which sell the specified asset from the order book.
+    /// Anyone can execute this.
+    RemoveExpiredLimitOrders {
+        offer_asset_info: AssetInfo,
+        /// The maximum number of order book entries examined
+        limit: Option<u32>,
+    },
 }
 
 /// This structure describes a CW20 hook message.
@@ -88,6 +110,19 @@ pub enum Cw20HookMsg {
         #[serde(default)]
         assets: Vec<Asset>,
     },
+    /// Withdraw liquidity from the pool into a single asset
+    WithdrawSingleAsset {
+        asset_info: AssetInfo,
+        /// The minimum amount of the asset to receive
+        min_amount: Option<Uint128>,
+    },
+    /// Place a limit order which sells the sent tokens
+    PlaceLimitOrder {
+        /// The minimum amount of the ask asset received per unit of the offer asset
+        min_price: Decimal,
+        /// The timestamp in seconds after which the order can't be filled
+        expiry: Option<u64>,
+    },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -133,6 +168,29 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Returns the amount of LP tokens that must be burnt to withdraw the specified assets
+    #[returns(Uint128)]
+    SimulateImbalancedWithdraw { assets: Vec<Asset> },
+    /// Returns the asset received for burning the specified amount of LP tokens into a single asset
+    #[returns(Asset)]
+    SimulateSingleAssetWithdraw {
+        lp_amount: Uint128,
+        asset_info: AssetInfo,
+    },
+    /// Returns the swap fee currently charged by the pool in a [`CurrentFeeResponse`] object
+    #[returns(CurrentFeeResponse)]
+    CurrentFee {},
+    /// Returns a limit order in a [`LimitOrderResponse`] object
+    #[returns(LimitOrderResponse)]
+    LimitOrder { order_id: u64 },
+    /// Returns the resting limit orders which sell the specified asset sorted by the limit price
+    #[returns(Vec<LimitOrderResponse>)]
+    LimitOrders {
+        offer_asset_info: AssetInfo,
+        /// The order ID to start reading after
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 /// This struct is used to return a query result with the total amount of LP tokens and assets in a specific pool.
@@ -144,6 +202,23 @@ pub struct PoolResponse {
     pub total_share: Uint128,
 }
 
+/// This structure describes a limit order resting on the pool.
+#[cw_serde]
+pub struct LimitOrderResponse {
+    /// The order identifier
+    pub order_id: u64,
+    /// The order owner
+    pub owner: Addr,
+    /// The offer asset amount which is not filled yet
+    pub offer_asset: Asset,
+    /// The ask asset amount received from fills which is not claimed yet
+    pub filled_asset: Asset,
+    /// The minimum amount of the ask asset received per unit of the offer asset
+    pub min_price: Decimal,
+    /// The timestamp in seconds after which the order can't be filled
+    pub expiry: Option<u64>,
+}
+
 /// This struct is used to return a query result with the general contract configuration.
 #[cw_serde]
 pub struct ConfigResponse {
@@ -211,6 +286,8 @@ pub struct XYKPoolParams {
     /// They will not be tracked if the parameter is ignored.
     /// It can not be disabled later once enabled.
     pub track_asset_balances: Option<bool>,
+    /// Enables the dynamic swap fee which replaces the factory fee
+    pub dynamic_fee: Option<DynamicFeeParams>,
 }
 
 /// This structure stores a XYK pool's configuration.
@@ -220,6 +297,33 @@ pub struct XYKPoolConfig {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The dynamic swap fee parameters
+    pub dynamic_fee: Option<DynamicFeeParams>,
+}
+
+/// This structure holds the parameters of the dynamic swap fee. The fee scales linearly from
+/// `min_fee_bps` to `max_fee_bps` with the price volatility observed within the last `window` seconds.
+#[cw_serde]
+pub struct DynamicFeeParams {
+    /// The fee charged when the price is stable
+    pub min_fee_bps: u16,
+    /// The fee charged when the volatility reaches `max_volatility_bps`
+    pub max_fee_bps: u16,
+    /// The price range (relative to the lowest observed price) at which the fee is maximal
+    pub max_volatility_bps: u16,
+    /// The period in seconds over which the price volatility is measured
+    pub window: u64,
+}
+
+/// This structure is used to return the swap fee currently charged by a pool.
+#[cw_serde]
+pub struct CurrentFeeResponse {
+    /// The total fee rate charged on swaps
+    pub total_fee_rate: Decimal,
+    /// The share of the total fee sent to the Maker contract
+    pub maker_fee_rate: Decimal,
+    /// The observed price volatility if the dynamic fee is enabled
+    pub volatility: Option<Decimal>,
 }
 
 /// This enum stores the option available to enable asset balances tracking over blocks.
@@ -235,6 +339,12 @@ pub enum XYKPoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Enables the dynamic swap fee or updates its parameters.
+    UpdateDynamicFee {
+        params: DynamicFeeParams,
+    },
+    /// Disables the dynamic swap fee. The factory fee is charged again.
+    DisableDynamicFee,
 }
 
 /// This structure holds stableswap pool parameters.
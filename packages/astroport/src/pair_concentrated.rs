@@ -156,6 +156,12 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Returns the asset received for burning the specified amount of LP tokens into a single asset
+    #[returns(Asset)]
+    SimulateSingleAssetWithdraw {
+        lp_amount: Uint128,
+        asset_info: AssetInfo,
+    },
 }
 
 #[cw_serde]
@@ -0,0 +1,107 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Decimal256, Uint128};
+
+use crate::asset::PairInfo;
+use crate::asset::{Asset, AssetInfo};
+use crate::pair::{ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse};
+use crate::pair_concentrated::{PromoteParams, UpdatePoolParams};
+
+/// This structure holds tricrypto pool parameters.
+#[cw_serde]
+pub struct TricryptoPoolParams {
+    /// Amplification coefficient affects trades close to price_scales
+    pub amp: Decimal,
+    /// Affects how gradual the curve changes from constant sum to constant product
+    /// as prices move away from price scales. Low values mean more gradual.
+    pub gamma: Decimal,
+    /// The minimum fee, charged when pool is fully balanced
+    pub mid_fee: Decimal,
+    /// The maximum fee, charged when pool is imbalanced
+    pub out_fee: Decimal,
+    /// Parameter that defines how gradual the fee changes from fee_mid to fee_out
+    /// based on distance from price_scales.
+    pub fee_gamma: Decimal,
+    /// Minimum profit before initiating a new repeg
+    pub repeg_profit_threshold: Decimal,
+    /// Minimum amount to change price_scales when repegging.
+    pub min_price_scale_delta: Decimal,
+    /// Prices of the 2nd and the 3rd assets denominated in the 1st one.
+    /// 1 x\[0] = price_scales\[i - 1] * x\[i].
+    pub price_scales: Vec<Decimal>,
+    /// Half-time used for calculating the price oracle.
+    pub ma_half_time: u64,
+}
+
+/// This enum intended for parameters update.
+#[cw_serde]
+pub enum TricryptoPoolUpdateParams {
+    /// Allows to update fee parameters as well as repeg_profit_threshold, min_price_scale_delta and EMA interval.
+    Update(UpdatePoolParams),
+    /// Starts gradual (de/in)crease of Amp or Gamma parameters. Can handle an update of both of them.
+    Promote(PromoteParams),
+    /// Stops Amp and Gamma update and stores current values.
+    StopChangingAmpGamma {},
+}
+
+/// This structure stores a tricrypto pool's configuration.
+#[cw_serde]
+pub struct TricryptoPoolConfig {
+    /// Amplification coefficient affects trades close to price_scales
+    pub amp: Decimal,
+    /// Affects how gradual the curve changes from constant sum to constant product
+    /// as prices move away from price scales. Low values mean more gradual.
+    pub gamma: Decimal,
+    /// The minimum fee, charged when pool is fully balanced
+    pub mid_fee: Decimal,
+    /// The maximum fee, charged when pool is imbalanced
+    pub out_fee: Decimal,
+    /// Parameter that defines how gradual the fee changes from fee_mid to fee_out
+    /// based on distance from price_scales.
+    pub fee_gamma: Decimal,
+    /// Minimum profit before initiating a new repeg
+    pub repeg_profit_threshold: Decimal,
+    /// Minimum amount to change price_scales when repegging.
+    pub min_price_scale_delta: Decimal,
+    /// Prices of the 2nd and the 3rd assets denominated in the 1st one.
+    pub price_scales: Vec<Decimal>,
+    /// Internal oracle prices of the 2nd and the 3rd assets denominated in the 1st one.
+    pub oracle_prices: Vec<Decimal>,
+    /// Half-time used for calculating the price oracle.
+    pub ma_half_time: u64,
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns information about a pair
+    #[returns(PairInfo)]
+    Pair {},
+    /// Returns information about a pool
+    #[returns(PoolResponse)]
+    Pool {},
+    /// Returns contract configuration
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
+    #[returns(Vec<Asset>)]
+    Share { amount: Uint128 },
+    /// Returns information about a swap simulation. The ask asset must be specified.
+    #[returns(SimulationResponse)]
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    /// Returns information about a reverse swap simulation. The offer asset must be specified.
+    #[returns(ReverseSimulationResponse)]
+    ReverseSimulation {
+        offer_asset_info: Option<AssetInfo>,
+        ask_asset: Asset,
+    },
+    /// Returns current D invariant
+    #[returns(Decimal256)]
+    ComputeD {},
+    /// Query LP token virtual price
+    #[returns(Decimal256)]
+    LpPrice {},
+}
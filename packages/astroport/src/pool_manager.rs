@@ -0,0 +1,113 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, Decimal, Decimal256, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::pair::{ConfigResponse, PoolResponse};
+use crate::router::SimulateSwapOperationsResponse;
+
+/// This structure describes a swap in one of the pools hosted by the pool manager.
+#[cw_serde]
+pub struct SwapOperation {
+    /// Information about the asset being swapped
+    pub offer_asset_info: AssetInfo,
+    /// Information about the asset we swap to
+    pub ask_asset_info: AssetInfo,
+    /// The key of the pool to swap in. Required for pools with more than two assets,
+    /// otherwise derived from the offer and ask assets
+    #[serde(default)]
+    pub pool_key: Option<String>,
+}
+
+/// This structure describes the parameters used for creating the contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The factory contract address. Every new pool is registered in the factory if set
+    pub factory_addr: Option<String>,
+}
+
+/// This structure describes a migration message.
+#[cw_serde]
+pub struct MigrateMsg {
+    /// The factory contract address. Every new pool is registered in the factory if set
+    pub factory_addr: Option<String>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// ExecuteSwapOperations processes multiple swaps while mentioning the minimum amount of tokens to receive for the last swap operation
+    ExecuteSwapOperations {
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+    },
+    ProvideLiquidity {
+        /// The assets available in the pool
+        assets: Vec<Asset>,
+        /// The slippage tolerance that allows liquidity provision only if the price in the pool doesn't move too much
+        slippage_tolerance: Option<Decimal>,
+        /// Determines whether the LP tokens minted for the user is auto_staked in the Generator contract
+        auto_stake: Option<bool>,
+        /// The receiver of LP tokens
+        receiver: Option<String>,
+    },
+    CreatePair {
+        /// Information about assets in the pool
+        asset_infos: Vec<AssetInfo>,
+        /// The token contract code ID used for the tokens in the pool
+        token_code_id: u64,
+        /// Optional binary serialised parameters for custom pool types
+        init_params: Option<Binary>,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// SimulateSwapOperations simulates multi-hop swap operations
+    #[returns(SimulateSwapOperationsResponse)]
+    SimulateSwapOperations {
+        /// The amount of tokens to swap
+        offer_amount: Uint128,
+        /// The swap operations to perform, each swap involving a specific pool
+        operations: Vec<SwapOperation>,
+    },
+    #[returns(ConfigResponse)]
+    Config { pool_key: String },
+    #[returns(PoolResponse)]
+    Pool { pool_key: String },
+    #[returns(PairInfo)]
+    Pair { pool_key: String },
+    /// Returns the pool which mints the specified LP token
+    #[returns(PairInfo)]
+    PairByLpToken { lp_token: String },
+    #[returns(Decimal256)]
+    ComputeD { pool_key: String },
+    /// Query LP token virtual price
+    #[returns(Decimal256)]
+    LpPrice { pool_key: String },
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    ExecuteSwapOperations {
+        /// A vector of swap operations
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of tokens to get from a swap
+        minimum_receive: Option<Uint128>,
+        /// The recipient
+        to: Option<String>,
+        /// Max spread
+        max_spread: Option<Decimal>,
+    },
+    /// Withdraw liquidity from the pool
+    WithdrawLiquidity {
+        #[serde(default)]
+        assets: Vec<Asset>,
+    },
+}
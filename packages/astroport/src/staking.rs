@@ -1,3 +1,4 @@
+use crate::asset::AssetInfo;
 use crate::xastro_token::InstantiateMarketingInfo;
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Uint128};
@@ -8,12 +9,17 @@ use cw20::Cw20ReceiveMsg;
 pub struct InstantiateMsg {
     /// The contract owner address
     pub owner: String,
-    /// CW20 token code identifier
+    /// CW20 token code identifier. Used only if xASTRO is issued as cw20 token
     pub token_code_id: u64,
-    /// The ASTRO token contract address
-    pub deposit_token_addr: String,
+    /// The ASTRO token: either cw20 contract or native denom
+    pub deposit_token: AssetInfo,
     /// the marketing info of type [`InstantiateMarketingInfo`]
     pub marketing: Option<InstantiateMarketingInfo>,
+    /// The number of seconds ASTRO stays locked after leaving. ASTRO is returned at once if not set
+    pub unbonding_period: Option<u64>,
+    /// If set, xASTRO is issued as tokenfactory denom factory/{staking_contract}/{tokenfactory_subdenom}
+    /// instead of cw20 token
+    pub tokenfactory_subdenom: Option<String>,
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -21,6 +27,12 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Receive receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
     Receive(Cw20ReceiveMsg),
+    /// Deposits native ASTRO attached to the message in exchange for xASTRO
+    Enter {},
+    /// Burns tokenfactory xASTRO attached to the message in exchange for ASTRO
+    Leave {},
+    /// Claim ASTRO from all unbondings which passed the unbonding period
+    Claim {},
 }
 
 /// This structure describes the query messages available in the contract.
@@ -32,27 +44,60 @@ pub enum QueryMsg {
     Config {},
     #[returns(Uint128)]
     TotalShares {},
+    /// TotalDeposit returns the amount of ASTRO which belongs to xASTRO holders. Pending unbondings are excluded
     #[returns(Uint128)]
     TotalDeposit {},
+    /// Returns the list of user unbondings sorted by release time
+    #[returns(Vec<UnbondingInfo>)]
+    PendingUnbondings { user: String },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
-    /// The ASTRO token address
-    pub deposit_token_addr: Addr,
-    /// The xASTRO token address
-    pub share_token_addr: Addr,
+    /// The ASTRO token
+    pub deposit_token: AssetInfo,
+    /// The xASTRO token
+    pub share_token: AssetInfo,
+    /// The ASTRO token address. Kept for backward compatibility, empty if ASTRO is a native denom
+    pub deposit_token_addr: Option<Addr>,
+    /// The xASTRO token address. Kept for backward compatibility, empty if xASTRO is a tokenfactory denom
+    pub share_token_addr: Option<Addr>,
+    /// The number of seconds ASTRO stays locked after leaving
+    pub unbonding_period: u64,
+}
+
+/// This structure describes a pending withdrawal of ASTRO.
+#[cw_serde]
+pub struct UnbondingInfo {
+    /// The amount of ASTRO to be released
+    pub amount: Uint128,
+    /// The timestamp when ASTRO can be claimed
+    pub release_ts: u64,
 }
 
 /// This structure describes a migration message.
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The new unbonding period in seconds. 0 disables unbonding
+    pub unbonding_period: Option<u64>,
+    /// If set, cw20 ASTRO is replaced with native ASTRO
+    pub convert_deposit_token: Option<DepositTokenConversion>,
+}
+
+/// This structure describes the switch from cw20 ASTRO to native ASTRO.
+#[cw_serde]
+pub struct DepositTokenConversion {
+    /// The native ASTRO denom. The contract must already hold at least as much native ASTRO as cw20 ASTRO
+    pub denom: String,
+    /// The address which receives all cw20 ASTRO held by the contract
+    pub cw20_recipient: String,
+}
 
 /// This structure describes a CW20 hook message.
 #[cw_serde]
 pub enum Cw20HookMsg {
-    /// Deposits ASTRO in exchange for xASTRO
+    /// Deposits cw20 ASTRO in exchange for xASTRO
     Enter {},
-    /// Burns xASTRO in exchange for ASTRO
+    /// Burns cw20 xASTRO in exchange for ASTRO
     Leave {},
 }
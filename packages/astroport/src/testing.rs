@@ -233,6 +233,7 @@ fn query_astroport_pair_contract() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         },
     )]);
 
@@ -271,6 +272,7 @@ fn test_format_lp_token_name() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            pool_key: None,
         },
     )]);
 
@@ -0,0 +1,160 @@
+use cosmwasm_std::{Addr, Coin, CosmosMsg, CustomMsg};
+use prost::Message;
+
+/// Tokenfactory denom created by `creator` with the given `subdenom`.
+/// The denom is deterministic thus it is known before the denom creation message is executed.
+pub fn tf_denom(creator: &Addr, subdenom: &str) -> String {
+    format!("factory/{creator}/{subdenom}")
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+impl From<Coin> for ProtoCoin {
+    fn from(coin: Coin) -> Self {
+        Self {
+            denom: coin.denom,
+            amount: coin.amount.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgCreateDenom {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub subdenom: String,
+}
+
+impl MsgCreateDenom {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgMint {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+}
+
+impl MsgMint {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgBurn {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+}
+
+impl MsgBurn {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+}
+
+/// Creates a new tokenfactory denom. Sender becomes the denom admin.
+pub fn tf_create_denom_msg<T>(
+    sender: impl Into<String>,
+    subdenom: impl Into<String>,
+) -> CosmosMsg<T>
+where
+    T: CustomMsg,
+{
+    let msg = MsgCreateDenom {
+        sender: sender.into(),
+        subdenom: subdenom.into(),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgCreateDenom::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
+
+/// Mints tokenfactory coins to the sender which must be the denom admin.
+pub fn tf_mint_msg<T>(sender: impl Into<String>, coin: Coin) -> CosmosMsg<T>
+where
+    T: CustomMsg,
+{
+    let msg = MsgMint {
+        sender: sender.into(),
+        amount: Some(coin.into()),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgMint::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
+
+/// Burns tokenfactory coins from the sender balance. Sender must be the denom admin.
+pub fn tf_burn_msg<T>(sender: impl Into<String>, coin: Coin) -> CosmosMsg<T>
+where
+    T: CustomMsg,
+{
+    let msg = MsgBurn {
+        sender: sender.into(),
+        amount: Some(coin.into()),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgBurn::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{coin, Binary, Empty};
+
+    use super::*;
+
+    #[test]
+    fn test_tf_messages() {
+        let creator = Addr::unchecked("wasm1staking");
+        assert_eq!(tf_denom(&creator, "xastro"), "factory/wasm1staking/xastro");
+
+        let msg: CosmosMsg<Empty> =
+            tf_mint_msg(creator.as_str(), coin(100, "factory/wasm1staking/xastro"));
+        let (type_url, value) = match msg {
+            CosmosMsg::Stargate { type_url, value } => (type_url, value),
+            _ => panic!("Expected stargate message"),
+        };
+        assert_eq!(type_url, MsgMint::TYPE_URL);
+
+        let decoded = MsgMint::decode(value.as_slice()).unwrap();
+        assert_eq!(
+            decoded,
+            MsgMint {
+                sender: "wasm1staking".to_string(),
+                amount: Some(ProtoCoin {
+                    denom: "factory/wasm1staking/xastro".to_string(),
+                    amount: "100".to_string(),
+                }),
+            }
+        );
+
+        let msg: CosmosMsg<Empty> = tf_create_denom_msg(creator.as_str(), "xastro");
+        assert_eq!(
+            msg,
+            CosmosMsg::Stargate {
+                type_url: MsgCreateDenom::TYPE_URL.to_string(),
+                value: Binary(
+                    MsgCreateDenom {
+                        sender: "wasm1staking".to_string(),
+                        subdenom: "xastro".to_string(),
+                    }
+                    .encode_to_vec()
+                ),
+            }
+        );
+    }
+}
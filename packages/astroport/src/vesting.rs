@@ -22,10 +22,13 @@ pub enum ExecuteMsg {
         recipient: Option<String>,
         /// The amount of tokens to claim
         amount: Option<Uint128>,
+        /// The asset to claim. The main vesting token is used if not specified
+        asset_info: Option<AssetInfo>,
     },
     /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
     Receive(Cw20ReceiveMsg),
-    /// RegisterVestingAccounts registers vesting targets/accounts
+    /// RegisterVestingAccounts registers vesting targets/accounts.
+    /// Native tokens attached to the message are vested
     RegisterVestingAccounts {
         vesting_accounts: Vec<VestingAccount>,
     },
@@ -37,6 +40,42 @@ pub enum ExecuteMsg {
         recipient: Option<String>,
         /// The amount of tokens to withdraw
         withdraw_amount: Uint128,
+        /// The asset to withdraw. The main vesting token is used if not specified
+        asset_info: Option<AssetInfo>,
+    },
+    /// Revokes all revocable schedules of the account in all vested assets. Vested tokens stay claimable by the account
+    /// while unvested tokens are sent to the recipient.
+    /// ## Executor
+    /// Only the current owner can execute this
+    RevokeVestingSchedules {
+        /// The account whose schedules are revoked
+        account: String,
+        /// The address that receives unvested tokens. Owner is used if not specified
+        recipient: Option<String>,
+    },
+    /// Moves all schedules and released amounts of the sender in all vested assets to a new address.
+    /// If transfer approval is required, creates a pending transfer which must be approved by the owner
+    TransferVestingAccount {
+        /// The address that will receive vested tokens
+        new_address: String,
+    },
+    /// Cancels the pending transfer of the sender vesting account
+    CancelVestingAccountTransfer {},
+    /// Approves a pending transfer of the vesting account
+    /// ## Executor
+    /// Only the current owner can execute this
+    ApproveVestingAccountTransfer {
+        /// The account whose transfer is approved
+        account: String,
+    },
+    /// Updates contract parameters
+    /// ## Executor
+    /// Only the current owner can execute this
+    UpdateConfig {
+        /// Whether vesting account transfers must be approved by the owner
+        transfer_approval_required: Option<bool>,
+        /// New assets which can be vested besides the main vesting token
+        add_vesting_assets: Option<Vec<AssetInfo>>,
     },
     /// Creates a request to change contract ownership
     /// ## Executor
@@ -82,6 +121,28 @@ pub struct VestingSchedule {
     pub start_point: VestingSchedulePoint,
     /// The end point for the vesting schedule
     pub end_point: Option<VestingSchedulePoint>,
+    /// The way tokens are unlocked between the start and end points
+    #[serde(default)]
+    pub curve: VestingCurve,
+    /// Whether the owner can revoke unvested tokens
+    #[serde(default)]
+    pub revocable: bool,
+}
+
+/// This enum describes how tokens are unlocked between the start and end points of a vesting schedule.
+/// Cliff and step curves require the end point to be set.
+#[cw_serde]
+#[derive(Default)]
+pub enum VestingCurve {
+    /// Start amount is unlocked at the start time, the rest is unlocked linearly till the end time
+    #[default]
+    Linear {},
+    /// Nothing is unlocked before the cliff time. Afterwards tokens are unlocked
+    /// as if the schedule was linear, thus everything accrued so far is unlocked at the cliff time
+    Cliff { cliff_time: u64 },
+    /// Start amount is unlocked at the start time, the rest is unlocked in equal portions
+    /// every `step_duration` seconds. The last step might be shorter and ends at the end time
+    Step { step_duration: u64 },
 }
 
 /// This structure stores the parameters used to create a vesting schedule.
@@ -103,20 +164,36 @@ pub enum QueryMsg {
     Config {},
     /// Returns information about an address vesting tokens using a [`VestingAccountResponse`] object.
     #[returns(VestingAccountResponse)]
-    VestingAccount { address: String },
+    VestingAccount {
+        address: String,
+        /// The vested asset. The main vesting token is used if not specified
+        asset_info: Option<AssetInfo>,
+    },
     /// Returns a list of addresses that are vesting tokens using a [`VestingAccountsResponse`] object.
     #[returns(VestingAccountsResponse)]
     VestingAccounts {
         start_after: Option<String>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
+        /// The vested asset. The main vesting token is used if not specified
+        asset_info: Option<AssetInfo>,
     },
-    /// Returns the total unvested amount of tokens for a specific address.
+    /// Returns the amount of vested and not yet claimed tokens for a specific address.
     #[returns(Uint128)]
-    AvailableAmount { address: String },
+    AvailableAmount {
+        address: String,
+        /// The vested asset. The main vesting token is used if not specified
+        asset_info: Option<AssetInfo>,
+    },
     /// Timestamp returns the current timestamp
     #[returns(u64)]
     Timestamp {},
+    /// Returns vesting information of an address in all vested assets
+    #[returns(Vec<AssetVestingInfo>)]
+    VestingAccountAssets { address: String },
+    /// Returns the new address of a pending vesting account transfer if any
+    #[returns(Option<Addr>)]
+    PendingTransfer { address: String },
 }
 
 /// This structure describes a custom struct used to return the contract configuration.
@@ -126,6 +203,10 @@ pub struct ConfigResponse {
     pub owner: Addr,
     /// [`AssetInfo`] of the token being vested
     pub vesting_token: AssetInfo,
+    /// Whether vesting account transfers must be approved by the owner
+    pub transfer_approval_required: bool,
+    /// Other assets which can be vested besides the main vesting token
+    pub extra_vesting_assets: Vec<AssetInfo>,
 }
 
 /// This structure describes a custom struct used to return vesting data about a specific vesting target.
@@ -137,6 +218,17 @@ pub struct VestingAccountResponse {
     pub info: VestingInfo,
 }
 
+/// This structure describes a custom struct used to return vesting data of a specific asset.
+#[cw_serde]
+pub struct AssetVestingInfo {
+    /// The vested asset
+    pub asset_info: AssetInfo,
+    /// Vesting information
+    pub info: VestingInfo,
+    /// The amount of vested and not yet claimed tokens
+    pub available_amount: Uint128,
+}
+
 /// This structure describes a custom struct used to return vesting data for multiple vesting targets.
 #[cw_serde]
 pub struct VestingAccountsResponse {
@@ -80,6 +80,8 @@ where
                 pair_type: PairType::Xyk {},
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
                 total_fee_bps: 30,
                 maker_fee_bps: 3333,
             },
@@ -88,6 +90,8 @@ where
                 pair_type: PairType::Stable {},
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
                 total_fee_bps: 5,
                 maker_fee_bps: 5000,
             },
@@ -96,6 +100,8 @@ where
                 pair_type: PairType::Custom("concentrated".to_owned()),
                 is_disabled: false,
                 is_generator_disabled: false,
+                pool_creation_fee: None,
+                listing_policy: None,
                 total_fee_bps: 30,
                 maker_fee_bps: 3333,
             },
@@ -6,7 +6,8 @@ use astroport::{
     generator::{Config, ExecuteMsg, InstantiateMsg, PendingTokenResponse, QueryMsg},
     token::ExecuteMsg as Cw20ExecuteMsg,
     vesting::{
-        Cw20HookMsg as VestingCw20HookMsg, VestingAccount, VestingSchedule, VestingSchedulePoint,
+        Cw20HookMsg as VestingCw20HookMsg, VestingAccount, VestingCurve, VestingSchedule,
+        VestingSchedulePoint,
     },
 };
 use cosmwasm_std::{to_binary, Addr, Api, CustomQuery, Storage, Uint128};
@@ -115,6 +116,9 @@ where
                     generator_address: Some(address.to_string()),
                     whitelist_code_id: None,
                     coin_registry_address: None,
+                    pool_manager: None,
+                    pool_creation_fee: None,
+                    listing_policy: None,
                 },
                 &[],
             )
@@ -140,6 +144,8 @@ where
                                     amount: Uint128::new(1_000_000_000_000),
                                 },
                                 end_point: None,
+                                curve: VestingCurve::Linear {},
+                                revocable: false,
                             }],
                         }],
                     })
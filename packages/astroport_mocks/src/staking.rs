@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use astroport::{
+    asset::AssetInfo,
     staking::{ConfigResponse, Cw20HookMsg, InstantiateMsg, QueryMsg},
     token::ExecuteMsg,
 };
@@ -92,7 +93,9 @@ where
                     owner: ASTROPORT.to_owned(),
                     marketing: None,
                     token_code_id,
-                    deposit_token_addr: astro_token.address.to_string(),
+                    deposit_token: AssetInfo::cw20(astro_token.address.clone()),
+                    unbonding_period: None,
+                    tokenfactory_subdenom: None,
                 },
                 &[],
                 "Astroport Staking",
@@ -135,7 +138,7 @@ where
 
         MockToken {
             app: self.app.clone(),
-            address: config.deposit_token_addr,
+            address: Addr::unchecked(config.deposit_token.to_string()),
         }
     }
 
@@ -166,7 +169,7 @@ where
 
         MockToken {
             app: self.app.clone(),
-            address: config.share_token_addr,
+            address: Addr::unchecked(config.share_token.to_string()),
         }
     }
 
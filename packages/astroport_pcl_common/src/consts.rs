@@ -24,6 +24,12 @@ pub const HALFPOW_TOL: Decimal256 = Decimal256::raw(100000000);
 pub const TWO: Decimal256 = Decimal256::raw(2000000000000000000);
 /// Iterations limit for Newton's method
 pub const MAX_ITER: usize = 64;
+/// The smallest internal pool volume accepted by swaps and provides (0.0001).
+/// Below it (D * gamma)^3 vanishes in the 18-decimal denominator of df/dD when gamma = GAMMA_MIN.
+pub const MIN_POOL_VOLUME: Decimal256 = Decimal256::raw(100000000000000);
+/// Headroom left in Decimal256 for intermediate values of the 2-coin solvers (8.0).
+/// Newton iterations may overshoot the final D thus the largest value must fit 8 times over.
+pub const POOL_VOLUMES_SAFETY_MARGIN: Decimal256 = Decimal256::raw(8000000000000000000);
 
 /// ## Validation constants
 /// 0.00005 (0.005%)
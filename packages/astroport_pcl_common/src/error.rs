@@ -1,7 +1,7 @@
 use cosmwasm_std::{Decimal, StdError};
 use thiserror::Error;
 
-use crate::consts::MIN_AMP_CHANGING_TIME;
+use crate::consts::{MIN_AMP_CHANGING_TIME, MIN_POOL_VOLUME};
 
 /// This enum describes pair contract errors
 #[derive(Error, Debug, PartialEq)]
@@ -40,4 +40,19 @@ pub enum PclError {
 
     #[error("The asset {0} does not belong to the pair")]
     InvalidAsset(String),
+
+    #[error("Ask or offer asset is missed")]
+    VariableAssetMissed {},
+
+    #[error("Source and target assets are the same")]
+    SameAssets {},
+
+    #[error("{0} is not converging")]
+    NotConverging(String),
+
+    #[error(
+        "Pool volumes must not be less than {} and must not overflow the invariant with current amp and gamma",
+        MIN_POOL_VOLUME
+    )]
+    UnsafePoolVolumes {},
 }
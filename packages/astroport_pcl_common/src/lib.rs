@@ -5,3 +5,4 @@ pub mod error;
 mod math;
 pub mod state;
 pub mod utils;
+pub mod utils_n_coins;
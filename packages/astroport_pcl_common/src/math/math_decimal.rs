@@ -1,11 +1,12 @@
-use cosmwasm_std::{Decimal256, Fraction, StdError, StdResult, Uint128};
+use cosmwasm_std::{Decimal256, Fraction, StdError, Uint128};
 
 use crate::consts::{HALFPOW_TOL, MAX_ITER, N, N_POW2, TOL};
+use crate::error::PclError;
 use crate::math::signed_decimal::SignedDecimal256;
 use itertools::Itertools;
 
 /// Internal constant to increase calculation accuracy.
-const PADDING: Decimal256 = Decimal256::raw(1e36 as u128);
+pub(crate) const PADDING: Decimal256 = Decimal256::raw(1e36 as u128);
 
 pub fn geometric_mean(x: &[Decimal256]) -> Decimal256 {
     (x[0] * x[1]).sqrt()
@@ -51,23 +52,49 @@ pub(crate) fn df_dd(
         - (d / N)
 }
 
+/// Returns true if D is zero or K0 is so close to the pole at gamma + 1
+/// that denominators of the invariant derivatives vanish.
+fn is_singular(d: SignedDecimal256, x: &[SignedDecimal256], gamma: Decimal256) -> bool {
+    let d_pow2 = d.pow(2);
+    if d_pow2.is_zero() {
+        return true;
+    }
+
+    let k0 = x[0] * x[1] * N_POW2 / d_pow2;
+    let gamma_one_k0 = SignedDecimal256::from(gamma + Decimal256::one()) - k0;
+    let gamma_one_k0_pow2 = gamma_one_k0.pow(2);
+
+    (PADDING * d.pow(3) * gamma_one_k0_pow2 * gamma_one_k0).is_zero()
+        || (PADDING * d_pow2 * gamma_one_k0 * gamma_one_k0_pow2).is_zero()
+}
+
 pub(crate) fn newton_d(
     x: &[Decimal256],
     a: Decimal256,
     gamma: Decimal256,
-) -> StdResult<Decimal256> {
+) -> Result<Decimal256, PclError> {
     let mut d_prev: SignedDecimal256 = (N * geometric_mean(x)).into();
     let x = x.iter().map(SignedDecimal256::from).collect_vec();
 
     for _ in 0..MAX_ITER {
-        let d = d_prev - f(d_prev, &x, a, gamma) / df_dd(d_prev, &x, a, gamma);
+        if is_singular(d_prev, &x, gamma) {
+            break;
+        }
+        let derivative = df_dd(d_prev, &x, a, gamma);
+        if derivative.is_zero() {
+            break;
+        }
+        let d = d_prev - f(d_prev, &x, a, gamma) / derivative;
         if d.diff(d_prev) <= TOL {
-            return d.try_into();
+            // Negative D means that iterations diverged
+            return d
+                .try_into()
+                .map_err(|_| PclError::NotConverging("newton_d".to_string()));
         }
         d_prev = d;
     }
 
-    Err(StdError::generic_err("newton_d is not converging"))
+    Err(PclError::NotConverging("newton_d".to_string()))
 }
 
 /// df/dx
@@ -100,28 +127,39 @@ pub(crate) fn newton_y(
     gamma: Decimal256,
     d: Decimal256,
     j: usize,
-) -> StdResult<Decimal256> {
+) -> Result<Decimal256, PclError> {
     let mut x = xs.iter().map(SignedDecimal256::from).collect_vec();
     let x0 = d.pow(2) / (N_POW2 * x[1 - j]);
     let mut xi_1 = x0;
     x[j] = x0;
 
     for _ in 0..MAX_ITER {
-        let xi = xi_1 - f(d.into(), &x, a, gamma) / df_dx(d, &x, a, gamma, j);
+        if is_singular(d.into(), &x, gamma) {
+            break;
+        }
+        let derivative = df_dx(d, &x, a, gamma, j);
+        if derivative.is_zero() {
+            break;
+        }
+        let xi = xi_1 - f(d.into(), &x, a, gamma) / derivative;
         if xi.diff(xi_1) <= TOL {
-            return xi.try_into();
+            return xi
+                .try_into()
+                .map_err(|_| PclError::NotConverging("newton_y".to_string()));
         }
         x[j] = xi;
         xi_1 = xi;
     }
 
-    Err(StdError::generic_err("newton_y is not converging"))
+    Err(PclError::NotConverging("newton_y".to_string()))
 }
 
 /// Calculates 0.5^power.
-pub fn half_float_pow(power: Decimal256) -> StdResult<Decimal256> {
+pub fn half_float_pow(power: Decimal256) -> Result<Decimal256, PclError> {
     let intpow = power.floor();
-    let intpow_u128: Uint128 = (intpow.numerator() / intpow.denominator()).try_into()?;
+    let intpow_u128: Uint128 = (intpow.numerator() / intpow.denominator())
+        .try_into()
+        .map_err(StdError::from)?;
 
     let half = Decimal256::from_ratio(1u8, 2u8);
     let frac_pow = power - intpow;
@@ -145,7 +183,7 @@ pub fn half_float_pow(power: Decimal256) -> StdResult<Decimal256> {
         }
     }
 
-    Err(StdError::generic_err("halfpow is not converging"))
+    Err(PclError::NotConverging("halfpow".to_string()))
 }
 
 #[cfg(test)]
@@ -75,3 +75,56 @@ pub fn newton_d(x: &[f64], a: f64, gamma: f64) -> f64 {
 
     di
 }
+
+/// The invariant of a pool with an arbitrary number of coins divided by (D/n)^n.
+pub fn f_n(d: f64, x: &[f64], a: f64, gamma: f64) -> f64 {
+    let n = x.len() as f64;
+    let k0 = x.iter().fold(1.0, |acc, x_i| acc * n * x_i / d);
+    let k = a * gamma.powi(2) * k0 / (gamma + 1.0 - k0).powi(2);
+
+    k * n.powi(x.len() as i32) * (x.iter().sum::<f64>() / d - 1.0) + k0 - 1.0
+}
+
+/// Finds a root of the increasing (or decreasing) function on the given interval.
+fn bisect(mut f: impl FnMut(f64) -> f64, mut lo: f64, mut hi: f64, increasing: bool) -> f64 {
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.0;
+        if (f(mid) > 0.0) == increasing {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    (lo + hi) / 2.0
+}
+
+/// D lies between n * geometric_mean(x) where K0 = 1 and sum(x) where f <= 0.
+pub fn calc_d_n(x: &[f64], a: f64, gamma: f64) -> f64 {
+    let n = x.len() as f64;
+    let lo = n * x.iter().product::<f64>().powf(1.0 / n);
+    let hi = x.iter().sum::<f64>();
+
+    bisect(|d| f_n(d, x, a, gamma), lo, hi, false)
+}
+
+/// x_j lies between zero and the value which makes K0 = 1.
+pub fn calc_y_n(xs: &[f64], a: f64, gamma: f64, d: f64, j: usize) -> f64 {
+    let n = xs.len() as f64;
+    let hi = xs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != j)
+        .fold(d / n, |acc, (_, x_i)| acc * d / (n * x_i));
+
+    let mut x = xs.to_vec();
+    bisect(
+        |y| {
+            x[j] = y;
+            f_n(d, &x, a, gamma)
+        },
+        0.0,
+        hi,
+        true,
+    )
+}
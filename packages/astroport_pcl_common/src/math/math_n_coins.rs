@@ -0,0 +1,272 @@
+use cosmwasm_std::Decimal256;
+
+use crate::consts::{MAX_ITER, TOL, TWO};
+use crate::error::PclError;
+use crate::math::signed_decimal::SignedDecimal256;
+
+/// Returns the number of coins as well as n^n.
+fn coins_number(x: &[Decimal256]) -> (Decimal256, Decimal256) {
+    let n = Decimal256::from_ratio(x.len() as u128, 1u8);
+    (n, n.pow(x.len() as u32))
+}
+
+/// K0 = n^n * prod(x) / D^n. It is calculated as a product of n * x_i / D in order to avoid overflows.
+fn calc_k0(d: Decimal256, x: &[Decimal256], n: Decimal256) -> Decimal256 {
+    x.iter()
+        .fold(Decimal256::one(), |acc, x_i| acc * n * *x_i / d)
+}
+
+/// Calculates the geometric mean of an arbitrary number of values using Newton's method.
+pub fn geometric_mean(x: &[Decimal256]) -> Result<Decimal256, PclError> {
+    let n = Decimal256::from_ratio(x.len() as u128, 1u8);
+    // The arithmetic mean is never less than the geometric one thus iterations converge monotonically
+    let mut d_prev = x.iter().fold(Decimal256::zero(), |acc, x_i| acc + *x_i) / n;
+    if d_prev.is_zero() {
+        return Ok(d_prev);
+    }
+
+    for _ in 0..MAX_ITER {
+        let prod = x
+            .iter()
+            .fold(Decimal256::one(), |acc, x_i| acc * *x_i / d_prev);
+        let d = d_prev * (n - Decimal256::one() + prod) / n;
+        if d.abs_diff(d_prev) <= TOL {
+            return Ok(d);
+        }
+        d_prev = d;
+    }
+
+    Err(PclError::NotConverging("geometric_mean".to_string()))
+}
+
+/// Terms shared by the invariant and its derivatives.
+struct Terms {
+    n: Decimal256,
+    k0: Decimal256,
+    /// sum(x) / D
+    sum_d: Decimal256,
+    /// A * K0 * n^n
+    a_k0_n_pow_n: Decimal256,
+    /// (sum(x) - D) / D
+    u: SignedDecimal256,
+    /// (gamma + 1 - K0) / gamma
+    g: SignedDecimal256,
+    /// d((K0 - 1) * g^2) / dK0
+    dg_dk0: SignedDecimal256,
+}
+
+impl Terms {
+    fn new(d: Decimal256, x: &[Decimal256], a: Decimal256, gamma: Decimal256) -> Self {
+        let (n, n_pow_n) = coins_number(x);
+        let three = Decimal256::from_ratio(3u8, 1u8);
+        let sum = x.iter().fold(Decimal256::zero(), |acc, x_i| acc + *x_i);
+
+        let k0 = calc_k0(d, x, n);
+        let g = (SignedDecimal256::from(gamma + Decimal256::one()) - k0) / gamma;
+        let dg_dk0 = g * (SignedDecimal256::from(gamma + three) - three * k0) / gamma;
+
+        Self {
+            n,
+            k0,
+            sum_d: sum / d,
+            a_k0_n_pow_n: a * k0 * n_pow_n,
+            u: (SignedDecimal256::from(sum) - d) / d,
+            g,
+            dg_dk0,
+        }
+    }
+}
+
+/// The invariant multiplied by (gamma + 1 - K0)^2 / (gamma^2 * (D/n)^n):
+/// A * K0 * n^n * (sum(x) - D) / D + (K0 - 1) * ((gamma + 1 - K0) / gamma)^2
+///
+/// Unlike the original form it has no pole at K0 = gamma + 1 and keeps precision for small gamma.
+pub(crate) fn f(
+    d: Decimal256,
+    x: &[Decimal256],
+    a: Decimal256,
+    gamma: Decimal256,
+) -> SignedDecimal256 {
+    let t = Terms::new(d, x, a, gamma);
+
+    t.a_k0_n_pow_n * t.u + (SignedDecimal256::from(t.k0) - Decimal256::one()) * t.g.pow(2)
+}
+
+/// D * df/dD
+pub(crate) fn d_df_dd(
+    d: Decimal256,
+    x: &[Decimal256],
+    a: Decimal256,
+    gamma: Decimal256,
+) -> SignedDecimal256 {
+    let t = Terms::new(d, x, a, gamma);
+
+    // D * dK0/dD = -n * K0
+    -(t.a_k0_n_pow_n * t.n * t.u) - t.a_k0_n_pow_n * t.sum_d - t.n * t.k0 * t.dg_dk0
+}
+
+/// x_i * df/dx_i
+pub(crate) fn x_df_dx(
+    d: Decimal256,
+    x: &[Decimal256],
+    a: Decimal256,
+    gamma: Decimal256,
+    i: usize,
+) -> SignedDecimal256 {
+    let t = Terms::new(d, x, a, gamma);
+
+    // x_i * dK0/dx_i = K0
+    t.a_k0_n_pow_n * t.u + t.a_k0_n_pow_n * x[i] / d + t.k0 * t.dg_dk0
+}
+
+pub(crate) fn newton_d(
+    x: &[Decimal256],
+    a: Decimal256,
+    gamma: Decimal256,
+) -> Result<Decimal256, PclError> {
+    let n = Decimal256::from_ratio(x.len() as u128, 1u8);
+    let mut d_prev = n * geometric_mean(x)?;
+
+    for _ in 0..MAX_ITER {
+        let derivative = d_df_dd(d_prev, x, a, gamma);
+        if derivative.is_zero() {
+            break;
+        }
+        let step = d_prev * f(d_prev, x, a, gamma) / derivative;
+        // D must stay positive. Otherwise, it is halved
+        let d = (SignedDecimal256::from(d_prev) - step)
+            .try_into()
+            .ok()
+            .filter(|d: &Decimal256| !d.is_zero())
+            .unwrap_or(d_prev / TWO);
+        if d.abs_diff(d_prev) <= TOL {
+            return Ok(d);
+        }
+        d_prev = d;
+    }
+
+    Err(PclError::NotConverging("newton_d".to_string()))
+}
+
+pub(crate) fn newton_y(
+    xs: &[Decimal256],
+    a: Decimal256,
+    gamma: Decimal256,
+    d: Decimal256,
+    j: usize,
+) -> Result<Decimal256, PclError> {
+    let n = Decimal256::from_ratio(xs.len() as u128, 1u8);
+    let mut x = xs.to_vec();
+
+    // x_j such that K0 = 1
+    let mut xi_1 = xs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != j)
+        .fold(d / n, |acc, (_, x_i)| acc * d / (n * *x_i));
+    x[j] = xi_1;
+
+    for _ in 0..MAX_ITER {
+        let derivative = x_df_dx(d, &x, a, gamma, j);
+        if derivative.is_zero() {
+            break;
+        }
+        let step = xi_1 * f(d, &x, a, gamma) / derivative;
+        // x_j must stay positive. Otherwise, it is halved
+        let xi = (SignedDecimal256::from(xi_1) - step)
+            .try_into()
+            .ok()
+            .filter(|xi: &Decimal256| !xi.is_zero())
+            .unwrap_or(xi_1 / TWO);
+        if xi.abs_diff(xi_1) <= TOL {
+            return Ok(xi);
+        }
+        x[j] = xi;
+        xi_1 = xi;
+    }
+
+    Err(PclError::NotConverging("newton_y".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use crate::math::math_f64::{calc_d_n, calc_y_n};
+    use crate::math::{get_xcp, get_xcp_n};
+
+    use super::*;
+
+    fn f64_to_dec(val: f64) -> Decimal256 {
+        Decimal256::from_str(&val.to_string()).unwrap()
+    }
+
+    fn dec_to_f64(val: impl Display) -> f64 {
+        f64::from_str(&val.to_string()).unwrap()
+    }
+
+    fn assert_values(dec: impl Display, f64_val: f64) {
+        let dec_val = dec_to_f64(dec);
+        if (dec_val - f64_val).abs() > 0.001f64 {
+            assert_eq!(dec_val, f64_val)
+        }
+    }
+
+    fn compute(x: &[f64], a: f64, gamma: f64) {
+        let x_dec = x.iter().map(|x_i| f64_to_dec(*x_i)).collect::<Vec<_>>();
+        let a_dec = f64_to_dec(a);
+        let gamma_dec = f64_to_dec(gamma);
+
+        let d_f64 = calc_d_n(x, a, gamma);
+        let d_dec = newton_d(&x_dec, a_dec, gamma_dec).unwrap();
+        assert_values(d_dec, d_f64);
+
+        for offer_ind in 0..x.len() {
+            let ask_ind = (offer_ind + 1) % x.len();
+            let mut xs = x.to_vec();
+            xs[offer_ind] *= 1.1;
+            let xs_dec = xs.iter().map(|x_i| f64_to_dec(*x_i)).collect::<Vec<_>>();
+
+            let y_f64 = calc_y_n(&xs, a, gamma, d_f64, ask_ind);
+            let y_dec = newton_y(&xs_dec, a_dec, gamma_dec, d_dec, ask_ind).unwrap();
+            assert_values(y_dec, y_f64);
+        }
+    }
+
+    #[test]
+    fn test_three_coins() {
+        compute(&[1000f64, 1000f64, 1000f64], 40f64, 0.000145);
+        compute(&[1_000_000f64, 500_000f64, 2_000_000f64], 40f64, 0.000145);
+        compute(&[1000f64, 2000f64, 500f64], 3500f64, 0.000145);
+        compute(&[1e9, 1.5e9, 0.8e9], 10f64, 0.00001);
+        compute(&[10f64, 25f64, 5f64], 1f64, 0.02);
+    }
+
+    #[test]
+    fn test_four_coins() {
+        compute(&[1000f64, 1200f64, 900f64, 1000f64], 100f64, 0.0001);
+        compute(
+            &[50_000f64, 10_000f64, 20_000f64, 40_000f64],
+            10f64,
+            0.000145,
+        );
+    }
+
+    #[test]
+    fn test_geometric_mean() {
+        let x = [
+            f64_to_dec(1000f64),
+            f64_to_dec(8000f64),
+            f64_to_dec(27000f64),
+        ];
+        assert_values(geometric_mean(&x).unwrap(), 6000f64);
+
+        let d = f64_to_dec(2_000_000f64);
+        let price_scale = f64_to_dec(2f64);
+        assert_values(
+            get_xcp_n(d, &[price_scale]).unwrap(),
+            dec_to_f64(get_xcp(d, price_scale)),
+        );
+    }
+}
@@ -1,22 +1,64 @@
-use cosmwasm_std::{Decimal256, StdResult};
+use cosmwasm_std::Decimal256;
 
-use crate::consts::N;
-use crate::math::math_decimal::{geometric_mean, newton_d, newton_y};
+use crate::consts::{MIN_POOL_VOLUME, N, POOL_VOLUMES_SAFETY_MARGIN, TWO};
+use crate::error::PclError;
+use crate::math::math_decimal::{geometric_mean, newton_d, newton_y, PADDING};
 use crate::state::AmpGamma;
 
 mod math_decimal;
 #[cfg(test)]
 mod math_f64;
+mod math_n_coins;
+#[cfg(test)]
+mod reference;
 mod signed_decimal;
 
 pub use math_decimal::half_float_pow;
 
+/// Checks that the Newton solvers neither overflow nor divide by zero on the given pool volumes.
+/// D never exceeds the sum of pool volumes thus the sum bounds all intermediate values.
+/// The largest intermediate values of the 2-coin df/dD are 1e18 * D^3 * (gamma + 1)^3
+/// and 1e18 * D^3 * 2 * A * gamma^2 * (gamma + 2) so the upper bound depends on current amp and gamma.
+/// The N-coin solvers work with normalized values and are only bounded from below.
+/// This check must be applied only to swaps and provides so liquidity can always be withdrawn.
+///
+/// * **xs** - internal representation of pool volumes.
+///
+/// * **amp_gamma** - an object which represents current Amp and Gamma parameters.
+pub fn assert_safe_pool_volumes(xs: &[Decimal256], amp_gamma: &AmpGamma) -> Result<(), PclError> {
+    if xs.iter().any(|x| *x < MIN_POOL_VOLUME) {
+        return Err(PclError::UnsafePoolVolumes {});
+    }
+
+    if xs.len() == 2 {
+        let amp: Decimal256 = amp_gamma.amp.into();
+        let gamma: Decimal256 = amp_gamma.gamma.into();
+        let factor = (gamma + Decimal256::one())
+            .pow(3)
+            .max(TWO * amp * gamma.pow(2) * (gamma + TWO));
+
+        xs[0]
+            .checked_add(xs[1])
+            .and_then(|sum| sum.checked_pow(3))
+            .and_then(|sum_pow3| sum_pow3.checked_mul(factor))
+            .and_then(|val| val.checked_mul(PADDING))
+            .and_then(|val| val.checked_mul(POOL_VOLUMES_SAFETY_MARGIN))
+            .map_err(|_| PclError::UnsafePoolVolumes {})?;
+    }
+
+    Ok(())
+}
+
 /// Calculate D invariant based on known pool volumes.
 ///
 /// * **xs** - internal representation of pool volumes.
 /// * **amp_gamma** - an object which represents current Amp and Gamma parameters.
-pub fn calc_d(xs: &[Decimal256], amp_gamma: &AmpGamma) -> StdResult<Decimal256> {
-    newton_d(xs, amp_gamma.amp.into(), amp_gamma.gamma.into())
+pub fn calc_d(xs: &[Decimal256], amp_gamma: &AmpGamma) -> Result<Decimal256, PclError> {
+    if xs.len() == 2 {
+        newton_d(xs, amp_gamma.amp.into(), amp_gamma.gamma.into())
+    } else {
+        math_n_coins::newton_d(xs, amp_gamma.amp.into(), amp_gamma.gamma.into())
+    }
 }
 
 /// Calculate unknown pool's volume based on the other side of pools which is known and D.
@@ -30,8 +72,12 @@ pub fn calc_y(
     d: Decimal256,
     amp_gamma: &AmpGamma,
     ask_ind: usize,
-) -> StdResult<Decimal256> {
-    newton_y(xs, amp_gamma.amp.into(), amp_gamma.gamma.into(), d, ask_ind)
+) -> Result<Decimal256, PclError> {
+    if xs.len() == 2 {
+        newton_y(xs, amp_gamma.amp.into(), amp_gamma.gamma.into(), d, ask_ind)
+    } else {
+        math_n_coins::newton_y(xs, amp_gamma.amp.into(), amp_gamma.gamma.into(), d, ask_ind)
+    }
 }
 
 /// Get current XCP.
@@ -41,3 +87,187 @@ pub fn get_xcp(d: Decimal256, price_scale: Decimal256) -> Decimal256 {
     let xs = [d / N, d / (N * price_scale)];
     geometric_mean(&xs)
 }
+
+/// Get current XCP of a pool with an arbitrary number of assets.
+/// * **d** - internal D invariant.
+/// * **price_scales** - prices of all assets except the first one denominated in the first asset.
+pub fn get_xcp_n(d: Decimal256, price_scales: &[Decimal256]) -> Result<Decimal256, PclError> {
+    let n = Decimal256::from_ratio(price_scales.len() as u128 + 1, 1u8);
+    let xs = std::iter::once(d / n)
+        .chain(
+            price_scales
+                .iter()
+                .map(|price_scale| d / (n * *price_scale)),
+        )
+        .collect::<Vec<_>>();
+    math_n_coins::geometric_mean(&xs)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Decimal;
+    use proptest::prelude::*;
+
+    use crate::consts::{AMP_MAX, AMP_MIN, GAMMA_MAX, GAMMA_MIN, TOL};
+
+    use super::*;
+
+    fn to_dec(val: f64) -> Decimal256 {
+        Decimal256::from_atomics((val * 1e6) as u128, 6).unwrap()
+    }
+
+    /// Pool volumes are generated as x0 * 10^ratio in order to cover imbalanced pools.
+    fn pool_volumes(x0_pow: f64, ratios: &[f64]) -> Vec<Decimal256> {
+        let x0 = 10f64.powf(x0_pow);
+        std::iter::once(x0)
+            .chain(ratios.iter().map(|ratio| x0 * 10f64.powf(*ratio)))
+            .map(to_dec)
+            .collect()
+    }
+
+    /// Relative error must not exceed TOL. Values less than 1 are compared with absolute TOL.
+    fn assert_close(val: Decimal256, reference: Decimal256) {
+        let max_diff = TOL * reference.max(Decimal256::one());
+        assert!(
+            val.abs_diff(reference) <= max_diff,
+            "{val} differs from reference value {reference}"
+        );
+    }
+
+    #[test]
+    fn check_unsafe_pool_volumes() {
+        let amp_gamma = AmpGamma {
+            amp: AMP_MAX,
+            gamma: GAMMA_MAX,
+        };
+        let xs = [to_dec(1000f64), to_dec(1000f64)];
+        assert_safe_pool_volumes(&xs, &amp_gamma).unwrap();
+
+        let err = assert_safe_pool_volumes(&[Decimal256::zero(), xs[1]], &amp_gamma).unwrap_err();
+        assert_eq!(err, PclError::UnsafePoolVolumes {});
+        let err =
+            assert_safe_pool_volumes(&[xs[0], MIN_POOL_VOLUME - Decimal256::raw(1)], &amp_gamma)
+                .unwrap_err();
+        assert_eq!(err, PclError::UnsafePoolVolumes {});
+
+        // The most extreme parameters allow pools up to about 4.47e12
+        let xs = [to_dec(4.4e12) - MIN_POOL_VOLUME, MIN_POOL_VOLUME];
+        assert_safe_pool_volumes(&xs, &amp_gamma).unwrap();
+        let _ = calc_d(&xs, &amp_gamma);
+        let xs = [to_dec(2.2e12), to_dec(2.2e12)];
+        assert_safe_pool_volumes(&xs, &amp_gamma).unwrap();
+        let d = calc_d(&xs, &amp_gamma).unwrap();
+        calc_y(&xs, d, &amp_gamma, 1).unwrap();
+        let err =
+            assert_safe_pool_volumes(&[to_dec(2.5e12), to_dec(2.5e12)], &amp_gamma).unwrap_err();
+        assert_eq!(err, PclError::UnsafePoolVolumes {});
+
+        // Pools with common parameters are allowed to grow up to about 2.43e13
+        let amp_gamma = AmpGamma {
+            amp: Decimal::from_ratio(40u8, 1u8),
+            gamma: Decimal::from_ratio(145u16, 1000000u32),
+        };
+        let xs = [to_dec(1e13), to_dec(1e13)];
+        assert_safe_pool_volumes(&xs, &amp_gamma).unwrap();
+        let d = calc_d(&xs, &amp_gamma).unwrap();
+        let new_xs = [to_dec(1.001e13), xs[1]];
+        assert_safe_pool_volumes(&new_xs, &amp_gamma).unwrap();
+        calc_y(&new_xs, d, &amp_gamma, 1).unwrap();
+        let err = assert_safe_pool_volumes(&[to_dec(2e13), to_dec(2e13)], &amp_gamma).unwrap_err();
+        assert_eq!(err, PclError::UnsafePoolVolumes {});
+
+        // N-coin pools are bounded only from below
+        let xs = [to_dec(1e15), to_dec(1e15), to_dec(1e15)];
+        assert_safe_pool_volumes(&xs, &amp_gamma).unwrap();
+    }
+
+    #[test]
+    fn check_not_converging() {
+        let amp_gamma = AmpGamma {
+            amp: Decimal::from_ratio(10u8, 1u8),
+            gamma: Decimal::from_ratio(1u8, 1000000u32),
+        };
+        let xs = [to_dec(1e9), to_dec(1e9)];
+        let d = calc_d(&xs, &amp_gamma).unwrap();
+        let new_xs = [to_dec(1.01e9), xs[1]];
+
+        let err =
+            newton_y(&new_xs, amp_gamma.amp.into(), amp_gamma.gamma.into(), d, 1).unwrap_err();
+        assert_eq!(err, PclError::NotConverging("newton_y".to_string()));
+
+        let err = calc_y(&new_xs, d, &amp_gamma, 1).unwrap_err();
+        assert_eq!(err, PclError::NotConverging("newton_y".to_string()));
+    }
+
+    proptest! {
+        /// Small gamma values are excluded since gamma^2 doesn't fit into 18 decimals
+        /// and the 2-coin invariant loses precision.
+        #[test]
+        fn calc_d_and_y_match_reference(
+            x0_pow in 1.5f64..6.5f64,
+            ratios in prop::collection::vec(-1.5f64..1.5f64, 1..=2),
+            amp in AMP_MIN.atomics().u128()..=AMP_MAX.atomics().u128(),
+            gamma in 50_000_000_000_000u128..=GAMMA_MAX.atomics().u128(),
+            offer_ind in 0usize..3,
+            offer_percent in 1u64..=100,
+        ) {
+            let xs = pool_volumes(x0_pow, &ratios);
+            let amp_gamma = AmpGamma {
+                amp: Decimal::raw(amp),
+                gamma: Decimal::raw(gamma),
+            };
+            let (amp, gamma) = (amp_gamma.amp.into(), amp_gamma.gamma.into());
+
+            let d = calc_d(&xs, &amp_gamma).unwrap();
+            assert_close(d, reference::calc_d(&xs, amp, gamma));
+
+            let offer_ind = offer_ind % xs.len();
+            let ask_ind = (offer_ind + 1) % xs.len();
+            let mut new_xs = xs.clone();
+            new_xs[offer_ind] += xs[offer_ind] * Decimal256::percent(offer_percent);
+
+            let y = calc_y(&new_xs, d, &amp_gamma, ask_ind).unwrap();
+            assert_close(y, reference::calc_y(&new_xs, amp, gamma, d, ask_ind));
+        }
+
+        /// Swaps and provides can't make solvers panic if pool volumes pass the safety check.
+        #[test]
+        fn solvers_never_panic(
+            x0_pow in -5f64..13f64,
+            ratios in prop::collection::vec(-6f64..6f64, 1..=2),
+            amp in prop_oneof![
+                Just(AMP_MIN.atomics().u128()),
+                Just(AMP_MAX.atomics().u128()),
+                AMP_MIN.atomics().u128()..=AMP_MAX.atomics().u128(),
+            ],
+            gamma in prop_oneof![
+                Just(GAMMA_MIN.atomics().u128()),
+                Just(GAMMA_MAX.atomics().u128()),
+                GAMMA_MIN.atomics().u128()..=GAMMA_MAX.atomics().u128(),
+            ],
+            offer_ind in 0usize..3,
+            offer_pow in -3f64..3f64,
+        ) {
+            let xs = pool_volumes(x0_pow, &ratios);
+            let amp_gamma = AmpGamma {
+                amp: Decimal::raw(amp),
+                gamma: Decimal::raw(gamma),
+            };
+
+            let offer_ind = offer_ind % xs.len();
+            let ask_ind = (offer_ind + 1) % xs.len();
+            let mut new_xs = xs.clone();
+            new_xs[offer_ind] += xs[offer_ind] * to_dec(10f64.powf(offer_pow));
+
+            if assert_safe_pool_volumes(&xs, &amp_gamma).is_err()
+                || assert_safe_pool_volumes(&new_xs, &amp_gamma).is_err()
+            {
+                return Ok(());
+            }
+
+            if let Ok(d) = calc_d(&xs, &amp_gamma) {
+                let _ = calc_y(&new_xs, d, &amp_gamma, ask_ind);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Integer-only reference implementation of the PCL invariant which is used to validate Newton solvers.
+//! Numbers are fixed point values with 36 decimals stored in [`Uint512`]. Roots are found with bisection
+//! which is slow but can't diverge.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use cosmwasm_std::{Decimal256, Uint256, Uint512};
+
+/// Multiplier between [`Decimal256`] atomics and the reference fixed point representation.
+const SHIFT: u128 = 1_000000000000000000;
+/// 1.0 in the reference fixed point representation.
+const ONE: u128 = 1_000000000000000000_000000000000000000;
+/// The maximum number of bisection steps. Each step halves the search interval.
+const MAX_STEPS: usize = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Fixed(Uint512);
+
+impl Fixed {
+    fn one() -> Self {
+        Self(ONE.into())
+    }
+
+    fn from_int(val: usize) -> Self {
+        Self(Uint512::from(val as u128) * Uint512::from(ONE))
+    }
+
+    fn diff(self, rhs: Self) -> Self {
+        if self > rhs {
+            self - rhs
+        } else {
+            rhs - self
+        }
+    }
+}
+
+impl From<Decimal256> for Fixed {
+    fn from(val: Decimal256) -> Self {
+        Self(Uint512::from(val.atomics()) * Uint512::from(SHIFT))
+    }
+}
+
+impl From<Fixed> for Decimal256 {
+    fn from(val: Fixed) -> Self {
+        Decimal256::new(Uint256::try_from(val.0 / Uint512::from(SHIFT)).unwrap())
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0 / Uint512::from(ONE))
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self(self.0 * Uint512::from(ONE) / rhs.0)
+    }
+}
+
+/// Finds the boundary between `lo` and `hi` where `pred` switches from true to false.
+fn bisect(mut lo: Fixed, mut hi: Fixed, mut pred: impl FnMut(Fixed) -> bool) -> Fixed {
+    let two = Fixed::from_int(2);
+    for _ in 0..MAX_STEPS {
+        if hi - lo <= Fixed(Uint512::one()) {
+            break;
+        }
+        let mid = (lo + hi) / two;
+        if pred(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Returns true if the invariant
+/// A * K0 * n^n * (sum(x) - D) / D + (K0 - 1) * ((gamma + 1 - K0) / gamma)^2
+/// is non-negative. Its roots are the same as the ones of the original Curve V2 invariant.
+fn invariant_is_non_negative(d: Fixed, x: &[Fixed], amp: Fixed, gamma: Fixed) -> bool {
+    let n = Fixed::from_int(x.len());
+    let n_pow_n = (1..x.len()).fold(n, |acc, _| acc * n);
+    let sum = x.iter().fold(Fixed(Uint512::zero()), |acc, x_i| acc + *x_i);
+    let k0 = x.iter().fold(Fixed::one(), |acc, x_i| acc * n * *x_i / d);
+    let g = (gamma + Fixed::one()).diff(k0) / gamma;
+    let g_pow2 = g * g;
+    let a_k0_n_pow_n = amp * k0 * n_pow_n;
+
+    a_k0_n_pow_n * sum / d + k0 * g_pow2 >= a_k0_n_pow_n + g_pow2
+}
+
+/// Calculates D. It lies between n * geometric_mean(x) (where the invariant is non-negative)
+/// and sum(x) (where the invariant is non-positive).
+pub fn calc_d(xs: &[Decimal256], amp: Decimal256, gamma: Decimal256) -> Decimal256 {
+    let x = xs.iter().map(|x_i| Fixed::from(*x_i)).collect::<Vec<_>>();
+    let (amp, gamma) = (amp.into(), gamma.into());
+    let n = Fixed::from_int(x.len());
+
+    let min = *x.iter().min().unwrap();
+    let max = *x.iter().max().unwrap();
+    let geometric_mean = bisect(min, max + Fixed::one(), |m| {
+        x.iter().fold(Fixed::one(), |acc, x_i| acc * *x_i / m) >= Fixed::one()
+    });
+
+    let lo = n * geometric_mean;
+    let hi = x.iter().fold(Fixed(Uint512::zero()), |acc, x_i| acc + *x_i);
+
+    bisect(lo, hi, |d| invariant_is_non_negative(d, &x, amp, gamma)).into()
+}
+
+/// Calculates the unknown volume x_j. It lies between D - sum(x_i, i != j) (where the invariant is non-positive)
+/// and the value at which K0 = 1 (where the invariant is non-negative).
+pub fn calc_y(
+    xs: &[Decimal256],
+    amp: Decimal256,
+    gamma: Decimal256,
+    d: Decimal256,
+    j: usize,
+) -> Decimal256 {
+    let mut x = xs.iter().map(|x_i| Fixed::from(*x_i)).collect::<Vec<_>>();
+    let (amp, gamma, d) = (amp.into(), gamma.into(), d.into());
+    let n = Fixed::from_int(x.len());
+
+    let others = x
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != j)
+        .map(|(_, x_i)| *x_i)
+        .collect::<Vec<_>>();
+    let others_sum = others
+        .iter()
+        .fold(Fixed(Uint512::zero()), |acc, x_i| acc + *x_i);
+    let lo = if d > others_sum {
+        d - others_sum
+    } else {
+        Fixed(Uint512::one())
+    };
+    let hi = others.iter().fold(d / n, |acc, x_i| acc * d / (n * *x_i));
+
+    bisect(lo, hi, |y| {
+        x[j] = y;
+        !invariant_is_non_negative(d, &x, amp, gamma)
+    })
+    .into()
+}
@@ -31,6 +31,9 @@ impl SignedDecimal256 {
             }
         }
     }
+    pub fn is_zero(&self) -> bool {
+        self.val.is_zero()
+    }
     pub fn diff(self, other: SignedDecimal256) -> Decimal256 {
         if self.neg == other.neg {
             self.val.abs_diff(other.val)
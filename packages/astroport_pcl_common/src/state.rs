@@ -19,11 +19,11 @@ use crate::consts::{
     PRICE_SCALE_DELTA_MIN, REPEG_PROFIT_THRESHOLD_MAX, REPEG_PROFIT_THRESHOLD_MIN, TWO,
 };
 use crate::error::PclError;
-use crate::math::{calc_d, get_xcp, half_float_pow};
+use crate::math::{calc_d, get_xcp, get_xcp_n, half_float_pow};
 
 /// This structure stores the concentrated pair parameters.
 #[cw_serde]
-pub struct Config {
+pub struct Config<P = PriceState> {
     /// The pair information stored in a [`PairInfo`] struct
     pub pair_info: PairInfo,
     /// The factory contract address
@@ -31,7 +31,7 @@ pub struct Config {
     /// Pool parameters
     pub pool_params: PoolParams,
     /// Pool state
-    pub pool_state: PoolState,
+    pub pool_state: PoolState<P>,
     /// Pool's owner
     pub owner: Option<Addr>,
     /// Whether asset balances are tracked over blocks or not.
@@ -154,8 +154,16 @@ impl PoolParams {
 
     pub fn fee(&self, xp: &[Decimal256]) -> Decimal256 {
         let fee_gamma: Decimal256 = self.fee_gamma.into();
-        let sum = xp[0] + xp[1];
-        let mut k = xp[0] * xp[1] * N_POW2 / sum.pow(2);
+        let mut k = if xp.len() == 2 {
+            let sum = xp[0] + xp[1];
+            xp[0] * xp[1] * N_POW2 / sum.pow(2)
+        } else {
+            // n^n * prod(x) / sum(x)^n
+            let n = Decimal256::from_ratio(xp.len() as u128, 1u8);
+            let sum = xp.iter().fold(Decimal256::zero(), |acc, x| acc + *x);
+            xp.iter()
+                .fold(Decimal256::one(), |acc, x| acc * n * *x / sum)
+        };
         k = fee_gamma / (fee_gamma + Decimal256::one() - k);
 
         if k <= FEE_TOL {
@@ -205,9 +213,30 @@ pub struct PriceState {
     pub xcp_profit_real: Decimal256,
 }
 
+/// Internal structure which stores the price state of a pool with more than two assets.
+/// All prices are denominated in the 1st asset thus each vector has one element less than the pool.
+/// This structure cannot be updated via update_config.
+#[cw_serde]
+#[derive(Default)]
+pub struct MultiPriceState {
+    /// Internal oracle prices
+    pub oracle_prices: Vec<Decimal256>,
+    /// The last saved prices
+    pub last_prices: Vec<Decimal256>,
+    /// Current price scales of all assets except the 1st one.
+    /// I.e. such C_i that x = C_i * y_i where x - 1st asset, y_i - (i + 1)th asset.
+    pub price_scales: Vec<Decimal256>,
+    /// Last timestamp when the oracle prices were updated.
+    pub last_price_update: u64,
+    /// Keeps track of positive change in xcp due to fees accruing
+    pub xcp_profit: Decimal256,
+    /// Profits due to fees inclusive of realized losses from rebalancing
+    pub xcp_profit_real: Decimal256,
+}
+
 /// Internal structure which stores the pool's state.
 #[cw_serde]
-pub struct PoolState {
+pub struct PoolState<P = PriceState> {
     /// Initial Amp and Gamma
     pub initial: AmpGamma,
     /// Future Amp and Gamma
@@ -217,10 +246,10 @@ pub struct PoolState {
     /// Timestamp when Amp and Gamma started being changed
     pub initial_time: u64,
     /// Current price state
-    pub price_state: PriceState,
+    pub price_state: P,
 }
 
-impl PoolState {
+impl<P> PoolState<P> {
     /// Validates Amp and Gamma promotion parameters.
     /// Saves current values in self.initial and setups self.future.
     /// If amp and gamma are being changed then current values will be used as initial values.
@@ -290,7 +319,9 @@ impl PoolState {
             }
         }
     }
+}
 
+impl PoolState {
     /// The function is responsible for repegging mechanism.
     /// It updates internal oracle price and adjusts price scale.
     ///
@@ -304,7 +335,7 @@ impl PoolState {
         total_lp: Decimal256,
         cur_xs: &[Decimal256],
         cur_price: Decimal256,
-    ) -> StdResult<()> {
+    ) -> Result<(), PclError> {
         //println!("getting price state");
         let amp_gamma = self.get_amp_gamma(env);
         let block_time = env.block.time.seconds();
@@ -335,7 +366,8 @@ impl PoolState {
             if xcp_profit_real < price_state.xcp_profit_real && block_time >= self.future_time {
                 return Err(StdError::generic_err(
                     "XCP profit real value dropped. This action makes loss",
-                ));
+                )
+                .into());
             }
 
             price_state.xcp_profit =
@@ -377,6 +409,118 @@ impl PoolState {
     }
 }
 
+impl PoolState<MultiPriceState> {
+    /// The repegging mechanism for pools with more than two assets.
+    /// It updates internal oracle prices and moves all price scales towards them at once.
+    ///
+    /// * **total_lp** total LP tokens were minted
+    /// * **cur_xs** - internal representation of pool volumes
+    /// * **cur_prices** - last prices happened in the previous action (swap, provide or withdraw)
+    pub fn update_price(
+        &mut self,
+        pool_params: &PoolParams,
+        env: &Env,
+        total_lp: Decimal256,
+        cur_xs: &[Decimal256],
+        cur_prices: &[Decimal256],
+    ) -> Result<(), PclError> {
+        let amp_gamma = self.get_amp_gamma(env);
+        let block_time = env.block.time.seconds();
+        let price_state = &mut self.price_state;
+
+        if price_state.last_price_update < block_time {
+            let arg = Decimal256::from_ratio(
+                block_time - price_state.last_price_update,
+                pool_params.ma_half_time,
+            );
+            let alpha = half_float_pow(arg)?;
+            price_state.oracle_prices = price_state
+                .last_prices
+                .iter()
+                .zip(&price_state.oracle_prices)
+                .map(|(last_price, oracle_price)| {
+                    *last_price * (Decimal256::one() - alpha) + *oracle_price * alpha
+                })
+                .collect();
+            price_state.last_price_update = block_time;
+        }
+        price_state.last_prices = cur_prices.to_vec();
+
+        let cur_d = calc_d(cur_xs, &amp_gamma)?;
+
+        let xcp = get_xcp_n(cur_d, &price_state.price_scales)?;
+        if !price_state.xcp_profit_real.is_zero() {
+            let xcp_profit_real = xcp / total_lp;
+
+            // If xcp dropped and no ramping happens then this swap makes loss
+            if xcp_profit_real < price_state.xcp_profit_real && block_time >= self.future_time {
+                return Err(StdError::generic_err(
+                    "XCP profit real value dropped. This action makes loss",
+                )
+                .into());
+            }
+
+            price_state.xcp_profit =
+                price_state.xcp_profit * xcp_profit_real / price_state.xcp_profit_real;
+            price_state.xcp_profit_real = xcp_profit_real;
+        }
+
+        let xcp_profit = price_state.xcp_profit;
+        // Euclidean distance between oracle prices and price scales relative to the latter
+        let norm = price_state
+            .oracle_prices
+            .iter()
+            .zip(&price_state.price_scales)
+            .fold(Decimal256::zero(), |acc, (oracle_price, price_scale)| {
+                acc + (*oracle_price / *price_scale)
+                    .diff(Decimal256::one())
+                    .pow(2)
+            })
+            .sqrt();
+        let scale_delta = Decimal256::from(pool_params.min_price_scale_delta)
+            .max(norm * Decimal256::from_ratio(1u8, 10u8));
+
+        if !norm.is_zero()
+            && norm >= scale_delta
+            && price_state.xcp_profit_real - Decimal256::one()
+                > (xcp_profit - Decimal256::one()) / TWO
+                    + Decimal256::from(pool_params.repeg_profit_threshold)
+        {
+            let price_scales_new = price_state
+                .price_scales
+                .iter()
+                .zip(&price_state.oracle_prices)
+                .map(|(price_scale, oracle_price)| {
+                    (*price_scale * (norm - scale_delta) + scale_delta * *oracle_price) / norm
+                })
+                .collect::<Vec<_>>();
+
+            let xs = cur_xs
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    if i == 0 {
+                        *x
+                    } else {
+                        *x * price_scales_new[i - 1] / price_state.price_scales[i - 1]
+                    }
+                })
+                .collect::<Vec<_>>();
+            let new_d = calc_d(&xs, &amp_gamma)?;
+
+            let new_xcp = get_xcp_n(new_d, &price_scales_new)?;
+            let new_xcp_profit_real = new_xcp / total_lp;
+
+            if TWO * new_xcp_profit_real > xcp_profit + Decimal256::one() {
+                price_state.price_scales = price_scales_new;
+                price_state.xcp_profit_real = new_xcp_profit_real;
+            };
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Precisions(Vec<(String, u8)>);
 
 impl<'a> Precisions {
@@ -468,7 +612,7 @@ mod test {
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(86400);
 
-        let mut state = PoolState {
+        let mut state: PoolState = PoolState {
             initial: AmpGamma {
                 amp: Decimal::zero(),
                 gamma: Decimal::zero(),
@@ -504,7 +648,7 @@ mod test {
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(86400);
 
-        let mut state = PoolState {
+        let mut state: PoolState = PoolState {
             initial: AmpGamma {
                 amp: Decimal::zero(),
                 gamma: Decimal::zero(),
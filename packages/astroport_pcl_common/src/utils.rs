@@ -7,13 +7,45 @@ use itertools::Itertools;
 
 use astroport::asset::{Asset, AssetInfo, DecimalAsset};
 use astroport::cosmwasm_ext::AbsDiff;
-use astroport::querier::query_factory_config;
+use astroport::querier::{query_factory_config, query_supply};
 use astroport_factory::state::pair_key;
 
 use crate::consts::{DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, N, OFFER_PERCENT, TWO};
 use crate::error::PclError;
-use crate::state::{Config, PoolParams, PriceState};
-use crate::{calc_d, calc_y};
+use crate::state::{Config, PoolParams, Precisions, PriceState};
+use crate::{assert_safe_pool_volumes, calc_d, calc_y};
+
+/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
+pub fn pool_info<P>(
+    querier: QuerierWrapper,
+    config: &Config<P>,
+) -> StdResult<(Vec<Asset>, Uint128)> {
+    let pools = config
+        .pair_info
+        .query_pools(&querier, &config.pair_info.contract_addr)?;
+    let total_share = query_supply(&querier, &config.pair_info.liquidity_token)?;
+
+    Ok((pools, total_share))
+}
+
+/// Returns current pool's volumes where amount is in [`Decimal256`] form.
+pub fn query_pools<P>(
+    querier: QuerierWrapper,
+    addr: &Addr,
+    config: &Config<P>,
+    precisions: &Precisions,
+) -> Result<Vec<DecimalAsset>, PclError> {
+    config
+        .pair_info
+        .query_pools(&querier, addr)?
+        .into_iter()
+        .map(|asset| {
+            asset
+                .to_decimal_asset(precisions.get_precision(&asset.info)?)
+                .map_err(Into::into)
+        })
+        .collect()
+}
 
 /// Helper function to check the given asset infos are valid.
 pub fn check_asset_infos(api: &dyn Api, asset_infos: &[AssetInfo]) -> Result<(), PclError> {
@@ -36,7 +68,7 @@ pub fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), PclError> {
 /// Checks that cw20 token is part of the pool.
 ///
 /// * **cw20_sender** is cw20 token address which is being checked.
-pub fn check_cw20_in_pool(config: &Config, cw20_sender: &Addr) -> Result<(), PclError> {
+pub fn check_cw20_in_pool<P>(config: &Config<P>, cw20_sender: &Addr) -> Result<(), PclError> {
     for asset_info in &config.pair_info.asset_infos {
         match asset_info {
             AssetInfo::Token { contract_addr } if contract_addr == cw20_sender => return Ok(()),
@@ -55,9 +87,9 @@ pub fn check_cw20_in_pool(config: &Config, cw20_sender: &Addr) -> Result<(), Pcl
 ///
 /// * **auto_stake** determines whether the newly minted LP tokens will
 /// be automatically staked in the Generator on behalf of the recipient.
-pub fn mint_liquidity_token_message<T, C>(
+pub fn mint_liquidity_token_message<T, C, P>(
     querier: QuerierWrapper<C>,
-    config: &Config,
+    config: &Config<P>,
     contract_address: &Addr,
     recipient: &Addr,
     amount: Uint128,
@@ -222,7 +254,11 @@ impl SwapResult {
 }
 
 /// Performs swap simulation to calculate a price.
-pub fn calc_last_prices(xs: &[Decimal256], config: &Config, env: &Env) -> StdResult<Decimal256> {
+pub fn calc_last_prices(
+    xs: &[Decimal256],
+    config: &Config,
+    env: &Env,
+) -> Result<Decimal256, PclError> {
     let mut offer_amount = Decimal256::one().min(xs[0] * OFFER_PERCENT);
     if offer_amount.is_zero() {
         offer_amount = Decimal256::raw(1u128);
@@ -251,13 +287,14 @@ pub fn compute_swap(
     env: &Env,
     maker_fee_share: Decimal256,
     share_fee_share: Decimal256,
-) -> StdResult<SwapResult> {
+) -> Result<SwapResult, PclError> {
     let offer_ind = 1 ^ ask_ind;
 
     let mut ixs = xs.to_vec();
     ixs[1] *= config.pool_state.price_state.price_scale;
 
     let amp_gamma = config.pool_state.get_amp_gamma(env);
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
     let d = calc_d(&ixs, &amp_gamma)?;
 
     if offer_ind == 1 {
@@ -265,6 +302,7 @@ pub fn compute_swap(
     } else {
         ixs[offer_ind] += offer_amount;
     }
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
 
     let new_y = calc_y(&ixs, d, &amp_gamma, ask_ind)?;
     let mut dy = ixs[ask_ind] - new_y;
@@ -300,7 +338,7 @@ pub fn compute_offer_amount(
     ask_ind: usize,
     config: &Config,
     env: &Env,
-) -> StdResult<(Decimal256, Decimal256, Decimal256)> {
+) -> Result<(Decimal256, Decimal256, Decimal256), PclError> {
     let offer_ind = 1 ^ ask_ind;
 
     if ask_ind == 1 {
@@ -311,6 +349,7 @@ pub fn compute_offer_amount(
     ixs[1] *= config.pool_state.price_state.price_scale;
 
     let amp_gamma = config.pool_state.get_amp_gamma(env);
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
     let d = calc_d(&ixs, &amp_gamma)?;
 
     // It's hard to predict fee rate thus we use maximum possible fee rate
@@ -321,6 +360,7 @@ pub fn compute_offer_amount(
     let mut fee = before_fee - want_amount;
 
     ixs[ask_ind] -= before_fee;
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
 
     let new_y = calc_y(&ixs, d, &amp_gamma, offer_ind)?;
     let mut dy = new_y - ixs[offer_ind];
@@ -0,0 +1,325 @@
+use cosmwasm_std::{Decimal, Decimal256, Env, Fraction};
+
+use astroport::asset::{AssetInfo, DecimalAsset};
+use astroport::cosmwasm_ext::AbsDiff;
+
+use crate::consts::{DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, OFFER_PERCENT};
+use crate::error::PclError;
+use crate::state::{Config, MultiPriceState, PoolParams};
+use crate::utils::SwapResult;
+use crate::{assert_safe_pool_volumes, calc_d, calc_y, get_xcp_n};
+
+/// Returns the indexes of the offer and ask pools.
+/// Both assets must be specified as the pool contains more than two assets.
+pub fn select_pools(
+    offer_asset_info: Option<&AssetInfo>,
+    ask_asset_info: Option<&AssetInfo>,
+    pools: &[DecimalAsset],
+) -> Result<(usize, usize), PclError> {
+    let (offer_asset_info, ask_asset_info) = offer_asset_info
+        .zip(ask_asset_info)
+        .ok_or(PclError::VariableAssetMissed {})?;
+    if offer_asset_info == ask_asset_info {
+        return Err(PclError::SameAssets {});
+    }
+
+    let find_ind = |asset_info: &AssetInfo| {
+        pools
+            .iter()
+            .position(|pool| pool.info == *asset_info)
+            .ok_or_else(|| PclError::InvalidAsset(asset_info.to_string()))
+    };
+
+    Ok((find_ind(offer_asset_info)?, find_ind(ask_asset_info)?))
+}
+
+/// Returns the price scale of the asset with the given index. The 1st asset's price scale is always 1.
+pub fn price_scale(price_scales: &[Decimal256], ind: usize) -> Decimal256 {
+    if ind == 0 {
+        Decimal256::one()
+    } else {
+        price_scales[ind - 1]
+    }
+}
+
+/// Converts pool volumes into the internal representation, i.e. all volumes are denominated in the 1st asset.
+pub fn to_internal_repr(xs: &[Decimal256], price_scales: &[Decimal256]) -> Vec<Decimal256> {
+    xs.iter()
+        .enumerate()
+        .map(|(ind, x)| *x * price_scale(price_scales, ind))
+        .collect()
+}
+
+/// Performs swap simulation to calculate prices of all assets denominated in the 1st one.
+pub fn calc_last_prices(
+    xs: &[Decimal256],
+    config: &Config<MultiPriceState>,
+    env: &Env,
+) -> Result<Vec<Decimal256>, PclError> {
+    let mut offer_amount = Decimal256::one().min(xs[0] * OFFER_PERCENT);
+    if offer_amount.is_zero() {
+        offer_amount = Decimal256::raw(1u128);
+    }
+
+    (1..xs.len())
+        .map(|ask_ind| {
+            let swap_result = compute_swap(
+                xs,
+                offer_amount,
+                0,
+                ask_ind,
+                config,
+                env,
+                Decimal256::zero(),
+            )?;
+            Ok(offer_amount / (swap_result.dy + swap_result.maker_fee))
+        })
+        .collect()
+}
+
+/// Calculate swap result.
+pub fn compute_swap(
+    xs: &[Decimal256],
+    offer_amount: Decimal256,
+    offer_ind: usize,
+    ask_ind: usize,
+    config: &Config<MultiPriceState>,
+    env: &Env,
+    maker_fee_share: Decimal256,
+) -> Result<SwapResult, PclError> {
+    let price_state = &config.pool_state.price_state;
+    let mut ixs = to_internal_repr(xs, &price_state.price_scales);
+
+    let amp_gamma = config.pool_state.get_amp_gamma(env);
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
+    let d = calc_d(&ixs, &amp_gamma)?;
+
+    ixs[offer_ind] += offer_amount * price_scale(&price_state.price_scales, offer_ind);
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
+
+    let new_y = calc_y(&ixs, d, &amp_gamma, ask_ind)?;
+    let mut dy = (ixs[ask_ind] - new_y) / price_scale(&price_state.price_scales, ask_ind);
+    ixs[ask_ind] = new_y;
+
+    // Derive spread using oracle prices
+    let spread_fee = (offer_amount * price_scale(&price_state.oracle_prices, offer_ind)
+        / price_scale(&price_state.oracle_prices, ask_ind))
+    .saturating_sub(dy);
+
+    let fee_rate = config.pool_params.fee(&ixs);
+    let total_fee = fee_rate * dy;
+    dy -= total_fee;
+
+    Ok(SwapResult {
+        dy,
+        spread_fee,
+        maker_fee: total_fee * maker_fee_share,
+        share_fee: Decimal256::zero(),
+        total_fee,
+    })
+}
+
+/// Returns an amount of offer assets for a specified amount of ask assets.
+pub fn compute_offer_amount(
+    xs: &[Decimal256],
+    want_amount: Decimal256,
+    offer_ind: usize,
+    ask_ind: usize,
+    config: &Config<MultiPriceState>,
+    env: &Env,
+) -> Result<(Decimal256, Decimal256, Decimal256), PclError> {
+    let price_scales = &config.pool_state.price_state.price_scales;
+    let want_amount = want_amount * price_scale(price_scales, ask_ind);
+    let mut ixs = to_internal_repr(xs, price_scales);
+
+    let amp_gamma = config.pool_state.get_amp_gamma(env);
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
+    let d = calc_d(&ixs, &amp_gamma)?;
+
+    // It's hard to predict fee rate thus we use maximum possible fee rate
+    let before_fee = want_amount
+        * (Decimal256::one() - Decimal256::from(config.pool_params.out_fee))
+            .inv()
+            .unwrap();
+    let fee = before_fee - want_amount;
+
+    ixs[ask_ind] -= before_fee;
+    assert_safe_pool_volumes(&ixs, &amp_gamma)?;
+
+    let new_y = calc_y(&ixs, d, &amp_gamma, offer_ind)?;
+    let dy = new_y - ixs[offer_ind];
+    let spread_fee = dy.saturating_sub(before_fee);
+
+    let offer_price_scale = price_scale(price_scales, offer_ind);
+
+    Ok((
+        dy / offer_price_scale,
+        spread_fee / offer_price_scale,
+        fee / offer_price_scale,
+    ))
+}
+
+/// Calculate provide fee applied on the amount of LP tokens. Only charged for imbalanced provide.
+/// * `deposits` - internal repr of deposit
+/// * `xp` - internal repr of pools
+pub fn calc_provide_fee(
+    deposits: &[Decimal256],
+    xp: &[Decimal256],
+    params: &PoolParams,
+) -> Decimal256 {
+    let n = Decimal256::from_ratio(deposits.len() as u128, 1u8);
+    let sum = deposits.iter().fold(Decimal256::zero(), |acc, x| acc + *x);
+    let avg = sum / n;
+    let diff_sum = deposits
+        .iter()
+        .fold(Decimal256::zero(), |acc, x| acc + x.diff(avg));
+
+    params.fee(xp) * n / (Decimal256::from_ratio(4u8, 1u8) * (n - Decimal256::one())) * diff_sum
+        / sum
+}
+
+/// This is an internal function that enforces slippage tolerance for provides. Returns actual slippage.
+pub fn assert_slippage_tolerance(
+    deposits: &[Decimal256],
+    actual_share: Decimal256,
+    price_state: &MultiPriceState,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Decimal256, PclError> {
+    let slippage_tolerance = slippage_tolerance
+        .map(Into::into)
+        .unwrap_or(DEFAULT_SLIPPAGE);
+    if slippage_tolerance > MAX_ALLOWED_SLIPPAGE {
+        return Err(PclError::AllowedSpreadAssertion {});
+    }
+
+    let deposit_value = to_internal_repr(deposits, &price_state.price_scales)
+        .into_iter()
+        .fold(Decimal256::zero(), |acc, x| acc + x);
+    let lp_expected =
+        get_xcp_n(deposit_value, &price_state.price_scales)? / price_state.xcp_profit_real;
+    let slippage = lp_expected.saturating_sub(actual_share) / lp_expected;
+
+    if slippage > slippage_tolerance {
+        return Err(PclError::MaxSpreadAssertion {});
+    }
+
+    Ok(slippage)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use super::*;
+
+    pub fn f64_to_dec<T>(val: f64) -> T
+    where
+        T: FromStr,
+        T::Err: Error,
+    {
+        T::from_str(&val.to_string()).unwrap()
+    }
+
+    pub fn dec_to_f64(val: impl Display) -> f64 {
+        f64::from_str(&val.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_provide_fees() {
+        let params = PoolParams {
+            mid_fee: f64_to_dec(0.0026),
+            out_fee: f64_to_dec(0.0045),
+            fee_gamma: f64_to_dec(0.00023),
+            ..PoolParams::default()
+        };
+
+        let fee_rate = calc_provide_fee(
+            &[
+                f64_to_dec(50_000f64),
+                f64_to_dec(50_000f64),
+                f64_to_dec(50_000f64),
+            ],
+            &[
+                f64_to_dec(100_000f64),
+                f64_to_dec(100_000f64),
+                f64_to_dec(100_000f64),
+            ],
+            &params,
+        );
+        assert_eq!(dec_to_f64(fee_rate), 0.0);
+
+        // A one-sided deposit into a balanced pool is charged with the half of mid_fee
+        let fee_rate = calc_provide_fee(
+            &[
+                f64_to_dec(90_000f64),
+                Decimal256::zero(),
+                Decimal256::zero(),
+            ],
+            &[
+                f64_to_dec(100_000f64),
+                f64_to_dec(100_000f64),
+                f64_to_dec(100_000f64),
+            ],
+            &params,
+        );
+        assert_eq!(dec_to_f64(fee_rate), 0.0013);
+    }
+
+    #[test]
+    fn test_select_pools() {
+        let pools = ["uusd", "uluna", "ueth"]
+            .into_iter()
+            .map(|denom| DecimalAsset {
+                info: AssetInfo::NativeToken {
+                    denom: denom.to_string(),
+                },
+                amount: Decimal256::one(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            select_pools(Some(&pools[2].info), Some(&pools[0].info), &pools).unwrap(),
+            (2, 0)
+        );
+        assert_eq!(
+            select_pools(Some(&pools[1].info), None, &pools).unwrap_err(),
+            PclError::VariableAssetMissed {}
+        );
+        assert_eq!(
+            select_pools(Some(&pools[1].info), Some(&pools[1].info), &pools).unwrap_err(),
+            PclError::SameAssets {}
+        );
+        assert_eq!(
+            select_pools(
+                Some(&pools[1].info),
+                Some(&AssetInfo::NativeToken {
+                    denom: "uatom".to_string()
+                }),
+                &pools
+            )
+            .unwrap_err(),
+            PclError::InvalidAsset("uatom".to_string())
+        );
+    }
+
+    #[test]
+    fn test_internal_repr() {
+        let price_scales = [f64_to_dec(2f64), f64_to_dec(0.5f64)];
+
+        assert_eq!(price_scale(&price_scales, 0), Decimal256::one());
+        assert_eq!(price_scale(&price_scales, 2), f64_to_dec(0.5f64));
+        assert_eq!(
+            to_internal_repr(
+                &[f64_to_dec(100f64), f64_to_dec(50f64), f64_to_dec(200f64)],
+                &price_scales
+            ),
+            vec![
+                f64_to_dec::<Decimal256>(100f64),
+                f64_to_dec(100f64),
+                f64_to_dec(100f64)
+            ]
+        );
+    }
+}
@@ -44,6 +44,7 @@ pub trait PairBonded<'a> {
                 liquidity_token: Addr::unchecked(""),
                 asset_infos: msg.asset_infos.clone(),
                 pair_type: PairType::Custom(String::from("Bonded")),
+                pool_key: None,
             },
             factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
         };
@@ -203,7 +204,9 @@ pub trait PairBonded<'a> {
                     to_addr,
                 )
             }
-            Cw20HookMsg::WithdrawLiquidity { .. } => Err(ContractError::NotSupported {}),
+            Cw20HookMsg::WithdrawLiquidity { .. }
+            | Cw20HookMsg::WithdrawSingleAsset { .. }
+            | Cw20HookMsg::PlaceLimitOrder { .. } => Err(ContractError::NotSupported {}),
         }
     }
 